@@ -1,25 +1,32 @@
 use axum::{
     body::Body,
-    extract::{Path, Query, State},
+    extract::{FromRequestParts, Path, Query, State},
     http::{
         header::{
-            ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE,
+            ACCEPT_RANGES, CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE,
+            CONTENT_TYPE, ETAG, IF_NONE_MATCH, RANGE,
         },
-        HeaderMap, HeaderName, HeaderValue, Response, StatusCode,
+        request::Parts,
+        HeaderMap, HeaderName, HeaderValue, Request, Response, StatusCode,
     },
+    middleware::Next,
+    response::IntoResponse,
     Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::process::Command;
 use std::str::FromStr;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::AppState;
 
 const DEFAULT_PAGE_LIMIT: u64 = 200;
 const MAX_PAGE_LIMIT: u64 = 10_000;
+const SNAPSHOT_SPACE_MAX_LIMIT: u64 = 1_000;
 const SPACEMAP_DEFAULT_LIMIT: u64 = 200;
 const SPACEMAP_MAX_LIMIT: u64 = 2_000;
 const SPACEMAP_BINS_DEFAULT_LIMIT: u64 = 256;
@@ -33,7 +40,66 @@ const BLOCK_TREE_DEFAULT_NODES: u64 = 2000;
 const BLOCK_TREE_MAX_NODES: u64 = 50_000;
 const OBJSET_DATA_DEFAULT_LIMIT: u64 = 64 * 1024;
 const OBJSET_DATA_MAX_LIMIT: u64 = 1 << 20;
+const READ_BATCH_MAX_ITEMS: usize = 64;
+const READ_BATCH_MAX_BYTES: u64 = 8 << 20;
 const ZPL_DOWNLOAD_MAX_BYTES: u64 = 512 * 1024 * 1024;
+const FILE_HISTORY_DEFAULT_SNAPSHOTS: u64 = 256;
+const FILE_HISTORY_MAX_SNAPSHOTS: u64 = 4_096;
+const LARGEST_FILES_DEFAULT_LIMIT: u64 = 50;
+const LARGEST_FILES_MAX_LIMIT: u64 = 1_000;
+const LARGEST_FILES_DEFAULT_SCAN: u64 = 100_000;
+const LARGEST_FILES_MAX_SCAN: u64 = 1_000_000;
+const LARGEST_FILES_SCAN_BATCH: u64 = 10_000;
+const SPACE_BY_TYPE_DEFAULT_SCAN: u64 = 50_000;
+const SPACE_BY_TYPE_MAX_SCAN: u64 = 1_000_000;
+const SPACE_BY_TYPE_SCAN_BATCH: u64 = 1_000;
+const DVA_OWNER_MAX_SCAN: u64 = 1_000_000;
+const DVA_OWNER_MAX_BLKPTRS: u64 = 10_000_000;
+const DVA_OWNER_SCAN_BATCH: u64 = 1_000;
+const DIR_SORT_MAX_ENTRIES: u64 = 100_000;
+const DIR_SORT_PAGE: u64 = 5_000;
+const WALK_RECOVERY_SAMPLE: u64 = 50;
+const SNIFF_BYTES: u64 = 4096;
+const OFFLINE_NO_POOLS_NOTE: &str =
+    "offline mode lists only configured pool names; set ZFS_EXPLORER_OFFLINE_POOLS";
+const VDEV_IOSTAT_MIN_INTERVAL_MS: u64 = 100;
+const VDEV_IOSTAT_MAX_INTERVAL_MS: u64 = 10_000;
+const VDEV_IOSTAT_MAX_SAMPLES: u64 = 10;
+const TAR_EXPORT_DEFAULT_MAX_ENTRIES: u64 = 10_000;
+const TAR_EXPORT_MAX_ENTRIES: u64 = 1_000_000;
+const TAR_EXPORT_DEFAULT_MAX_BYTES: u64 = 4 << 30; // 4 GiB
+const TAR_EXPORT_MAX_BYTES: u64 = 64 << 30; // 64 GiB
+const TAR_EXPORT_DIR_PAGE: u64 = 1_000;
+const TAR_EXPORT_SENTINEL: &str = ".zfs-explorer-export-report.json";
+const ERRORS_EXPORT_MAX_ENTRIES: u64 = 1_000_000;
+/// Chunks buffered between a blocking export/stream task and the response body.
+const STREAM_CHANNEL_DEPTH: usize = 8;
+const METADATA_EXPORT_SCHEMA_VERSION: u64 = 1;
+const METADATA_EXPORT_MAX_DATASETS: usize = 10_000;
+const METADATA_EXPORT_MAX_ERRORS: u64 = 10_000;
+const FEATURE_HOLDER_MAX_DATASETS: usize = 1_000;
+const FEATURE_HOLDER_SCAN_BATCH: usize = 64;
+const OBJECT_STREAM_BATCH: u64 = 1_000;
+const LINEAGE_MAX_PER_SIDE: u64 = 4096;
+const LINEAGE_DEFAULT_MAX_ENTRIES: u64 = 1024;
+/// Largest combined lineage budget a server may allow; both sides at their cap.
+pub const LINEAGE_MAX_ENTRIES: u64 = 2 * LINEAGE_MAX_PER_SIDE;
+const TAR_BLOCK_SIZE: usize = 512;
+const ZVOL_DATA_OBJECT: u64 = 1;
+const DATASET_CATALOG_DEFAULT_TTL_SECS: u64 = 30;
+const TXG_TIME_MAX_ANCHORS: u64 = 100_000;
+const TIMELINE_MAX_HISTORY: u64 = 10_000;
+const TIMELINE_MAX_DATASETS: usize = 1_000;
+const ZIL_DEFAULT_RECORDS: u64 = 1_000;
+const ZIL_MAX_RECORDS: u64 = 10_000;
+const DATASET_TREE_MAX_CHILD_LIMIT: usize = 100_000;
+const RECURSIVE_USAGE_DEFAULT_DEPTH: u8 = 8;
+const RECURSIVE_USAGE_MAX_DEPTH: u8 = 64;
+const RECURSIVE_USAGE_DEFAULT_LIMIT: usize = 1_000;
+const RECURSIVE_USAGE_MAX_LIMIT: usize = 10_000;
+const MOS_OBJECT_DIRECTORY: u64 = 1;
+// Rough upper bound on JSON bytes per listed item, used for in-flight accounting.
+const INFLIGHT_BYTES_PER_ITEM: u64 = 256;
 const BACKEND_NAME: &str = env!("CARGO_PKG_NAME");
 const BACKEND_VERSION: &str = env!("CARGO_PKG_VERSION");
 const BACKEND_BUILD_VERSION: &str = match option_env!("ZFS_EXPLORER_BUILD_VERSION") {
@@ -121,6 +187,129 @@ fn api_error(status: StatusCode, message: impl Into<String>) -> ApiError {
     )
 }
 
+tokio::task_local! {
+    /// Correlation ID of the request being served, installed by `with_request_id`.
+    static REQUEST_ID: String;
+}
+
+/// Run `fut` with `id` as the request ID reported in error envelopes.
+pub async fn with_request_id<F: std::future::Future>(id: String, fut: F) -> F::Output {
+    REQUEST_ID.scope(id, fut).await
+}
+
+/// Serialized shape of every error response. `error` mirrors `message` for
+/// older clients; `code` and `recoverable` are always present.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiErrorBody {
+    pub error: String,
+    pub message: String,
+    pub code: String,
+    pub recoverable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiErrorBody {
+    pub fn new(
+        code: impl Into<String>,
+        message: impl Into<String>,
+        hint: Option<String>,
+        recoverable: bool,
+    ) -> Self {
+        let message = message.into();
+        Self {
+            error: message.clone(),
+            message,
+            code: code.into(),
+            recoverable,
+            hint,
+            parameter: None,
+            details: None,
+            request_id: REQUEST_ID.try_with(|id| id.clone()).ok(),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    fn into_error(self, status: StatusCode) -> ApiError {
+        (status, Json(self.into_value()))
+    }
+}
+
+/// Success envelope: the handler's payload, serialized as-is except that paged
+/// payloads (objects carrying `next`) always expose `cursor`, `count`, and
+/// `has_more`, whichever native call produced them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct ApiResponse(Value);
+
+impl ApiResponse {
+    pub fn new(mut body: Value) -> Self {
+        if let Some(map) = body.as_object_mut() {
+            if let Some(next) = map.get("next") {
+                let has_more = !next.is_null();
+                map.insert("has_more".to_string(), Value::Bool(has_more));
+                if !map.contains_key("cursor") {
+                    let cursor = map.get("start").cloned().unwrap_or(json!(0));
+                    map.insert("cursor".to_string(), cursor);
+                }
+                if !map.contains_key("count") {
+                    let mut arrays = map.values().filter_map(Value::as_array);
+                    if let (Some(items), None) = (arrays.next(), arrays.next()) {
+                        let count = items.len();
+                        map.insert("count".to_string(), json!(count));
+                    }
+                }
+            }
+        }
+        Self(body)
+    }
+
+    fn into_json(self) -> Json<Value> {
+        Json(self.0)
+    }
+
+    /// Record when a success body was produced: `generated_at_unix_sec` plus
+    /// the same instant as an RFC 3339 UTC string. Payloads that already
+    /// carry the keys keep their own values.
+    fn stamp_generated_at(map: &mut serde_json::Map<String, Value>, unix_sec: u64) {
+        map.entry("generated_at_unix_sec")
+            .or_insert_with(|| json!(unix_sec));
+        map.entry("generated_at")
+            .or_insert_with(|| json!(rfc3339_utc(unix_sec)));
+    }
+}
+
+/// Format a unix timestamp as `YYYY-MM-DDTHH:MM:SSZ` (proleptic Gregorian).
+fn rfc3339_utc(unix_sec: u64) -> String {
+    let days = unix_sec / 86_400;
+    let secs = unix_sec % 86_400;
+    // Civil-from-days (Howard Hinnant), shifted so the era starts in March.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
 fn api_error_with(
     status: StatusCode,
     code: impl Into<String>,
@@ -128,19 +317,550 @@ fn api_error_with(
     hint: Option<String>,
     recoverable: bool,
 ) -> ApiError {
-    let message = message.into();
-    let mut payload = json!({
-        "error": message,
-        "message": message,
-        "code": code.into(),
-        "recoverable": recoverable,
+    ApiErrorBody::new(code, message, hint, recoverable).into_error(status)
+}
+
+/// Query-string extractor that reports deserialization failures using the
+/// standard JSON error envelope (`code: "INVALID_QUERY"`).
+pub struct ApiQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(value)) => Ok(ApiQuery(value)),
+            Err(rejection) => Err(invalid_query_error(&rejection.body_text())),
+        }
+    }
+}
+
+fn invalid_query_parameter(detail: &str) -> Option<&str> {
+    let is_param_name = |name: &str| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    if let Some((path, _)) = detail.split_once(": ") {
+        if is_param_name(path) {
+            return Some(path);
+        }
+    }
+
+    let (_, rest) = detail.split_once("missing field `")?;
+    let (name, _) = rest.split_once('`')?;
+    Some(name).filter(|name| is_param_name(name))
+}
+
+fn invalid_query_error(rejection_text: &str) -> ApiError {
+    let detail = rejection_text
+        .trim_start_matches("Failed to deserialize query string:")
+        .trim();
+    let parameter = invalid_query_parameter(detail);
+    let message = match parameter {
+        Some(name) => {
+            let reason = detail.strip_prefix(&format!("{name}: ")).unwrap_or(detail);
+            format!("invalid query parameter '{name}': {reason}")
+        }
+        None => format!("invalid query string: {detail}"),
+    };
+
+    let mut body = ApiErrorBody::new("INVALID_QUERY", message, None, true);
+    body.parameter = parameter.map(str::to_string);
+    body.into_error(StatusCode::BAD_REQUEST)
+}
+
+const OFFLINE_CACHE_CONTROL: &str = "no-cache";
+const LIVE_CACHE_CONTROL: &str = "no-store";
+
+/// Cache policy for a finished request. Offline pools cannot change on disk
+/// while a handle is open, so successful pool-scoped GETs may be stored but
+/// must be revalidated against `offline_pool_etag` (a refresh, mode switch or
+/// restart with another txg changes it); everything else that could differ
+/// between requests (live data, mode, pool list, errors) is `no-store`.
+/// Perf endpoints are left alone.
+fn cache_control_for(
+    mode: crate::PoolOpenMode,
+    method: &axum::http::Method,
+    path: &str,
+    status: StatusCode,
+) -> Option<&'static str> {
+    if method != axum::http::Method::GET || path.starts_with("/api/perf/") {
+        return None;
+    }
+    let revalidate = matches!(mode, crate::PoolOpenMode::Offline)
+        && status.is_success()
+        && path.starts_with("/api/pools/");
+    Some(if revalidate {
+        OFFLINE_CACHE_CONTROL
+    } else {
+        LIVE_CACHE_CONTROL
+    })
+}
+
+fn not_ready_error() -> ApiError {
+    api_error_with(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "NOT_READY",
+        "server is still initializing",
+        Some("Retry once GET /api/ready returns 200.".to_string()),
+        true,
+    )
+}
+
+/// GET /api/ready - 200 once startup initialization has finished, else 503
+pub async fn api_ready(State(state): State<AppState>) -> ApiResult {
+    if !state.ready.load(Ordering::Acquire) {
+        return Err(not_ready_error());
+    }
+    Ok(Json(json!({ "ready": true })))
+}
+
+/// Middleware: refuse requests with 503 `NOT_READY` until startup
+/// initialization has finished, so nothing reaches the FFI early.
+/// `/api/ready` answers for itself.
+pub async fn readiness_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response<Body> {
+    if state.ready.load(Ordering::Acquire) || request.uri().path() == "/api/ready" {
+        return next.run(request).await;
+    }
+    not_ready_error().into_response()
+}
+
+/// Bumped whenever the active handle is dropped (refresh, mode switch, pool
+/// switch), so validators issued for the previous handle stop matching.
+static POOL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn bump_pool_generation() {
+    POOL_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Validator for offline pool-scoped responses: the process start time and
+/// handle generation identify the open pool, and the configured txg the view
+/// of it. Weak because the JSON envelope (e.g. `generated_at`) varies.
+fn offline_pool_etag(txg: Option<u64>) -> String {
+    static PROCESS_NONCE: std::sync::OnceLock<u128> = std::sync::OnceLock::new();
+    let nonce = PROCESS_NONCE.get_or_init(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
     });
+    let generation = POOL_GENERATION.load(Ordering::Relaxed);
+    let txg = txg.map_or_else(|| "latest".to_string(), |txg| txg.to_string());
+    format!("W/\"offline-{nonce:x}-{generation}-{txg}\"")
+}
+
+/// Middleware: attach `Cache-Control` per `cache_control_for`, unless the
+/// handler already set one. Offline pool-scoped successes also get an
+/// `offline_pool_etag` (unless the handler set its own), and a matching
+/// `If-None-Match` is answered with 304 before the handler runs.
+pub async fn cache_control_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response<Body> {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let config = pool_open_config(&state);
+    let mode = config.mode;
+    let etag = (matches!(mode, crate::PoolOpenMode::Offline)
+        && method == axum::http::Method::GET
+        && path.starts_with("/api/pools/"))
+    .then(|| offline_pool_etag(config.offline_txg));
+
+    if let Some(etag) = etag.as_deref() {
+        if if_none_match_hits(request.headers(), etag) {
+            let mut response = Response::new(Body::empty());
+            *response.status_mut() = StatusCode::NOT_MODIFIED;
+            let headers = response.headers_mut();
+            headers.insert(
+                CACHE_CONTROL,
+                HeaderValue::from_static(OFFLINE_CACHE_CONTROL),
+            );
+            if let Ok(value) = HeaderValue::from_str(etag) {
+                headers.insert(ETAG, value);
+            }
+            return response;
+        }
+    }
+    let mut response = next.run(request).await;
+
+    if response.headers().contains_key(CACHE_CONTROL) {
+        return response;
+    }
+    if let Some(policy) = cache_control_for(mode, &method, &path, response.status()) {
+        response
+            .headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static(policy));
+        let tag = etag
+            .filter(|_| policy == OFFLINE_CACHE_CONTROL && !response.headers().contains_key(ETAG));
+        if let Some(value) = tag.and_then(|tag| HeaderValue::from_str(&tag).ok()) {
+            response.headers_mut().insert(ETAG, value);
+        }
+    }
+    response
+}
+
+const FFI_CALLS_HEADER: &str = "x-zfs-ffi-calls";
+
+fn debug_requested(query: Option<&str>) -> bool {
+    query.is_some_and(|query| {
+        query
+            .split('&')
+            .any(|pair| matches!(pair, "debug=true" | "debug=1"))
+    })
+}
+
+fn raw_requested(query: Option<&str>) -> bool {
+    query.is_some_and(|query| {
+        query
+            .split('&')
+            .any(|pair| matches!(pair, "raw=true" | "raw=1"))
+    })
+}
+
+/// `?raw=true` exposes unprocessed FFI output, so it only works in debug
+/// builds or when the operator sets `ZFS_EXPLORER_ALLOW_RAW=1`.
+fn raw_allowed() -> bool {
+    cfg!(debug_assertions)
+        || std::env::var("ZFS_EXPLORER_ALLOW_RAW").is_ok_and(|value| value.trim() == "1")
+}
+
+/// Rewrite a buffered JSON object body in place. Non-JSON (including
+/// streamed downloads) and non-object bodies pass through untouched; a body
+/// that fails to buffer becomes a 500 envelope rather than a truncated reply.
+async fn edit_json_object_body(
+    response: Response<Body>,
+    edit: impl FnOnce(&mut serde_json::Map<String, Value>),
+) -> Response<Body> {
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    // Streamed bodies (no exact length) are left alone rather than buffered.
+    let is_buffered = axum::body::HttpBody::size_hint(response.body())
+        .exact()
+        .is_some();
+    if !is_json || !is_buffered {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => match serde_json::from_slice::<Value>(&bytes) {
+            Ok(Value::Object(mut map)) => {
+                edit(&mut map);
+                parts.headers.remove(CONTENT_LENGTH);
+                Body::from(Value::Object(map).to_string())
+            }
+            _ => Body::from(bytes),
+        },
+        Err(err) => {
+            tracing::error!("failed to buffer JSON response body: {}", err);
+            return api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to read response body: {err}"),
+            )
+            .into_response();
+        }
+    };
+    Response::from_parts(parts, body)
+}
+
+/// `Server-Timing` value with FFI, JSON parse, and whole-handler durations in
+/// milliseconds.
+fn server_timing_value(ffi: Duration, parse: Duration, total: Duration) -> String {
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    format!(
+        "ffi;dur={:.1}, parse;dur={:.1}, total;dur={:.1}",
+        ms(ffi),
+        ms(parse),
+        ms(total)
+    )
+}
+
+/// Middleware: with `?debug=true`, report the request's FFI call count via the
+/// `X-ZFS-FFI-Calls` header and a `_debug.ffi_calls` field on JSON objects,
+/// and phase durations via `Server-Timing`.
+pub async fn ffi_debug_middleware(request: Request<Body>, next: Next) -> Response<Body> {
+    if !debug_requested(request.uri().query()) {
+        return next.run(request).await;
+    }
+
+    let counter = Arc::new(AtomicU64::new(0));
+    let timings = Arc::new(crate::ffi::PhaseTimings::default());
+    let started = Instant::now();
+    let response = crate::ffi::with_ffi_call_counter(
+        counter.clone(),
+        crate::ffi::with_phase_timings(timings.clone(), next.run(request)),
+    )
+    .await;
+    let total = started.elapsed();
+    let calls = counter.load(Ordering::Relaxed);
+    let server_timing = server_timing_value(
+        Duration::from_nanos(timings.ffi_nanos.load(Ordering::Relaxed)),
+        Duration::from_nanos(timings.parse_nanos.load(Ordering::Relaxed)),
+        total,
+    );
+
+    let mut response = edit_json_object_body(response, |map| {
+        map.insert("_debug".to_string(), json!({ "ffi_calls": calls }));
+    })
+    .await;
+    response.headers_mut().insert(
+        HeaderName::from_static(FFI_CALLS_HEADER),
+        HeaderValue::from(calls),
+    );
+    if let Ok(value) = HeaderValue::from_str(&server_timing) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static("server-timing"), value);
+    }
+    response
+}
+
+/// Middleware: rewrite 413 rejections from the request body limit (plain text
+/// from tower-http or the JSON extractor) into the standard error envelope.
+pub async fn payload_too_large_middleware(request: Request<Body>, next: Next) -> Response<Body> {
+    let response = next.run(request).await;
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return response;
+    }
+    api_error_with(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        "PAYLOAD_TOO_LARGE",
+        "request body exceeds the configured size limit",
+        Some("Send a smaller body or raise ZFS_EXPLORER_MAX_BODY_BYTES.".to_string()),
+        true,
+    )
+    .into_response()
+}
+
+/// Middleware: with `?raw=true` (when allowed), attach the untouched FFI JSON
+/// payloads behind a response as `_raw`, in call order, so enrichment in the
+/// Rust layer can be told apart from what libzdbdecode returned.
+pub async fn ffi_raw_middleware(request: Request<Body>, next: Next) -> Response<Body> {
+    if !raw_requested(request.uri().query()) || !raw_allowed() {
+        return next.run(request).await;
+    }
+
+    let capture = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let response = crate::ffi::with_ffi_raw_capture(capture.clone(), next.run(request)).await;
+    let payloads: Vec<Value> = std::mem::take(&mut *capture.lock().unwrap())
+        .into_iter()
+        .map(|json| serde_json::from_str(&json).unwrap_or(Value::String(json)))
+        .collect();
+    if payloads.is_empty() {
+        return response;
+    }
+
+    edit_json_object_body(response, |map| {
+        map.insert("_raw".to_string(), Value::Array(payloads));
+    })
+    .await
+}
+
+/// Middleware: stamp every successful JSON object response with the time it
+/// was generated, so saved (especially offline/forensic) reads say when they
+/// were taken.
+pub async fn generated_at_middleware(request: Request<Body>, next: Next) -> Response<Body> {
+    let response = next.run(request).await;
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let unix_sec = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    edit_json_object_body(response, |map| {
+        ApiResponse::stamp_generated_at(map, unix_sec)
+    })
+    .await
+}
+
+const X_CACHE_HEADER: &str = "x-cache";
+/// Larger bodies are served normally but not kept.
+const RESPONSE_CACHE_MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+/// Total bytes of cached bodies; least recently used entries are evicted
+/// until the cache fits, whatever the entry count allows.
+const RESPONSE_CACHE_MAX_TOTAL_BYTES: usize = 64 * 1024 * 1024;
+
+/// A finished 200 JSON response: its headers and serialized body.
+#[derive(Clone, Debug)]
+struct CachedResponse {
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+}
+
+#[derive(Debug, Default)]
+struct ResponseCacheEntries {
+    entries: HashMap<String, (u64, CachedResponse)>,
+    /// Last-use tick -> key, oldest first, for LRU eviction.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    bytes: usize,
+}
+
+/// Offline-mode cache of serialized responses keyed by method, path and
+/// query (`ZFS_EXPLORER_RESPONSE_CACHE` entries; 0 disables it), bounded by
+/// both entry count and total body bytes.
+#[derive(Debug)]
+pub struct ResponseCache {
+    capacity: usize,
+    max_bytes: usize,
+    inner: std::sync::Mutex<ResponseCacheEntries>,
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_byte_budget(capacity, RESPONSE_CACHE_MAX_TOTAL_BYTES)
+    }
+
+    fn with_byte_budget(capacity: usize, max_bytes: usize) -> Self {
+        ResponseCache {
+            capacity,
+            max_bytes,
+            inner: std::sync::Mutex::new(ResponseCacheEntries::default()),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut guard = self.inner.lock().unwrap();
+        let inner = &mut *guard;
+        inner.tick += 1;
+        let (last_used, cached) = inner.entries.get_mut(key)?;
+        inner.recency.remove(last_used);
+        *last_used = inner.tick;
+        inner.recency.insert(inner.tick, key.to_string());
+        Some(cached.clone())
+    }
+
+    fn insert(&self, key: String, cached: CachedResponse) {
+        let mut guard = self.inner.lock().unwrap();
+        let inner = &mut *guard;
+        inner.tick += 1;
+        if let Some((last_used, old)) = inner.entries.remove(&key) {
+            inner.recency.remove(&last_used);
+            inner.bytes -= old.body.len();
+        }
+        if cached.body.len() > self.max_bytes {
+            return;
+        }
+        inner.bytes += cached.body.len();
+        inner.recency.insert(inner.tick, key.clone());
+        inner.entries.insert(key, (inner.tick, cached));
+        while inner.entries.len() > self.capacity || inner.bytes > self.max_bytes {
+            let Some((_, oldest)) = inner.recency.pop_first() else {
+                break;
+            };
+            if let Some((_, evicted)) = inner.entries.remove(&oldest) {
+                inner.bytes -= evicted.body.len();
+            }
+        }
+    }
 
-    if let Some(hint) = hint {
-        payload["hint"] = Value::String(hint);
+    /// Drop every entry; called when the mode or the open pool changes.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.entries.clear();
+        inner.recency.clear();
+        inner.bytes = 0;
     }
+}
+
+/// Cache key for a request the response cache may serve: offline GETs of
+/// pool-scoped routes (the ones `cache_control_for` lets clients store), minus
+/// `debug`/`raw` requests whose bodies differ per call and `Range` or
+/// `If-None-Match` requests that expect a 206 or 304.
+fn response_cache_key(mode: crate::PoolOpenMode, request: &Request<Body>) -> Option<String> {
+    let uri = request.uri();
+    let cacheable = matches!(mode, crate::PoolOpenMode::Offline)
+        && request.method() == axum::http::Method::GET
+        && uri.path().starts_with("/api/pools/")
+        && !request.headers().contains_key(RANGE)
+        && !request.headers().contains_key(IF_NONE_MATCH)
+        && !debug_requested(uri.query())
+        && !raw_requested(uri.query());
+    cacheable.then(|| format!("{} {}", request.method(), uri))
+}
+
+/// Middleware: serve repeated offline reads from `ResponseCache`, tagging
+/// cacheable requests `X-Cache: HIT` or `MISS`. Only buffered 200 JSON
+/// bodies are stored, so downloads and streamed exports always pass through.
+pub async fn response_cache_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response<Body> {
+    let cache = state.response_cache.clone();
+    let key = cache
+        .enabled()
+        .then(|| response_cache_key(pool_open_config(&state).mode, &request))
+        .flatten();
+    let Some(key) = key else {
+        return next.run(request).await;
+    };
 
-    (status, Json(payload))
+    if let Some(cached) = cache.get(&key) {
+        let mut response = Response::new(Body::from(cached.body));
+        *response.headers_mut() = cached.headers;
+        response
+            .headers_mut()
+            .insert(X_CACHE_HEADER, HeaderValue::from_static("HIT"));
+        return response;
+    }
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let small = axum::body::HttpBody::size_hint(response.body())
+        .exact()
+        .is_some_and(|len| len <= RESPONSE_CACHE_MAX_BODY_BYTES as u64);
+    let mut response = if response.status() == StatusCode::OK && is_json && small {
+        let (parts, body) = response.into_parts();
+        match axum::body::to_bytes(body, RESPONSE_CACHE_MAX_BODY_BYTES).await {
+            Ok(bytes) => {
+                cache.insert(
+                    key,
+                    CachedResponse {
+                        headers: parts.headers.clone(),
+                        body: bytes.clone(),
+                    },
+                );
+                Response::from_parts(parts, Body::from(bytes))
+            }
+            Err(err) => {
+                tracing::error!("failed to buffer response for caching: {}", err);
+                return api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("failed to read response body: {err}"),
+                )
+                .into_response();
+            }
+        }
+    } else {
+        response
+    };
+    response
+        .headers_mut()
+        .insert(X_CACHE_HEADER, HeaderValue::from_static("MISS"));
+    response
 }
 
 fn is_dataset_user_input_error(err_msg: &str) -> bool {
@@ -158,6 +878,10 @@ fn is_spacemap_user_input_error(err_msg: &str) -> bool {
                 || err_msg.contains("No such file or directory")))
 }
 
+fn is_vdev_not_found_error(err_msg: &str) -> bool {
+    err_msg.starts_with("top-level vdev ") && err_msg.ends_with(" not found")
+}
+
 fn is_objset_user_input_error(err_msg: &str) -> bool {
     err_msg.contains("dnode_hold failed for object")
         || err_msg.contains("objset is not ZFS")
@@ -170,6 +894,12 @@ fn is_objset_user_input_error(err_msg: &str) -> bool {
         || err_msg.contains("zap_cursor_retrieve failed")
 }
 
+fn is_object_not_found_error(err_msg: &str) -> bool {
+    err_msg.contains(" not allocated")
+        || (err_msg.contains("dmu_object_info failed for object")
+            && err_msg.contains("No such file or directory"))
+}
+
 fn is_zap_unreadable_error(err_msg: &str) -> bool {
     (err_msg.contains("zap_get_stats failed")
         || err_msg.contains("zap_lookup failed")
@@ -184,6 +914,16 @@ for encrypted dataset contents when key material is unavailable."
 }
 
 fn api_error_for_objset(err_msg: &str) -> ApiError {
+    if is_object_not_found_error(err_msg) {
+        return api_error_with(
+            StatusCode::NOT_FOUND,
+            "OBJECT_NOT_FOUND",
+            err_msg.to_string(),
+            Some("The object number is not allocated in this objset.".to_string()),
+            true,
+        );
+    }
+
     if is_zap_unreadable_error(err_msg) {
         return api_error_with(
             StatusCode::BAD_REQUEST,
@@ -208,12 +948,10 @@ fn inline_zap_error_payload(err_msg: &str) -> Option<Value> {
         return None;
     }
 
-    Some(json!({
-        "code": "ZAP_UNREADABLE",
-        "message": err_msg,
-        "hint": zap_unreadable_hint(),
-        "recoverable": true
-    }))
+    Some(
+        ApiErrorBody::new("ZAP_UNREADABLE", err_msg, Some(zap_unreadable_hint()), true)
+            .into_value(),
+    )
 }
 
 fn libzfs_error_name(code: i32) -> Option<&'static str> {
@@ -324,6 +1062,28 @@ fn libzfs_error_name(code: i32) -> Option<&'static str> {
     }
 }
 
+/// Error codes a live pool returns while a scrub, resilver, or other
+/// exclusive operation holds it; the same read succeeds once it settles.
+fn is_pool_busy_code(code: i32) -> bool {
+    code == libc::EBUSY
+        || matches!(
+            libzfs_error_name(code),
+            Some("EZFS_BUSY" | "EZFS_RESILVERING" | "EZFS_SCRUBBING")
+        )
+}
+
+fn pool_busy_error(code_label: &str, err_msg: &str) -> ApiError {
+    api_error_with(
+        StatusCode::CONFLICT,
+        "POOL_BUSY",
+        err_msg.to_string(),
+        Some(format!(
+            "The pool is busy ({code_label}), typically mid-scrub or mid-resilver; retry the request later."
+        )),
+        true,
+    )
+}
+
 fn pool_open_error_code(code: i32) -> String {
     if let Some(name) = libzfs_error_name(code) {
         return name.to_string();
@@ -334,6 +1094,37 @@ fn pool_open_error_code(code: i32) -> String {
     format!("ZDX_{code}")
 }
 
+/// Opens and immediately closes each configured offline pool, logging the
+/// outcome with the same hints API callers would get. Returns how many opened.
+pub fn offline_selftest(
+    pool_names: &[String],
+    search_paths: Option<&str>,
+    txg: Option<u64>,
+) -> usize {
+    let mut opened = 0;
+    for pool in pool_names {
+        match crate::ffi::pool_open_offline(pool, search_paths, txg) {
+            Ok(handle) => {
+                drop(handle);
+                opened += 1;
+                tracing::info!("Offline self-test: pool {} opened OK", pool);
+            }
+            Err((code, msg)) => {
+                tracing::error!(
+                    "Offline self-test: pool {} failed to open (code={}): {}",
+                    pool,
+                    pool_open_error_code(code),
+                    msg
+                );
+                if let Some(hint) = offline_pool_open_hint(pool, code) {
+                    tracing::error!("Offline self-test hint: {}", hint);
+                }
+            }
+        }
+    }
+    opened
+}
+
 fn offline_pool_open_hint(pool: &str, code: i32) -> Option<String> {
     let pool_name = pool.to_string();
     if matches!(libzfs_error_name(code), Some("EZFS_NOENT")) || code == libc::ENOENT {
@@ -420,6 +1211,7 @@ fn build_version_payload(pool_open: &crate::PoolOpenConfig) -> Value {
             "mode": pool_open_mode_name(pool_open.mode),
             "offline_search_paths": pool_open.offline_search_paths.clone(),
             "offline_pools": pool_open.offline_pool_names.clone(),
+            "offline_txg": pool_open.offline_txg,
         },
     })
 }
@@ -429,6 +1221,7 @@ fn build_mode_payload(pool_open: &crate::PoolOpenConfig) -> Value {
         "mode": pool_open_mode_name(pool_open.mode),
         "offline_search_paths": pool_open.offline_search_paths.clone(),
         "offline_pools": pool_open.offline_pool_names.clone(),
+        "offline_txg": pool_open.offline_txg,
     })
 }
 
@@ -757,6 +1550,34 @@ fn parse_vdev_iostat_output(output: &str) -> Vec<VdevIostatRow> {
         .collect()
 }
 
+/// Split `zpool iostat -v` interval output into one row set per sample. Each
+/// sample starts with the unindented pool row.
+fn split_vdev_iostat_samples(rows: Vec<VdevIostatRow>, pool: &str) -> Vec<Vec<VdevIostatRow>> {
+    let mut samples: Vec<Vec<VdevIostatRow>> = Vec::new();
+    for row in rows {
+        if (row.depth == 0 && row.name == pool) || samples.is_empty() {
+            samples.push(Vec::new());
+        }
+        if let Some(sample) = samples.last_mut() {
+            sample.push(row);
+        }
+    }
+    samples
+}
+
+/// Per-second rates for one vdev. Interval samples from `zpool iostat` are
+/// already averaged over the interval, unlike the first since-import sample.
+fn vdev_iostat_rate(row: &VdevIostatRow) -> Value {
+    json!({
+        "name": row.name,
+        "depth": row.depth,
+        "read_ops_per_sec": row.read_ops,
+        "write_ops_per_sec": row.write_ops,
+        "read_bytes_per_sec": row.read_bytes,
+        "write_bytes_per_sec": row.write_bytes,
+    })
+}
+
 fn parse_ddt_summary(output: &str) -> DdtSummary {
     let mut entries = None;
     let mut size_on_disk = None;
@@ -1006,40 +1827,235 @@ pub async fn perf_arc(State(state): State<AppState>) -> ApiResult {
     Ok(Json(build_arc_payload(&counters)))
 }
 
-#[derive(Debug, Deserialize)]
-pub struct PerfVdevIostatQuery {
-    pub pool: String,
+#[derive(Debug, Clone, PartialEq)]
+struct PoolMetricsRow {
+    name: String,
+    size_bytes: Option<u64>,
+    allocated_bytes: Option<u64>,
+    free_bytes: Option<u64>,
+    frag_percent: Option<f64>,
+    dedup_ratio: Option<f64>,
+    health: String,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct PerfTxgQuery {
-    pub pool: Option<String>,
+/// Parses `zpool list -H -p -o name,size,alloc,free,frag,dedupratio,health`.
+fn parse_zpool_metrics_rows(output: &str) -> Vec<PoolMetricsRow> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts = line.split('\t').collect::<Vec<_>>();
+            if parts.len() < 7 || parts[0].trim().is_empty() {
+                return None;
+            }
+            let (size_bytes, allocated_bytes, free_bytes, frag_percent, dedup_ratio) =
+                parse_zpool_space_summary(&parts[1..6].join("\t"))?;
+            Some(PoolMetricsRow {
+                name: parts[0].trim().to_string(),
+                size_bytes,
+                allocated_bytes,
+                free_bytes,
+                frag_percent,
+                dedup_ratio,
+                health: parts[6].trim().to_string(),
+            })
+        })
+        .collect()
 }
 
-fn txgs_path_for_pool(pool: &str) -> String {
-    format!("/proc/spl/kstat/zfs/{}/txgs", pool)
+fn prometheus_label_value(raw: &str) -> String {
+    raw.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
 }
 
-fn map_kstat_read_error(path: &str, err: &std::io::Error) -> ApiError {
-    let (status, message) = match err.kind() {
-        std::io::ErrorKind::NotFound => (
-            StatusCode::NOT_IMPLEMENTED,
-            format!("txg stats file not found: {}", path),
+fn push_metric_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+}
+
+fn render_pool_metrics(rows: &[PoolMetricsRow]) -> String {
+    type PoolGauge = fn(&PoolMetricsRow) -> Option<f64>;
+    let gauges: [(&str, &str, PoolGauge); 5] = [
+        (
+            "zfs_pool_size_bytes",
+            "Total pool capacity in bytes.",
+            |row| row.size_bytes.map(|v| v as f64),
         ),
-        std::io::ErrorKind::PermissionDenied => (
-            StatusCode::FORBIDDEN,
-            format!("permission denied reading {}", path),
+        (
+            "zfs_pool_alloc_bytes",
+            "Allocated pool space in bytes.",
+            |row| row.allocated_bytes.map(|v| v as f64),
         ),
-        _ => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed reading {}: {}", path, err),
+        ("zfs_pool_free_bytes", "Free pool space in bytes.", |row| {
+            row.free_bytes.map(|v| v as f64)
+        }),
+        (
+            "zfs_pool_frag_ratio",
+            "Free-space fragmentation as a 0-1 ratio.",
+            |row| row.frag_percent.map(|v| v / 100.0),
         ),
-    };
-    api_error(status, message)
-}
-
-fn resolve_txg_source<F>(
-    requested_pool: Option<&str>,
+        ("zfs_pool_dedup_ratio", "Pool deduplication ratio.", |row| {
+            row.dedup_ratio
+        }),
+    ];
+
+    let mut out = String::new();
+    for (name, help, value) in gauges {
+        push_metric_header(&mut out, name, help, "gauge");
+        for row in rows {
+            if let Some(v) = value(row) {
+                out.push_str(&format!(
+                    "{name}{{pool=\"{}\"}} {v}\n",
+                    prometheus_label_value(&row.name)
+                ));
+            }
+        }
+    }
+
+    push_metric_header(
+        &mut out,
+        "zfs_pool_state",
+        "Pool health; 1 for the current state label.",
+        "gauge",
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "zfs_pool_state{{pool=\"{}\",state=\"{}\"}} 1\n",
+            prometheus_label_value(&row.name),
+            prometheus_label_value(&row.health)
+        ));
+    }
+    out
+}
+
+fn render_arc_metrics(counters: &HashMap<String, u64>) -> String {
+    let mut out = String::new();
+    for (name, help, kind, key) in [
+        (
+            "zfs_arc_size_bytes",
+            "Current ARC size in bytes.",
+            "gauge",
+            "size",
+        ),
+        (
+            "zfs_arc_target_bytes",
+            "ARC target size (c) in bytes.",
+            "gauge",
+            "c",
+        ),
+        (
+            "zfs_arc_hits_total",
+            "ARC hits since module load.",
+            "counter",
+            "hits",
+        ),
+        (
+            "zfs_arc_misses_total",
+            "ARC misses since module load.",
+            "counter",
+            "misses",
+        ),
+    ] {
+        if let Some(value) = counters.get(key) {
+            push_metric_header(&mut out, name, help, kind);
+            out.push_str(&format!("{name} {value}\n"));
+        }
+    }
+    out
+}
+
+async fn run_zpool_metrics_list() -> Result<String, String> {
+    let output = tokio::task::spawn_blocking(|| {
+        let mut command = host_cli_command("zpool");
+        command
+            .arg("list")
+            .arg("-H")
+            .arg("-p")
+            .arg("-o")
+            .arg("name,size,alloc,free,frag,dedupratio,health")
+            .output()
+    })
+    .await
+    .map_err(|err| format!("failed to collect zpool list: {err}"))?
+    .map_err(|err| format!("failed to execute zpool list: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format!("zpool list exited with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// GET /metrics - Prometheus text exposition
+pub async fn metrics(State(state): State<AppState>) -> Response<Body> {
+    let config = pool_open_config(&state);
+    let mode = pool_open_mode_name(config.mode);
+
+    let mut out = String::new();
+    push_metric_header(
+        &mut out,
+        "zfs_explorer_info",
+        "Backend build and pool-open mode.",
+        "gauge",
+    );
+    out.push_str(&format!(
+        "zfs_explorer_info{{version=\"{}\",mode=\"{mode}\"}} 1\n",
+        prometheus_label_value(BACKEND_BUILD_VERSION)
+    ));
+
+    // Host telemetry and `zpool list` describe imported pools only.
+    if matches!(config.mode, crate::PoolOpenMode::Live) {
+        if let Ok(contents) = std::fs::read_to_string(ARCSTATS_PATH) {
+            out.push_str(&render_arc_metrics(&parse_arcstats(&contents)));
+        }
+        match run_zpool_metrics_list().await {
+            Ok(raw) => out.push_str(&render_pool_metrics(&parse_zpool_metrics_rows(&raw))),
+            Err(err) => tracing::warn!("skipping pool metrics: {}", err),
+        }
+    }
+
+    let mut response = Response::new(Body::from(out));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+    );
+    response
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PerfVdevIostatQuery {
+    pub pool: String,
+    pub interval_ms: Option<u64>,
+    pub count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PerfTxgQuery {
+    pub pool: Option<String>,
+}
+
+fn txgs_path_for_pool(pool: &str) -> String {
+    format!("/proc/spl/kstat/zfs/{}/txgs", pool)
+}
+
+fn map_kstat_read_error(path: &str, err: &std::io::Error) -> ApiError {
+    let (status, message) = match err.kind() {
+        std::io::ErrorKind::NotFound => (
+            StatusCode::NOT_IMPLEMENTED,
+            format!("txg stats file not found: {}", path),
+        ),
+        std::io::ErrorKind::PermissionDenied => (
+            StatusCode::FORBIDDEN,
+            format!("permission denied reading {}", path),
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed reading {}: {}", path, err),
+        ),
+    };
+    api_error(status, message)
+}
+
+fn resolve_txg_source<F>(
+    requested_pool: Option<&str>,
     mut read_file: F,
 ) -> Result<(String, String), ApiError>
 where
@@ -1120,10 +2136,10 @@ fn build_txg_payload(
     })))
 }
 
-/// GET /api/perf/vdev_iostat?pool= - per-vdev iostat sample (live mode only)
+/// GET /api/perf/vdev_iostat?pool=&interval_ms=&count= - per-vdev iostat sample or interval rates (live mode only)
 pub async fn perf_vdev_iostat(
     State(state): State<AppState>,
-    Query(params): Query<PerfVdevIostatQuery>,
+    ApiQuery(params): ApiQuery<PerfVdevIostatQuery>,
 ) -> ApiResult {
     let config = pool_open_config(&state);
     if matches!(config.mode, crate::PoolOpenMode::Offline) {
@@ -1141,15 +2157,25 @@ pub async fn perf_vdev_iostat(
         ));
     }
 
+    // Interval mode: `count` samples `interval_ms` apart; rates come from the
+    // last one.
+    let interval = params.interval_ms.map(|interval_ms| {
+        let interval_ms =
+            interval_ms.clamp(VDEV_IOSTAT_MIN_INTERVAL_MS, VDEV_IOSTAT_MAX_INTERVAL_MS);
+        let count = params.count.unwrap_or(2).clamp(2, VDEV_IOSTAT_MAX_SAMPLES);
+        (interval_ms, count)
+    });
+
     let pool_name = pool.to_string();
     let output = tokio::task::spawn_blocking(move || {
         let mut command = host_cli_command("zpool");
-        command
-            .arg("iostat")
-            .arg("-vH")
-            .arg("-p")
-            .arg(&pool_name)
-            .output()
+        command.arg("iostat").arg("-vH").arg("-p").arg(&pool_name);
+        if let Some((interval_ms, count)) = interval {
+            command
+                .arg(format!("{}.{:03}", interval_ms / 1000, interval_ms % 1000))
+                .arg(count.to_string());
+        }
+        command.output()
     })
     .await
     .map_err(|err| {
@@ -1189,17 +2215,43 @@ pub async fn perf_vdev_iostat(
         .map(|duration| duration.as_secs())
         .unwrap_or(0);
 
+    let Some((interval_ms, count)) = interval else {
+        return Ok(Json(json!({
+            "pool": pool,
+            "sampled_at_unix_sec": sampled_at_unix_sec,
+            "rows": rows,
+        })));
+    };
+
+    let samples = split_vdev_iostat_samples(rows, pool);
+    let sample_count = samples.len();
+    if sample_count < 2 {
+        return Err(api_error(
+            StatusCode::BAD_GATEWAY,
+            format!(
+                "zpool iostat returned {} sample(s); expected at least 2",
+                sample_count
+            ),
+        ));
+    }
+    let last = samples.into_iter().next_back().unwrap_or_default();
+    let rates: Vec<Value> = last.iter().map(vdev_iostat_rate).collect();
+
     Ok(Json(json!({
         "pool": pool,
         "sampled_at_unix_sec": sampled_at_unix_sec,
-        "rows": rows,
+        "interval_ms": interval_ms,
+        "count": count,
+        "samples": sample_count,
+        "rates": rates,
+        "rows": last,
     })))
 }
 
 /// GET /api/perf/txg?pool= - txg runtime indicators (live mode only)
 pub async fn perf_txg(
     State(state): State<AppState>,
-    Query(params): Query<PerfTxgQuery>,
+    ApiQuery(params): ApiQuery<PerfTxgQuery>,
 ) -> ApiResult {
     let config = pool_open_config(&state);
     if matches!(config.mode, crate::PoolOpenMode::Offline) {
@@ -1282,21 +2334,136 @@ pub async fn pool_dedup_summary(
     })))
 }
 
-/// GET /api/pools/:pool/space-amplification - logical vs physical usage hints
-pub async fn pool_space_amplification(
-    State(state): State<AppState>,
-    Path(pool): Path<String>,
-) -> ApiResult {
-    let config = pool_open_config(&state);
-    if matches!(config.mode, crate::PoolOpenMode::Offline) {
-        return Err(api_error(
-            StatusCode::BAD_REQUEST,
-            "space amplification is unavailable in offline mode",
-        ));
+/// Run `zpool status -p` for one pool.
+async fn run_zpool_status(pool: &str) -> Result<String, ApiError> {
+    let pool_name = pool.to_string();
+    let output = tokio::task::spawn_blocking(move || {
+        let mut command = host_cli_command("zpool");
+        command.arg("status").arg("-p").arg(&pool_name).output()
+    })
+    .await
+    .map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to collect zpool status: {}", err),
+        )
+    })?
+    .map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to execute zpool status: {}", err),
+        )
+    })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.trim().is_empty() {
+            format!("zpool status exited with {}", output.status)
+        } else {
+            stderr.trim().to_string()
+        };
+        return Err(api_error(StatusCode::BAD_GATEWAY, message));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+const ZPOOL_STATUS_KEYS: &[&str] = &[
+    "pool",
+    "id",
+    "state",
+    "status",
+    "action",
+    "see",
+    "scan",
+    "remove",
+    "checkpoint",
+    "config",
+    "errors",
+];
+
+/// Health rollup from `zpool status -p`: pool `state`, per-state vdev counts
+/// (every config row below the pool row, spares and cache included), error
+/// totals over all rows, and the free-text `status`/`action`/`scan`/`errors`
+/// messages with continuation lines joined.
+fn parse_zpool_status_health(output: &str) -> Value {
+    let mut fields: BTreeMap<&str, String> = BTreeMap::new();
+    let mut current: Option<&str> = None;
+    let mut seen_pool_row = false;
+    let mut vdev_states: BTreeMap<String, u64> = BTreeMap::new();
+    let (mut read_errors, mut write_errors, mut cksum_errors) = (0u64, 0u64, 0u64);
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let key = trimmed.split_once(':').and_then(|(key, rest)| {
+            ZPOOL_STATUS_KEYS
+                .iter()
+                .find(|known| **known == key)
+                .map(|known| (*known, rest.trim()))
+        });
+        if let Some((key, rest)) = key {
+            current = Some(key);
+            fields.insert(key, rest.to_string());
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match current {
+            Some("config") => {
+                let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+                if tokens.len() < 2 || tokens[0] == "NAME" {
+                    continue;
+                }
+                let counters: Vec<u64> = tokens
+                    .iter()
+                    .skip(2)
+                    .take(3)
+                    .map_while(|raw| raw.parse().ok())
+                    .collect();
+                if counters.len() == 3 {
+                    read_errors += counters[0];
+                    write_errors += counters[1];
+                    cksum_errors += counters[2];
+                }
+                if seen_pool_row {
+                    *vdev_states.entry(tokens[1].to_string()).or_insert(0) += 1;
+                } else {
+                    seen_pool_row = true;
+                }
+            }
+            Some(key) => {
+                if let Some(text) = fields.get_mut(key) {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(trimmed);
+                }
+            }
+            None => {}
+        }
     }
 
-    let pool_name_for_zpool = pool.clone();
-    let zpool_output = tokio::task::spawn_blocking(move || {
+    let text = |key: &str| fields.get(key).filter(|value| !value.is_empty()).cloned();
+    json!({
+        "source": "zpool status",
+        "state": text("state"),
+        "vdev_states": vdev_states,
+        "read_errors": read_errors,
+        "write_errors": write_errors,
+        "cksum_errors": cksum_errors,
+        "status": text("status"),
+        "action": text("action"),
+        "scan": text("scan"),
+        "errors": text("errors"),
+    })
+}
+
+/// Run `zpool list -H -p -o size,alloc,free,frag,dedupratio` for one pool.
+async fn run_zpool_space_list(pool: &str) -> Result<String, ApiError> {
+    let pool_name = pool.to_string();
+    let output = tokio::task::spawn_blocking(move || {
         let mut command = host_cli_command("zpool");
         command
             .arg("list")
@@ -1304,7 +2471,7 @@ pub async fn pool_space_amplification(
             .arg("-p")
             .arg("-o")
             .arg("size,alloc,free,frag,dedupratio")
-            .arg(&pool_name_for_zpool)
+            .arg(&pool_name)
             .output()
     })
     .await
@@ -1321,17 +2488,33 @@ pub async fn pool_space_amplification(
         )
     })?;
 
-    if !zpool_output.status.success() {
-        let stderr = String::from_utf8_lossy(&zpool_output.stderr);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
         let message = if stderr.trim().is_empty() {
-            format!("zpool list exited with {}", zpool_output.status)
+            format!("zpool list exited with {}", output.status)
         } else {
             stderr.trim().to_string()
         };
         return Err(api_error(StatusCode::BAD_GATEWAY, message));
     }
 
-    let pool_summary_raw = String::from_utf8_lossy(&zpool_output.stdout).to_string();
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// GET /api/pools/:pool/space-amplification - logical vs physical usage hints
+pub async fn pool_space_amplification(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+) -> ApiResult {
+    let config = pool_open_config(&state);
+    if matches!(config.mode, crate::PoolOpenMode::Offline) {
+        return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "space amplification is unavailable in offline mode",
+        ));
+    }
+
+    let pool_summary_raw = run_zpool_space_list(&pool).await?;
     let (size_bytes, allocated_bytes, free_bytes, frag_percent, dedup_ratio) =
         parse_zpool_space_summary(&pool_summary_raw).ok_or_else(|| {
             api_error(
@@ -1483,30 +2666,171 @@ pub async fn set_mode(
     }
 
     if changed {
-        let mut pool_guard = state.pool.lock().unwrap();
-        if let Some(old) = pool_guard.take() {
-            crate::ffi::pool_close(old.ptr);
-        }
+        // Requests still holding the old handle keep it open until they finish.
+        state.pool.lock().unwrap().take();
+        bump_pool_generation();
+        state.dataset_catalog.lock().unwrap().take();
+        state.txg_time_index.lock().unwrap().take();
+        state.pool_timeline.lock().unwrap().take();
+        state.known_names.lock().unwrap().take();
+        state.response_cache.clear();
     }
 
     let config = pool_open_config(&state);
     Ok(Json(build_mode_payload(&config)))
 }
 
+const SESSION_MARKS_MAX: usize = 10_000;
+const SESSION_MARK_MAX_TEXT: usize = 256;
+
+static NEXT_SESSION_MARK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// An in-memory navigation bookmark; lives until the process exits.
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionMark {
+    pub id: u64,
+    pub pool: String,
+    pub label: String,
+    pub kind: String,
+    #[serde(rename = "ref")]
+    pub reference: Value,
+    pub created_unix_sec: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSessionMarkRequest {
+    pub pool: String,
+    pub label: String,
+    pub kind: String,
+    #[serde(rename = "ref")]
+    pub reference: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionMarksQuery {
+    pub pool: Option<String>,
+}
+
+fn invalid_session_mark(message: String) -> ApiError {
+    api_error_with(
+        StatusCode::BAD_REQUEST,
+        "INVALID_MARK",
+        message,
+        Some(format!(
+            "Send non-empty `pool`, `label`, and `kind` of at most {SESSION_MARK_MAX_TEXT} characters."
+        )),
+        true,
+    )
+}
+
+fn validate_session_mark_text(field: &str, value: &str) -> Result<String, ApiError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(invalid_session_mark(format!("`{field}` must not be empty")));
+    }
+    if value.chars().count() > SESSION_MARK_MAX_TEXT {
+        return Err(invalid_session_mark(format!(
+            "`{field}` is longer than {SESSION_MARK_MAX_TEXT} characters"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// GET /api/session/marks?pool=
+pub async fn list_session_marks(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<SessionMarksQuery>,
+) -> ApiResult {
+    let marks = state.session_marks.lock().unwrap();
+    let marks: Vec<&SessionMark> = marks
+        .iter()
+        .filter(|mark| params.pool.as_deref().is_none_or(|pool| mark.pool == pool))
+        .collect();
+    Ok(Json(json!({
+        "pool": params.pool,
+        "count": marks.len(),
+        "marks": marks,
+    })))
+}
+
+/// POST /api/session/marks
+pub async fn create_session_mark(
+    State(state): State<AppState>,
+    Json(request): Json<CreateSessionMarkRequest>,
+) -> ApiResult {
+    let pool = validate_session_mark_text("pool", &request.pool)?;
+    let label = validate_session_mark_text("label", &request.label)?;
+    let kind = validate_session_mark_text("kind", &request.kind)?;
+    if request.reference.is_null() {
+        return Err(invalid_session_mark("`ref` must not be null".to_string()));
+    }
+
+    let mut marks = state.session_marks.lock().unwrap();
+    if marks.len() >= SESSION_MARKS_MAX {
+        return Err(api_error_with(
+            StatusCode::CONFLICT,
+            "TOO_MANY_MARKS",
+            format!("session already holds {SESSION_MARKS_MAX} marks"),
+            Some("Delete marks you no longer need.".to_string()),
+            true,
+        ));
+    }
+    let mark = SessionMark {
+        id: NEXT_SESSION_MARK_ID.fetch_add(1, Ordering::Relaxed),
+        pool,
+        label,
+        kind,
+        reference: request.reference,
+        created_unix_sec: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+    marks.push(mark.clone());
+    Ok(Json(json!(mark)))
+}
+
+/// DELETE /api/session/marks/:id
+pub async fn delete_session_mark(State(state): State<AppState>, Path(id): Path<u64>) -> ApiResult {
+    let mut marks = state.session_marks.lock().unwrap();
+    let Some(index) = marks.iter().position(|mark| mark.id == id) else {
+        return Err(api_error_with(
+            StatusCode::NOT_FOUND,
+            "MARK_NOT_FOUND",
+            format!("no session mark with id {id}"),
+            None,
+            true,
+        ));
+    };
+    let mark = marks.remove(index);
+    Ok(Json(json!({ "deleted": true, "mark": mark })))
+}
+
 /// GET /api/pools - List all imported pools
-pub async fn list_pools(State(state): State<AppState>) -> ApiResult {
+pub async fn list_pools(State(state): State<AppState>) -> Result<Response<Body>, ApiError> {
     let pool_open = pool_open_config(&state);
 
-    if matches!(pool_open.mode, crate::PoolOpenMode::Offline)
-        && !pool_open.offline_pool_names.is_empty()
-    {
+    if matches!(pool_open.mode, crate::PoolOpenMode::Offline) {
+        // Offline mode only serves configured names. The FFI lists imported
+        // pools, which offline mode does not open, so never fall back to it.
+        // An empty list explains itself in `X-Zfs-Note`; the body stays an array.
+        if pool_open.offline_pool_names.is_empty() {
+            return Ok((
+                [(
+                    HeaderName::from_static("x-zfs-note"),
+                    HeaderValue::from_static(OFFLINE_NO_POOLS_NOTE),
+                )],
+                Json(json!([])),
+            )
+                .into_response());
+        }
         let pools = pool_open
             .offline_pool_names
             .iter()
             .cloned()
             .map(Value::String)
             .collect::<Vec<_>>();
-        return Ok(Json(Value::Array(pools)));
+        return Ok(Json(Value::Array(pools)).into_response());
     }
 
     let result = crate::ffi::list_pools();
@@ -1525,23 +2849,72 @@ pub async fn list_pools(State(state): State<AppState>) -> ApiResult {
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
     let value = parse_json_value(json_str)?;
 
-    Ok(Json(value))
+    Ok(Json(value).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DatasetListQuery {
+    #[serde(rename = "type")]
+    pub type_filter: Option<String>,
+}
+
+/// Parse `?type=`; `None` means no filtering.
+fn parse_dataset_type_filter(raw: Option<&str>) -> Result<Option<&'static str>, ApiError> {
+    match raw
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("all") => Ok(None),
+        Some("filesystem") => Ok(Some("filesystem")),
+        Some("volume") => Ok(Some("volume")),
+        Some("snapshot") => Ok(Some("snapshot")),
+        Some(other) => Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_QUERY",
+            format!("invalid dataset type '{other}'"),
+            Some("Use type=filesystem, volume, snapshot, or all.".to_string()),
+            true,
+        )),
+    }
+}
+
+fn filter_dataset_catalog(catalog: Value, type_filter: Option<&str>) -> Value {
+    let Some(type_filter) = type_filter else {
+        return catalog;
+    };
+    match catalog {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .filter(|item| item["type"].as_str() == Some(type_filter))
+                .collect(),
+        ),
+        other => other,
+    }
 }
 
-/// GET /api/pools/:pool/datasets
+/// GET /api/pools/:pool/datasets?type=filesystem|volume|snapshot|all
 pub async fn list_pool_datasets(
     State(state): State<AppState>,
     Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<DatasetListQuery>,
 ) -> ApiResult {
+    let type_filter = parse_dataset_type_filter(params.type_filter.as_deref())?;
+    let Json(catalog) = load_pool_datasets(&state, &pool).await?;
+    Ok(Json(filter_dataset_catalog(catalog, type_filter)))
+}
+
+async fn load_pool_datasets(state: &AppState, pool: &str) -> ApiResult {
     let fallback_reason = {
-        let pool_ptr = ensure_pool(&state, &pool)?;
+        let pool_handle = ensure_pool(state, pool)?;
+        let pool_ptr = pool_handle.ptr;
         let result = crate::ffi::pool_datasets(pool_ptr);
         if result.is_ok() {
             return json_from_result(result);
         }
 
         let err_msg = result.error_msg().unwrap_or("Unknown error").to_string();
-        let pool_open = pool_open_config(&state);
+        let pool_open = pool_open_config(state);
         let libzfs_unavailable = err_msg.contains("Failed to initialize the libzfs library")
             || err_msg.contains("failed to open dataset root");
         if !(matches!(pool_open.mode, crate::PoolOpenMode::Offline) && libzfs_unavailable) {
@@ -1558,10 +2931,14 @@ pub async fn list_pool_datasets(
     );
     let tree = dataset_tree(
         State(state.clone()),
-        Path(pool.clone()),
-        Query(DatasetTreeQuery {
+        Path(pool.to_string()),
+        ApiQuery(DatasetTreeQuery {
             depth: Some(64),
             limit: Some(100_000),
+            dir: None,
+            child_cursor: None,
+            child_limit: None,
+            strict: None,
         }),
     )
     .await?;
@@ -1574,1113 +2951,1859 @@ pub async fn list_pool_datasets(
 
 /// GET /api/pools/:pool/summary
 pub async fn pool_summary(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::pool_summary(pool_ptr);
-    json_from_result(result)
-}
+    let Json(mut value) = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        json_from_result(crate::ffi::pool_summary(pool_ptr))?
+    };
 
-#[derive(Debug, Deserialize)]
-pub struct PoolErrorsQuery {
-    pub cursor: Option<u64>,
-    pub limit: Option<u64>,
-    pub resolve_paths: Option<bool>,
+    // Live pools get the CLI's view (current error counters and the
+    // status/action text); offline opens keep the vdev-tree rollup.
+    if matches!(pool_open_config(&state).mode, crate::PoolOpenMode::Live) {
+        match run_zpool_status(&pool).await {
+            Ok(output) => value["health"] = parse_zpool_status_health(&output),
+            Err((_, Json(body))) => {
+                value["health"]["cli_error"] = body["message"].clone();
+            }
+        }
+    }
+
+    let compatibility = &value["compatibility"];
+    if compatibility["supported"] == Value::Bool(false) {
+        tracing::warn!(
+            "pool {pool} is not fully supported by the linked libzpool: {}",
+            compatibility["warning"]
+                .as_str()
+                .unwrap_or("unsupported on-disk format")
+        );
+    }
+    if value["byte_order"] == "swapped" {
+        tracing::warn!("pool {pool} was written on an opposite-endian host");
+    }
+    Ok(Json(value))
 }
 
-/// GET /api/pools/:pool/errors?cursor=&limit=&resolve_paths=
-pub async fn pool_errors(
+/// POST /api/pools/:pool/refresh
+pub async fn refresh_pool(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
+    open_pool(&state, &pool, true)?;
+    tracing::info!("reopened pool {pool} and dropped its caches");
+    pool_summary(State(state), Path(pool)).await
+}
+
+/// GET /api/pools/:pool/alloc-classes
+pub async fn pool_alloc_classes(
     State(state): State<AppState>,
     Path(pool): Path<String>,
-    Query(params): Query<PoolErrorsQuery>,
 ) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
-    let resolve_paths = params.resolve_paths.unwrap_or(true);
-    let result = crate::ffi::pool_errors(pool_ptr, cursor, limit, resolve_paths);
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::pool_alloc_classes(pool_ptr);
     json_from_result(result)
 }
 
-#[derive(Debug, Deserialize)]
-pub struct MosListQuery {
-    #[serde(rename = "type")]
-    pub type_filter: Option<i32>,
-    pub start: Option<u64>,
-    pub limit: Option<u64>,
+/// GET /api/pools/:pool/checkpoint
+pub async fn pool_checkpoint(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::pool_checkpoint(pool_ptr);
+    json_from_result(result)
 }
 
-fn parse_json_value(json_str: &str) -> Result<Value, ApiError> {
-    serde_json::from_str(json_str).map_err(|e| {
-        tracing::error!("Failed to parse JSON: {}", e);
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("JSON parse error: {}", e),
-        )
-    })
+/// GET /api/pools/:pool/version-info
+pub async fn pool_version_info(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::pool_version_info(pool_ptr);
+    json_from_result(result)
 }
 
-fn normalize_limit(limit: Option<u64>) -> u64 {
-    limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+/// Active (nonzero refcount) features from the `features_for_read` and
+/// `features_for_write` refcount ZAPs, largest refcount first. `holders`
+/// maps feature GUIDs to the datasets that record them; features with no
+/// recorded holder get `null`.
+fn build_active_features(
+    for_read: &[Value],
+    for_write: &[Value],
+    enabled_txg: &[Value],
+    descriptions: &[Value],
+    holders: &HashMap<String, Vec<Value>>,
+) -> Vec<Value> {
+    let lookup = |entries: &[Value], guid: &str| -> Value {
+        entries
+            .iter()
+            .find(|entry| entry["name"].as_str() == Some(guid))
+            .map(|entry| entry["value_u64"].clone())
+            .unwrap_or(Value::Null)
+    };
+    let description = |guid: &str| -> Value {
+        descriptions
+            .iter()
+            .find(|entry| entry["name"].as_str() == Some(guid))
+            .map(|entry| entry["value_preview"].clone())
+            .unwrap_or(Value::Null)
+    };
+
+    let mut features: Vec<Value> = [(for_read, true), (for_write, false)]
+        .into_iter()
+        .flat_map(|(entries, required_for_read)| {
+            entries.iter().filter_map(move |entry| {
+                let guid = entry["name"].as_str()?;
+                let refcount = entry["value_u64"].as_u64().filter(|count| *count > 0)?;
+                Some((guid, refcount, required_for_read))
+            })
+        })
+        .map(|(guid, refcount, required_for_read)| {
+            json!({
+                "guid": guid,
+                "state": "active",
+                "refcount": refcount,
+                "required_for_read": required_for_read,
+                "enabled_txg": lookup(enabled_txg, guid),
+                "description": description(guid),
+                "holders": holders.get(guid),
+            })
+        })
+        .collect();
+    features.sort_by(|a, b| {
+        let refcount = |value: &Value| value["refcount"].as_u64().unwrap_or(0);
+        refcount(b)
+            .cmp(&refcount(a))
+            .then_with(|| a["guid"].as_str().cmp(&b["guid"].as_str()))
+    });
+    features
+}
+
+#[derive(Default)]
+struct FeatureHolderScan {
+    /// Datasets recording each feature GUID, as `{ name, dsobj }`.
+    holders: HashMap<String, Vec<Value>>,
+    scanned: usize,
+    truncated: bool,
+    /// DSL dirs that could not be read, as `{ name, dir_obj, error }`.
+    errors: Vec<Value>,
+}
+
+impl FeatureHolderScan {
+    /// Record the feature GUIDs stored in one dataset object's ZAP.
+    fn record_dataset(&mut self, pool_ptr: *mut crate::ffi::zdx_pool_t, name: &str, dsobj: u64) {
+        self.scanned += 1;
+        // Datasets that never activated a per-dataset feature are not
+        // zapified, so a failed read just means "nothing recorded".
+        let Ok(entries) = read_mos_zap(pool_ptr, dsobj) else {
+            return;
+        };
+        for entry in entries {
+            let Some(key) = entry["name"].as_str() else {
+                continue;
+            };
+            if key.contains(':') {
+                self.holders
+                    .entry(key.to_string())
+                    .or_default()
+                    .push(json!({ "name": name, "dsobj": dsobj }));
+            }
+        }
+    }
+
+    fn record_error(&mut self, name: &str, dir_obj: u64, error: String) {
+        self.errors
+            .push(json!({ "name": name, "dir_obj": dir_obj, "error": error }));
+    }
 }
 
-fn normalize_cursor_limit(cursor: Option<u64>, limit: Option<u64>) -> (u64, u64) {
-    (cursor.unwrap_or(0), normalize_limit(limit))
+fn api_error_message((_, Json(body)): ApiError) -> String {
+    body["message"]
+        .as_str()
+        .unwrap_or("Unknown error")
+        .to_string()
 }
 
-fn append_dataset_catalog_from_tree(node: &Value, prefix: Option<&str>, out: &mut Vec<Value>) {
-    let Some(name) = node["name"].as_str() else {
-        return;
-    };
+/// One pending step of a feature-holder scan.
+enum FeatureHolderStep {
+    /// A DSL dir still to expand into its head, snapshots, and children.
+    Dir(String, u64),
+    /// A head dataset or snapshot object still to read.
+    Dataset(String, u64),
+}
 
-    let full_name = if let Some(parent) = prefix {
-        if parent.is_empty() {
-            name.to_string()
-        } else {
-            format!("{parent}/{name}")
+/// Expand one DSL dir: its head dataset and snapshots are pushed so they are
+/// read before its children, keeping the walk depth-first in name order.
+fn expand_feature_holder_dir(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    scan: &mut FeatureHolderScan,
+    stack: &mut Vec<FeatureHolderStep>,
+    name: String,
+    dir_obj: u64,
+) {
+    match fetch_dsl_dir_children(pool_ptr, dir_obj) {
+        Ok(children) => {
+            let mut children: Vec<_> = parse_dsl_children(&children)
+                .into_iter()
+                .filter(|(child_name, _)| !is_special_dsl_dir(child_name))
+                .collect();
+            children.sort();
+            for (child_name, child_dir) in children.into_iter().rev() {
+                stack.push(FeatureHolderStep::Dir(
+                    format!("{name}/{child_name}"),
+                    child_dir,
+                ));
+            }
         }
-    } else {
-        name.to_string()
-    };
-
-    let has_head = node["head_dataset_obj"]
-        .as_u64()
-        .map(|value| value != 0)
-        .unwrap_or(false);
-    if has_head && !name.starts_with('$') {
-        out.push(json!({
-            "name": full_name,
-            "type": "filesystem",
-            "mountpoint": null,
-            "mounted": null,
-        }));
+        Err(err) => scan.record_error(&name, dir_obj, api_error_message(err)),
     }
 
-    if let Some(children) = node["children"].as_array() {
-        for child in children {
-            append_dataset_catalog_from_tree(child, Some(&full_name), out);
+    let head = match fetch_dsl_dir_head(pool_ptr, dir_obj) {
+        Ok(head) => head,
+        Err(err) => {
+            scan.record_error(&name, dir_obj, api_error_message(err));
+            return;
+        }
+    };
+    let Some(dsobj) = head["head_dataset_obj"].as_u64().filter(|obj| *obj != 0) else {
+        return;
+    };
+    match ffi_result_value(crate::ffi::dataset_snapshots(pool_ptr, dir_obj)).and_then(|value| {
+        serde_json::from_value::<SnapshotListPayload>(value)
+            .map_err(|err| format!("failed to parse snapshots payload: {err}"))
+    }) {
+        Ok(snapshots) => {
+            for snapshot in snapshots.entries.into_iter().rev() {
+                stack.push(FeatureHolderStep::Dataset(
+                    format!("{name}@{}", snapshot.name),
+                    snapshot.dsobj,
+                ));
+            }
+        }
+        Err(err) => scan.record_error(&name, dir_obj, err),
+    }
+    stack.push(FeatureHolderStep::Dataset(name, dsobj));
+}
+
+/// Per-dataset feature activations: walk the DSL tree (at most `max_datasets`
+/// head datasets and snapshots) and read each zapified dataset object, whose
+/// keys include the GUIDs of features it has activated. A DSL dir that cannot
+/// be read is recorded in `errors` and the walk carries on. The walk runs in
+/// batches of FFI reads, re-running `ensure_pool` for each batch and yielding
+/// at a [`scan_checkpoint`] between them.
+async fn scan_feature_holders(
+    state: &AppState,
+    pool: &str,
+    root_dir: u64,
+    max_datasets: usize,
+) -> Result<FeatureHolderScan, ApiError> {
+    let mut scan = FeatureHolderScan::default();
+    let mut stack = vec![FeatureHolderStep::Dir(pool.to_string(), root_dir)];
+    while !stack.is_empty() {
+        {
+            let pool_handle = ensure_pool(state, pool)?;
+            for _ in 0..FEATURE_HOLDER_SCAN_BATCH {
+                match stack.pop() {
+                    None => break,
+                    Some(FeatureHolderStep::Dir(name, dir_obj)) => expand_feature_holder_dir(
+                        pool_handle.ptr,
+                        &mut scan,
+                        &mut stack,
+                        name,
+                        dir_obj,
+                    ),
+                    Some(FeatureHolderStep::Dataset(name, dsobj)) => {
+                        if scan.scanned >= max_datasets {
+                            scan.truncated = true;
+                            return Ok(scan);
+                        }
+                        scan.record_dataset(pool_handle.ptr, &name, dsobj);
+                    }
+                }
+            }
         }
+        scan_checkpoint().await;
     }
+    Ok(scan)
 }
 
-fn normalize_spacemap_limit(limit: Option<u64>) -> u64 {
-    limit
-        .unwrap_or(SPACEMAP_DEFAULT_LIMIT)
-        .clamp(1, SPACEMAP_MAX_LIMIT)
+/// GET /api/pools/:pool/features/active
+pub async fn pool_active_features(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+) -> ApiResult {
+    let (for_read, for_write, enabled_txg, descriptions, root_dir) = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        let directory = read_object_directory(pool_ptr)?;
+        let zap_named = |name: &str| -> Result<Vec<Value>, ApiError> {
+            match directory
+                .iter()
+                .find(|entry| entry["name"].as_str() == Some(name))
+                .and_then(|entry| entry["target_obj"].as_u64())
+                .filter(|objid| *objid != 0)
+            {
+                Some(objid) => read_mos_zap(pool_ptr, objid),
+                // Pre-feature-flags pools have none of these objects.
+                None => Ok(Vec::new()),
+            }
+        };
+        (
+            zap_named("features_for_read")?,
+            zap_named("features_for_write")?,
+            zap_named("feature_enabled_txg")?,
+            zap_named("feature_descriptions")?,
+            resolve_pool_root_dir_obj(pool_ptr, &pool)?,
+        )
+    };
+    let scan = scan_feature_holders(&state, &pool, root_dir, FEATURE_HOLDER_MAX_DATASETS).await?;
+
+    let features = build_active_features(
+        &for_read,
+        &for_write,
+        &enabled_txg,
+        &descriptions,
+        &scan.holders,
+    );
+    Ok(Json(json!({
+        "count": features.len(),
+        "features": features,
+        "holder_scan": {
+            "datasets_scanned": scan.scanned,
+            "max_datasets": FEATURE_HOLDER_MAX_DATASETS,
+            "truncated": scan.truncated,
+            "errors": scan.errors,
+        },
+    })))
 }
 
-fn normalize_spacemap_cursor_limit(cursor: Option<u64>, limit: Option<u64>) -> (u64, u64) {
-    (cursor.unwrap_or(0), normalize_spacemap_limit(limit))
-}
+/// GET /api/pools/:pool/l2arc
+pub async fn pool_l2arc(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
+    let Json(mut value) = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        json_from_result(crate::ffi::pool_l2arc(pool_ptr))?
+    };
 
-fn normalize_spacemap_bins_limit(limit: Option<u64>) -> u64 {
-    limit
-        .unwrap_or(SPACEMAP_BINS_DEFAULT_LIMIT)
-        .clamp(1, SPACEMAP_BINS_MAX_LIMIT)
-}
+    // Live hosts add the global L2ARC counters (all cache devices combined)
+    // when arcstats is readable; the device list stands on its own otherwise.
+    value["arcstats"] = Value::Null;
+    if matches!(pool_open_config(&state).mode, crate::PoolOpenMode::Live) {
+        if let Ok(contents) = std::fs::read_to_string(ARCSTATS_PATH) {
+            let counters = parse_arcstats(&contents);
+            if !counters.is_empty() {
+                let mut l2arc = build_arc_payload(&counters)["l2arc"].clone();
+                let rebuild: BTreeMap<&str, u64> = counters
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        key.strip_prefix("l2_rebuild_").map(|name| (name, *value))
+                    })
+                    .collect();
+                l2arc["rebuild"] = json!(rebuild);
+                value["arcstats"] = l2arc;
+            }
+        }
+    }
 
-fn normalize_spacemap_bin_size(bin_size: Option<u64>) -> u64 {
-    bin_size
-        .unwrap_or(SPACEMAP_BINS_DEFAULT_SIZE)
-        .clamp(SPACEMAP_BINS_MIN_SIZE, SPACEMAP_BINS_MAX_SIZE)
+    Ok(Json(value))
 }
 
-fn normalize_spacemap_bins_cursor_limit(cursor: Option<u64>, limit: Option<u64>) -> (u64, u64) {
-    (cursor.unwrap_or(0), normalize_spacemap_bins_limit(limit))
+#[derive(Debug, Deserialize)]
+pub struct PoolErrorsQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+    pub resolve_paths: Option<bool>,
+    pub since_txg: Option<u64>,
 }
 
-fn normalize_block_tree_depth(depth: Option<u64>) -> u64 {
-    depth
-        .unwrap_or(BLOCK_TREE_DEFAULT_DEPTH)
-        .min(BLOCK_TREE_MAX_DEPTH)
+/// Wrap a sub-result as `{ ok, data }` / `{ ok, status, error }` for aggregate views.
+fn overview_section(result: Result<Value, ApiError>) -> Value {
+    match result {
+        Ok(data) => json!({ "ok": true, "data": data }),
+        Err((status, Json(error))) => json!({
+            "ok": false,
+            "status": status.as_u16(),
+            "error": error,
+        }),
+    }
 }
 
-fn normalize_block_tree_nodes(max_nodes: Option<u64>) -> u64 {
-    max_nodes
-        .unwrap_or(BLOCK_TREE_DEFAULT_NODES)
-        .clamp(1, BLOCK_TREE_MAX_NODES)
+fn rollup_vdev_node(node: &Value, rollup: &mut VdevRollup) {
+    let Some(map) = node.as_object() else {
+        return;
+    };
+    rollup.total += 1;
+    let vdev_type = map
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+        .to_string();
+    *rollup.by_type.entry(vdev_type).or_insert(0) += 1;
+
+    let flagged = |key: &str| map.get(key).and_then(Value::as_u64).unwrap_or(0) != 0;
+    match map.get("children").and_then(Value::as_array) {
+        Some(children) if !children.is_empty() => {
+            for child in children {
+                rollup_vdev_node(child, rollup);
+            }
+        }
+        _ => {
+            rollup.leaves += 1;
+            if flagged("faulted") {
+                rollup.faulted += 1;
+            } else if flagged("removed") {
+                rollup.removed += 1;
+            } else if flagged("offline") {
+                rollup.offline += 1;
+            } else if flagged("degraded") {
+                rollup.degraded += 1;
+            }
+        }
+    }
 }
 
-fn normalize_objset_data_limit(limit: Option<u64>) -> u64 {
-    limit
-        .unwrap_or(OBJSET_DATA_DEFAULT_LIMIT)
-        .clamp(1, OBJSET_DATA_MAX_LIMIT)
+#[derive(Debug, Default, Serialize)]
+struct VdevRollup {
+    total: u64,
+    leaves: u64,
+    faulted: u64,
+    degraded: u64,
+    offline: u64,
+    removed: u64,
+    by_type: std::collections::BTreeMap<String, u64>,
 }
 
-fn parse_spacemap_op_filter(op: Option<&str>) -> Result<i32, ApiError> {
-    let normalized = op.unwrap_or("all").trim().to_ascii_lowercase();
-    match normalized.as_str() {
-        "" | "all" => Ok(0),
-        "alloc" => Ok(1),
-        "free" => Ok(2),
-        _ => Err(api_error(
-            StatusCode::BAD_REQUEST,
-            format!("invalid op filter '{normalized}'; expected all, alloc, or free"),
-        )),
+impl VdevRollup {
+    fn unhealthy_leaves(&self) -> u64 {
+        self.faulted + self.degraded + self.offline + self.removed
     }
 }
 
-fn parse_graph_include(include: Option<&str>) -> (bool, bool, bool) {
-    let include = include.unwrap_or("semantic,physical");
-    (
-        include.contains("semantic"),
-        include.contains("physical"),
-        include.contains("zap"),
-    )
+fn summarize_vdev_tree(vdev_tree: &Value) -> Value {
+    let mut rollup = VdevRollup::default();
+    rollup_vdev_node(vdev_tree, &mut rollup);
+    let unhealthy_leaves = rollup.unhealthy_leaves();
+    let mut value = serde_json::to_value(&rollup).unwrap_or(Value::Null);
+    value["unhealthy_leaves"] = json!(unhealthy_leaves);
+    value
 }
 
-fn parse_dsl_children(value: &Value) -> Vec<(String, u64)> {
-    let Some(children) = value["children"].as_array() else {
-        return Vec::new();
-    };
+#[derive(Debug, Deserialize)]
+pub struct TimelineQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+}
 
-    children
-        .iter()
-        .filter_map(|child| {
-            let child_objid = child["dir_objid"].as_u64()?;
-            if child_objid == 0 {
-                return None;
-            }
-            let child_name = child["name"].as_str().unwrap_or("dataset").to_string();
-            Some((child_name, child_objid))
+fn timeline_event(unix_sec: u64, txg: Option<u64>, kind: &str, detail: String) -> Value {
+    json!({ "unix_sec": unix_sec, "txg": txg, "kind": kind, "detail": detail })
+}
+
+/// One event per distinct uberblock txg across every label in a
+/// `/vdev/{vdev}/labels` payload.
+fn uberblock_timeline_events(labels: &Value) -> Vec<Value> {
+    let mut seen: BTreeMap<u64, (u64, bool)> = BTreeMap::new();
+    let uberblocks = labels["devices"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|device| device["labels"].as_array())
+        .flatten()
+        .filter_map(|label| label["uberblocks"].as_array())
+        .flatten();
+    for ub in uberblocks {
+        let (Some(txg), Some(timestamp)) = (ub["txg"].as_u64(), ub["timestamp"].as_u64()) else {
+            continue;
+        };
+        let active = ub["active"].as_bool() == Some(true);
+        let entry = seen.entry(txg).or_insert((timestamp, active));
+        entry.1 |= active;
+    }
+    seen.into_iter()
+        .map(|(txg, (timestamp, active))| {
+            let detail = if active {
+                "active uberblock"
+            } else {
+                "uberblock in label ring"
+            };
+            timeline_event(timestamp, Some(txg), "uberblock", detail.to_string())
         })
         .collect()
 }
 
-fn build_dataset_objset_response(dir_obj: u64, head_obj: u64, objset_value: &Value) -> Value {
-    serde_json::json!({
-        "dsl_dir_obj": dir_obj,
-        "head_dataset_obj": head_obj,
-        "objset_id": objset_value["objset_id"],
-        "rootbp": objset_value["rootbp"]
-    })
+/// `zpool history -i`-style events: user commands keep their command line,
+/// internal events read `<operation> <dataset> <message>`.
+fn history_timeline_events(history: &Value) -> Vec<Value> {
+    history["events"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|record| {
+            let time = record["time"].as_u64()?;
+            let txg = record["txg"].as_u64();
+            if let Some(command) = record["command"].as_str() {
+                return Some(timeline_event(time, txg, "command", command.to_string()));
+            }
+            let text = |key: &str| record[key].as_str().filter(|text| !text.is_empty());
+            let (kind, parts) = match text("internal") {
+                Some(operation) => (
+                    "internal",
+                    [Some(operation), text("dataset"), text("message")],
+                ),
+                None => ("ioctl", [text("ioctl"), text("dataset"), text("message")]),
+            };
+            let detail = parts.into_iter().flatten().collect::<Vec<_>>().join(" ");
+            Some(timeline_event(time, txg, kind, detail))
+        })
+        .collect()
 }
 
-fn json_from_result(result: crate::ffi::ZdxResult) -> ApiResult {
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let err_code = result.error_code();
-        let code_label = pool_open_error_code(err_code);
-        tracing::error!("FFI error: {}", err_msg);
-        return Err(api_error_with(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            code_label,
-            err_msg.to_string(),
-            None,
-            false,
-        ));
-    }
-
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-
-    let value = parse_json_value(json_str)?;
+/// Snapshot creations from a `/snapshots` payload of one dataset.
+fn snapshot_timeline_events(dataset: &str, snapshots: &Value) -> Vec<Value> {
+    snapshots["entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry["name"].as_str()?;
+            Some(timeline_event(
+                entry["creation_time"].as_u64()?,
+                entry["creation_txg"].as_u64(),
+                "snapshot",
+                format!("{dataset}@{name}"),
+            ))
+        })
+        .collect()
+}
 
-    Ok(Json(value))
+/// Oldest first; within one second, txg order with txg-less records last.
+fn sort_timeline(events: &mut [Value]) {
+    events.sort_by_key(|event| {
+        (
+            event["unix_sec"].as_u64().unwrap_or(0),
+            event["txg"].as_u64().unwrap_or(u64::MAX),
+        )
+    });
 }
 
-fn ensure_pool(state: &AppState, pool: &str) -> Result<*mut crate::ffi::zdx_pool_t, ApiError> {
-    let pool_open = pool_open_config(state);
-    let mut guard = state.pool.lock().unwrap();
+/// Every timeline event for one pool, merged and sorted. Building it walks
+/// the dataset tree, labels, history and every dataset's snapshot list, so it
+/// is built once per handle (per catalog TTL in live mode) and paged from.
+pub struct PoolTimeline {
+    pool: String,
+    loaded_at: Instant,
+    events: Vec<Value>,
+    truncated: bool,
+    source_errors: serde_json::Map<String, Value>,
+}
 
-    if let Some(existing) = guard.as_ref() {
-        if existing.name == pool {
-            return Ok(existing.ptr);
+async fn cached_pool_timeline(state: &AppState, pool: &str) -> Result<Arc<PoolTimeline>, ApiError> {
+    let ttl = dataset_catalog_ttl(pool_open_config(state).mode);
+    {
+        let guard = state.pool_timeline.lock().unwrap();
+        if let Some(timeline) = guard.as_ref() {
+            let fresh = ttl.is_none_or(|ttl| timeline.loaded_at.elapsed() < ttl);
+            if timeline.pool == pool && fresh {
+                return Ok(timeline.clone());
+            }
         }
     }
 
-    if let Some(old) = guard.take() {
-        crate::ffi::pool_close(old.ptr);
-    }
+    let mut source_errors = serde_json::Map::new();
+    let mut truncated = false;
 
-    let mode = pool_open.mode;
-    let mode_name = pool_open_mode_name(mode);
-    let handle = match mode {
-        crate::PoolOpenMode::Live => crate::ffi::pool_open(pool),
-        crate::PoolOpenMode::Offline => {
-            crate::ffi::pool_open_offline(pool, pool_open.offline_search_paths.as_deref())
+    let tree = dataset_tree(
+        State(state.clone()),
+        Path(pool.to_string()),
+        ApiQuery(DatasetTreeQuery {
+            depth: Some(64),
+            limit: Some(TIMELINE_MAX_DATASETS),
+            dir: None,
+            child_cursor: None,
+            child_limit: None,
+            strict: None,
+        }),
+    )
+    .await;
+    let mut datasets: Vec<(String, u64)> = Vec::new();
+    match tree {
+        Ok(Json(tree)) => {
+            truncated |= tree["truncated"].as_bool() == Some(true);
+            for_each_tree_dataset(&tree["root"], None, &mut |name, node| {
+                if let Some(dir_obj) = node["dsl_dir_obj"].as_u64() {
+                    datasets.push((name.to_string(), dir_obj));
+                }
+            });
+        }
+        Err((_, Json(body))) => {
+            source_errors.insert("snapshots".to_string(), body["message"].clone());
         }
     }
-    .map_err(|(code, msg)| {
-        let err_code = pool_open_error_code(code);
-        let hint = if matches!(mode, crate::PoolOpenMode::Offline) {
-            offline_pool_open_hint(pool, code)
-        } else if code == libc::EACCES || code == libc::EPERM {
-            Some("Run backend with sudo for live imported pools.".to_string())
-        } else {
-            None
-        };
-
-        let expected_client_error = matches!(mode, crate::PoolOpenMode::Offline)
-            && matches!(
-                libzfs_error_name(code),
-                Some("EZFS_NOENT" | "EZFS_PERM" | "EZFS_ACTIVE_POOL" | "EZFS_CRYPTOFAILED")
-            )
-            || matches!(
-                code,
-                libc::ENOENT | libc::EACCES | libc::EPERM | libc::EEXIST
-            );
 
-        if expected_client_error {
-            tracing::warn!(
-                "Pool open warning for {} (mode={}, code={}): {}",
-                pool,
-                mode_name,
-                err_code,
-                msg
-            );
-        } else {
-            tracing::error!(
-                "Failed to open pool {} (mode={}, code={}): {}",
-                pool,
-                mode_name,
-                err_code,
-                msg
-            );
+    let mut events = Vec::new();
+    {
+        let pool_handle = ensure_pool(state, pool)?;
+        let pool_ptr = pool_handle.ptr;
+        match json_from_result(crate::ffi::vdev_labels(pool_ptr, 0)) {
+            Ok(Json(labels)) => events.extend(uberblock_timeline_events(&labels)),
+            Err((_, Json(body))) => {
+                source_errors.insert("uberblocks".to_string(), body["message"].clone());
+            }
         }
+        match json_from_result(crate::ffi::pool_history(pool_ptr, TIMELINE_MAX_HISTORY)) {
+            Ok(Json(history)) => {
+                truncated |= history["truncated"].as_bool() == Some(true);
+                if let Some(err) = history["history_error"].as_str() {
+                    source_errors.insert("history".to_string(), json!(err));
+                }
+                events.extend(history_timeline_events(&history));
+            }
+            Err((_, Json(body))) => {
+                source_errors.insert("history".to_string(), body["message"].clone());
+            }
+        }
+        for (name, dir_obj) in &datasets {
+            match json_from_result(crate::ffi::dataset_snapshots(pool_ptr, *dir_obj)) {
+                Ok(Json(snapshots)) => events.extend(snapshot_timeline_events(name, &snapshots)),
+                Err((_, Json(body))) => {
+                    source_errors
+                        .entry("snapshots".to_string())
+                        .or_insert_with(|| {
+                            let message = body["message"].as_str().unwrap_or("Unknown error");
+                            json!(format!("{name}: {message}"))
+                        });
+                }
+            }
+        }
+    }
+    sort_timeline(&mut events);
 
-        let status = if expected_client_error {
-            StatusCode::BAD_REQUEST
-        } else {
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-
-        api_error_with(
-            status,
-            err_code,
-            format!("pool open failed ({mode_name}): {msg}"),
-            hint,
-            true,
-        )
-    })?;
-
-    let ptr = handle.ptr;
-    *guard = Some(handle);
-    Ok(ptr)
+    let timeline = Arc::new(PoolTimeline {
+        pool: pool.to_string(),
+        loaded_at: Instant::now(),
+        events,
+        truncated,
+        source_errors,
+    });
+    *state.pool_timeline.lock().unwrap() = Some(timeline.clone());
+    Ok(timeline)
 }
 
-/// GET /api/pools/:pool/mos/objects
-pub async fn mos_list_objects(
+/// GET /api/pools/:pool/timeline?cursor=&limit=
+pub async fn pool_timeline(
     State(state): State<AppState>,
     Path(pool): Path<String>,
-    Query(params): Query<MosListQuery>,
+    ApiQuery(params): ApiQuery<TimelineQuery>,
 ) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-
-    let type_filter = params.type_filter.unwrap_or(-1);
-    let start = params.start.unwrap_or(0);
-    let limit = normalize_limit(params.limit);
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let timeline = cached_pool_timeline(&state, &pool).await?;
 
-    let result = crate::ffi::mos_list_objects(pool_ptr, type_filter, start, limit);
-    json_from_result(result)
+    let total = timeline.events.len() as u64;
+    let start = cursor.min(total);
+    let end = start.saturating_add(limit).min(total);
+    let page = &timeline.events[start as usize..end as usize];
+    Ok(Json(json!({
+        "pool": pool,
+        "cursor": cursor,
+        "limit": limit,
+        "count": page.len(),
+        "total": total,
+        "next": (end < total).then_some(end),
+        "has_more": end < total,
+        "truncated": timeline.truncated,
+        "source_errors": timeline.source_errors,
+        "events": page,
+    })))
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/objects
-pub async fn objset_list_objects(
-    State(state): State<AppState>,
-    Path((pool, objset_id)): Path<(String, u64)>,
-    Query(params): Query<MosListQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
+/// GET /api/pools/:pool/overview - landing-page aggregate with per-section status
+pub async fn pool_overview(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
+    let mode = pool_open_config(&state).mode;
 
-    let type_filter = params.type_filter.unwrap_or(-1);
-    let start = params.start.unwrap_or(0);
-    let limit = normalize_limit(params.limit);
+    let summary = pool_summary(State(state.clone()), Path(pool.clone()))
+        .await
+        .map(|Json(value)| value);
+    let errors = pool_errors(
+        State(state.clone()),
+        Path(pool.clone()),
+        ApiQuery(PoolErrorsQuery {
+            cursor: Some(0),
+            limit: Some(1),
+            resolve_paths: Some(false),
+            since_txg: None,
+        }),
+    )
+    .await
+    .map(|Json(value)| {
+        json!({
+            "error_count": value["error_count"],
+            "approx_entries": value["approx_entries"],
+        })
+    });
+    let vdevs = match &summary {
+        Ok(value) if value["vdev_tree"].is_object() => Ok(summarize_vdev_tree(&value["vdev_tree"])),
+        Ok(_) => Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "pool summary has no vdev_tree",
+        )),
+        Err(err) => Err(err.clone()),
+    };
 
-    let result = crate::ffi::objset_list_objects(pool_ptr, objset_id, type_filter, start, limit);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_objset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+    let error_count = errors
+        .as_ref()
+        .ok()
+        .and_then(|value| value["error_count"].as_u64());
+    let unhealthy_leaves = vdevs
+        .as_ref()
+        .ok()
+        .and_then(|value| value["unhealthy_leaves"].as_u64());
+    let healthy = match (error_count, unhealthy_leaves) {
+        (Some(errors), Some(leaves)) => Some(errors == 0 && leaves == 0),
+        _ => None,
+    };
+
+    let mut payload = json!({
+        "pool": pool,
+        "mode": pool_open_mode_name(mode),
+        "healthy": healthy,
+        "summary": overview_section(summary),
+        "errors": overview_section(errors),
+        "vdevs": overview_section(vdevs),
+    });
+
+    if matches!(mode, crate::PoolOpenMode::Live) {
+        let space = run_zpool_space_list(&pool).await.and_then(|raw| {
+            parse_zpool_space_summary(&raw).ok_or_else(|| {
+                api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "zpool list returned no parseable summary row",
+                )
+            })
+        });
+        let capacity = space.clone().map(|(size, alloc, free, frag, _)| {
+            json!({
+                "size_bytes": size,
+                "allocated_bytes": alloc,
+                "free_bytes": free,
+                "frag_percent": frag,
+                "capacity_ratio": ratio_u64(alloc, size),
+            })
+        });
+        let dedup = space.map(|(_, _, _, _, ratio)| json!({ "dedup_ratio": ratio }));
+        payload["capacity"] = overview_section(capacity);
+        payload["dedup"] = overview_section(dedup);
     }
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
-}
 
-/// GET /api/pools/:pool/obj/:objid
-pub async fn mos_get_object(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::mos_get_object(pool_ptr, objid);
-    json_from_result(result)
+    Ok(Json(payload))
 }
 
-/// GET /api/pools/:pool/obj/:objid/blkptrs
-pub async fn mos_get_blkptrs(
+/// GET /api/pools/:pool/errors?cursor=&limit=&resolve_paths=
+pub async fn pool_errors(
     State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<PoolErrorsQuery>,
 ) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::mos_get_blkptrs(pool_ptr, objid);
-    json_from_result(result)
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let resolve_paths = params.resolve_paths.unwrap_or(true);
+    let since_txg = params.since_txg.unwrap_or(0);
+    let result = crate::ffi::pool_errors(
+        pool_ptr,
+        cursor,
+        fetch_limit(limit),
+        resolve_paths,
+        since_txg,
+    );
+    finish_page(limit, "entries", json_from_result(result))
 }
 
 #[derive(Debug, Deserialize)]
-pub struct BlockTreeQuery {
-    pub max_depth: Option<u64>,
-    pub max_nodes: Option<u64>,
+pub struct PoolErrorsExportQuery {
+    pub resolve_paths: Option<bool>,
+    pub since_txg: Option<u64>,
+}
+
+/// Appends one page of error log entries as NDJSON lines. Returns the next
+/// cursor to fetch, or None once the log is exhausted or `max_entries` lines
+/// have been written with more remaining (a trailing `{"truncated":true}`
+/// line then closes the export).
+fn append_error_export_lines(
+    page: &Value,
+    out: &mut Vec<u8>,
+    written: &mut u64,
+    max_entries: u64,
+) -> Option<u64> {
+    const TRUNCATED_LINE: &[u8] = b"{\"truncated\":true}\n";
+    for entry in page["entries"].as_array().into_iter().flatten() {
+        if *written >= max_entries {
+            out.extend_from_slice(TRUNCATED_LINE);
+            return None;
+        }
+        out.extend_from_slice(entry.to_string().as_bytes());
+        out.push(b'\n');
+        *written += 1;
+    }
+    let next = page["next"].as_u64();
+    if next.is_some() && *written >= max_entries {
+        out.extend_from_slice(TRUNCATED_LINE);
+        return None;
+    }
+    next
 }
 
-/// GET /api/pools/:pool/obj/:objid/block-tree?max_depth=&max_nodes=
-pub async fn mos_block_tree(
+/// GET /api/pools/:pool/errors/export?resolve_paths=&since_txg=
+/// (the whole error log as NDJSON, one entry per line)
+pub async fn pool_errors_export(
     State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-    Query(params): Query<BlockTreeQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let max_depth = normalize_block_tree_depth(params.max_depth);
-    let max_nodes = normalize_block_tree_nodes(params.max_nodes);
-    let result = crate::ffi::mos_block_tree(pool_ptr, objid, max_depth, max_nodes);
-    json_from_result(result)
-}
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<PoolErrorsExportQuery>,
+) -> Result<Response<Body>, ApiError> {
+    let resolve_paths = params.resolve_paths.unwrap_or(true);
+    let since_txg = params.since_txg.unwrap_or(0);
+    let fetch_page = move |pool_ptr, cursor| {
+        crate::ffi::pool_errors(pool_ptr, cursor, MAX_PAGE_LIMIT, resolve_paths, since_txg)
+    };
 
-/// GET /api/pools/:pool/obj/:objid/full
-pub async fn obj_get_full(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::obj_get(pool_ptr, objid);
-    json_from_result(result)
-}
+    // The first page is read up front so open/FFI failures still surface as
+    // regular error envelopes; later pages are streamed from a blocking task.
+    let Json(first_page) = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        json_from_result(fetch_page(pool_ptr, 0))?
+    };
 
-/// GET /api/mos/types
-pub async fn list_dmu_types() -> ApiResult {
-    let result = crate::ffi::list_dmu_types();
-    json_from_result(result)
-}
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_DEPTH);
+    let export_state = state.clone();
+    let export_pool = pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut written = 0u64;
+        let mut page = first_page;
+        loop {
+            let mut chunk = Vec::new();
+            let next = append_error_export_lines(
+                &page,
+                &mut chunk,
+                &mut written,
+                ERRORS_EXPORT_MAX_ENTRIES,
+            );
+            if tx.blocking_send(Ok(chunk)).is_err() {
+                return;
+            }
+            let Some(cursor) = next else {
+                return;
+            };
+            page = match stream_ffi_value(&export_state, &export_pool, |pool_ptr| {
+                fetch_page(pool_ptr, cursor)
+            }) {
+                Ok(value) => value,
+                Err(err) => {
+                    tracing::warn!("error log export of {export_pool} aborted: {err}");
+                    let _ = tx.blocking_send(Err(std::io::Error::other(err)));
+                    return;
+                }
+            };
+        }
+    });
 
-#[derive(Debug, Deserialize)]
-pub struct ZapEntriesQuery {
-    pub cursor: Option<u64>,
-    pub limit: Option<u64>,
+    let generated = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let filename = format!("{pool}-errors-{generated}.ndjson");
+    let mut response = Response::new(Body::from_stream(ChannelBodyStream(rx)));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    response.headers_mut().insert(
+        CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+            .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    Ok(response)
 }
 
-/// GET /api/pools/:pool/obj/:objid/zap/info
-pub async fn zap_info(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::zap_info(pool_ptr, objid);
-    json_from_result(result)
+/// Splits a `pool_summary` payload into the metadata export's `summary`,
+/// `features`, and `vdev_tree` sections.
+fn split_metadata_summary(mut summary: Value) -> (Value, Value, Value) {
+    let Some(map) = summary.as_object_mut() else {
+        return (summary, Value::Null, Value::Null);
+    };
+    let vdev_tree = map.remove("vdev_tree").unwrap_or(Value::Null);
+    let features = json!({
+        "features_for_read": map.remove("features_for_read").unwrap_or(Value::Null),
+        "compatibility": map.remove("compatibility").unwrap_or(Value::Null),
+    });
+    (summary, features, vdev_tree)
 }
 
-/// GET /api/pools/:pool/obj/:objid/zap
-pub async fn zap_entries(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-    Query(params): Query<ZapEntriesQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
-    let result = crate::ffi::zap_entries(pool_ptr, objid, cursor, limit);
-    json_from_result(result)
+/// The pool config nvlist stored in the MOS, found via the object directory.
+fn metadata_export_config(pool_ptr: *mut crate::ffi::zdx_pool_t) -> Result<Value, ApiError> {
+    let entries = read_object_directory(pool_ptr)?;
+    let config_obj = entries
+        .iter()
+        .find(|entry| entry["name"].as_str() == Some("config"))
+        .and_then(|entry| entry["target_obj"].as_u64())
+        .filter(|objid| *objid != 0)
+        .ok_or_else(|| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "object directory has no config entry",
+            )
+        })?;
+    let Json(config) = json_from_result(crate::ffi::decode_nvlist(pool_ptr, config_obj))?;
+    Ok(config)
+}
+
+/// Appends one error log page as JSON array items. Returns the next cursor to
+/// fetch, or None once the log is exhausted or `max_entries` items have been
+/// written (setting `truncated` if entries remain).
+fn append_metadata_error_items(
+    page: &Value,
+    out: &mut Vec<u8>,
+    written: &mut u64,
+    max_entries: u64,
+    truncated: &mut bool,
+) -> Option<u64> {
+    for entry in page["entries"].as_array().into_iter().flatten() {
+        if *written >= max_entries {
+            *truncated = true;
+            return None;
+        }
+        if *written > 0 {
+            out.push(b',');
+        }
+        out.extend_from_slice(entry.to_string().as_bytes());
+        *written += 1;
+    }
+    let next = page["next"].as_u64();
+    if next.is_some() && *written >= max_entries {
+        *truncated = true;
+        return None;
+    }
+    next
 }
 
-/// GET /api/pools/:pool/dsl/dir/:objid/children
-pub async fn dsl_dir_children(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::dsl_dir_children(pool_ptr, objid);
-    json_from_result(result)
+/// Serializes a JSON object one member at a time, so a streamed document can
+/// send each member as soon as its value is ready.
+#[derive(Default)]
+struct JsonObjectFragments {
+    members: usize,
 }
 
-/// GET /api/pools/:pool/dsl/dir/:objid/head
-pub async fn dsl_dir_head(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::dsl_dir_head(pool_ptr, objid);
-    json_from_result(result)
-}
+impl JsonObjectFragments {
+    /// `{"key":` for the first member, `,"key":` after that; the caller
+    /// writes the value.
+    fn key(&mut self, key: &str) -> Vec<u8> {
+        let mut out = vec![if self.members == 0 { b'{' } else { b',' }];
+        out.extend_from_slice(Value::from(key).to_string().as_bytes());
+        out.push(b':');
+        self.members += 1;
+        out
+    }
 
-/// GET /api/pools/:pool/dsl/root
-pub async fn dsl_root_dir(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::dsl_root_dir(pool_ptr);
-    json_from_result(result)
-}
+    /// One complete `"key":value` member.
+    fn member(&mut self, key: &str, value: &Value) -> Vec<u8> {
+        let mut out = self.key(key);
+        out.extend_from_slice(value.to_string().as_bytes());
+        out
+    }
 
-#[derive(Debug, Deserialize)]
-pub struct BlockQuery {
-    pub vdev: u64,
-    pub offset: u64,
-    pub asize: u64,
-    pub limit: Option<u64>,
+    /// Closes the object (`{}` when no member was written).
+    fn close(self) -> Vec<u8> {
+        if self.members == 0 {
+            b"{}".to_vec()
+        } else {
+            b"}".to_vec()
+        }
+    }
 }
 
-/// GET /api/pools/:pool/block?vdev=...&offset=...&asize=...&limit=...
-pub async fn read_block(
-    State(state): State<AppState>,
-    Path(pool): Path<String>,
-    Query(params): Query<BlockQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-
-    if params.asize == 0 {
-        return Err(api_error(StatusCode::BAD_REQUEST, "asize must be > 0"));
-    }
+type StreamSender = tokio::sync::mpsc::Sender<Result<Vec<u8>, std::io::Error>>;
+type StreamSendError = tokio::sync::mpsc::error::SendError<Result<Vec<u8>, std::io::Error>>;
+
+/// Writes the metadata export document to `tx`, sending each section as soon
+/// as it is loaded. A section that fails is recorded in `section_errors` and
+/// left `null`; a closed channel (client gone) ends the export. Runs on a
+/// blocking thread: every section is read through the FFI.
+fn stream_metadata_export(
+    tx: StreamSender,
+    state: AppState,
+    pool: String,
+    generated: u64,
+    summary: Value,
+) -> Result<(), StreamSendError> {
+    let send = |bytes: Vec<u8>| tx.blocking_send(Ok(bytes));
+    let mut doc = JsonObjectFragments::default();
+    let mut truncated_sections: Vec<&'static str> = Vec::new();
+    let mut section_errors = serde_json::Map::new();
+    let mut section = |name: &str, value: Result<Value, ApiError>| match value {
+        Ok(value) => value,
+        Err(err) => {
+            section_errors.insert(name.to_string(), json!(api_error_message(err)));
+            Value::Null
+        }
+    };
 
-    let max_read: u64 = 1 << 20;
-    let limit = params.limit.unwrap_or(64 * 1024);
-    let mut size = params.asize.min(limit).min(max_read);
-
-    if size == 0 {
-        size = params.asize.min(max_read);
+    let (summary, features, vdev_tree) = split_metadata_summary(summary);
+    let mut head = doc.member("schema_version", &json!(METADATA_EXPORT_SCHEMA_VERSION));
+    head.extend(doc.member("pool", &json!(pool)));
+    head.extend(doc.member("generated_at_unix_sec", &json!(generated)));
+    head.extend(doc.member("generated_at", &json!(rfc3339_utc(generated))));
+    head.extend(doc.member("summary", &summary));
+    send(head)?;
+
+    let config =
+        ensure_pool(&state, &pool).and_then(|pool_handle| metadata_export_config(pool_handle.ptr));
+    send(doc.member("config", &section("config", config)))?;
+    send(doc.member("features", &features))?;
+    send(doc.member("vdev_tree", &vdev_tree))?;
+
+    let datasets = dataset_tree_value(
+        &state,
+        &pool,
+        &DatasetTreeQuery {
+            depth: Some(64),
+            limit: Some(METADATA_EXPORT_MAX_DATASETS),
+            dir: None,
+            child_cursor: None,
+            child_limit: None,
+            strict: None,
+        },
+    );
+    let datasets = section("datasets", datasets);
+    if datasets["truncated"].as_bool().unwrap_or(false) {
+        truncated_sections.push("datasets");
+    }
+    send(doc.member("datasets", &datasets))?;
+
+    let mut errors = JsonObjectFragments::default();
+    let mut chunk = doc.key("errors");
+    chunk.extend(errors.key("entries"));
+    chunk.push(b'[');
+    let mut written = 0u64;
+    let mut errors_truncated = false;
+    let mut errors_error = None;
+    let mut cursor = 0;
+    loop {
+        let page = match stream_ffi_value(&state, &pool, |pool_ptr| {
+            crate::ffi::pool_errors(pool_ptr, cursor, MAX_PAGE_LIMIT, true, 0)
+        }) {
+            Ok(page) => page,
+            Err(err) => {
+                tracing::warn!("metadata export of {pool} stopped early: {err}");
+                errors_error = Some(err);
+                break;
+            }
+        };
+        let next = append_metadata_error_items(
+            &page,
+            &mut chunk,
+            &mut written,
+            METADATA_EXPORT_MAX_ERRORS,
+            &mut errors_truncated,
+        );
+        send(std::mem::take(&mut chunk))?;
+        let Some(next) = next else {
+            break;
+        };
+        cursor = next;
     }
-
-    let result = crate::ffi::read_block(pool_ptr, params.vdev, params.offset, size);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        tracing::error!("FFI error: {}", err_msg);
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            err_msg.to_string(),
-        ));
+    if errors_truncated {
+        truncated_sections.push("errors");
     }
-
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-
-    let mut value = parse_json_value(json_str)?;
-
-    value["asize"] = Value::from(params.asize);
-    value["truncated"] = Value::from(size < params.asize);
-    value["requested"] = Value::from(size);
-
-    Ok(Json(value))
-}
-
-#[derive(Debug, Deserialize)]
-pub struct DatasetTreeQuery {
-    pub depth: Option<u8>,
-    pub limit: Option<usize>,
+    if let Some(err) = &errors_error {
+        section_errors.insert("errors".to_string(), json!(err));
+    }
+    chunk.push(b']');
+    chunk.extend(errors.member("count", &json!(written)));
+    chunk.extend(errors.member("truncated", &json!(errors_truncated)));
+    chunk.extend(errors.member("error", &json!(errors_error)));
+    chunk.extend(errors.close());
+    chunk.extend(doc.member("truncated_sections", &json!(truncated_sections)));
+    chunk.extend(doc.member("section_errors", &Value::Object(section_errors)));
+    chunk.extend(doc.close());
+    send(chunk)
 }
 
-/// GET /api/pools/:pool/datasets/tree?depth=&limit=
-pub async fn dataset_tree(
+/// GET /api/pools/:pool/export/metadata.json
+/// (summary, config, features, vdev tree, bounded dataset tree and error log
+/// as one streamed JSON document)
+pub async fn pool_metadata_export(
     State(state): State<AppState>,
     Path(pool): Path<String>,
-    Query(params): Query<DatasetTreeQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let max_depth = params.depth.unwrap_or(4);
-    let limit = params.limit.unwrap_or(500);
-
-    let root_dir = resolve_pool_root_dir_obj(pool_ptr, &pool)?;
+) -> Result<Response<Body>, ApiError> {
+    // The summary must load for the export to be worth anything; the other
+    // sections record their failure and the document carries on.
+    let summary = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let Json(summary) = json_from_result(crate::ffi::pool_summary(pool_handle.ptr))?;
+        summary
+    };
+    let generated = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
 
-    let mut seen = 0usize;
-    let mut truncated = false;
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_DEPTH);
+    let export_state = state.clone();
+    let export_pool = pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let _ = stream_metadata_export(tx, export_state, export_pool, generated, summary);
+    });
 
-    fn build_node(
-        pool_ptr: *mut crate::ffi::zdx_pool_t,
-        name: String,
-        objid: u64,
-        depth: u8,
-        seen: &mut usize,
-        limit: usize,
-        truncated: &mut bool,
-    ) -> Result<Value, ApiError> {
-        if *seen >= limit {
-            *truncated = true;
-            return Ok(serde_json::json!({
-                "name": name,
-                "dsl_dir_obj": objid,
-                "head_dataset_obj": null,
-                "child_dir_zapobj": null,
-                "children": []
-            }));
-        }
-        *seen += 1;
+    let filename = format!("{pool}-metadata-{generated}.json");
+    let mut response = Response::new(Body::from_stream(ChannelBodyStream(rx)));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response.headers_mut().insert(
+        CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+            .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    Ok(response)
+}
 
-        let head_result = crate::ffi::dsl_dir_head(pool_ptr, objid);
-        if !head_result.is_ok() {
-            let err_msg = head_result.error_msg().unwrap_or("Unknown error");
-            tracing::error!("FFI error: {}", err_msg);
-            return Err(api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                err_msg.to_string(),
-            ));
-        }
-        let head_json = head_result.json().ok_or_else(|| {
-            api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Missing JSON in head result",
-            )
-        })?;
-        let head_value = parse_json_value(head_json)?;
-        let head_dataset_obj = head_value["head_dataset_obj"]
-            .as_u64()
-            .filter(|value| *value != 0);
+#[derive(Debug, Deserialize)]
+pub struct MosListQuery {
+    #[serde(rename = "type")]
+    pub type_filter: Option<i32>,
+    pub start: Option<u64>,
+    pub limit: Option<u64>,
+}
 
-        let children_result = crate::ffi::dsl_dir_children(pool_ptr, objid);
-        if !children_result.is_ok() {
-            let err_msg = children_result.error_msg().unwrap_or("Unknown error");
-            tracing::error!("FFI error: {}", err_msg);
-            return Err(api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                err_msg.to_string(),
-            ));
+#[derive(Debug, Deserialize)]
+pub struct ObjsetListQuery {
+    #[serde(rename = "type")]
+    pub type_filter: Option<i32>,
+    pub start: Option<u64>,
+    pub limit: Option<u64>,
+    pub min_size: Option<u64>,
+    pub allocated_only: Option<bool>,
+}
+
+/// Bytes of unparseable native JSON echoed back in `FFI_BAD_JSON` details.
+const FFI_BAD_JSON_EXCERPT_BYTES: usize = 256;
+
+/// Fields blanked out of an `FFI_BAD_JSON` excerpt: file contents, names,
+/// ZAP values, and host or device identity have no diagnostic value there.
+const FFI_BAD_JSON_REDACTED_KEYS: &[&str] =
+    &["data_hex", "hostname", "path", "comment", "name", "value"];
+
+/// Byte length of the JSON value at the start of `text` (a string, scalar,
+/// array, or object), or all of `text` when the value is cut short.
+fn json_value_len(text: &str) -> usize {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, ch) in text.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    in_string = false;
+                    if depth == 0 {
+                        return idx + 1;
+                    }
+                }
+                _ => {}
+            }
+            continue;
         }
-        let children_json = children_result.json().ok_or_else(|| {
-            api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Missing JSON in children result",
-            )
-        })?;
-        let children_value = parse_json_value(children_json)?;
-        let child_dir_zapobj = children_value["child_dir_zapobj"].as_u64();
-
-        let mut children_nodes: Vec<Value> = Vec::new();
-        if depth > 0 {
-            for (child_name, child_objid) in parse_dsl_children(&children_value) {
-                let node = build_node(
-                    pool_ptr,
-                    child_name,
-                    child_objid,
-                    depth - 1,
-                    seen,
-                    limit,
-                    truncated,
-                )?;
-                children_nodes.push(node);
-                if *truncated {
-                    break;
+        match ch {
+            '"' => in_string = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    return idx + 1;
                 }
             }
+            ',' | ']' | '}' if depth == 0 => return idx,
+            _ => {}
         }
-
-        Ok(serde_json::json!({
-            "name": name,
-            "dsl_dir_obj": objid,
-            "head_dataset_obj": head_dataset_obj,
-            "child_dir_zapobj": child_dir_zapobj,
-            "children": children_nodes
-        }))
     }
+    text.len()
+}
+
+/// Leading slice of a native JSON string for error details: values of
+/// `FFI_BAD_JSON_REDACTED_KEYS` replaced across the whole string first, then
+/// cut at a char boundary, with control characters shown as `.`.
+fn ffi_json_excerpt(json_str: &str) -> String {
+    const REDACTED: &str = "\"<redacted>\"";
+    let mut redacted = json_str.to_string();
+    for key in FFI_BAD_JSON_REDACTED_KEYS {
+        let marker = format!("\"{key}\":");
+        let mut from = 0;
+        while let Some(found) = redacted[from..].find(&marker) {
+            let value_start = from + found + marker.len();
+            let value_end = value_start + json_value_len(&redacted[value_start..]);
+            redacted.replace_range(value_start..value_end, REDACTED);
+            from = value_start + REDACTED.len();
+        }
+    }
+    let mut end = redacted.len().min(FFI_BAD_JSON_EXCERPT_BYTES);
+    while !redacted.is_char_boundary(end) {
+        end -= 1;
+    }
+    redacted[..end]
+        .chars()
+        .map(|ch| if ch.is_control() { '.' } else { ch })
+        .collect()
+}
 
-    let root_node = build_node(
-        pool_ptr,
-        pool.clone(),
-        root_dir,
-        max_depth,
-        &mut seen,
-        limit,
-        &mut truncated,
-    )?;
+/// Parse JSON produced by a native call that reported success. Failing to
+/// parse it is a native-layer fault (typically output cut short under memory
+/// pressure), so it maps to 502 `FFI_BAD_JSON` with a redacted excerpt; the
+/// full string is logged at debug level.
+fn parse_json_value(json_str: &str) -> Result<Value, ApiError> {
+    let started = Instant::now();
+    let parsed = serde_json::from_str(json_str);
+    crate::ffi::record_parse_time(started);
+    parsed.map_err(|e| {
+        let truncated = e.is_eof();
+        tracing::error!(
+            "Failed to parse FFI JSON ({} bytes, {}): {}",
+            json_str.len(),
+            if truncated { "truncated" } else { "invalid" },
+            e
+        );
+        tracing::debug!("Unparseable FFI JSON: {}", json_str);
+        let mut body = ApiErrorBody::new(
+            "FFI_BAD_JSON",
+            format!("native library returned unparseable JSON: {e}"),
+            Some(
+                "This is a native-layer fault, not a bad request; retrying may help if \
+memory was short. Report it with the request_id if it persists."
+                    .to_string(),
+            ),
+            true,
+        );
+        body.details = Some(json!({
+            "length": json_str.len(),
+            "line": e.line(),
+            "column": e.column(),
+            "truncated": truncated,
+            "excerpt": ffi_json_excerpt(json_str),
+        }));
+        body.into_error(StatusCode::BAD_GATEWAY)
+    })
+}
 
-    let response = serde_json::json!({
-        "root": root_node,
-        "depth": max_depth,
-        "limit": limit,
-        "truncated": truncated,
-        "count": seen
-    });
+// Zero semantics for query limits. A `limit` that sizes a page (items or
+// bytes) may be 0 and yields an empty page: normalizers keep the 0, the
+// native call runs with `fetch_limit` so the endpoint's envelope (cursor,
+// next, totals) is unchanged, and `finish_page` empties the items. Budgets
+// and sizes with no useful zero (node caps, scan limits, bin sizes, export
+// caps) reject 0 with 400 INVALID_QUERY through `reject_zero`. A block-tree
+// `max_depth` of 0 is meaningful (root only) and stays accepted.
 
-    Ok(Json(response))
+fn normalize_limit(limit: Option<u64>) -> u64 {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT)
 }
 
-/// GET /api/pools/:pool/dataset/:dsl_dir_obj/head
-pub async fn dataset_head(
-    State(state): State<AppState>,
-    Path((pool, dir_obj)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let response = resolve_dataset_objset(pool_ptr, dir_obj)?;
-    Ok(Json(response))
+/// Item count to request from the native layer for a normalized page limit.
+fn fetch_limit(limit: u64) -> u64 {
+    limit.max(1)
 }
 
-/// GET /api/pools/:pool/dataset/:dsl_dir_obj/objset
-pub async fn dataset_objset(
-    State(state): State<AppState>,
-    Path((pool, dir_obj)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let response = resolve_dataset_objset(pool_ptr, dir_obj)?;
-    Ok(Json(response))
+/// Reject an explicit 0 for a parameter where it has no meaning.
+fn reject_zero<T: Copy + Default + PartialEq>(
+    parameter: &str,
+    value: Option<T>,
+) -> Result<Option<T>, ApiError> {
+    if value != Some(T::default()) {
+        return Ok(value);
+    }
+    let mut body = ApiErrorBody::new(
+        "INVALID_QUERY",
+        format!("invalid query parameter '{parameter}': must be > 0"),
+        Some(format!("Omit {parameter} to use the default.")),
+        true,
+    );
+    body.parameter = Some(parameter.to_string());
+    Err(body.into_error(StatusCode::BAD_REQUEST))
 }
 
-/// GET /api/pools/:pool/dataset/:dsl_dir_obj/snapshots
-pub async fn dataset_snapshots(
-    State(state): State<AppState>,
-    Path((pool, dir_obj)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::dataset_snapshots(pool_ptr, dir_obj);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_dataset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+/// Turn a page fetched with `fetch_limit(0)` into the empty page `limit=0`
+/// asked for: `items_key` is emptied, `count`/`limit` read 0, and when the
+/// fetch found an item `next` points back at the requested cursor.
+fn empty_page(mut value: Value, items_key: &str) -> Value {
+    let Some(map) = value.as_object_mut() else {
+        return value;
+    };
+    let had_items = map
+        .get(items_key)
+        .and_then(Value::as_array)
+        .is_some_and(|items| !items.is_empty());
+    map.insert(items_key.to_string(), json!([]));
+    for key in ["count", "limit"] {
+        if map.contains_key(key) {
+            map.insert(key.to_string(), json!(0));
+        }
     }
+    let cursor = map.get("cursor").or_else(|| map.get("start")).cloned();
+    if let (true, Some(cursor)) = (had_items && map.contains_key("next"), cursor) {
+        map.insert("next".to_string(), cursor);
+    }
+    value
+}
 
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+/// Data-read counterpart of `empty_page` for a slice fetched with
+/// `fetch_limit(0)`: no bytes, and `eof` only when the offset is at or past
+/// the end of the object.
+fn empty_data_slice(mut value: Value) -> Value {
+    let at_end = match (value["offset"].as_u64(), value["max_offset"].as_u64()) {
+        (Some(offset), Some(max_offset)) => offset >= max_offset,
+        _ => value["eof"].as_bool().unwrap_or(false),
+    };
+    value["data_hex"] = json!("");
+    value["size"] = json!(0);
+    value["eof"] = json!(at_end);
+    value
 }
 
-/// GET /api/pools/:pool/dataset/:dsl_dir_obj/snapshot-count
-pub async fn dataset_snapshot_count(
-    State(state): State<AppState>,
-    Path((pool, dir_obj)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::dataset_snapshot_count(pool_ptr, dir_obj);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_dataset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+/// Apply `empty_page` to a handler's result when the normalized limit is 0.
+fn finish_page(limit: u64, items_key: &str, result: ApiResult) -> ApiResult {
+    if limit != 0 {
+        return result;
     }
+    let Json(value) = result?;
+    Ok(ApiResponse::new(empty_page(value, items_key)).into_json())
+}
 
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+fn normalize_cursor_limit(cursor: Option<u64>, limit: Option<u64>) -> (u64, u64) {
+    (cursor.unwrap_or(0), normalize_limit(limit))
+}
 
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+fn append_dataset_catalog_from_tree(node: &Value, prefix: Option<&str>, out: &mut Vec<Value>) {
+    for_each_tree_dataset(node, prefix, &mut |full_name, _| {
+        out.push(json!({
+            "name": full_name,
+            "type": "filesystem",
+            "mountpoint": null,
+            "mounted": null,
+        }));
+    });
 }
 
-/// GET /api/pools/:pool/snapshot/:dsobj/objset
-pub async fn snapshot_objset(
-    State(state): State<AppState>,
-    Path((pool, dsobj)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::dataset_objset(pool_ptr, dsobj);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_dataset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
+/// Visit every `/datasets/tree` node that has a head dataset, skipping the
+/// `$ORIGIN`-style special dirs, with its full slash-joined name.
+fn for_each_tree_dataset(node: &Value, prefix: Option<&str>, visit: &mut dyn FnMut(&str, &Value)) {
+    let Some(name) = node["name"].as_str() else {
+        return;
+    };
+
+    let full_name = if let Some(parent) = prefix {
+        if parent.is_empty() {
+            name.to_string()
         } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+            format!("{parent}/{name}")
+        }
+    } else {
+        name.to_string()
+    };
+
+    let has_head = node["head_dataset_obj"]
+        .as_u64()
+        .map(|value| value != 0)
+        .unwrap_or(false);
+    if has_head && !name.starts_with('$') {
+        visit(&full_name, node);
     }
 
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    if let Some(children) = node["children"].as_array() {
+        for child in children {
+            for_each_tree_dataset(child, Some(&full_name), visit);
+        }
+    }
+}
 
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+fn normalize_spacemap_limit(limit: Option<u64>) -> u64 {
+    limit
+        .unwrap_or(SPACEMAP_DEFAULT_LIMIT)
+        .min(SPACEMAP_MAX_LIMIT)
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SnapshotLineageQuery {
-    pub max_prev: Option<u64>,
-    pub max_next: Option<u64>,
+fn normalize_spacemap_cursor_limit(cursor: Option<u64>, limit: Option<u64>) -> (u64, u64) {
+    (cursor.unwrap_or(0), normalize_spacemap_limit(limit))
 }
 
-/// GET /api/pools/:pool/snapshot/:dsobj/lineage?max_prev=&max_next=
-pub async fn snapshot_lineage(
-    State(state): State<AppState>,
-    Path((pool, dsobj)): Path<(String, u64)>,
-    Query(params): Query<SnapshotLineageQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let max_prev = params.max_prev.unwrap_or(64).clamp(1, 4096);
-    let max_next = params.max_next.unwrap_or(64).clamp(1, 4096);
-    let result = crate::ffi::dataset_lineage(pool_ptr, dsobj, max_prev, max_next);
-    json_from_result(result)
+fn normalize_spacemap_bins_limit(limit: Option<u64>) -> u64 {
+    limit
+        .unwrap_or(SPACEMAP_BINS_DEFAULT_LIMIT)
+        .min(SPACEMAP_BINS_MAX_LIMIT)
 }
 
-fn resolve_dataset_objset(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    dir_obj: u64,
-) -> Result<Value, ApiError> {
-    let head_obj = resolve_dataset_head_dataset_obj(pool_ptr, dir_obj)?;
+fn normalize_spacemap_bin_size(bin_size: Option<u64>) -> Result<u64, ApiError> {
+    Ok(reject_zero("bin_size", bin_size)?
+        .unwrap_or(SPACEMAP_BINS_DEFAULT_SIZE)
+        .clamp(SPACEMAP_BINS_MIN_SIZE, SPACEMAP_BINS_MAX_SIZE))
+}
 
-    let objset_result = crate::ffi::dataset_objset(pool_ptr, head_obj);
-    if !objset_result.is_ok() {
-        let err_msg = objset_result.error_msg().unwrap_or("Unknown error");
-        tracing::error!(
-            "dataset_objset failed: dsl_dir_obj={} head_dataset_obj={} err={}",
-            dir_obj,
-            head_obj,
-            err_msg
-        );
-        let status = if is_dataset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
-    }
+fn normalize_spacemap_bins_cursor_limit(cursor: Option<u64>, limit: Option<u64>) -> (u64, u64) {
+    (cursor.unwrap_or(0), normalize_spacemap_bins_limit(limit))
+}
 
-    let objset_json = objset_result.json().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Missing JSON in objset result",
-        )
-    })?;
-    let objset_value = parse_json_value(objset_json)?;
+fn normalize_block_tree_depth(depth: Option<u64>) -> u64 {
+    depth
+        .unwrap_or(BLOCK_TREE_DEFAULT_DEPTH)
+        .min(BLOCK_TREE_MAX_DEPTH)
+}
 
-    let response = build_dataset_objset_response(dir_obj, head_obj, &objset_value);
+fn normalize_block_tree_nodes(max_nodes: Option<u64>) -> Result<u64, ApiError> {
+    Ok(reject_zero("max_nodes", max_nodes)?
+        .unwrap_or(BLOCK_TREE_DEFAULT_NODES)
+        .min(BLOCK_TREE_MAX_NODES))
+}
 
-    Ok(response)
+fn normalize_objset_data_limit(limit: Option<u64>) -> u64 {
+    limit
+        .unwrap_or(OBJSET_DATA_DEFAULT_LIMIT)
+        .min(OBJSET_DATA_MAX_LIMIT)
 }
 
-fn resolve_dataset_head_dataset_obj(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    dir_obj: u64,
-) -> Result<u64, ApiError> {
-    let head_result = crate::ffi::dsl_dir_head(pool_ptr, dir_obj);
-    if !head_result.is_ok() {
-        let err_msg = head_result.error_msg().unwrap_or("Unknown error");
-        tracing::error!("FFI error: {}", err_msg);
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            err_msg.to_string(),
-        ));
-    }
+fn normalize_largest_files_limit(limit: Option<u64>) -> u64 {
+    limit
+        .unwrap_or(LARGEST_FILES_DEFAULT_LIMIT)
+        .min(LARGEST_FILES_MAX_LIMIT)
+}
 
-    let head_json = head_result.json().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Missing JSON in head result",
-        )
-    })?;
-    let head_value = parse_json_value(head_json)?;
+fn normalize_largest_files_scan(scan_limit: Option<u64>) -> Result<u64, ApiError> {
+    Ok(reject_zero("scan_limit", scan_limit)?
+        .unwrap_or(LARGEST_FILES_DEFAULT_SCAN)
+        .min(LARGEST_FILES_MAX_SCAN))
+}
 
-    let head_obj = head_value["head_dataset_obj"].as_u64().unwrap_or(0);
-    if head_obj == 0 {
-        return Err(api_error(
+fn parse_spacemap_op_filter(op: Option<&str>) -> Result<i32, ApiError> {
+    let normalized = op.unwrap_or("all").trim().to_ascii_lowercase();
+    match normalized.as_str() {
+        "" | "all" => Ok(0),
+        "alloc" => Ok(1),
+        "free" => Ok(2),
+        _ => Err(api_error(
             StatusCode::BAD_REQUEST,
-            format!(
-                "DSL dir {} has no head dataset (special internal dir such as $FREE/$MOS)",
-                dir_obj
-            ),
-        ));
+            format!("invalid op filter '{normalized}'; expected all, alloc, or free"),
+        )),
     }
+}
 
-    tracing::debug!(
-        "resolved dataset head: dsl_dir_obj={} head_dataset_obj={}",
-        dir_obj,
-        head_obj
-    );
+/// `type=user|group` for userspace accounting; true selects groups.
+fn parse_userspace_type(kind: Option<&str>) -> Result<bool, ApiError> {
+    let normalized = kind.unwrap_or("user").trim().to_ascii_lowercase();
+    match normalized.as_str() {
+        "" | "user" => Ok(false),
+        "group" => Ok(true),
+        _ => Err(api_error(
+            StatusCode::BAD_REQUEST,
+            format!("invalid userspace type '{normalized}'; expected user or group"),
+        )),
+    }
+}
 
-    Ok(head_obj)
+fn parse_graph_include(include: Option<&str>) -> (bool, bool, bool) {
+    let include = include.unwrap_or("semantic,physical");
+    (
+        include.contains("semantic"),
+        include.contains("physical"),
+        include.contains("zap"),
+    )
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/root
-pub async fn objset_root(
-    State(state): State<AppState>,
-    Path((pool, objset_id)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
+fn parse_dsl_children(value: &Value) -> Vec<(String, u64)> {
+    let Some(children) = value["children"].as_array() else {
+        return Vec::new();
+    };
 
-    let result = crate::ffi::objset_root(pool_ptr, objset_id);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        tracing::error!("FFI error: {}", err_msg);
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            err_msg.to_string(),
-        ));
-    }
+    children
+        .iter()
+        .filter_map(|child| {
+            let child_objid = child["dir_objid"].as_u64()?;
+            if child_objid == 0 {
+                return None;
+            }
+            let child_name = child["name"].as_str().unwrap_or("dataset").to_string();
+            Some((child_name, child_objid))
+        })
+        .collect()
+}
 
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
+/// Internal DSL dirs that never carry a user-visible head dataset.
+const SPECIAL_DSL_DIRS: &[&str] = &["$MOS", "$FREE", "$ORIGIN", "$LEAK"];
 
-    Ok(Json(value))
+fn is_special_dsl_dir(name: &str) -> bool {
+    SPECIAL_DSL_DIRS.contains(&name)
 }
 
-#[derive(Debug, Deserialize)]
-pub struct DirEntriesQuery {
-    pub cursor: Option<u64>,
-    pub limit: Option<u64>,
+fn special_dataset_tree_node(name: &str, objid: u64) -> Value {
+    serde_json::json!({
+        "name": name,
+        "dsl_dir_obj": objid,
+        "head_dataset_obj": null,
+        "child_dir_zapobj": null,
+        "special": true,
+        "child_count": 0,
+        "child_next_cursor": null,
+        "children_truncated": false,
+        "children": []
+    })
 }
 
-#[derive(Debug, Deserialize)]
-pub struct WalkQuery {
-    pub path: Option<String>,
+fn build_dataset_objset_response(dir_obj: u64, head_obj: u64, objset_value: &Value) -> Value {
+    serde_json::json!({
+        "dsl_dir_obj": dir_obj,
+        "head_dataset_obj": head_obj,
+        "objset_id": objset_value["objset_id"],
+        "rootbp": objset_value["rootbp"]
+    })
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/dir/:dir_obj/entries
-pub async fn objset_dir_entries(
-    State(state): State<AppState>,
-    Path((pool, objset_id, dir_obj)): Path<(String, u64, u64)>,
-    Query(params): Query<DirEntriesQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
-    let result = crate::ffi::objset_dir_entries(pool_ptr, objset_id, dir_obj, cursor, limit);
-    json_from_result(result)
+/// Process-wide ceiling on approximate in-flight JSON bytes for heavy listings.
+#[derive(Debug)]
+pub struct InflightBudget {
+    used: AtomicU64,
+    limit: u64,
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/walk?path=/a/b/c
-pub async fn objset_walk(
-    State(state): State<AppState>,
-    Path((pool, objset_id)): Path<(String, u64)>,
-    Query(params): Query<WalkQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let path = params.path.unwrap_or_else(|| "/".to_string());
-    let result = crate::ffi::objset_walk(pool_ptr, objset_id, &path)
-        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
-    json_from_result(result)
+impl InflightBudget {
+    pub fn new(limit: u64) -> Self {
+        InflightBudget {
+            used: AtomicU64::new(0),
+            limit,
+        }
+    }
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/stat/:objid
-pub async fn objset_stat(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::objset_stat(pool_ptr, objset_id, objid);
-    json_from_result(result)
+/// Released when dropped, i.e. once the handler has built its response.
+struct InflightReservation {
+    budget: Arc<InflightBudget>,
+    bytes: u64,
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid
-pub async fn objset_get_object(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::objset_get_object(pool_ptr, objset_id, objid);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_objset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+impl Drop for InflightReservation {
+    fn drop(&mut self) {
+        self.budget.used.fetch_sub(self.bytes, Ordering::SeqCst);
     }
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/blkptrs
-pub async fn objset_get_blkptrs(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::objset_get_blkptrs(pool_ptr, objset_id, objid);
+fn reserve_inflight(state: &AppState, items: u64) -> Result<InflightReservation, ApiError> {
+    let budget = state.inflight.clone();
+    let bytes = items.saturating_mul(INFLIGHT_BYTES_PER_ITEM);
+    let reserved = budget
+        .used
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+            used.checked_add(bytes)
+                .filter(|total| *total <= budget.limit)
+        });
+    if let Err(used) = reserved {
+        tracing::warn!(
+            "rejecting request: in-flight estimate {} + {} bytes exceeds {}",
+            used,
+            bytes,
+            budget.limit
+        );
+        return Err(api_error_with(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "MEMORY_PRESSURE",
+            format!(
+                "request would exceed the in-flight response budget ({} bytes)",
+                budget.limit
+            ),
+            Some(
+                "Retry shortly, lower `limit`, or raise ZFS_EXPLORER_MAX_INFLIGHT_BYTES."
+                    .to_string(),
+            ),
+            true,
+        ));
+    }
+    Ok(InflightReservation { budget, bytes })
+}
+
+fn json_from_result(result: crate::ffi::ZdxResult) -> ApiResult {
     if !result.is_ok() {
         let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_objset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+        let err_code = result.error_code();
+        let code_label = pool_open_error_code(err_code);
+        if is_pool_busy_code(err_code) {
+            tracing::warn!("FFI busy ({}): {}", code_label, err_msg);
+            return Err(pool_busy_error(&code_label, err_msg));
+        }
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error_with(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            code_label,
+            err_msg.to_string(),
+            None,
+            false,
+        ));
     }
+
     let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+
     let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+
+    Ok(ApiResponse::new(value).into_json())
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/block-tree?max_depth=&max_nodes=
-pub async fn objset_block_tree(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-    Query(params): Query<BlockTreeQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let max_depth = normalize_block_tree_depth(params.max_depth);
-    let max_nodes = normalize_block_tree_nodes(params.max_nodes);
-    let result = crate::ffi::objset_block_tree(pool_ptr, objset_id, objid, max_depth, max_nodes);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_objset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
-    }
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
-}
+/// Top-level flags a traversal sets when it stopped at a node/depth/record cap.
+const TRUNCATION_FLAGS: &[&str] = &["truncated", "prev_truncated", "next_truncated"];
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/zap/info
-pub async fn objset_zap_info(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::objset_zap_info(pool_ptr, objset_id, objid);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error_for_objset(err_msg));
+/// Finish a traversal response. With `strict=true` a truncated result becomes
+/// a 422 `RESULT_TRUNCATED` whose `details` carry the partial counts (numeric
+/// fields and array lengths), so scripts know to raise limits instead of
+/// working from partial data. Otherwise the flagged result is returned as-is.
+fn finish_traversal(strict: Option<bool>, value: Value) -> ApiResult {
+    let truncated = TRUNCATION_FLAGS
+        .iter()
+        .any(|flag| value[*flag].as_bool() == Some(true));
+    if !strict.unwrap_or(false) || !truncated {
+        return Ok(Json(value));
+    }
+
+    let mut details = serde_json::Map::new();
+    if let Some(map) = value.as_object() {
+        for (key, field) in map {
+            if field.is_u64() || (field.is_boolean() && TRUNCATION_FLAGS.contains(&key.as_str())) {
+                details.insert(key.clone(), field.clone());
+            } else if let Some(items) = field.as_array() {
+                details.insert(format!("{key}_count"), json!(items.len()));
+            }
+        }
     }
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
-}
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/zap
-pub async fn objset_zap_entries(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-    Query(params): Query<ZapEntriesQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
-    let result = crate::ffi::objset_zap_entries(pool_ptr, objset_id, objid, cursor, limit);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error_for_objset(err_msg));
-    }
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+    let mut body = ApiErrorBody::new(
+        "RESULT_TRUNCATED",
+        "result exceeds the requested traversal limits",
+        Some("Raise the depth/node/record limits, or drop strict=true to accept a flagged partial result.".to_string()),
+        true,
+    );
+    body.details = Some(Value::Object(details));
+    Err(body.into_error(StatusCode::UNPROCESSABLE_ENTITY))
+}
+
+/// The active handle for `pool`, opening it if needed. Callers must keep the
+/// returned `Arc` alive while they use its pointer: a concurrent refresh or
+/// mode change only drops the server's reference, and the native handle is
+/// closed once the last holder lets go.
+fn ensure_pool(state: &AppState, pool: &str) -> Result<Arc<crate::ffi::PoolHandle>, ApiError> {
+    open_pool(state, pool, false)
+}
+
+/// Reject pool names no pool can have before they reach the FFI. `pool` is
+/// the percent-decoded path segment, which is also the form the active handle
+/// stores, so names with spaces or escaped characters compare as written.
+fn validate_pool_name(pool: &str) -> Result<(), ApiError> {
+    let problem = if pool.trim().is_empty() {
+        "pool name is empty"
+    } else if pool.contains('\0') {
+        "pool name contains a NUL byte"
+    } else if pool.contains('/') {
+        "pool name contains '/'; pass the pool name, not a dataset path"
+    } else {
+        return Ok(());
+    };
+    Err(api_error_with(
+        StatusCode::BAD_REQUEST,
+        "INVALID_POOL_NAME",
+        problem,
+        None,
+        true,
+    ))
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/full
-pub async fn objset_get_full(
-    State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
+/// Make `pool` the active handle. With `reopen`, an already-active handle for
+/// the same pool is closed first, so the pool (and every per-pool cache) is
+/// read afresh. The same pool cannot be held twice (offline opens import it
+/// by name), so a reopen is refused with 409 while other requests still hold
+/// the handle, and if the reopen fails the pool is opened again as before so
+/// the server is not left without a handle. A handle for a different pool is
+/// only dropped once the new open succeeds, so probing a bad pool name keeps
+/// the working handle (and its caches) in place.
+fn open_pool(
+    state: &AppState,
+    pool: &str,
+    reopen: bool,
+) -> Result<Arc<crate::ffi::PoolHandle>, ApiError> {
+    open_pool_with(state, pool, reopen, open_native_pool)
+}
+
+/// [`open_pool`] with the native open step supplied by the caller.
+fn open_pool_with(
+    state: &AppState,
+    pool: &str,
+    reopen: bool,
+    mut open: impl FnMut(&str, &crate::PoolOpenConfig) -> Result<crate::ffi::PoolHandle, ApiError>,
+) -> Result<Arc<crate::ffi::PoolHandle>, ApiError> {
+    validate_pool_name(pool)?;
+    let pool_open = pool_open_config(state);
+    let mut guard = state.pool.lock().unwrap();
 
-    let obj_result = crate::ffi::objset_get_object(pool_ptr, objset_id, objid);
-    if !obj_result.is_ok() {
-        let err_msg = obj_result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error_for_objset(err_msg));
+    if let Some(existing) = guard.as_ref() {
+        if existing.name == pool && !reopen {
+            return Ok(Arc::clone(existing));
+        }
     }
 
-    let blk_result = crate::ffi::objset_get_blkptrs(pool_ptr, objset_id, objid);
-    if !blk_result.is_ok() {
-        let err_msg = blk_result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error_for_objset(err_msg));
+    let reopening = guard.as_ref().is_some_and(|existing| existing.name == pool);
+    if reopening {
+        let in_use = guard
+            .as_ref()
+            .is_some_and(|existing| Arc::strong_count(existing) > 1);
+        if in_use {
+            return Err(api_error_with(
+                StatusCode::CONFLICT,
+                "POOL_BUSY",
+                format!("pool '{pool}' is in use by other requests"),
+                Some("Retry the refresh once in-flight reads and exports finish.".to_string()),
+                true,
+            ));
+        }
+        release_pool_handle(state, &mut guard);
     }
 
-    let obj_json = obj_result.json().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Missing JSON in object result",
-        )
-    })?;
-    let blk_json = blk_result.json().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Missing JSON in blkptr result",
-        )
-    })?;
+    let handle = match open(pool, &pool_open) {
+        Ok(handle) => handle,
+        Err(err) if reopening => match open(pool, &pool_open) {
+            Ok(previous) => {
+                tracing::warn!("refresh of pool {pool} failed; reopened it as before");
+                *guard = Some(Arc::new(previous));
+                return Err(err);
+            }
+            Err(_) => return Err(err),
+        },
+        Err(err) => return Err(err),
+    };
 
-    let obj_value = parse_json_value(obj_json)?;
-    let blk_value = parse_json_value(blk_json)?;
+    release_pool_handle(state, &mut guard);
+    let handle = Arc::new(handle);
+    *guard = Some(Arc::clone(&handle));
+    Ok(handle)
+}
 
-    let mut zap_info_value = Value::Null;
-    let mut zap_entries_value = Value::Null;
-    let mut zap_error_value = Value::Null;
-    let is_zap = obj_value
-        .get("is_zap")
-        .and_then(Value::as_bool)
-        .unwrap_or(false);
-    if is_zap {
-        let zinfo_result = crate::ffi::objset_zap_info(pool_ptr, objset_id, objid);
-        if !zinfo_result.is_ok() {
-            let err_msg = zinfo_result.error_msg().unwrap_or("Unknown error");
-            if let Some(payload) = inline_zap_error_payload(err_msg) {
-                zap_error_value = payload;
-            } else {
-                return Err(api_error_for_objset(err_msg));
-            }
+/// Open `pool` in the configured mode, mapping failures to API errors.
+fn open_native_pool(
+    pool: &str,
+    pool_open: &crate::PoolOpenConfig,
+) -> Result<crate::ffi::PoolHandle, ApiError> {
+    let mode = pool_open.mode;
+    let mode_name = pool_open_mode_name(mode);
+    match mode {
+        crate::PoolOpenMode::Live => crate::ffi::pool_open(pool),
+        crate::PoolOpenMode::Offline => crate::ffi::pool_open_offline(
+            pool,
+            pool_open.offline_search_paths.as_deref(),
+            pool_open.offline_txg,
+        ),
+    }
+    .map_err(|(code, msg)| {
+        let err_code = pool_open_error_code(code);
+        let hint = if matches!(mode, crate::PoolOpenMode::Offline) {
+            offline_pool_open_hint(pool, code)
+        } else if code == libc::EACCES || code == libc::EPERM {
+            Some("Run backend with sudo for live imported pools.".to_string())
         } else {
-            let zinfo_json = zinfo_result.json().ok_or_else(|| {
-                api_error(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Missing JSON in objset zap info result",
-                )
-            })?;
-            zap_info_value = parse_json_value(zinfo_json)?;
-        }
+            None
+        };
 
-        if zap_error_value.is_null() {
-            let zents_result =
-                crate::ffi::objset_zap_entries(pool_ptr, objset_id, objid, 0, DEFAULT_PAGE_LIMIT);
-            if !zents_result.is_ok() {
-                let err_msg = zents_result.error_msg().unwrap_or("Unknown error");
-                if let Some(payload) = inline_zap_error_payload(err_msg) {
-                    zap_error_value = payload;
-                } else {
-                    return Err(api_error_for_objset(err_msg));
-                }
-            } else {
-                let zents_json = zents_result.json().ok_or_else(|| {
-                    api_error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "Missing JSON in objset zap entries result",
-                    )
-                })?;
-                zap_entries_value = parse_json_value(zents_json)?;
-            }
+        let expected_client_error = matches!(mode, crate::PoolOpenMode::Offline)
+            && matches!(
+                libzfs_error_name(code),
+                Some("EZFS_NOENT" | "EZFS_PERM" | "EZFS_ACTIVE_POOL" | "EZFS_CRYPTOFAILED")
+            )
+            || matches!(
+                code,
+                libc::ENOENT | libc::EACCES | libc::EPERM | libc::EEXIST
+            );
+
+        if expected_client_error {
+            tracing::warn!(
+                "Pool open warning for {} (mode={}, code={}): {}",
+                pool,
+                mode_name,
+                err_code,
+                msg
+            );
+        } else {
+            tracing::error!(
+                "Failed to open pool {} (mode={}, code={}): {}",
+                pool,
+                mode_name,
+                err_code,
+                msg
+            );
         }
-    }
 
-    Ok(Json(json!({
-        "object": obj_value,
-        "blkptrs": blk_value,
-        "zap_info": zap_info_value,
-        "zap_entries": zap_entries_value,
-        "zap_error": zap_error_value
-    })))
+        let status = if expected_client_error {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+
+        api_error_with(
+            status,
+            err_code,
+            format!("pool open failed ({mode_name}): {msg}"),
+            hint,
+            true,
+        )
+    })
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ObjsetDataQuery {
-    pub offset: Option<u64>,
-    pub limit: Option<u64>,
+/// Drop the server's reference to the active handle, if any, and every cache
+/// derived from it. The handle closes once in-flight requests release it.
+fn release_pool_handle(state: &AppState, guard: &mut Option<Arc<crate::ffi::PoolHandle>>) {
+    if guard.take().is_some() {
+        bump_pool_generation();
+        state.dataset_catalog.lock().unwrap().take();
+        state.txg_time_index.lock().unwrap().take();
+        state.pool_timeline.lock().unwrap().take();
+        state.known_names.lock().unwrap().take();
+        state.response_cache.clear();
+    }
 }
 
-/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/data?offset=&limit=
-pub async fn objset_read_data(
+/// GET /api/pools/:pool/mos/objects
+pub async fn mos_list_objects(
     State(state): State<AppState>,
-    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
-    Query(params): Query<ObjsetDataQuery>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<MosListQuery>,
 ) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let offset = params.offset.unwrap_or(0);
-    let limit = normalize_objset_data_limit(params.limit);
-    let result = crate::ffi::objset_read_data(pool_ptr, objset_id, objid, offset, limit);
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+
+    let type_filter = params.type_filter.unwrap_or(-1);
+    let start = params.start.unwrap_or(0);
+    let limit = normalize_limit(params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+
+    let result = crate::ffi::mos_list_objects(pool_ptr, type_filter, start, fetch_limit(limit));
+    finish_page(limit, "objects", json_from_result(result))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/objects?type=&start=&limit=&min_size=&allocated_only=
+pub async fn objset_list_objects(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<ObjsetListQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+
+    let type_filter = params.type_filter.unwrap_or(-1);
+    let start = params.start.unwrap_or(0);
+    let limit = normalize_limit(params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+
+    let result = crate::ffi::objset_list_objects(
+        pool_ptr,
+        objset_id,
+        type_filter,
+        start,
+        fetch_limit(limit),
+        params.min_size.unwrap_or(0),
+        params.allocated_only.unwrap_or(false),
+    );
     if !result.is_ok() {
         let err_msg = result.error_msg().unwrap_or("Unknown error");
         let status = if is_objset_user_input_error(err_msg) {
@@ -2695,1190 +4818,1736 @@ pub async fn objset_read_data(
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
     let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+    finish_page(limit, "objects", Ok(Json(value)))
 }
 
 #[derive(Debug, Deserialize)]
-struct DatasetCatalogEntry {
-    name: String,
+pub struct ObjsetObjectsStreamQuery {
     #[serde(rename = "type")]
-    dataset_type: String,
-    mountpoint: Option<String>,
-    mounted: Option<bool>,
+    pub type_filter: Option<i32>,
+    pub start: Option<u64>,
+    pub min_size: Option<u64>,
+    pub allocated_only: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ObjsetWalkPayload {
-    objid: u64,
-    found: bool,
-    remaining: String,
+/// Appends one listing page to a streamed JSON array. `written` counts the
+/// elements sent so far, to place separators. Returns the next start object.
+fn append_object_stream_items(page: &Value, out: &mut Vec<u8>, written: &mut u64) -> Option<u64> {
+    for object in page["objects"].as_array().into_iter().flatten() {
+        if *written > 0 {
+            out.push(b',');
+        }
+        out.extend_from_slice(object.to_string().as_bytes());
+        *written += 1;
+    }
+    page["next"].as_u64()
 }
 
-#[derive(Debug, Deserialize)]
-struct ObjsetStatPayload {
-    size: u64,
-    type_name: String,
+/// Closes a streamed array. A stream cut short by an error ends with a
+/// `{"truncated":true,"error":...}` element so the body stays valid JSON.
+fn object_stream_tail(written: u64, error: Option<&str>) -> Vec<u8> {
+    let mut out = Vec::new();
+    if let Some(error) = error {
+        if written > 0 {
+            out.push(b',');
+        }
+        let marker = json!({ "truncated": true, "error": error });
+        out.extend_from_slice(marker.to_string().as_bytes());
+    }
+    out.push(b']');
+    out
 }
 
-#[derive(Debug, Deserialize)]
-struct ObjsetDataPayload {
-    data_hex: String,
-}
-
-#[derive(Debug, Clone)]
-struct ZplPathContext {
-    dataset_name: String,
-    objset_id: u64,
-    rel_path: String,
-    objid: u64,
-    file_size: u64,
-    filename: String,
-}
-
-#[derive(Debug, Clone)]
-struct ObjsetScopedZplPathContext {
-    objset_id: u64,
-    rel_path: String,
-    objid: u64,
-    file_size: u64,
-    filename: String,
-}
+/// GET /api/pools/:pool/objset/:objset_id/objects/stream?type=&start=&min_size=&allocated_only=
+/// (every matching object as one JSON array, fetched and flushed in batches)
+pub async fn objset_objects_stream(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<ObjsetObjectsStreamQuery>,
+) -> Result<Response<Body>, ApiError> {
+    let type_filter = params.type_filter.unwrap_or(-1);
+    let min_size = params.min_size.unwrap_or(0);
+    let allocated_only = params.allocated_only.unwrap_or(false);
+    let fetch_page = move |pool_ptr, start| {
+        crate::ffi::objset_list_objects(
+            pool_ptr,
+            objset_id,
+            type_filter,
+            start,
+            OBJECT_STREAM_BATCH,
+            min_size,
+            allocated_only,
+        )
+    };
 
-fn decode_hex_bytes(data_hex: &str) -> Result<Vec<u8>, ApiError> {
-    let trimmed = data_hex.trim();
-    if trimmed.is_empty() {
-        return Ok(Vec::new());
-    }
-    if trimmed.len() % 2 != 0 {
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "invalid hex payload length from backend read",
-        ));
-    }
+    // The first batch is read up front so open/FFI failures still surface as
+    // regular error envelopes; later batches are streamed from a blocking task.
+    let first_page = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        let result = fetch_page(pool_ptr, params.start.unwrap_or(0));
+        if !result.is_ok() {
+            let err_msg = result.error_msg().unwrap_or("Unknown error");
+            let status = if is_objset_user_input_error(err_msg) {
+                StatusCode::BAD_REQUEST
+            } else {
+                tracing::error!("FFI error: {}", err_msg);
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            return Err(api_error(status, err_msg.to_string()));
+        }
+        let Json(value) = json_from_result(result)?;
+        value
+    };
 
-    fn nibble(byte: u8) -> Option<u8> {
-        match byte {
-            b'0'..=b'9' => Some(byte - b'0'),
-            b'a'..=b'f' => Some(byte - b'a' + 10),
-            b'A'..=b'F' => Some(byte - b'A' + 10),
-            _ => None,
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_DEPTH);
+    let stream_state = state.clone();
+    let stream_pool = pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut written = 0u64;
+        let mut chunk = vec![b'['];
+        let mut page = first_page;
+        loop {
+            let next = append_object_stream_items(&page, &mut chunk, &mut written);
+            let Some(start) = next else {
+                chunk.extend_from_slice(&object_stream_tail(written, None));
+                let _ = tx.blocking_send(Ok(chunk));
+                return;
+            };
+            if tx.blocking_send(Ok(std::mem::take(&mut chunk))).is_err() {
+                return;
+            }
+            page = match stream_ffi_value(&stream_state, &stream_pool, |pool_ptr| {
+                fetch_page(pool_ptr, start)
+            }) {
+                Ok(value) => value,
+                Err(err) => {
+                    tracing::warn!(
+                        "object stream of {stream_pool} objset {objset_id} aborted: {err}"
+                    );
+                    let _ = tx.blocking_send(Ok(object_stream_tail(written, Some(&err))));
+                    return;
+                }
+            };
         }
-    }
+    });
 
-    let bytes = trimmed.as_bytes();
-    let mut out = Vec::with_capacity(bytes.len() / 2);
-    let mut idx = 0usize;
-    while idx < bytes.len() {
-        let hi = nibble(bytes[idx]).ok_or_else(|| {
-            api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "invalid hex payload from backend read",
-            )
-        })?;
-        let lo = nibble(bytes[idx + 1]).ok_or_else(|| {
-            api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "invalid hex payload from backend read",
-            )
-        })?;
-        out.push((hi << 4) | lo);
-        idx += 2;
-    }
+    let mut response = Response::new(Body::from_stream(ChannelBodyStream(rx)));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(response)
+}
 
-    Ok(out)
+/// GET /api/pools/:pool/obj/:objid
+pub async fn mos_get_object(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::mos_get_object(pool_ptr, objid);
+    let Json(value) = json_from_result(result)?;
+    let value = with_known_name(&state, pool_ptr, &pool, objid, value);
+    Ok(Json(with_txg_times(&state, pool_ptr, &pool, value)))
 }
 
-fn split_clean_path(path: &str) -> Vec<&str> {
-    path.split('/')
-        .filter(|segment| !segment.is_empty())
-        .collect()
+/// GET /api/pools/:pool/obj/:objid/blkptrs
+pub async fn mos_get_blkptrs(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::mos_get_blkptrs(pool_ptr, objid);
+    let Json(value) = json_from_result(result)?;
+    Ok(Json(with_txg_times(&state, pool_ptr, &pool, value)))
 }
 
-fn dataset_path_match(dataset: &str, path: &str) -> Option<String> {
-    if path == dataset {
-        return Some(String::new());
-    }
+const BLKPTR_SIZE: usize = 128;
 
-    let prefix = format!("{dataset}/");
-    if path.starts_with(&prefix) {
-        return Some(path[prefix.len()..].to_string());
+#[derive(Debug, Deserialize)]
+pub struct DecodeBlkptrRequest {
+    pub hex: String,
+}
+
+/// Parse a hexdump-style blkptr: whitespace and an optional `0x` prefix are
+/// ignored, and exactly `BLKPTR_SIZE` bytes must remain.
+fn parse_blkptr_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let trimmed = hex.trim();
+    let trimmed = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    let digits: String = trimmed
+        .chars()
+        .filter(|ch| !ch.is_ascii_whitespace())
+        .collect();
+    if digits.len() != BLKPTR_SIZE * 2 {
+        return Err(format!(
+            "hex must encode exactly {BLKPTR_SIZE} bytes ({} hex digits), got {} digits",
+            BLKPTR_SIZE * 2,
+            digits.len()
+        ));
     }
 
-    None
+    decode_hex_bytes(&digits).map_err(|_| "hex contains non-hexadecimal characters".to_string())
 }
 
-fn mountpoint_path_match(mountpoint: &str, absolute_path: &str) -> Option<String> {
-    if absolute_path == mountpoint {
-        return Some(String::new());
-    }
+/// POST /api/pools/:pool/blkptr/decode
+pub async fn decode_blkptr(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+    Json(request): Json<DecodeBlkptrRequest>,
+) -> ApiResult {
+    let bytes = parse_blkptr_hex(&request.hex).map_err(|message| {
+        api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_BLKPTR_HEX",
+            message,
+            Some(
+                "Pass the raw 128-byte blkptr_t as hex in native byte order, e.g. \
+copied from a zdb or hexdump of an indirect block."
+                    .to_string(),
+            ),
+            true,
+        )
+    })?;
 
-    let prefix = format!("{mountpoint}/");
-    if absolute_path.starts_with(&prefix) {
-        return Some(absolute_path[prefix.len()..].to_string());
-    }
+    let pool_handle = ensure_pool(&state, &pool)?;
 
-    None
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::decode_blkptr(pool_ptr, &bytes);
+    let Json(value) = json_from_result(result)?;
+    Ok(Json(with_txg_times(&state, pool_ptr, &pool, value)))
 }
 
-fn load_dataset_catalog(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-) -> Result<Vec<DatasetCatalogEntry>, ApiError> {
-    let datasets_result = crate::ffi::pool_datasets(pool_ptr);
-    if !datasets_result.is_ok() {
-        let err_msg = datasets_result.error_msg().unwrap_or("Unknown error");
-        let err_code = datasets_result.error_code();
-        let code = pool_open_error_code(err_code);
-        return Err(api_error_with(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            code,
-            format!("failed to list datasets: {err_msg}"),
-            None,
-            false,
-        ));
+/// GET /api/pools/:pool/obj/:objid/data?offset=&limit=
+pub async fn mos_read_data(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<ObjsetDataQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let offset = params.offset.unwrap_or(0);
+    let limit = normalize_objset_data_limit(params.limit);
+    let result = crate::ffi::mos_read_data(pool_ptr, objid, offset, fetch_limit(limit));
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_objset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
-
-    let datasets_json = datasets_result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let datasets_value = parse_json_value(datasets_json)?;
-    serde_json::from_value::<Vec<DatasetCatalogEntry>>(datasets_value).map_err(|err| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to parse dataset catalog: {err}"),
-        )
-    })
+    let Json(value) = json_from_result(result)?;
+    if limit == 0 {
+        return Ok(Json(empty_data_slice(value)));
+    }
+    Ok(Json(value))
 }
 
-fn resolve_pool_root_dir_obj(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    pool_name: &str,
-) -> Result<u64, ApiError> {
-    let root_result = crate::ffi::dsl_root_dir(pool_ptr);
-    if !root_result.is_ok() {
-        let err_msg = root_result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to resolve DSL root: {err_msg}"),
-        ));
+/// GET /api/pools/:pool/obj/:objid/nvlist
+pub async fn mos_decode_nvlist(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::decode_nvlist(pool_ptr, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("is not a packed nvlist") {
+            return Err(api_error_with(
+                StatusCode::BAD_REQUEST,
+                "NOT_AN_NVLIST",
+                err_msg.to_string(),
+                Some(
+                    "Packed nvlists live in objects such as the pool config or \
+feature descriptions; check the object type first."
+                        .to_string(),
+                ),
+                true,
+            ));
+        }
+        let status = if err_msg.contains("dmu_object_info failed for object")
+            || err_msg.contains("nvlist too large")
+        {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
-    let root_json = root_result
+    let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let root_value = parse_json_value(root_json)?;
-    let root_dir_obj = root_value["root_dir_obj"].as_u64().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "root_dir_obj missing in DSL root payload",
-        )
-    })?;
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
 
-    let children_result = crate::ffi::dsl_dir_children(pool_ptr, root_dir_obj);
-    if !children_result.is_ok() {
-        let err_msg = children_result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to enumerate DSL children: {err_msg}"),
-        ));
+#[derive(Debug, Deserialize)]
+pub struct ObjArrayQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+    pub types: Option<bool>,
+}
+
+/// GET /api/pools/:pool/obj/:objid/array?cursor=&limit=&types=
+pub async fn mos_obj_array(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<ObjArrayQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let cursor = params.cursor.unwrap_or(0);
+    let limit = normalize_limit(params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+    let with_types = params.types.unwrap_or(true);
+    let result = crate::ffi::obj_array(pool_ptr, objid, cursor, fetch_limit(limit), with_types);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("is not an object array") {
+            return Err(api_error_with(
+                StatusCode::BAD_REQUEST,
+                "NOT_AN_ARRAY",
+                err_msg.to_string(),
+                Some(
+                    "Only object_array and bpobj_subobj objects hold object ID arrays.".to_string(),
+                ),
+                true,
+            ));
+        }
+        let status = if err_msg.contains("dmu_object_info failed for object") {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
-    let children_json = children_result
+    let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let children_value = parse_json_value(children_json)?;
-    let children = parse_dsl_children(&children_value);
-    if let Some((_, objid)) = children.iter().find(|(name, _)| name == pool_name) {
-        return Ok(*objid);
-    }
+    let value = parse_json_value(json_str)?;
+    finish_page(limit, "entries", Ok(Json(value)))
+}
 
-    let head_result = crate::ffi::dsl_dir_head(pool_ptr, root_dir_obj);
-    if head_result.is_ok() {
-        let head_json = head_result.json().ok_or_else(|| {
-            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
-        })?;
-        let head_value = parse_json_value(head_json)?;
-        let head_obj = head_value["head_dataset_obj"].as_u64().unwrap_or(0);
-        if head_obj != 0 {
-            return Ok(root_dir_obj);
-        }
-    } else {
-        let err_msg = head_result.error_msg().unwrap_or("Unknown error");
-        tracing::debug!(
-            "dsl_dir_head failed for DSL root {} while resolving pool {}: {}",
-            root_dir_obj,
-            pool_name,
-            err_msg
-        );
-    }
+/// `dmu_object_type_t` value of `DMU_OT_DEADLIST`.
+const DMU_OT_DEADLIST: i32 = 50;
 
-    tracing::debug!(
-        "using DSL root {} as pool root for {} (no matching child and no head dataset)",
-        root_dir_obj,
-        pool_name
-    );
+/// Pool-wide bpobjs named in the MOS object directory, with their role.
+const POOL_BPOBJ_KEYS: &[(&str, &str)] = &[
+    ("free_bpobj", "free"),
+    ("com.delphix:obsolete_bpobj", "obsolete"),
+];
 
-    Ok(root_dir_obj)
+#[derive(Debug, Deserialize)]
+pub struct DeadlistsQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
 }
 
-fn resolve_dataset_dir_obj_by_name(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    pool_name: &str,
-    dataset_name: &str,
-) -> Result<u64, ApiError> {
-    let pool_prefix = format!("{pool_name}/");
-    if dataset_name != pool_name && !dataset_name.starts_with(&pool_prefix) {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "INVALID_DATASET_PATH",
-            format!("dataset '{dataset_name}' is not under pool '{pool_name}'"),
-            Some("Use paths rooted at the selected pool name.".to_string()),
-            true,
-        ));
+/// Stats row for one bpobj/deadlist; a failed read becomes an `error` row so
+/// one damaged header does not hide the rest of the page.
+fn bpobj_stats_row(pool_ptr: *mut crate::ffi::zdx_pool_t, objid: u64) -> Value {
+    match json_from_result(crate::ffi::bpobj_stats(pool_ptr, objid)) {
+        Ok(Json(value)) => value,
+        Err((_, Json(err))) => json!({ "objid": objid, "error": err["message"] }),
     }
+}
 
-    let result = crate::ffi::dsl_dir_by_name(pool_ptr, dataset_name)
-        .map_err(|err| api_error(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+/// GET /api/pools/:pool/deadlists?cursor=&limit=
+pub async fn pool_deadlists(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<DeadlistsQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+
+    let result = crate::ffi::zap_entries(pool_ptr, MOS_OBJECT_DIRECTORY, 0, MAX_PAGE_LIMIT);
+    let Json(directory) = json_from_result(result)?;
+    let pool_bpobjs: Vec<Value> = directory["entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry["name"].as_str()?;
+            let (_, role) = POOL_BPOBJ_KEYS.iter().find(|(key, _)| *key == name)?;
+            let objid = entry["value_u64"].as_u64().filter(|objid| *objid != 0)?;
+            let mut row = bpobj_stats_row(pool_ptr, objid);
+            row["role"] = json!(role);
+            Some(row)
+        })
+        .collect();
+
+    let result =
+        crate::ffi::mos_list_objects(pool_ptr, DMU_OT_DEADLIST, cursor, fetch_limit(limit));
+    let Json(page) = json_from_result(result)?;
+    let deadlists: Vec<Value> = page["objects"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|object| object["id"].as_u64())
+        .map(|objid| bpobj_stats_row(pool_ptr, objid))
+        .collect();
+
+    let value = json!({
+        "pool_bpobjs": pool_bpobjs,
+        "cursor": cursor,
+        "limit": limit,
+        "count": deadlists.len(),
+        "next": page["next"],
+        "has_more": !page["next"].is_null(),
+        "deadlists": deadlists,
+    });
+    finish_page(limit, "deadlists", Ok(Json(value)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BpobjBlkptrsQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+/// GET /api/pools/:pool/bpobj/:objid/blkptrs?cursor=&limit=
+pub async fn bpobj_blkptrs(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<BpobjBlkptrsQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+    let result = crate::ffi::bpobj_iter(pool_ptr, objid, cursor, fetch_limit(limit));
     if !result.is_ok() {
         let err_msg = result.error_msg().unwrap_or("Unknown error");
-        if err_msg.contains("No such file or directory")
-            || err_msg.contains("dsl_dir_hold failed")
-            || err_msg.contains("not found")
-        {
+        if err_msg.contains("expected bpobj") {
             return Err(api_error_with(
-                StatusCode::NOT_FOUND,
-                "DATASET_NOT_FOUND",
-                format!("dataset '{dataset_name}' not found"),
-                Some("Refresh dataset tree and verify the dataset path exists.".to_string()),
+                StatusCode::BAD_REQUEST,
+                "NOT_A_BPOBJ",
+                err_msg.to_string(),
+                Some(
+                    "Deadlists map txgs to bpobjs; list them with /obj/{objid}/zap and page each bpobj here."
+                        .to_string(),
+                ),
                 true,
             ));
         }
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to resolve dataset '{dataset_name}': {err_msg}"),
-        ));
+        let status = if err_msg.starts_with("dmu_object_info") {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
-
     let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
     let value = parse_json_value(json_str)?;
-    let head_obj = value["head_dataset_obj"].as_u64().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("head_dataset_obj missing in DSL payload for dataset '{dataset_name}'"),
-        )
-    })?;
-    if head_obj == 0 {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "DATASET_NO_HEAD",
-            format!(
-                "dataset '{}' has no head dataset (special/internal dataset)",
-                dataset_name
-            ),
-            Some("Use a user-visible filesystem dataset path.".to_string()),
-            true,
-        ));
-    }
-    Ok(head_obj)
+    finish_page(limit, "blkptrs", Ok(Json(value)))
 }
 
-fn resolve_dataset_candidates_from_pool_path_via_dsl(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    pool_name: &str,
-    normalized_path: &str,
-) -> Result<Vec<(String, String, u64)>, ApiError> {
-    let components = split_clean_path(normalized_path);
-    if components.is_empty() || components[0] != pool_name {
-        return Ok(Vec::new());
-    }
+fn read_object_directory(pool_ptr: *mut crate::ffi::zdx_pool_t) -> Result<Vec<Value>, ApiError> {
+    read_mos_zap(pool_ptr, MOS_OBJECT_DIRECTORY)
+}
 
-    let mut candidates = Vec::new();
-    for dataset_depth in (1..=components.len()).rev() {
-        let dataset_name = components[..dataset_depth].join("/");
-        let rel_path = if dataset_depth < components.len() {
-            components[dataset_depth..].join("/")
-        } else {
-            String::new()
-        };
-        match resolve_dataset_dir_obj_by_name(pool_ptr, pool_name, &dataset_name) {
-            Ok(objset_id) => candidates.push((dataset_name, rel_path, objset_id)),
-            Err(err) if err.0 == StatusCode::NOT_FOUND => continue,
-            Err(err) if err.0 == StatusCode::BAD_REQUEST => continue,
-            Err(err) => return Err(err),
+/// Every entry of a MOS ZAP object, paging through `zap_entries`.
+fn read_mos_zap(pool_ptr: *mut crate::ffi::zdx_pool_t, objid: u64) -> Result<Vec<Value>, ApiError> {
+    let mut raw_entries: Vec<Value> = Vec::new();
+    let mut cursor = 0u64;
+    loop {
+        let result = crate::ffi::zap_entries(pool_ptr, objid, cursor, MAX_PAGE_LIMIT);
+        let Json(page) = json_from_result(result)?;
+        if let Some(entries) = page["entries"].as_array() {
+            raw_entries.extend(entries.iter().cloned());
+        }
+        match page["next"].as_u64() {
+            Some(next) if next != cursor => cursor = next,
+            _ => break,
         }
     }
+    Ok(raw_entries)
+}
 
-    Ok(candidates)
+/// GET /api/pools/:pool/mos/directory
+pub async fn mos_directory(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let raw_entries = read_object_directory(pool_ptr)?;
+
+    let mut entries: Vec<Value> = raw_entries
+        .iter()
+        .map(|entry| {
+            let objid = entry["maybe_object_ref"]
+                .as_bool()
+                .unwrap_or(false)
+                .then(|| entry["target_obj"].as_u64())
+                .flatten()
+                .filter(|objid| *objid != 0);
+            let type_name = objid.and_then(|objid| {
+                let result = crate::ffi::mos_get_object(pool_ptr, objid);
+                let value = parse_json_value(result.json()?).ok()?;
+                value["type"]["name"].as_str().map(str::to_string)
+            });
+            json!({
+                "name": entry["name"],
+                "objid": objid,
+                "type_name": type_name,
+                "value_preview": entry["value_preview"],
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        a["name"]
+            .as_str()
+            .unwrap_or_default()
+            .cmp(b["name"].as_str().unwrap_or_default())
+    });
+
+    Ok(Json(json!({
+        "object": MOS_OBJECT_DIRECTORY,
+        "count": entries.len(),
+        "entries": entries,
+    })))
 }
 
-fn resolve_zpl_path_context(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    pool_name: &str,
-    zpl_path: &str,
-    prefer_dsl_resolution: bool,
-) -> Result<ZplPathContext, ApiError> {
-    let trimmed = zpl_path.trim();
-    if trimmed.is_empty() {
-        return Err(api_error_with(
+#[derive(Debug, Deserialize)]
+pub struct BlockTreeQuery {
+    pub max_depth: Option<u64>,
+    pub max_nodes: Option<u64>,
+    pub min_txg: Option<u64>,
+    pub max_txg: Option<u64>,
+    pub strict: Option<bool>,
+}
+
+/// Birth txg window for a block tree; unset bounds are open.
+fn block_tree_txg_range(params: &BlockTreeQuery) -> Result<(u64, u64), ApiError> {
+    let min_txg = params.min_txg.unwrap_or(0);
+    let max_txg = params.max_txg.unwrap_or(u64::MAX);
+    if min_txg > max_txg {
+        return Err(api_error(
             StatusCode::BAD_REQUEST,
-            "INVALID_PATH",
-            "path is empty",
-            Some(
-                "Provide a dataset-relative path like pool/dataset/file or an absolute mount path."
-                    .to_string(),
-            ),
-            true,
+            "min_txg must be <= max_txg",
         ));
     }
+    Ok((min_txg, max_txg))
+}
 
-    let absolute_path = if trimmed.starts_with('/') {
-        trimmed.to_string()
-    } else {
-        format!("/{trimmed}")
-    };
-    let normalized_path = trimmed.trim_start_matches('/').to_string();
+/// Report the txg window as requested (`null` for open bounds) and finish.
+fn finish_block_tree(params: &BlockTreeQuery, mut value: Value) -> ApiResult {
+    value["min_txg"] = json!(params.min_txg);
+    value["max_txg"] = json!(params.max_txg);
+    finish_traversal(params.strict, value)
+}
 
-    let (dataset_name, rel_path, objset_id) = if prefer_dsl_resolution {
-        let dsl_candidates = resolve_dataset_candidates_from_pool_path_via_dsl(
-            pool_ptr,
-            pool_name,
-            &normalized_path,
-        )?;
-        if dsl_candidates.is_empty() {
-            return Err(api_error_with(
-                StatusCode::BAD_REQUEST,
-                "DATASET_PATH_UNRESOLVED",
-                format!("could not resolve dataset for path '{zpl_path}'"),
-                Some(
-                    "In offline mode, use pool/dataset-relative paths (for example: \
-pool/dataset/file)."
-                        .to_string(),
-                ),
-                true,
-            ));
-        }
+/// GET /api/pools/:pool/obj/:objid/block-tree?max_depth=&max_nodes=&min_txg=&max_txg=&strict=
+pub async fn mos_block_tree(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<BlockTreeQuery>,
+) -> ApiResult {
+    let (min_txg, max_txg) = block_tree_txg_range(&params)?;
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let max_depth = normalize_block_tree_depth(params.max_depth);
+    let max_nodes = normalize_block_tree_nodes(params.max_nodes)?;
+    let result =
+        crate::ffi::mos_block_tree(pool_ptr, objid, max_depth, max_nodes, min_txg, max_txg);
+    let Json(value) = json_from_result(result)?;
+    finish_block_tree(&params, value)
+}
 
-        let mut resolved: Option<(String, String, u64)> = None;
-        for (candidate_dataset_name, candidate_rel_path, candidate_objset_id) in dsl_candidates {
-            if candidate_objset_id == 0 {
-                continue;
-            }
-            resolved = Some((
-                candidate_dataset_name,
-                candidate_rel_path,
-                candidate_objset_id,
-            ));
-            break;
-        }
+/// GET /api/pools/:pool/obj/:objid/full
+pub async fn obj_get_full(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::obj_get(pool_ptr, objid);
+    let Json(value) = json_from_result(result)?;
+    let value = with_known_name(&state, pool_ptr, &pool, objid, value);
+    Ok(Json(with_txg_times(&state, pool_ptr, &pool, value)))
+}
 
-        if let Some(result) = resolved {
-            result
-        } else {
-            return Err(api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("failed to resolve dataset candidates for path '{zpl_path}'"),
-            ));
-        }
-    } else {
-        match load_dataset_catalog(pool_ptr) {
-            Ok(catalog) => {
-                let mut candidates: Vec<(usize, String, String)> = Vec::new();
-                for entry in catalog
-                    .iter()
-                    .filter(|entry| entry.dataset_type == "filesystem")
-                {
-                    if let Some(rel) = dataset_path_match(&entry.name, &normalized_path) {
-                        candidates.push((entry.name.len(), entry.name.clone(), rel));
-                    }
+/// GET /api/mos/types
+pub async fn list_dmu_types() -> ApiResult {
+    let result = crate::ffi::list_dmu_types();
+    json_from_result(result)
+}
 
-                    if let Some(mountpoint) = entry.mountpoint.as_deref() {
-                        if entry.mounted != Some(false) {
-                            if let Some(rel) = mountpoint_path_match(mountpoint, &absolute_path) {
-                                candidates.push((mountpoint.len(), entry.name.clone(), rel));
-                            }
-                        }
-                    }
-                }
+#[derive(Debug, Deserialize)]
+pub struct ZapEntriesQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+}
 
-                candidates.sort_by(|a, b| b.0.cmp(&a.0));
-                if candidates.is_empty() {
-                    return Err(api_error_with(
-                    StatusCode::BAD_REQUEST,
-                    "DATASET_PATH_UNRESOLVED",
-                    format!("could not resolve dataset for path '{zpl_path}'"),
-                    Some(
-                        "Use either an absolute mounted path (/pool/dataset/file) or a dataset path \
-like pool/dataset/file."
-                            .to_string(),
-                    ),
-                    true,
-                ));
-                }
+/// GET /api/pools/:pool/obj/:objid/zap/info
+pub async fn zap_info(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::zap_info(pool_ptr, objid);
+    json_from_result(result)
+}
 
-                let mut resolved: Option<(String, String, u64)> = None;
+/// GET /api/pools/:pool/obj/:objid/zap
+pub async fn zap_entries(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<ZapEntriesQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+    let result = crate::ffi::zap_entries(pool_ptr, objid, cursor, fetch_limit(limit));
+    finish_page(limit, "entries", json_from_result(result))
+}
 
-                for (_, candidate_dataset_name, candidate_rel_path) in candidates {
-                    let candidate_objset_id = match resolve_dataset_dir_obj_by_name(
-                        pool_ptr,
-                        pool_name,
-                        &candidate_dataset_name,
-                    ) {
-                        Ok(value) => value,
-                        Err(_) => continue,
-                    };
+/// GET /api/pools/:pool/dsl/dir/:objid/children
+pub async fn dsl_dir_children(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dsl_dir_children(pool_ptr, objid);
+    json_from_result(result)
+}
 
-                    if candidate_objset_id == 0 {
-                        continue;
-                    }
-                    resolved = Some((
-                        candidate_dataset_name,
-                        candidate_rel_path,
-                        candidate_objset_id,
-                    ));
-                    break;
-                }
+/// GET /api/pools/:pool/dsl/dir/:objid/head
+pub async fn dsl_dir_head(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dsl_dir_head(pool_ptr, objid);
+    json_from_result(result)
+}
 
-                if let Some(result) = resolved {
-                    result
-                } else {
-                    return Err(api_error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("failed to resolve dataset candidates for path '{zpl_path}'"),
-                    ));
-                }
-            }
-            Err(catalog_err) => {
-                let dsl_candidates = resolve_dataset_candidates_from_pool_path_via_dsl(
-                    pool_ptr,
-                    pool_name,
-                    &normalized_path,
-                )?;
-                if dsl_candidates.is_empty() {
-                    return Err(catalog_err);
-                }
+/// GET /api/pools/:pool/dsl/dir/:objid/props
+pub async fn dsl_dir_props(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dsl_dir_props(pool_ptr, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("is not DSL dir") {
+            return Err(api_error(StatusCode::BAD_REQUEST, err_msg.to_string()));
+        }
+    }
+    json_from_result(result)
+}
 
-                let mut resolved: Option<(String, String, u64)> = None;
-                for (candidate_dataset_name, candidate_rel_path, candidate_objset_id) in
-                    dsl_candidates
-                {
-                    if candidate_objset_id == 0 {
-                        continue;
-                    }
-                    resolved = Some((
-                        candidate_dataset_name,
-                        candidate_rel_path,
-                        candidate_objset_id,
-                    ));
-                    break;
-                }
+/// GET /api/pools/:pool/dsl/root
+pub async fn dsl_root_dir(State(state): State<AppState>, Path(pool): Path<String>) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dsl_root_dir(pool_ptr);
+    json_from_result(result)
+}
 
-                if let Some(result) = resolved {
-                    result
-                } else {
-                    return Err(catalog_err);
-                }
-            }
-        }
-    };
+#[derive(Debug, Deserialize)]
+pub struct BlockQuery {
+    pub vdev: u64,
+    pub offset: u64,
+    pub asize: u64,
+    pub limit: Option<u64>,
+    pub follow_gang: Option<bool>,
+}
 
-    let walk_path = if rel_path.is_empty() {
-        "/".to_string()
-    } else {
-        format!("/{rel_path}")
-    };
-    tracing::debug!(
-        "zpl context resolved: pool={} dataset={} objset_id={} rel_path={} walk_path={}",
-        pool_name,
-        dataset_name,
-        objset_id,
-        rel_path,
-        walk_path
+/// GET /api/pools/:pool/block?vdev=...&offset=...&asize=...&limit=...&follow_gang=...
+pub async fn read_block(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<BlockQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+
+    if params.asize == 0 {
+        return Err(api_error(StatusCode::BAD_REQUEST, "asize must be > 0"));
+    }
+
+    let max_read: u64 = 1 << 20;
+    let limit = reject_zero("limit", params.limit)?.unwrap_or(64 * 1024);
+    let size = params.asize.min(limit).min(max_read);
+
+    let result = crate::ffi::read_block(
+        pool_ptr,
+        params.vdev,
+        params.offset,
+        size,
+        params.follow_gang.unwrap_or(false),
     );
-    let walk_result = crate::ffi::objset_walk(pool_ptr, objset_id, &walk_path)
-        .map_err(|err| api_error(StatusCode::BAD_REQUEST, err))?;
-    if !walk_result.is_ok() {
-        let err_msg = walk_result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "ZPL_WALK_FAILED",
-            format!("failed to walk path '{walk_path}': {err_msg}"),
-            Some("Verify the file path and dataset context.".to_string()),
-            true,
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
         ));
     }
-    let walk_json = walk_result
+
+    let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let walk_value = parse_json_value(walk_json)?;
-    let walk = serde_json::from_value::<ObjsetWalkPayload>(walk_value).map_err(|err| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to parse walk payload: {err}"),
-        )
-    })?;
 
-    if !walk.found || !walk.remaining.is_empty() {
-        return Err(api_error_with(
-            StatusCode::NOT_FOUND,
-            "PATH_NOT_FOUND",
-            format!("path '{walk_path}' could not be fully resolved"),
-            Some("The requested file may not exist in this dataset or snapshot state.".to_string()),
-            true,
-        ));
-    }
+    let mut value = parse_json_value(json_str)?;
 
-    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, walk.objid);
-    if !stat_result.is_ok() {
-        let err_msg = stat_result.error_msg().unwrap_or("Unknown error");
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "OBJSET_STAT_FAILED",
-            format!("failed to stat object {}: {}", walk.objid, err_msg),
-            None,
-            true,
+    value["asize"] = Value::from(params.asize);
+    value["truncated"] = Value::from(size < params.asize);
+    value["requested"] = Value::from(size);
+
+    Ok(Json(value))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DvaOwnerQuery {
+    pub vdev: u64,
+    pub offset: u64,
+    pub asize: Option<u64>,
+    pub scan_limit: u64,
+    pub objset_id: Option<u64>,
+}
+
+/// GET /api/pools/:pool/dva/owner?vdev=&offset=&asize=&scan_limit=&objset_id=
+pub async fn dva_owner(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<DvaOwnerQuery>,
+) -> ApiResult {
+    ensure_pool(&state, &pool)?;
+    reject_zero("scan_limit", Some(params.scan_limit))?;
+    let scan_limit = params.scan_limit.min(DVA_OWNER_MAX_SCAN);
+    // An asize of 0 matches the single byte at `offset`.
+    let asize = params.asize.unwrap_or(0).min(u64::MAX - params.offset);
+    // The MOS has no dataset object, so 0 selects it on the native side.
+    let objset_id = params.objset_id.unwrap_or(0);
+
+    let mut blkptrs_visited = 0;
+    let mut found = Value::Null;
+    let scan = scan_objset_in_batches(
+        &state,
+        &pool,
+        scan_limit,
+        DVA_OWNER_SCAN_BATCH,
+        |pool_ptr, start, batch_limit| {
+            // The native side reports what it visited; never trust it to
+            // stay within the budget it was given.
+            let blkptr_budget = DVA_OWNER_MAX_BLKPTRS.saturating_sub(blkptrs_visited);
+            if blkptr_budget == 0 {
+                return Ok(ScanStep::Stop {
+                    scanned: 0,
+                    truncated: true,
+                });
+            }
+            let batch = objset_scan_batch(crate::ffi::dva_owner(
+                pool_ptr,
+                objset_id,
+                params.vdev,
+                params.offset,
+                asize,
+                start,
+                batch_limit,
+                blkptr_budget,
+            ))?;
+            let scanned = batch["scanned"].as_u64().unwrap_or(0);
+            blkptrs_visited =
+                blkptrs_visited.saturating_add(batch["blkptrs_visited"].as_u64().unwrap_or(0));
+            if !batch["match"].is_null() {
+                found = batch["match"].clone();
+                return Ok(ScanStep::Stop {
+                    scanned,
+                    truncated: false,
+                });
+            }
+            if batch["blkptr_budget_exhausted"].as_bool() == Some(true) {
+                return Ok(ScanStep::Stop {
+                    scanned,
+                    truncated: true,
+                });
+            }
+            Ok(ScanStep::from_batch(&batch))
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "vdev": params.vdev,
+        "offset": params.offset,
+        "asize": asize,
+        "scope": if params.objset_id.is_some() { "objset" } else { "mos" },
+        "scan_limit": scan_limit,
+        "scanned": scan.scanned,
+        "blkptrs_visited": blkptrs_visited,
+        "truncated": scan.truncated,
+        "found": !found.is_null(),
+        "objset_id": params.objset_id,
+        "objid": found["objid"],
+        "level": found["level"],
+        "block_id": found["block_id"],
+        "is_spill": found["is_spill"],
+        "dva_index": found["dva_index"],
+        "dva": found["dva"],
+    })))
+}
+
+fn fetch_dsl_dir_head(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    objid: u64,
+) -> Result<Value, ApiError> {
+    let head_result = crate::ffi::dsl_dir_head(pool_ptr, objid);
+    if !head_result.is_ok() {
+        let err_msg = head_result.error_msg().unwrap_or("Unknown error");
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
         ));
     }
-    let stat_json = stat_result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let stat_value = parse_json_value(stat_json)?;
-    let stat = serde_json::from_value::<ObjsetStatPayload>(stat_value).map_err(|err| {
+    let head_json = head_result.json().ok_or_else(|| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to parse stat payload: {err}"),
+            "Missing JSON in head result",
         )
     })?;
+    parse_json_value(head_json)
+}
 
-    if stat.type_name != "file" {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "NOT_A_FILE",
-            format!(
-                "resolved path '{walk_path}' is a {} object, not a file",
-                stat.type_name
-            ),
-            Some("Use this endpoint only for file paths.".to_string()),
-            true,
+fn fetch_dsl_dir_children(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    objid: u64,
+) -> Result<Value, ApiError> {
+    let children_result = crate::ffi::dsl_dir_children(pool_ptr, objid);
+    if !children_result.is_ok() {
+        let err_msg = children_result.error_msg().unwrap_or("Unknown error");
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
         ));
     }
+    let children_json = children_result.json().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Missing JSON in children result",
+        )
+    })?;
+    parse_json_value(children_json)
+}
 
-    let filename = split_clean_path(&rel_path)
-        .last()
-        .map(|segment| (*segment).to_string())
-        .filter(|value| !value.is_empty())
-        .unwrap_or_else(|| format!("objset-{objset_id}-obj-{}", walk.objid));
+#[derive(Debug, Deserialize)]
+pub struct DatasetTreeQuery {
+    pub depth: Option<u8>,
+    pub limit: Option<usize>,
+    pub dir: Option<u64>,
+    pub child_cursor: Option<usize>,
+    pub child_limit: Option<usize>,
+    pub strict: Option<bool>,
+}
+
+/// Sorts children by name and returns one page plus the cursor of the next page.
+fn page_dsl_children(
+    mut children: Vec<(String, u64)>,
+    cursor: usize,
+    limit: usize,
+) -> (Vec<(String, u64)>, Option<usize>) {
+    children.sort();
+    let total = children.len();
+    let end = cursor.saturating_add(limit).min(total);
+    let page = children.into_iter().skip(cursor).take(limit).collect();
+    let next = (end < total).then_some(end);
+    (page, next)
+}
+
+/// Budget and progress of one dataset-tree walk.
+struct DatasetTreeWalk {
+    limit: usize,
+    child_limit: usize,
+    seen: usize,
+    truncated: bool,
+}
+
+/// One DSL dir of the dataset tree with up to `depth` levels below it.
+/// `fetch_dir` returns a dir's `/head` and `/children` payloads; special dirs
+/// (`$MOS`, `$FREE`, ...) become leaves without being fetched.
+fn build_dataset_tree_node(
+    name: String,
+    objid: u64,
+    depth: u8,
+    child_cursor: usize,
+    walk: &mut DatasetTreeWalk,
+    fetch_dir: &mut dyn FnMut(u64) -> Result<(Value, Value), ApiError>,
+) -> Result<Value, ApiError> {
+    if walk.seen >= walk.limit {
+        walk.truncated = true;
+        return Ok(serde_json::json!({
+            "name": name,
+            "dsl_dir_obj": objid,
+            "head_dataset_obj": null,
+            "child_dir_zapobj": null,
+            "special": false,
+            "child_count": null,
+            "child_next_cursor": null,
+            "children_truncated": false,
+            "children": []
+        }));
+    }
+    walk.seen += 1;
 
-    Ok(ZplPathContext {
-        dataset_name,
-        objset_id,
-        rel_path,
-        objid: walk.objid,
-        file_size: stat.size,
-        filename,
-    })
+    let (head_value, children_value) = fetch_dir(objid)?;
+    let head_dataset_obj = head_value["head_dataset_obj"]
+        .as_u64()
+        .filter(|value| *value != 0);
+    let child_dir_zapobj = children_value["child_dir_zapobj"].as_u64();
+    let all_children = parse_dsl_children(&children_value);
+    let child_count = all_children.len();
+
+    let mut children_nodes: Vec<Value> = Vec::new();
+    let mut child_next_cursor = None;
+    if depth > 0 {
+        let (page, next) = page_dsl_children(all_children, child_cursor, walk.child_limit);
+        child_next_cursor = next;
+        for (child_name, child_objid) in page {
+            if is_special_dsl_dir(&child_name) {
+                children_nodes.push(special_dataset_tree_node(&child_name, child_objid));
+                continue;
+            }
+            let node =
+                build_dataset_tree_node(child_name, child_objid, depth - 1, 0, walk, fetch_dir)?;
+            children_nodes.push(node);
+            if walk.truncated {
+                break;
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "name": name,
+        "dsl_dir_obj": objid,
+        "head_dataset_obj": head_dataset_obj,
+        "child_dir_zapobj": child_dir_zapobj,
+        "special": false,
+        "child_count": child_count,
+        "child_next_cursor": child_next_cursor,
+        "children_truncated": child_next_cursor.is_some(),
+        "children": children_nodes
+    }))
 }
 
-fn normalize_objset_zpl_path(zpl_path: &str) -> Result<String, ApiError> {
-    let trimmed = zpl_path.trim();
-    if trimmed.is_empty() {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "INVALID_PATH",
-            "path is empty",
-            Some("Provide a file path rooted at the selected objset.".to_string()),
-            true,
-        ));
-    }
+/// GET /api/pools/:pool/datasets/tree?depth=&limit=&dir=&child_cursor=&child_limit=&strict=
+pub async fn dataset_tree(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<DatasetTreeQuery>,
+) -> ApiResult {
+    finish_traversal(params.strict, dataset_tree_value(&state, &pool, &params)?)
+}
 
-    let parts = split_clean_path(trimmed);
-    if parts.is_empty() {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "INVALID_PATH",
-            "path is empty",
-            Some("Provide a file path rooted at the selected objset.".to_string()),
-            true,
-        ));
-    }
+/// The `/datasets/tree` payload, before `strict` is applied.
+fn dataset_tree_value(
+    state: &AppState,
+    pool: &str,
+    params: &DatasetTreeQuery,
+) -> Result<Value, ApiError> {
+    let pool_handle = ensure_pool(state, pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let max_depth = params.depth.unwrap_or(4);
+    let limit = reject_zero("limit", params.limit)?.unwrap_or(500);
+    let child_cursor = params.child_cursor.unwrap_or(0);
+    // Unbounded unless asked for, so clients that ignore `child_next_cursor`
+    // still see every child.
+    let child_limit = reject_zero("child_limit", params.child_limit)?
+        .map(|limit| limit.min(DATASET_TREE_MAX_CHILD_LIMIT));
+
+    // A `dir` subtree is requested by a caller that already knows its name.
+    let (root_name, root_dir) = match params.dir {
+        Some(dir) => (dir.to_string(), dir),
+        None => (pool.to_string(), resolve_pool_root_dir_obj(pool_ptr, pool)?),
+    };
+
+    let mut walk = DatasetTreeWalk {
+        limit,
+        child_limit: child_limit.unwrap_or(usize::MAX),
+        seen: 0,
+        truncated: false,
+    };
+    let root_node = build_dataset_tree_node(
+        root_name,
+        root_dir,
+        max_depth,
+        child_cursor,
+        &mut walk,
+        &mut |objid| {
+            Ok((
+                fetch_dsl_dir_head(pool_ptr, objid)?,
+                fetch_dsl_dir_children(pool_ptr, objid)?,
+            ))
+        },
+    )?;
 
-    Ok(format!("/{}", parts.join("/")))
+    Ok(serde_json::json!({
+        "root": root_node,
+        "depth": max_depth,
+        "limit": limit,
+        "child_limit": child_limit,
+        "truncated": walk.truncated,
+        "count": walk.seen
+    }))
 }
 
-fn resolve_objset_scoped_zpl_path_context(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    objset_id: u64,
-    zpl_path: &str,
-) -> Result<ObjsetScopedZplPathContext, ApiError> {
-    let walk_path = normalize_objset_zpl_path(zpl_path)?;
+#[derive(Debug, Deserialize)]
+pub struct RecursiveUsageQuery {
+    pub depth: Option<u8>,
+    pub limit: Option<usize>,
+    pub format: Option<String>,
+}
 
-    let walk_result = crate::ffi::objset_walk(pool_ptr, objset_id, &walk_path)
-        .map_err(|err| api_error(StatusCode::BAD_REQUEST, err))?;
-    if !walk_result.is_ok() {
-        let err_msg = walk_result.error_msg().unwrap_or("Unknown error");
-        let status = if err_msg.contains("No such file or directory") {
-            StatusCode::NOT_FOUND
-        } else {
-            StatusCode::BAD_REQUEST
-        };
-        let code = if status == StatusCode::NOT_FOUND {
-            "PATH_NOT_FOUND"
-        } else {
-            "ZPL_WALK_FAILED"
-        };
-        return Err(api_error_with(
-            status,
-            code,
-            format!("failed to walk path '{walk_path}': {err_msg}"),
-            Some("Verify the requested objset id and file path.".to_string()),
+/// Parse `?format=` for recursive usage; absent means the flat row list.
+fn parse_recursive_usage_format(raw: Option<&str>) -> Result<&'static str, ApiError> {
+    match raw
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("flat") => Ok("flat"),
+        Some("treemap") => Ok("treemap"),
+        Some(other) => Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_QUERY",
+            format!("invalid recursive usage format '{other}'"),
+            Some("Use format=flat or treemap.".to_string()),
             true,
-        ));
+        )),
     }
+}
 
-    let walk_json = walk_result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let walk_value = parse_json_value(walk_json)?;
-    let walk = serde_json::from_value::<ObjsetWalkPayload>(walk_value).map_err(|err| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to parse walk payload: {err}"),
-        )
-    })?;
+/// One `recursive-usage` row from a `dsl_dir_head` payload.
+fn recursive_usage_row(name: &str, dir_obj: u64, depth: u8, head: &Value) -> Value {
+    let breakdown = &head["used_breakdown"];
+    json!({
+        "name": name,
+        "dsl_dir_obj": dir_obj,
+        "head_dataset_obj": head["head_dataset_obj"].as_u64().filter(|obj| *obj != 0),
+        "depth": depth,
+        "used_bytes": head["used_bytes"],
+        "referenced_bytes": head["referenced_bytes"],
+        "used_by_dataset_bytes": breakdown["dataset"],
+        "used_by_snapshots_bytes": breakdown["snapshots"],
+        "used_by_children_bytes": breakdown["children"],
+        "used_by_refreservation_bytes": breakdown["refreservation"],
+    })
+}
 
-    if !walk.found || !walk.remaining.is_empty() {
-        return Err(api_error_with(
-            StatusCode::NOT_FOUND,
-            "PATH_NOT_FOUND",
-            format!("path '{walk_path}' could not be fully resolved"),
-            Some("The requested file may not exist in the selected objset.".to_string()),
-            true,
-        ));
+/// Subtree totals. `used_bytes` is the root's recursive figure; the other
+/// sums cover only the rows that were visited.
+fn recursive_usage_totals(rows: &[Value]) -> Value {
+    let sum = |key: &str| -> u64 {
+        rows.iter()
+            .filter_map(|row| row[key].as_u64())
+            .fold(0u64, |acc, value| acc.saturating_add(value))
+    };
+    json!({
+        "used_bytes": rows.first().map(|root| root["used_bytes"].clone()).unwrap_or(Value::Null),
+        "referenced_bytes": sum("referenced_bytes"),
+        "used_by_datasets_bytes": sum("used_by_dataset_bytes"),
+        "used_by_snapshots_bytes": sum("used_by_snapshots_bytes"),
+        "used_by_refreservation_bytes": sum("used_by_refreservation_bytes"),
+    })
+}
+
+/// Nest pre-order rows into `{ name, value, children }` nodes. Each `value`
+/// is the node's recursive `used_bytes` minus its visited children's, so
+/// summing a subtree reproduces its `used_bytes` even when the walk was cut
+/// short: space under unvisited descendants stays with the nearest visited
+/// ancestor.
+fn recursive_usage_treemap(rows: &[Value]) -> Value {
+    fn build(rows: &[Value], next: &mut usize) -> Value {
+        let row = &rows[*next];
+        *next += 1;
+        let depth = row["depth"].as_u64().unwrap_or(0);
+        let mut children = Vec::new();
+        while *next < rows.len() && rows[*next]["depth"].as_u64() == Some(depth + 1) {
+            children.push(build(rows, next));
+        }
+
+        let used = row["used_bytes"].as_u64().unwrap_or(0);
+        let children_used = children
+            .iter()
+            .filter_map(|child| child["used_bytes"].as_u64())
+            .fold(0u64, |acc, value| acc.saturating_add(value));
+        let path = row["name"].as_str().unwrap_or(".");
+        json!({
+            "name": path.rsplit('/').next().unwrap_or(path),
+            "path": path,
+            "dsl_dir_obj": row["dsl_dir_obj"],
+            "used_bytes": row["used_bytes"],
+            "value": used.saturating_sub(children_used),
+            "children": children,
+        })
     }
 
-    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, walk.objid);
-    if !stat_result.is_ok() {
-        let err_msg = stat_result.error_msg().unwrap_or("Unknown error");
-        let status = if is_objset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error_with(
-            status,
-            "OBJSET_STAT_FAILED",
-            format!("failed to stat object {}: {}", walk.objid, err_msg),
-            None,
-            status.is_client_error(),
-        ));
+    if rows.is_empty() {
+        return Value::Null;
     }
+    build(rows, &mut 0)
+}
 
-    let stat_json = stat_result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let stat_value = parse_json_value(stat_json)?;
-    let stat = serde_json::from_value::<ObjsetStatPayload>(stat_value).map_err(|err| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("failed to parse stat payload: {err}"),
-        )
-    })?;
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/recursive-usage?depth=&limit=&format=
+pub async fn dataset_recursive_usage(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<RecursiveUsageQuery>,
+) -> ApiResult {
+    let format = parse_recursive_usage_format(params.format.as_deref())?;
+    let limit = reject_zero("limit", params.limit)?
+        .unwrap_or(RECURSIVE_USAGE_DEFAULT_LIMIT)
+        .min(RECURSIVE_USAGE_MAX_LIMIT);
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let max_depth = params
+        .depth
+        .unwrap_or(RECURSIVE_USAGE_DEFAULT_DEPTH)
+        .min(RECURSIVE_USAGE_MAX_DEPTH);
+
+    // Pre-order walk so rows read like `zfs list -o space -r`.
+    let mut rows = Vec::new();
+    let mut stack = vec![(".".to_string(), dir_obj, 0u8)];
+    let mut truncated = false;
+    while let Some((name, objid, depth)) = stack.pop() {
+        if rows.len() >= limit {
+            truncated = true;
+            break;
+        }
+        let head = fetch_dsl_dir_head(pool_ptr, objid)?;
+        rows.push(recursive_usage_row(&name, objid, depth, &head));
 
-    if stat.type_name != "file" {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "NOT_A_FILE",
-            format!(
-                "resolved path '{walk_path}' is a {} object, not a file",
-                stat.type_name
-            ),
-            Some("Use this endpoint only for file paths.".to_string()),
-            true,
-        ));
+        let children = parse_dsl_children(&fetch_dsl_dir_children(pool_ptr, objid)?);
+        if children.is_empty() {
+            continue;
+        }
+        if depth >= max_depth {
+            truncated = true;
+            continue;
+        }
+        let mut children: Vec<_> = children
+            .into_iter()
+            .filter(|(child_name, _)| !is_special_dsl_dir(child_name))
+            .collect();
+        children.sort();
+        for (child_name, child_objid) in children.into_iter().rev() {
+            let child_path = if name == "." {
+                child_name
+            } else {
+                format!("{name}/{child_name}")
+            };
+            stack.push((child_path, child_objid, depth + 1));
+        }
     }
 
-    let rel_path = walk_path.trim_start_matches('/').to_string();
-    let filename = split_clean_path(&walk_path)
-        .last()
-        .map(|segment| (*segment).to_string())
-        .filter(|value| !value.is_empty())
-        .unwrap_or_else(|| format!("objset-{objset_id}-obj-{}", walk.objid));
+    let totals = recursive_usage_totals(&rows);
+    let mut response = json!({
+        "dsl_dir_obj": dir_obj,
+        "depth": max_depth,
+        "limit": limit,
+        "truncated": truncated,
+        "count": rows.len(),
+        "format": format,
+        "totals": totals,
+    });
+    if format == "treemap" {
+        response["tree"] = recursive_usage_treemap(&rows);
+    } else {
+        response["datasets"] = Value::Array(rows);
+    }
+    Ok(Json(response))
+}
 
-    Ok(ObjsetScopedZplPathContext {
-        objset_id,
-        rel_path,
-        objid: walk.objid,
-        file_size: stat.size,
-        filename,
-    })
+#[derive(Debug, Deserialize)]
+pub struct DatasetObjsetQuery {
+    pub follow_origin: Option<bool>,
 }
 
-fn resolve_snapshot_objset_id(
+/// Origin snapshot dsobj from a `dataset_origin` payload, only for real
+/// clones (not datasets whose origin is `$ORIGIN@$ORIGIN`).
+fn clone_origin_dsobj(origin: &Value) -> Option<u64> {
+    if !origin["is_clone"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    origin["origin"]["snapshot_dsobj"]
+        .as_u64()
+        .filter(|dsobj| *dsobj != 0)
+}
+
+/// `resolve_dataset_objset` plus the clone's origin snapshot objset:
+/// `is_clone`, `origin` (`null` unless a clone), and `origin_objset_id`.
+fn resolve_dataset_objset_with_origin(
     pool_ptr: *mut crate::ffi::zdx_pool_t,
-    dsobj: u64,
-) -> Result<u64, ApiError> {
-    let result = crate::ffi::dataset_objset(pool_ptr, dsobj);
+    dir_obj: u64,
+) -> Result<Value, ApiError> {
+    let mut response = resolve_dataset_objset(pool_ptr, dir_obj)?;
+
+    let result = crate::ffi::dataset_origin(pool_ptr, dir_obj);
     if !result.is_ok() {
         let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_dataset_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
+        ));
     }
-
     let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    value["objset_id"].as_u64().ok_or_else(|| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "snapshot objset payload missing objset_id",
-        )
-    })
-}
+    let origin_payload = parse_json_value(json_str)?;
 
-fn parse_range_header(headers: &HeaderMap, total_size: u64) -> Result<(u64, u64, bool), ApiError> {
-    let Some(range_header) = headers.get(RANGE) else {
-        if total_size == 0 {
-            return Ok((0, 0, false));
-        }
-        return Ok((0, total_size - 1, false));
+    let Some(snapshot_dsobj) = clone_origin_dsobj(&origin_payload) else {
+        response["is_clone"] = json!(false);
+        response["origin"] = Value::Null;
+        response["origin_objset_id"] = Value::Null;
+        return Ok(response);
     };
 
-    let header_value = range_header.to_str().map_err(|_| {
-        api_error_with(
-            StatusCode::BAD_REQUEST,
-            "BAD_RANGE",
-            "invalid Range header",
-            None,
-            true,
-        )
-    })?;
-    let trimmed = header_value.trim();
-    if !trimmed.starts_with("bytes=") {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "BAD_RANGE",
-            format!("unsupported Range header '{trimmed}'"),
-            Some("Use a single byte range, for example: bytes=0-1048575".to_string()),
-            true,
-        ));
+    let objset_result = crate::ffi::dataset_objset(pool_ptr, snapshot_dsobj);
+    let Json(origin_objset) = json_from_result(objset_result)?;
+    let mut origin = origin_payload["origin"].clone();
+    origin["objset_id"] = origin_objset["objset_id"].clone();
+    response["is_clone"] = json!(true);
+    response["origin_objset_id"] = origin_objset["objset_id"].clone();
+    response["origin"] = origin;
+    Ok(response)
+}
+
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/head
+pub async fn dataset_head(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<DatasetObjsetQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let response = if params.follow_origin.unwrap_or(false) {
+        resolve_dataset_objset_with_origin(pool_ptr, dir_obj)?
+    } else {
+        resolve_dataset_objset(pool_ptr, dir_obj)?
+    };
+    Ok(Json(response))
+}
+
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/objset
+pub async fn dataset_objset(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<DatasetObjsetQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let response = if params.follow_origin.unwrap_or(false) {
+        resolve_dataset_objset_with_origin(pool_ptr, dir_obj)?
+    } else {
+        resolve_dataset_objset(pool_ptr, dir_obj)?
+    };
+    Ok(Json(response))
+}
+
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/snapshots
+pub async fn dataset_snapshots(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dataset_snapshots(pool_ptr, dir_obj);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_dataset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
 
-    let range_expr = trimmed.trim_start_matches("bytes=").trim();
-    if range_expr.contains(',') {
-        return Err(api_error_with(
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotSpaceQuery {
+    pub sort: Option<String>,
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+/// Parse `?sort=` for snapshot space rows; absent means largest unique first.
+fn parse_snapshot_space_sort(raw: Option<&str>) -> Result<&'static str, ApiError> {
+    match raw
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("unique") => Ok("unique"),
+        Some("written") => Ok("written"),
+        Some("txg") => Ok("txg"),
+        Some("name") => Ok("name"),
+        Some(other) => Err(api_error_with(
             StatusCode::BAD_REQUEST,
-            "BAD_RANGE",
-            "multiple byte ranges are not supported",
-            Some("Use a single range request per call.".to_string()),
+            "INVALID_QUERY",
+            format!("invalid snapshot space sort '{other}'"),
+            Some("Use sort=unique, written, txg, or name.".to_string()),
             true,
-        ));
+        )),
     }
+}
 
-    if total_size == 0 {
-        return Err(api_error_with(
-            StatusCode::RANGE_NOT_SATISFIABLE,
-            "RANGE_NOT_SATISFIABLE",
-            "cannot satisfy range for empty file",
-            None,
-            true,
-        ));
+/// Byte sorts put the largest first; `txg` is creation order and `name` is
+/// alphabetical.
+fn sort_snapshot_space(entries: &mut [Value], sort: &str) {
+    let number = |entry: &Value, key: &str| entry[key].as_u64().unwrap_or(0);
+    match sort {
+        "written" => entries.sort_by_key(|e| std::cmp::Reverse(number(e, "written_bytes"))),
+        "txg" => entries.sort_by_key(|e| number(e, "creation_txg")),
+        "name" => entries.sort_by(|a, b| {
+            a["name"]
+                .as_str()
+                .unwrap_or("")
+                .cmp(b["name"].as_str().unwrap_or(""))
+        }),
+        _ => entries.sort_by_key(|e| std::cmp::Reverse(number(e, "unique_bytes"))),
     }
+}
 
-    let parts: Vec<&str> = range_expr.splitn(2, '-').collect();
-    if parts.len() != 2 {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "BAD_RANGE",
-            format!("invalid Range header '{trimmed}'"),
-            None,
-            true,
-        ));
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/snapshot-space?sort=&cursor=&limit=
+pub async fn dataset_snapshot_space(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<SnapshotSpaceQuery>,
+) -> ApiResult {
+    let sort = parse_snapshot_space_sort(params.sort.as_deref())?;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let limit = limit.min(SNAPSHOT_SPACE_MAX_LIMIT);
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::snapshot_space(pool_ptr, dir_obj, cursor, fetch_limit(limit));
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_dataset_user_input_error(err_msg) || err_msg.contains("is not DSL dir") {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
 
-    let start_raw = parts[0].trim();
-    let end_raw = parts[1].trim();
+    let Json(mut value) = finish_page(limit, "entries", json_from_result(result))?;
+    if let Some(entries) = value.get_mut("entries").and_then(Value::as_array_mut) {
+        sort_snapshot_space(entries, sort);
+    }
+    value["sort"] = json!(sort);
+    Ok(Json(value))
+}
 
-    let (start, end) = if start_raw.is_empty() {
-        let suffix_len = u64::from_str(end_raw).map_err(|_| {
-            api_error_with(
-                StatusCode::BAD_REQUEST,
-                "BAD_RANGE",
-                format!("invalid suffix range '{trimmed}'"),
-                None,
-                true,
-            )
-        })?;
-        if suffix_len == 0 {
-            return Err(api_error_with(
-                StatusCode::RANGE_NOT_SATISFIABLE,
-                "RANGE_NOT_SATISFIABLE",
-                "suffix length must be greater than zero",
-                None,
-                true,
-            ));
-        }
-        if suffix_len >= total_size {
-            (0, total_size - 1)
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/snapshot-count
+pub async fn dataset_snapshot_count(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dataset_snapshot_count(pool_ptr, dir_obj);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_dataset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
         } else {
-            (total_size - suffix_len, total_size - 1)
-        }
-    } else {
-        let start = u64::from_str(start_raw).map_err(|_| {
-            api_error_with(
-                StatusCode::BAD_REQUEST,
-                "BAD_RANGE",
-                format!("invalid range start '{start_raw}'"),
-                None,
-                true,
-            )
-        })?;
-        let end = if end_raw.is_empty() {
-            total_size - 1
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+/// GET /api/pools/:pool/snapshot/:dsobj/objset
+pub async fn snapshot_objset(
+    State(state): State<AppState>,
+    Path((pool, dsobj)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dataset_objset(pool_ptr, dsobj);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_dataset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
         } else {
-            u64::from_str(end_raw).map_err(|_| {
-                api_error_with(
-                    StatusCode::BAD_REQUEST,
-                    "BAD_RANGE",
-                    format!("invalid range end '{end_raw}'"),
-                    None,
-                    true,
-                )
-            })?
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
         };
-        if start >= total_size || start > end {
+        return Err(api_error(status, err_msg.to_string()));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+/// GET /api/pools/:pool/snapshot/:dsobj/holds
+pub async fn snapshot_holds(
+    State(state): State<AppState>,
+    Path((pool, dsobj)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::snapshot_holds(pool_ptr, dsobj);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("is not a snapshot") || err_msg.contains("is not a DSL dataset") {
             return Err(api_error_with(
-                StatusCode::RANGE_NOT_SATISFIABLE,
-                "RANGE_NOT_SATISFIABLE",
-                format!("range {start}-{end} is outside object size {total_size}"),
-                None,
+                StatusCode::BAD_REQUEST,
+                "NOT_A_SNAPSHOT",
+                err_msg.to_string(),
+                Some("Pass a snapshot dsobj from /dataset/{objid}/snapshots.".to_string()),
                 true,
             ));
         }
-        (start, end.min(total_size - 1))
-    };
+        let status = if err_msg.starts_with("dnode_hold failed") {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+    json_from_result(result)
+}
 
-    Ok((start, end, true))
+#[derive(Debug, Deserialize)]
+pub struct SnapshotLineageQuery {
+    pub max_prev: Option<u64>,
+    pub max_next: Option<u64>,
+    pub max_entries: Option<u64>,
+    pub strict: Option<bool>,
 }
 
-fn read_objset_bytes(
-    pool_ptr: *mut crate::ffi::zdx_pool_t,
-    objset_id: u64,
-    objid: u64,
-    start: u64,
-    end: u64,
-) -> Result<Vec<u8>, ApiError> {
-    if end < start {
-        return Ok(Vec::new());
+/// Split a combined entry budget between the two directions. Each side gets
+/// half; a side asking for less than its half hands the rest to the other.
+fn split_lineage_budget(max_prev: u64, max_next: u64, max_entries: u64) -> (u64, u64) {
+    if max_prev.saturating_add(max_next) <= max_entries {
+        return (max_prev, max_next);
     }
-    let total = end - start + 1;
-    if total > ZPL_DOWNLOAD_MAX_BYTES {
-        return Err(api_error_with(
-            StatusCode::BAD_REQUEST,
-            "DOWNLOAD_TOO_LARGE",
-            format!(
-                "requested byte range is {} bytes; max per request is {} bytes",
-                total, ZPL_DOWNLOAD_MAX_BYTES
-            ),
-            Some("Use HTTP Range requests to download the file in chunks.".to_string()),
-            true,
-        ));
+    let half = max_entries / 2;
+    if max_prev <= half {
+        (max_prev, max_entries - max_prev)
+    } else if max_next <= max_entries - half {
+        (max_entries - max_next, max_next)
+    } else {
+        (half, max_entries - half)
+    }
+}
+
+/// Resolve the combined lineage budget: the query value (or the default),
+/// clamped to the server ceiling from `ZFS_EXPLORER_MAX_LINEAGE_ENTRIES`.
+fn lineage_max_entries(requested: Option<u64>, ceiling: u64) -> Result<u64, ApiError> {
+    let ceiling = ceiling.clamp(2, LINEAGE_MAX_ENTRIES);
+    Ok(reject_zero("max_entries", requested)?
+        .unwrap_or(LINEAGE_DEFAULT_MAX_ENTRIES)
+        .clamp(2, ceiling))
+}
+
+/// Why a lineage walk stopped early, or `None` when it reached both ends.
+/// Per-side truncation counts against `max_entries` when the budget shrank
+/// that side below what was asked for.
+fn lineage_truncation_reason(
+    value: &Value,
+    requested: (u64, u64),
+    granted: (u64, u64),
+) -> Option<&'static str> {
+    let prev = value["prev_truncated"].as_bool() == Some(true);
+    let next = value["next_truncated"].as_bool() == Some(true);
+    if (prev && granted.0 < requested.0) || (next && granted.1 < requested.1) {
+        Some("max_entries")
+    } else if prev {
+        Some("max_prev")
+    } else if next {
+        Some("max_next")
+    } else {
+        None
     }
+}
 
-    let mut out = Vec::with_capacity(total as usize);
-    let mut offset = start;
-    while offset <= end {
-        let remaining = end - offset + 1;
-        let chunk_size = remaining.min(OBJSET_DATA_MAX_LIMIT);
-        let chunk_result =
-            crate::ffi::objset_read_data(pool_ptr, objset_id, objid, offset, chunk_size);
-        if !chunk_result.is_ok() {
-            let err_msg = chunk_result.error_msg().unwrap_or("Unknown error");
-            let status = if is_objset_user_input_error(err_msg) {
-                StatusCode::BAD_REQUEST
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR
-            };
-            return Err(api_error(
-                status,
-                format!("failed to read object data at offset {offset}: {err_msg}"),
-            ));
-        }
+/// GET /api/pools/:pool/snapshot/:dsobj/lineage?max_prev=&max_next=&max_entries=&strict=
+pub async fn snapshot_lineage(
+    State(state): State<AppState>,
+    Path((pool, dsobj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<SnapshotLineageQuery>,
+) -> ApiResult {
+    let requested = (
+        reject_zero("max_prev", params.max_prev)?
+            .unwrap_or(64)
+            .min(LINEAGE_MAX_PER_SIDE),
+        reject_zero("max_next", params.max_next)?
+            .unwrap_or(64)
+            .min(LINEAGE_MAX_PER_SIDE),
+    );
+    let max_entries = lineage_max_entries(params.max_entries, state.max_lineage_entries)?;
+    let granted = split_lineage_budget(requested.0, requested.1, max_entries);
+    let _inflight = reserve_inflight(&state, granted.0 + granted.1)?;
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dataset_lineage(pool_ptr, dsobj, granted.0, granted.1);
+    let Json(value) = json_from_result(result)?;
+    let mut value = with_txg_times(&state, pool_ptr, &pool, value);
+    let reason = lineage_truncation_reason(&value, requested, granted);
+    value["truncated"] = json!(reason.is_some());
+    value["truncation_reason"] = json!(reason);
+    value["max_entries"] = json!(max_entries);
+    finish_traversal(params.strict, value)
+}
 
-        let chunk_json = chunk_result.json().ok_or_else(|| {
-            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
-        })?;
-        let chunk_value = parse_json_value(chunk_json)?;
-        let chunk = serde_json::from_value::<ObjsetDataPayload>(chunk_value).map_err(|err| {
-            api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("failed to parse object data payload: {err}"),
-            )
-        })?;
+#[derive(Debug, Deserialize)]
+pub struct DatasetZilQuery {
+    pub limit: Option<u64>,
+    pub strict: Option<bool>,
+}
 
-        let mut bytes = decode_hex_bytes(&chunk.data_hex)?;
-        if bytes.is_empty() {
-            break;
-        }
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/zil?limit=&strict=
+pub async fn dataset_zil(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<DatasetZilQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let head_obj = resolve_dataset_head_dataset_obj(pool_ptr, dir_obj)?;
+    let limit = reject_zero("limit", params.limit)?
+        .unwrap_or(ZIL_DEFAULT_RECORDS)
+        .min(ZIL_MAX_RECORDS);
+    let result = crate::ffi::dataset_zil(pool_ptr, head_obj, limit);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_dataset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let mut value = parse_json_value(json_str)?;
+    value["dsl_dir_obj"] = json!(dir_obj);
+    finish_traversal(params.strict, value)
+}
 
-        if (bytes.len() as u64) > remaining {
-            bytes.truncate(remaining as usize);
-        }
+#[derive(Debug, Deserialize)]
+pub struct DatasetUserspaceQuery {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+}
 
-        let consumed = bytes.len() as u64;
-        out.extend_from_slice(&bytes);
-        if consumed == 0 {
-            break;
-        }
-        offset = offset.saturating_add(consumed);
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/userspace?type=&cursor=&limit=
+pub async fn dataset_userspace(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<DatasetUserspaceQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let group = parse_userspace_type(params.kind.as_deref())?;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let head_obj = resolve_dataset_head_dataset_obj(pool_ptr, dir_obj)?;
+    let result =
+        crate::ffi::dataset_userspace(pool_ptr, head_obj, group, cursor, fetch_limit(limit));
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
     }
+    let Json(mut value) = json_from_result(result)?;
+    value["dsl_dir_obj"] = json!(dir_obj);
+    finish_page(limit, "entries", Ok(Json(value)))
+}
 
-    if out.len() as u64 != total {
-        return Err(api_error_with(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "SHORT_READ",
-            format!(
-                "short read while exporting object data (expected {total} bytes, got {})",
-                out.len()
-            ),
-            Some(
-                "Try smaller range requests; the object may be sparse or partially unreadable."
-                    .to_string(),
-            ),
-            false,
-        ));
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/origin
+pub async fn dataset_origin(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::dataset_origin(pool_ptr, dir_obj);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if err_msg.contains("is not DSL dir") || is_dataset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
     }
 
-    Ok(out)
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
 }
 
-fn sanitize_download_filename(raw: &str) -> String {
-    let mut cleaned = raw.replace(['"', '\\', '/'], "_");
-    if cleaned.is_empty() {
-        cleaned = "download.bin".to_string();
-    }
-    cleaned
+#[derive(Debug, Deserialize)]
+pub struct FileHistoryQuery {
+    pub path: Option<String>,
+    pub limit: Option<u64>,
+    pub strict: Option<bool>,
 }
 
-fn build_file_download_response(
+#[derive(Debug, Deserialize)]
+struct SnapshotListPayload {
+    entries: Vec<SnapshotListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotListEntry {
+    name: String,
+    dsobj: u64,
+    /// `null` when the snapshot's dataset object could not be read.
+    #[serde(default)]
+    creation_txg: Option<u64>,
+    #[serde(default)]
+    creation_time: Option<u64>,
+}
+
+/// Look up `walk_path` in one objset; `Ok(None)` when it does not exist there.
+fn file_version_in_objset(
     pool_ptr: *mut crate::ffi::zdx_pool_t,
-    headers: HeaderMap,
     objset_id: u64,
-    objid: u64,
-    file_size: u64,
-    filename_raw: &str,
-    rel_path: &str,
-    dataset_name: Option<&str>,
-) -> Result<Response<Body>, ApiError> {
-    let filename = sanitize_download_filename(filename_raw);
-    let content_type = mime_guess::from_path(&filename)
-        .first_or_octet_stream()
-        .essence_str()
-        .to_string();
-
-    if file_size == 0 {
-        let content_type = mime_guess::from_path(&filename)
-            .first_or_octet_stream()
-            .essence_str()
-            .to_string();
-        let mut response = Response::new(Body::from(Vec::<u8>::new()));
-        *response.status_mut() = StatusCode::OK;
-        response
-            .headers_mut()
-            .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
-        response.headers_mut().insert(
-            CONTENT_TYPE,
-            HeaderValue::from_str(&content_type)
-                .unwrap_or(HeaderValue::from_static("application/octet-stream")),
-        );
-        response
-            .headers_mut()
-            .insert(CONTENT_LENGTH, HeaderValue::from_static("0"));
-        response.headers_mut().insert(
-            CONTENT_DISPOSITION,
-            HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
-                .unwrap_or(HeaderValue::from_static("attachment")),
-        );
-        response.headers_mut().insert(
-            HeaderName::from_static("x-zfs-objset-id"),
-            HeaderValue::from_str(&objset_id.to_string()).unwrap_or(HeaderValue::from_static("0")),
-        );
-        response.headers_mut().insert(
-            HeaderName::from_static("x-zfs-relpath"),
-            HeaderValue::from_str(rel_path).unwrap_or(HeaderValue::from_static("/")),
-        );
-        if let Some(dataset_name) = dataset_name {
-            response.headers_mut().insert(
-                HeaderName::from_static("x-zfs-dataset"),
-                HeaderValue::from_str(dataset_name).unwrap_or(HeaderValue::from_static("unknown")),
-            );
+    walk_path: &str,
+) -> Result<Option<(u64, u64)>, String> {
+    let walk_result = crate::ffi::objset_walk(pool_ptr, objset_id, walk_path)?;
+    if !walk_result.is_ok() {
+        let err_msg = walk_result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("No such file or directory") {
+            return Ok(None);
         }
-        return Ok(response);
+        return Err(err_msg.to_string());
     }
-
-    let (start, end, partial) = parse_range_header(&headers, file_size)?;
-    let bytes = read_objset_bytes(pool_ptr, objset_id, objid, start, end)?;
-
-    let mut response = Response::new(Body::from(bytes));
-    *response.status_mut() = if partial {
-        StatusCode::PARTIAL_CONTENT
-    } else {
-        StatusCode::OK
-    };
-
-    response
-        .headers_mut()
-        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
-    response.headers_mut().insert(
-        CONTENT_TYPE,
-        HeaderValue::from_str(&content_type)
-            .unwrap_or(HeaderValue::from_static("application/octet-stream")),
-    );
-    response.headers_mut().insert(
-        CONTENT_LENGTH,
-        HeaderValue::from_str(&(end - start + 1).to_string())
-            .unwrap_or(HeaderValue::from_static("0")),
-    );
-    response.headers_mut().insert(
-        CONTENT_DISPOSITION,
-        HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
-            .unwrap_or(HeaderValue::from_static("attachment")),
-    );
-    response.headers_mut().insert(
-        HeaderName::from_static("x-zfs-objset-id"),
-        HeaderValue::from_str(&objset_id.to_string()).unwrap_or(HeaderValue::from_static("0")),
-    );
-    response.headers_mut().insert(
-        HeaderName::from_static("x-zfs-relpath"),
-        HeaderValue::from_str(rel_path).unwrap_or(HeaderValue::from_static("/")),
-    );
-    if let Some(dataset_name) = dataset_name {
-        response.headers_mut().insert(
-            HeaderName::from_static("x-zfs-dataset"),
-            HeaderValue::from_str(dataset_name).unwrap_or(HeaderValue::from_static("unknown")),
-        );
+    let walk_json = walk_result.json().ok_or("Missing JSON in walk result")?;
+    let walk = serde_json::from_str::<ObjsetWalkPayload>(walk_json)
+        .map_err(|err| format!("failed to parse walk payload: {err}"))?;
+    if !walk.found || !walk.remaining.is_empty() {
+        return Ok(None);
     }
 
-    if partial {
-        response.headers_mut().insert(
-            CONTENT_RANGE,
-            HeaderValue::from_str(&format!("bytes {start}-{end}/{file_size}"))
-                .unwrap_or(HeaderValue::from_static("bytes */0")),
-        );
+    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, walk.objid);
+    if !stat_result.is_ok() {
+        return Err(stat_result
+            .error_msg()
+            .unwrap_or("Unknown error")
+            .to_string());
     }
-
-    Ok(response)
+    let stat_json = stat_result.json().ok_or("Missing JSON in stat result")?;
+    let stat = serde_json::from_str::<ObjsetStatPayload>(stat_json)
+        .map_err(|err| format!("failed to parse stat payload: {err}"))?;
+    Ok(Some((walk.objid, stat.size)))
 }
 
-/// GET /api/pools/{pool}/zpl/path/{*zpl_path}
-/// (supports single HTTP Range request)
-pub async fn zpl_path_download(
+/// GET /api/pools/:pool/dataset/:dsl_dir_obj/file-history?path=&limit=&strict=
+pub async fn dataset_file_history(
     State(state): State<AppState>,
-    Path((pool, zpl_path)): Path<(String, String)>,
-    headers: HeaderMap,
-) -> Result<Response<Body>, ApiError> {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let pool_open = pool_open_config(&state);
-    let ctx = resolve_zpl_path_context(
-        pool_ptr,
-        &pool,
-        &zpl_path,
-        matches!(pool_open.mode, crate::PoolOpenMode::Offline),
-    )?;
-
-    build_file_download_response(
-        pool_ptr,
-        headers,
-        ctx.objset_id,
-        ctx.objid,
-        ctx.file_size,
-        &ctx.filename,
-        &ctx.rel_path,
-        Some(&ctx.dataset_name),
-    )
-}
-
-/// GET /api/pools/{pool}/objset/{objset_id}/zpl/path/{*zpl_path}
-/// (supports single HTTP Range request)
-pub async fn objset_zpl_path_download(
-    State(state): State<AppState>,
-    Path((pool, objset_id, zpl_path)): Path<(String, u64, String)>,
-    headers: HeaderMap,
-) -> Result<Response<Body>, ApiError> {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let ctx = resolve_objset_scoped_zpl_path_context(pool_ptr, objset_id, &zpl_path)?;
-    build_file_download_response(
-        pool_ptr,
-        headers,
-        ctx.objset_id,
-        ctx.objid,
-        ctx.file_size,
-        &ctx.filename,
-        &ctx.rel_path,
-        None,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<FileHistoryQuery>,
+) -> ApiResult {
+    let Some(raw_path) = params.path.as_deref() else {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PATH",
+            "query parameter 'path' is required",
+            Some("Provide a file path relative to the dataset root.".to_string()),
+            true,
+        ));
+    };
+    let walk_path = normalize_objset_zpl_path(raw_path)?;
+    let limit = reject_zero("limit", params.limit)?
+        .unwrap_or(FILE_HISTORY_DEFAULT_SNAPSHOTS)
+        .min(FILE_HISTORY_MAX_SNAPSHOTS);
+
+    let snapshots = {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        file_history_snapshots(pool_handle.ptr, dir_obj)?
+    };
+    let total = snapshots.len();
+    let truncated = total as u64 > limit;
+    let mut versions = Vec::new();
+    for snapshot in snapshots.into_iter().take(limit as usize) {
+        let version = {
+            let pool_handle = ensure_pool(&state, &pool)?;
+            let pool_ptr = pool_handle.ptr;
+            file_history_version(pool_ptr, &snapshot, &walk_path)
+        };
+        versions.push(version);
+        scan_checkpoint().await;
+    }
+
+    finish_traversal(
+        params.strict,
+        json!({
+            "dsl_dir_obj": dir_obj,
+            "path": walk_path,
+            "snapshot_count": total,
+            "examined": versions.len(),
+            "truncated": truncated,
+            "versions": versions,
+        }),
     )
 }
 
-/// GET /api/pools/{pool}/snapshot/{dsobj}/zpl/path/{*zpl_path}
-/// (supports single HTTP Range request)
-pub async fn snapshot_zpl_path_download(
-    State(state): State<AppState>,
-    Path((pool, dsobj, zpl_path)): Path<(String, u64, String)>,
-    headers: HeaderMap,
-) -> Result<Response<Body>, ApiError> {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let objset_id = resolve_snapshot_objset_id(pool_ptr, dsobj)?;
-    let ctx = resolve_objset_scoped_zpl_path_context(pool_ptr, objset_id, &zpl_path)?;
-    let mut response = build_file_download_response(
-        pool_ptr,
-        headers,
-        ctx.objset_id,
-        ctx.objid,
-        ctx.file_size,
-        &ctx.filename,
-        &ctx.rel_path,
-        None,
-    )?;
-    response.headers_mut().insert(
-        HeaderName::from_static("x-zfs-snapshot-dsobj"),
-        HeaderValue::from_str(&dsobj.to_string()).unwrap_or(HeaderValue::from_static("0")),
-    );
-    Ok(response)
-}
-
-#[derive(Debug, Deserialize)]
-pub struct SpacemapRangesQuery {
-    pub cursor: Option<u64>,
-    pub limit: Option<u64>,
-    pub op: Option<String>,
-    pub min_length: Option<u64>,
-    pub txg_min: Option<u64>,
-    pub txg_max: Option<u64>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct SpacemapBinsQuery {
-    pub bin_size: Option<u64>,
-    pub cursor: Option<u64>,
-    pub limit: Option<u64>,
-    pub op: Option<String>,
-    pub min_length: Option<u64>,
-    pub txg_min: Option<u64>,
-    pub txg_max: Option<u64>,
-}
-
-/// GET /api/pools/:pool/spacemap/:objid/summary
-pub async fn spacemap_summary(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let result = crate::ffi::spacemap_summary(pool_ptr, objid);
+/// Snapshots of a DSL dir, newest first (by the creation txg the listing
+/// carries) so that a file-history limit drops the oldest ones.
+fn file_history_snapshots(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    dir_obj: u64,
+) -> Result<Vec<SnapshotListEntry>, ApiError> {
+    let result = crate::ffi::dataset_snapshots(pool_ptr, dir_obj);
     if !result.is_ok() {
         let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_spacemap_user_input_error(err_msg) {
+        let status = if err_msg.contains("is not DSL dir") || is_dataset_user_input_error(err_msg) {
             StatusCode::BAD_REQUEST
         } else {
             tracing::error!("FFI error: {}", err_msg);
@@ -3886,39 +6555,82 @@ pub async fn spacemap_summary(
         };
         return Err(api_error(status, err_msg.to_string()));
     }
-
     let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+    let mut snapshots = serde_json::from_str::<SnapshotListPayload>(json_str)
+        .map_err(|err| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to parse snapshots payload: {err}"),
+            )
+        })?
+        .entries;
+    sort_snapshots_newest_first(&mut snapshots);
+    Ok(snapshots)
+}
+
+/// Newest creation txg first; snapshots without one sort last, and ties fall
+/// back to the higher (later allocated) dataset object.
+fn sort_snapshots_newest_first(snapshots: &mut [SnapshotListEntry]) {
+    snapshots.sort_by(|a, b| {
+        b.creation_txg
+            .cmp(&a.creation_txg)
+            .then(b.dsobj.cmp(&a.dsobj))
+    });
 }
 
-/// GET /api/pools/:pool/spacemap/:objid/ranges?cursor=&limit=&op=&min_length=&txg_min=&txg_max=
-pub async fn spacemap_ranges(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-    Query(params): Query<SpacemapRangesQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let (cursor, limit) = normalize_spacemap_cursor_limit(params.cursor, params.limit);
-    let op_filter = parse_spacemap_op_filter(params.op.as_deref())?;
-    let min_length = params.min_length.unwrap_or(0);
-    let txg_min = params.txg_min.unwrap_or(0);
-    let txg_max = params.txg_max.unwrap_or(0);
-    if txg_min != 0 && txg_max != 0 && txg_min > txg_max {
-        return Err(api_error(
-            StatusCode::BAD_REQUEST,
-            "txg_min must be <= txg_max",
-        ));
-    }
+/// One file-history row: the file's object and size in `snapshot`, or the
+/// reason the snapshot could not be examined.
+fn file_history_version(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    snapshot: &SnapshotListEntry,
+    walk_path: &str,
+) -> Value {
+    let lookup = resolve_snapshot_objset_id(pool_ptr, snapshot.dsobj)
+        .map_err(|(_, Json(body))| {
+            body["message"]
+                .as_str()
+                .unwrap_or("failed to resolve snapshot objset")
+                .to_string()
+        })
+        .and_then(|objset_id| {
+            file_version_in_objset(pool_ptr, objset_id, walk_path).map(|found| (objset_id, found))
+        });
 
-    let result = crate::ffi::spacemap_ranges(
-        pool_ptr, objid, cursor, limit, op_filter, min_length, txg_min, txg_max,
-    );
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_spacemap_user_input_error(err_msg) {
+    let (objset_id, found, error) = match lookup {
+        Ok((objset_id, found)) => (Some(objset_id), found, None),
+        Err(err) => (None, None, Some(err)),
+    };
+    json!({
+        "snapshot_name": snapshot.name,
+        "dsobj": snapshot.dsobj,
+        "objset_id": objset_id,
+        "creation_txg": snapshot.creation_txg,
+        "creation_unix_sec": snapshot.creation_time,
+        "present": found.is_some(),
+        "objid": found.map(|(objid, _)| objid),
+        "size_bytes": found.map(|(_, size)| size),
+        "error": error,
+    })
+}
+
+fn resolve_dataset_objset(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    dir_obj: u64,
+) -> Result<Value, ApiError> {
+    let head_obj = resolve_dataset_head_dataset_obj(pool_ptr, dir_obj)?;
+
+    let objset_result = crate::ffi::dataset_objset(pool_ptr, head_obj);
+    if !objset_result.is_ok() {
+        let err_msg = objset_result.error_msg().unwrap_or("Unknown error");
+        tracing::error!(
+            "dataset_objset failed: dsl_dir_obj={} head_dataset_obj={} err={}",
+            dir_obj,
+            head_obj,
+            err_msg
+        );
+        let status = if is_dataset_user_input_error(err_msg) {
             StatusCode::BAD_REQUEST
         } else {
             tracing::error!("FFI error: {}", err_msg);
@@ -3927,454 +6639,5932 @@ pub async fn spacemap_ranges(
         return Err(api_error(status, err_msg.to_string()));
     }
 
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-    Ok(Json(value))
+    let objset_json = objset_result.json().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Missing JSON in objset result",
+        )
+    })?;
+    let objset_value = parse_json_value(objset_json)?;
+
+    let response = build_dataset_objset_response(dir_obj, head_obj, &objset_value);
+
+    Ok(response)
 }
 
-/// GET /api/pools/:pool/spacemap/:objid/bins?bin_size=&cursor=&limit=&op=&min_length=&txg_min=&txg_max=
-pub async fn spacemap_bins(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-    Query(params): Query<SpacemapBinsQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let bin_size = normalize_spacemap_bin_size(params.bin_size);
-    let (cursor, limit) = normalize_spacemap_bins_cursor_limit(params.cursor, params.limit);
-    let op_filter = parse_spacemap_op_filter(params.op.as_deref())?;
-    let min_length = params.min_length.unwrap_or(0);
-    let txg_min = params.txg_min.unwrap_or(0);
-    let txg_max = params.txg_max.unwrap_or(0);
-    if txg_min != 0 && txg_max != 0 && txg_min > txg_max {
+fn resolve_dataset_head_dataset_obj(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    dir_obj: u64,
+) -> Result<u64, ApiError> {
+    let head_result = crate::ffi::dsl_dir_head(pool_ptr, dir_obj);
+    if !head_result.is_ok() {
+        let err_msg = head_result.error_msg().unwrap_or("Unknown error");
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
+        ));
+    }
+
+    let head_json = head_result.json().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Missing JSON in head result",
+        )
+    })?;
+    let head_value = parse_json_value(head_json)?;
+
+    let head_obj = head_value["head_dataset_obj"].as_u64().unwrap_or(0);
+    if head_obj == 0 {
         return Err(api_error(
             StatusCode::BAD_REQUEST,
-            "txg_min must be <= txg_max",
+            format!(
+                "DSL dir {} has no head dataset (special internal dir such as $FREE/$MOS)",
+                dir_obj
+            ),
         ));
     }
 
-    let result = crate::ffi::spacemap_bins(
-        pool_ptr, objid, bin_size, cursor, limit, op_filter, min_length, txg_min, txg_max,
+    tracing::debug!(
+        "resolved dataset head: dsl_dir_obj={} head_dataset_obj={}",
+        dir_obj,
+        head_obj
     );
+
+    Ok(head_obj)
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/root
+pub async fn objset_root(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+
+    let result = crate::ffi::objset_root(pool_ptr, objset_id);
     if !result.is_ok() {
         let err_msg = result.error_msg().unwrap_or("Unknown error");
-        let status = if is_spacemap_user_input_error(err_msg) {
-            StatusCode::BAD_REQUEST
-        } else {
-            tracing::error!("FFI error: {}", err_msg);
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-        return Err(api_error(status, err_msg.to_string()));
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
+        ));
     }
 
     let json_str = result
         .json()
         .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
     let value = parse_json_value(json_str)?;
+
     Ok(Json(value))
 }
 
 #[derive(Debug, Deserialize)]
-pub struct GraphQuery {
-    pub depth: Option<u8>,
-    pub include: Option<String>,
+pub struct DirEntriesQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+    pub sort: Option<String>,
 }
 
-/// GET /api/pools/:pool/graph/from/:objid
-pub async fn graph_from(
-    State(state): State<AppState>,
-    Path((pool, objid)): Path<(String, u64)>,
-    Query(params): Query<GraphQuery>,
-) -> ApiResult {
-    let pool_ptr = ensure_pool(&state, &pool)?;
-    let include = params
-        .include
-        .unwrap_or_else(|| "semantic,physical".to_string());
-    let _depth = params.depth.unwrap_or(1);
-    let (include_semantic, include_physical, include_zap) = parse_graph_include(Some(&include));
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DirEntrySort {
+    None,
+    Name,
+    Objid,
+}
 
-    let result = crate::ffi::obj_get(pool_ptr, objid);
-    if !result.is_ok() {
-        let err_msg = result.error_msg().unwrap_or("Unknown error");
-        tracing::error!("FFI error: {}", err_msg);
-        return Err(api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            err_msg.to_string(),
+impl DirEntrySort {
+    fn as_str(self) -> &'static str {
+        match self {
+            DirEntrySort::None => "none",
+            DirEntrySort::Name => "name",
+            DirEntrySort::Objid => "objid",
+        }
+    }
+}
+
+/// Parse `?sort=`; absent means ZAP hash order.
+fn parse_dir_entry_sort(raw: Option<&str>) -> Result<DirEntrySort, ApiError> {
+    match raw
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("none") => Ok(DirEntrySort::None),
+        Some("name") => Ok(DirEntrySort::Name),
+        Some("objid") => Ok(DirEntrySort::Objid),
+        Some(other) => Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_QUERY",
+            format!("invalid directory sort '{other}'"),
+            Some("Use sort=name, objid, or none.".to_string()),
+            true,
+        )),
+    }
+}
+
+/// Sort collected entries and cut one page; `cursor` is an offset into the
+/// sorted list. Returns the page and the next offset, if any.
+fn page_sorted_dir_entries(
+    mut entries: Vec<Value>,
+    sort: DirEntrySort,
+    cursor: u64,
+    limit: u64,
+) -> (Vec<Value>, Option<u64>) {
+    match sort {
+        DirEntrySort::Name => entries.sort_by(|a, b| {
+            a["name"]
+                .as_str()
+                .unwrap_or("")
+                .cmp(b["name"].as_str().unwrap_or(""))
+        }),
+        DirEntrySort::Objid => entries.sort_by_key(|entry| entry["objid"].as_u64().unwrap_or(0)),
+        DirEntrySort::None => {}
+    }
+    let total = entries.len() as u64;
+    let start = cursor.min(total) as usize;
+    let end = cursor.saturating_add(limit).min(total) as usize;
+    let next = (end as u64) < total;
+    let page = entries.drain(start..end).collect();
+    (page, next.then_some(end as u64))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WalkQuery {
+    pub path: Option<String>,
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/dir/:dir_obj/entries
+pub async fn objset_dir_entries(
+    State(state): State<AppState>,
+    Path((pool, objset_id, dir_obj)): Path<(String, u64, u64)>,
+    ApiQuery(params): ApiQuery<DirEntriesQuery>,
+) -> ApiResult {
+    let sort = parse_dir_entry_sort(params.sort.as_deref())?;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    if sort == DirEntrySort::None {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        let _inflight = reserve_inflight(&state, limit)?;
+        let result = crate::ffi::objset_dir_entries(
+            pool_ptr,
+            objset_id,
+            dir_obj,
+            cursor,
+            fetch_limit(limit),
+        );
+        return finish_page(limit, "entries", json_from_result(result));
+    }
+
+    // Collect the whole directory in hash order, then sort. Directories past
+    // the cap fall back to hash-order paging below.
+    let _inflight = reserve_inflight(&state, DIR_SORT_MAX_ENTRIES)?;
+    let mut entries: Vec<Value> = Vec::new();
+    let mut zap_cursor = 0;
+    let mut complete = false;
+    while (entries.len() as u64) <= DIR_SORT_MAX_ENTRIES {
+        let page = {
+            let pool_handle = ensure_pool(&state, &pool)?;
+            let pool_ptr = pool_handle.ptr;
+            let result = crate::ffi::objset_dir_entries(
+                pool_ptr,
+                objset_id,
+                dir_obj,
+                zap_cursor,
+                DIR_SORT_PAGE,
+            );
+            if !result.is_ok() {
+                let err_msg = result.error_msg().unwrap_or("Unknown error");
+                return Err(api_error_for_objset(err_msg));
+            }
+            let json_str = result.json().ok_or_else(|| {
+                api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+            })?;
+            parse_json_value(json_str)?
+        };
+        if let Some(items) = page["entries"].as_array() {
+            entries.extend(items.iter().cloned());
+        }
+        match page["next"].as_u64() {
+            Some(next) => zap_cursor = next,
+            None => {
+                complete = true;
+                break;
+            }
+        }
+        scan_checkpoint().await;
+    }
+
+    if !complete || entries.len() as u64 > DIR_SORT_MAX_ENTRIES {
+        drop(entries);
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        let result = crate::ffi::objset_dir_entries(
+            pool_ptr,
+            objset_id,
+            dir_obj,
+            cursor,
+            fetch_limit(limit),
+        );
+        if !result.is_ok() {
+            let err_msg = result.error_msg().unwrap_or("Unknown error");
+            return Err(api_error_for_objset(err_msg));
+        }
+        let json_str = result.json().ok_or_else(|| {
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+        })?;
+        let mut payload = parse_json_value(json_str)?;
+        payload["sort"] = json!(sort.as_str());
+        payload["sorted"] = json!(false);
+        payload["note"] = json!(format!(
+            "directory has more than {DIR_SORT_MAX_ENTRIES} entries; returned in hash order and cursor is a ZAP cursor"
+        ));
+        return finish_page(limit, "entries", Ok(Json(payload)));
+    }
+
+    let total = entries.len();
+    let (page, next) = page_sorted_dir_entries(entries, sort, cursor, limit);
+    Ok(Json(json!({
+        "objset_id": objset_id,
+        "dir_obj": dir_obj,
+        "cursor": cursor,
+        "next": next,
+        "count": page.len(),
+        "total": total,
+        "sort": sort.as_str(),
+        "sorted": true,
+        "entries": page,
+    })))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/walk?path=/a/b/c
+pub async fn objset_walk(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<WalkQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let path = params.path.unwrap_or_else(|| "/".to_string());
+    let result = crate::ffi::objset_walk(pool_ptr, objset_id, &path)
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+    let Json(mut value) = json_from_result(result)?;
+    if value.get("found").and_then(Value::as_bool) != Some(false) {
+        return Ok(Json(value));
+    }
+
+    // Partial walk: point at the deepest directory that did resolve, with a
+    // sample of its entries so the caller can pick a nearby name.
+    let Some(dir_obj) = value.get("last_dir_obj").and_then(Value::as_u64) else {
+        return Ok(Json(value));
+    };
+    let resolved = value.get("resolved").and_then(Value::as_str).unwrap_or("/");
+    let error = value.get("error").and_then(Value::as_str);
+    let mut recovery = json!({
+        "objid": dir_obj,
+        "path": walk_recovery_dir_path(resolved, error),
+        "entries_sample": [],
+        "entries_truncated": false,
+    });
+    let sample =
+        crate::ffi::objset_dir_entries(pool_ptr, objset_id, dir_obj, 0, WALK_RECOVERY_SAMPLE);
+    match json_from_result(sample) {
+        Ok(Json(listing)) => {
+            recovery["entries_truncated"] =
+                Value::Bool(listing.get("next").is_some_and(|next| !next.is_null()));
+            if let Some(entries) = listing.get("entries") {
+                recovery["entries_sample"] = entries.clone();
+            }
+        }
+        Err((_, Json(err))) => {
+            recovery["entries_error"] = err.get("error").cloned().unwrap_or(Value::Null);
+        }
+    }
+    value["resolved_up_to"] = recovery;
+    Ok(Json(value))
+}
+
+/// Path of the directory a partial walk stopped in. On `not_dir` the resolved
+/// path ends at the non-directory component, so its parent is the directory.
+fn walk_recovery_dir_path(resolved: &str, error: Option<&str>) -> String {
+    if error != Some("not_dir") {
+        return resolved.to_string();
+    }
+    match resolved.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(idx) => resolved[..idx].to_string(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ObjsetStatQuery {
+    pub sniff: Option<bool>,
+    /// Optional filename used for the extension fallback.
+    pub name: Option<String>,
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/stat/:objid?sniff=true&name=
+pub async fn objset_stat(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+    ApiQuery(params): ApiQuery<ObjsetStatQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::objset_stat(pool_ptr, objset_id, objid);
+    let Json(mut value) = json_from_result(result)?;
+    if params.sniff != Some(true) {
+        return Ok(Json(value));
+    }
+
+    let stat = serde_json::from_value::<ObjsetStatPayload>(value.clone()).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse stat payload: {err}"),
+        )
+    })?;
+    if stat.type_name != "file" {
+        value["sniff"] = Value::Null;
+        return Ok(Json(value));
+    }
+
+    let length = stat.size.min(SNIFF_BYTES);
+    let head = if length == 0 {
+        Vec::new()
+    } else {
+        let result = crate::ffi::objset_read_data(pool_ptr, objset_id, objid, 0, length);
+        if !result.is_ok() {
+            let err_msg = result.error_msg().unwrap_or("Unknown error");
+            return Err(api_error_for_objset(err_msg));
+        }
+        let json_str = result.json().ok_or_else(|| {
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+        })?;
+        let chunk = serde_json::from_value::<ObjsetDataPayload>(parse_json_value(json_str)?)
+            .map_err(|err| {
+                api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("failed to parse file data payload: {err}"),
+                )
+            })?;
+        decode_hex_bytes(&chunk.data_hex)?
+    };
+    value["sniff"] = sniff_file_head(&head, params.name.as_deref());
+    Ok(Json(value))
+}
+
+/// Leading magic bytes for common formats, checked in order.
+const SNIFF_MAGIC: &[(&[u8], &str)] = &[
+    (b"%PDF-", "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"BZh", "application/x-bzip2"),
+    (b"\xfd7zXZ\x00", "application/x-xz"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"\x7fELF", "application/x-executable"),
+    (b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    (b"OggS", "audio/ogg"),
+    (b"fLaC", "audio/flac"),
+    (b"ID3", "audio/mpeg"),
+];
+
+fn sniff_magic(head: &[u8]) -> Option<&'static str> {
+    if let Some((_, mime)) = SNIFF_MAGIC
+        .iter()
+        .find(|(magic, _)| head.starts_with(magic))
+    {
+        return Some(mime);
+    }
+    if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if head.len() >= 262 && &head[257..262] == b"ustar" {
+        return Some("application/x-tar");
+    }
+    None
+}
+
+/// Text encoding of a file head, or `None` when it looks binary. A multi-byte
+/// UTF-8 sequence cut off by the sample boundary still counts as UTF-8.
+fn sniff_text_encoding(head: &[u8]) -> Option<&'static str> {
+    if head.starts_with(b"\xef\xbb\xbf") {
+        return Some("utf-8");
+    }
+    if head.starts_with(b"\xff\xfe") {
+        return Some("utf-16le");
+    }
+    if head.starts_with(b"\xfe\xff") {
+        return Some("utf-16be");
+    }
+    let valid = match std::str::from_utf8(head) {
+        Ok(_) => head.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(_) => return None,
+    };
+    let control = head[..valid]
+        .iter()
+        .filter(|&&byte| byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c))
+        .count();
+    if head.contains(&0) || control * 100 > valid.max(1) * 2 {
+        return None;
+    }
+    if head.is_ascii() {
+        Some("ascii")
+    } else {
+        Some("utf-8")
+    }
+}
+
+/// Best-effort content type of a file from its first bytes: magic numbers,
+/// then the filename extension, then text detection. Returns the type and
+/// which of those decided it. Shared by the sniff endpoint and downloads.
+fn detect_content_type(head: &[u8], name: Option<&str>) -> (String, &'static str) {
+    if let Some(mime) = sniff_magic(head) {
+        return (mime.to_string(), "magic");
+    }
+    if let Some(mime) = name.and_then(|name| mime_guess::from_path(name).first()) {
+        return (mime.essence_str().to_string(), "extension");
+    }
+    if !head.is_empty() && sniff_text_encoding(head).is_some() {
+        return ("text/plain".to_string(), "text");
+    }
+    ("application/octet-stream".to_string(), "default")
+}
+
+/// The `sniff` block of a file stat: [`detect_content_type`] plus the text
+/// encoding of the head.
+fn sniff_file_head(head: &[u8], name: Option<&str>) -> Value {
+    let encoding = sniff_text_encoding(head);
+    let (content_type, source) = detect_content_type(head, name);
+    json!({
+        "content_type_guess": content_type,
+        "source": source,
+        "is_probably_text": encoding.is_some() && sniff_magic(head).is_none(),
+        "detected_encoding": encoding,
+        "bytes_examined": head.len(),
+    })
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid
+pub async fn objset_get_object(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::objset_get_object(pool_ptr, objset_id, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+
+    let Some(etag) = object_etag(objset_id, objid, &value) else {
+        return Ok(Json(value).into_response());
+    };
+    let etag_value = HeaderValue::from_str(&etag).map_err(|_| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to encode ETag header",
+        )
+    })?;
+
+    let mut response = if if_none_match_hits(&headers, &etag) {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        response
+    } else {
+        Json(value).into_response()
+    };
+    response.headers_mut().insert(ETAG, etag_value);
+    Ok(response)
+}
+
+/// Weak validator for an objset object: any rewrite moves the newest
+/// top-level blkptr birth, and a hash of the handler payload covers changes
+/// that only touch the dnode or bonus (type, bonus length, maxblkid, used
+/// bytes). Objects without a birth (`birth_txg` 0, e.g. holes or bonus-only
+/// objects) get no tag. Weak because the JSON envelope (`generated_at`)
+/// varies.
+fn object_etag(objset_id: u64, objid: u64, value: &Value) -> Option<String> {
+    use std::hash::{Hash, Hasher};
+    let birth_txg = value["birth_txg"].as_u64().filter(|&txg| txg != 0)?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.to_string().hash(&mut hasher);
+    let digest = hasher.finish();
+    Some(format!(
+        "W/\"obj-{objset_id}-{objid}-{birth_txg}-{digest:016x}\""
+    ))
+}
+
+/// `If-None-Match` uses weak comparison (RFC 9110 13.1.2), so the `W/`
+/// prefix is ignored on both sides.
+fn if_none_match_hits(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let wanted = opaque(etag);
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == wanted)
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/dnode
+pub async fn objset_dnode(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::objset_dnode(pool_ptr, objset_id, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/blkptrs
+pub async fn objset_get_blkptrs(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::objset_get_blkptrs(pool_ptr, objset_id, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_objset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    Ok(Json(with_txg_times(&state, pool_ptr, &pool, value)))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/block-tree?max_depth=&max_nodes=&min_txg=&max_txg=&strict=
+pub async fn objset_block_tree(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+    ApiQuery(params): ApiQuery<BlockTreeQuery>,
+) -> ApiResult {
+    let (min_txg, max_txg) = block_tree_txg_range(&params)?;
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let max_depth = normalize_block_tree_depth(params.max_depth);
+    let max_nodes = normalize_block_tree_nodes(params.max_nodes)?;
+    let result = crate::ffi::objset_block_tree(
+        pool_ptr, objset_id, objid, max_depth, max_nodes, min_txg, max_txg,
+    );
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_objset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    finish_block_tree(&params, value)
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/rootbp/tree?max_depth=&max_nodes=&min_txg=&max_txg=&strict=
+pub async fn objset_rootbp_tree(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<BlockTreeQuery>,
+) -> ApiResult {
+    let (min_txg, max_txg) = block_tree_txg_range(&params)?;
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let max_depth = normalize_block_tree_depth(params.max_depth);
+    let max_nodes = normalize_block_tree_nodes(params.max_nodes)?;
+    let result =
+        crate::ffi::objset_rootbp_tree(pool_ptr, objset_id, max_depth, max_nodes, min_txg, max_txg);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    finish_block_tree(&params, value)
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/zap/info
+pub async fn objset_zap_info(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::objset_zap_info(pool_ptr, objset_id, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LargestFilesQuery {
+    pub limit: Option<u64>,
+    pub scan_limit: Option<u64>,
+}
+
+/// Await point between FFI batches of a long scan. Hyper drops the handler
+/// future once the client disconnects, so a scan that yields here stops after
+/// its current batch instead of pinning a core until the end of the objset.
+/// Scans must not hold the raw pool pointer across this point; they re-run
+/// `ensure_pool` per batch instead.
+async fn scan_checkpoint() {
+    tokio::task::yield_now().await;
+}
+
+/// What one batch of [`scan_objset_in_batches`] reports back to the driver.
+enum ScanStep {
+    /// The batch scanned `scanned` objects; the scan resumes at `next`, or
+    /// ends when it is `None`.
+    Next { scanned: u64, next: Option<u64> },
+    /// The batch ended the scan early; `truncated` reports whether objects
+    /// were left unscanned.
+    Stop { scanned: u64, truncated: bool },
+}
+
+impl ScanStep {
+    /// Continue with the batch's own `scanned`/`next` fields.
+    fn from_batch(batch: &Value) -> Self {
+        ScanStep::Next {
+            scanned: batch["scanned"].as_u64().unwrap_or(0),
+            next: batch["next"].as_u64(),
+        }
+    }
+}
+
+/// Totals of a finished [`scan_objset_in_batches`] walk.
+struct ObjsetScan {
+    scanned: u64,
+    truncated: bool,
+}
+
+/// Parsed payload of one native scan batch, with objset errors mapped.
+fn objset_scan_batch(result: crate::ffi::ZdxResult) -> Result<Value, ApiError> {
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    parse_json_value(json_str)
+}
+
+/// Shared driver of the batched object-list scans. Each batch re-runs
+/// `ensure_pool`, calls `batch(pool_ptr, start, batch_limit)`, and yields at a
+/// [`scan_checkpoint`] before the next one, until the objset ends, a batch
+/// stops the scan, or `scan_limit` objects were scanned.
+async fn scan_objset_in_batches(
+    state: &AppState,
+    pool: &str,
+    scan_limit: u64,
+    batch_size: u64,
+    mut batch: impl FnMut(*mut crate::ffi::zdx_pool_t, u64, u64) -> Result<ScanStep, ApiError>,
+) -> Result<ObjsetScan, ApiError> {
+    let mut start = 0;
+    let mut scanned = 0u64;
+    loop {
+        let batch_limit = (scan_limit - scanned).min(batch_size);
+        let step = {
+            let pool_handle = ensure_pool(state, pool)?;
+            batch(pool_handle.ptr, start, batch_limit)?
+        };
+        let next = match step {
+            ScanStep::Next {
+                scanned: batch_scanned,
+                next,
+            } => {
+                scanned += batch_scanned;
+                next
+            }
+            ScanStep::Stop {
+                scanned: batch_scanned,
+                truncated,
+            } => {
+                scanned += batch_scanned;
+                return Ok(ObjsetScan { scanned, truncated });
+            }
+        };
+        let Some(next) = next else {
+            return Ok(ObjsetScan {
+                scanned,
+                truncated: false,
+            });
+        };
+        if scanned >= scan_limit {
+            return Ok(ObjsetScan {
+                scanned,
+                truncated: true,
+            });
+        }
+        start = next;
+        scan_checkpoint().await;
+    }
+}
+
+/// Merge one batch's ranked files into the running top-N (size descending,
+/// earlier objects first on ties, matching the native ranking).
+fn merge_largest_files(top: &mut Vec<Value>, batch: Vec<Value>, limit: usize) {
+    top.extend(batch);
+    top.sort_by_key(|file| std::cmp::Reverse(file["size"].as_u64().unwrap_or(0)));
+    top.truncate(limit);
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/largest?limit=&scan_limit=
+pub async fn objset_largest_files(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<LargestFilesQuery>,
+) -> ApiResult {
+    let limit = normalize_largest_files_limit(params.limit);
+    let scan_limit = normalize_largest_files_scan(params.scan_limit)?;
+
+    let mut top: Vec<Value> = Vec::new();
+    let mut file_objects = 0;
+    // A top-0 request is an empty page; there is nothing to scan for.
+    let scan = if limit == 0 {
+        ObjsetScan {
+            scanned: 0,
+            truncated: false,
+        }
+    } else {
+        scan_objset_in_batches(
+            &state,
+            &pool,
+            scan_limit,
+            LARGEST_FILES_SCAN_BATCH,
+            |pool_ptr, start, batch_limit| {
+                // Once the top-N is full, only files strictly larger than its
+                // smallest entry can still place.
+                let min_size = if top.len() as u64 == limit {
+                    top.last()
+                        .and_then(|file| file["size"].as_u64())
+                        .map_or(0, |size| size.saturating_add(1))
+                } else {
+                    0
+                };
+                let mut batch = objset_scan_batch(crate::ffi::objset_largest_files(
+                    pool_ptr,
+                    objset_id,
+                    start,
+                    limit,
+                    batch_limit,
+                    min_size,
+                ))?;
+                file_objects += batch["file_objects"].as_u64().unwrap_or(0);
+                let files = match batch["files"].take() {
+                    Value::Array(files) => files,
+                    _ => Vec::new(),
+                };
+                merge_largest_files(&mut top, files, limit as usize);
+                Ok(ScanStep::from_batch(&batch))
+            },
+        )
+        .await?
+    };
+
+    Ok(Json(json!({
+        "objset_id": objset_id,
+        "limit": limit,
+        "scan_limit": scan_limit,
+        "scanned": scan.scanned,
+        "file_objects": file_objects,
+        "truncated": scan.truncated,
+        "count": top.len(),
+        "files": top,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpaceByTypeQuery {
+    pub scan_limit: Option<u64>,
+}
+
+/// Add one batch's per-type sums into the running totals, keyed by DMU type.
+fn merge_space_by_type(totals: &mut BTreeMap<u64, Value>, batch: &Value) {
+    const SUMMED: [&str; 4] = [
+        "count",
+        "logical_bytes",
+        "physical_bytes",
+        "allocated_bytes",
+    ];
+    for row in batch["types"].as_array().into_iter().flatten() {
+        let Some(type_id) = row["type"].as_u64() else {
+            continue;
+        };
+        let total = totals.entry(type_id).or_insert_with(|| {
+            let mut seed = row.clone();
+            for key in SUMMED {
+                seed[key] = json!(0);
+            }
+            seed
+        });
+        for key in SUMMED {
+            let sum = total[key]
+                .as_u64()
+                .unwrap_or(0)
+                .saturating_add(row[key].as_u64().unwrap_or(0));
+            total[key] = json!(sum);
+        }
+    }
+}
+
+/// Final per-type rows, largest allocation first, with `compress_ratio`
+/// (logical / physical level-0 bytes; null when nothing was written).
+fn finish_space_by_type(totals: BTreeMap<u64, Value>) -> Vec<Value> {
+    let mut rows: Vec<Value> = totals
+        .into_values()
+        .map(|mut row| {
+            let logical = row["logical_bytes"].as_u64().unwrap_or(0);
+            let physical = row["physical_bytes"].as_u64().unwrap_or(0);
+            row["compress_ratio"] = if physical == 0 {
+                Value::Null
+            } else {
+                json!(((logical as f64 / physical as f64) * 100.0).round() / 100.0)
+            };
+            row
+        })
+        .collect();
+    rows.sort_by_key(|row| std::cmp::Reverse(row["allocated_bytes"].as_u64().unwrap_or(0)));
+    rows
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/space-by-type?scan_limit=
+pub async fn objset_space_by_type(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<SpaceByTypeQuery>,
+) -> ApiResult {
+    let scan_limit = reject_zero("scan_limit", params.scan_limit)?
+        .unwrap_or(SPACE_BY_TYPE_DEFAULT_SCAN)
+        .min(SPACE_BY_TYPE_MAX_SCAN);
+
+    let mut totals = BTreeMap::new();
+    let scan = scan_objset_in_batches(
+        &state,
+        &pool,
+        scan_limit,
+        SPACE_BY_TYPE_SCAN_BATCH,
+        |pool_ptr, start, batch_limit| {
+            let batch = objset_scan_batch(crate::ffi::objset_space_by_type(
+                pool_ptr,
+                objset_id,
+                start,
+                batch_limit,
+            ))?;
+            merge_space_by_type(&mut totals, &batch);
+            Ok(ScanStep::from_batch(&batch))
+        },
+    )
+    .await?;
+
+    let types = finish_space_by_type(totals);
+    Ok(Json(json!({
+        "objset_id": objset_id,
+        "scan_limit": scan_limit,
+        "scanned": scan.scanned,
+        "truncated": scan.truncated,
+        "count": types.len(),
+        "types": types,
+    })))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/sa
+pub async fn objset_sa_layouts(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::objset_sa_layouts(pool_ptr, objset_id);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/zap
+pub async fn objset_zap_entries(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+    ApiQuery(params): ApiQuery<ZapEntriesQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let result =
+        crate::ffi::objset_zap_entries(pool_ptr, objset_id, objid, cursor, fetch_limit(limit));
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    finish_page(limit, "entries", Ok(Json(value)))
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/full
+pub async fn objset_get_full(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+
+    let obj_result = crate::ffi::objset_get_object(pool_ptr, objset_id, objid);
+    if !obj_result.is_ok() {
+        let err_msg = obj_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+
+    let blk_result = crate::ffi::objset_get_blkptrs(pool_ptr, objset_id, objid);
+    if !blk_result.is_ok() {
+        let err_msg = blk_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+
+    let obj_json = obj_result.json().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Missing JSON in object result",
+        )
+    })?;
+    let blk_json = blk_result.json().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Missing JSON in blkptr result",
+        )
+    })?;
+
+    let obj_value = parse_json_value(obj_json)?;
+    let blk_value = parse_json_value(blk_json)?;
+
+    let mut zap_info_value = Value::Null;
+    let mut zap_entries_value = Value::Null;
+    let mut zap_error_value = Value::Null;
+    let is_zap = obj_value
+        .get("is_zap")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if is_zap {
+        let zinfo_result = crate::ffi::objset_zap_info(pool_ptr, objset_id, objid);
+        if !zinfo_result.is_ok() {
+            let err_msg = zinfo_result.error_msg().unwrap_or("Unknown error");
+            if let Some(payload) = inline_zap_error_payload(err_msg) {
+                zap_error_value = payload;
+            } else {
+                return Err(api_error_for_objset(err_msg));
+            }
+        } else {
+            let zinfo_json = zinfo_result.json().ok_or_else(|| {
+                api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Missing JSON in objset zap info result",
+                )
+            })?;
+            zap_info_value = parse_json_value(zinfo_json)?;
+        }
+
+        if zap_error_value.is_null() {
+            let zents_result =
+                crate::ffi::objset_zap_entries(pool_ptr, objset_id, objid, 0, DEFAULT_PAGE_LIMIT);
+            if !zents_result.is_ok() {
+                let err_msg = zents_result.error_msg().unwrap_or("Unknown error");
+                if let Some(payload) = inline_zap_error_payload(err_msg) {
+                    zap_error_value = payload;
+                } else {
+                    return Err(api_error_for_objset(err_msg));
+                }
+            } else {
+                let zents_json = zents_result.json().ok_or_else(|| {
+                    api_error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Missing JSON in objset zap entries result",
+                    )
+                })?;
+                zap_entries_value = parse_json_value(zents_json)?;
+            }
+        }
+    }
+
+    Ok(Json(json!({
+        "object": obj_value,
+        "blkptrs": blk_value,
+        "zap_info": zap_info_value,
+        "zap_entries": zap_entries_value,
+        "zap_error": zap_error_value
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ObjsetDataQuery {
+    pub offset: Option<u64>,
+    pub limit: Option<u64>,
+    pub logical: Option<bool>,
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/obj/:objid/data?offset=&limit=&logical=
+/// (a `Range` header takes precedence over offset/limit)
+pub async fn objset_read_data(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+    ApiQuery(params): ApiQuery<ObjsetDataQuery>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let logical = params.logical.unwrap_or(false);
+
+    let range = if headers.contains_key(RANGE) {
+        let object_size = objset_object_size(pool_ptr, objset_id, objid)?;
+        let (start, end, partial) = parse_range_header(&headers, object_size)?;
+        Some((start, end, partial, object_size))
+    } else {
+        None
+    };
+    let logical_size = match (logical, range) {
+        (false, _) => None,
+        (true, Some((_, _, _, object_size))) => Some(object_size),
+        (true, None) => Some(objset_object_size(pool_ptr, objset_id, objid)?),
+    };
+    let (offset, limit) = match range {
+        Some((start, end, _, _)) => (start, (end - start + 1).min(OBJSET_DATA_MAX_LIMIT)),
+        None => (
+            params.offset.unwrap_or(0),
+            normalize_objset_data_limit(params.limit),
+        ),
+    };
+
+    let result =
+        crate::ffi::objset_read_data(pool_ptr, objset_id, objid, offset, fetch_limit(limit));
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_objset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    objset_data_response(value, offset, limit, logical_size, range)
+}
+
+/// Shape a native data read into the `objset/.../data` response: empty
+/// slice for `limit=0`, trim to the logical size, and range headers.
+fn objset_data_response(
+    mut value: Value,
+    offset: u64,
+    limit: u64,
+    logical_size: Option<u64>,
+    range: Option<(u64, u64, bool, u64)>,
+) -> Result<Response<Body>, ApiError> {
+    if limit == 0 {
+        value = empty_data_slice(value);
+    }
+    if let Some(logical_size) = logical_size {
+        trim_data_to_logical_size(&mut value, offset, logical_size)?;
+    }
+
+    let mut response = Json(&value).into_response();
+    response
+        .headers_mut()
+        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Some((start, _, true, object_size)) = range {
+        let served = value["size"].as_u64().unwrap_or(0);
+        *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+        if served > 0 {
+            let end = start + served - 1;
+            response.headers_mut().insert(
+                CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes {start}-{end}/{object_size}"))
+                    .unwrap_or(HeaderValue::from_static("bytes */0")),
+            );
+        }
+    }
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OfflineScanQuery {
+    pub path: Option<String>,
+}
+
+/// 400 envelope for a label scan the client can fix: the device cannot be
+/// opened, or it opens but carries no pool label. `None` for anything else.
+fn offline_scan_error(err_msg: &str) -> Option<ApiError> {
+    if err_msg.contains("no ZFS label found") {
+        return Some(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "NO_ZFS_LABEL",
+            err_msg.to_string(),
+            Some("Point path at a pool member device or a full-disk image.".to_string()),
+            true,
+        ));
+    }
+    if err_msg.starts_with("cannot open") {
+        return Some(invalid_path_error(
+            err_msg,
+            "Pass a device or image file readable by the server.",
+        ));
+    }
+    None
+}
+
+/// Directories `offline/scan` may read from: the configured offline search
+/// paths, or `/dev` when none are set.
+fn offline_scan_roots(search_paths: Option<&str>) -> Vec<std::path::PathBuf> {
+    let roots: Vec<std::path::PathBuf> = search_paths
+        .unwrap_or_default()
+        .split(':')
+        .map(str::trim)
+        .filter(|root| root.starts_with('/'))
+        .map(std::path::PathBuf::from)
+        .collect();
+    if roots.is_empty() {
+        vec![std::path::PathBuf::from("/dev")]
+    } else {
+        roots
+    }
+}
+
+/// Resolve `path` with `std::fs::canonicalize` and return it when it lies
+/// inside one of the (likewise canonicalized) `roots`. Relative paths, paths
+/// that cannot be resolved, and symlinks or `..` leading outside are refused.
+fn offline_scan_allowed_path(path: &str, roots: &[std::path::PathBuf]) -> Option<String> {
+    let path = std::path::Path::new(path);
+    if !path.is_absolute() {
+        return None;
+    }
+    let resolved = std::fs::canonicalize(path).ok()?;
+    let inside = roots
+        .iter()
+        .filter_map(|root| std::fs::canonicalize(root).ok())
+        .any(|root| resolved.starts_with(root));
+    if !inside {
+        return None;
+    }
+    resolved.into_os_string().into_string().ok()
+}
+
+/// GET /api/offline/scan?path=
+pub async fn offline_scan(
+    State(state): State<AppState>,
+    ApiQuery(params): ApiQuery<OfflineScanQuery>,
+) -> ApiResult {
+    let path = params
+        .path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| {
+            invalid_path_error(
+                "path is required",
+                "Use /api/offline/scan?path=/dev/sdX or an image file path.",
+            )
+        })?;
+    let roots = offline_scan_roots(pool_open_config(&state).offline_search_paths.as_deref());
+    let Some(path) = offline_scan_allowed_path(path, &roots) else {
+        let roots: Vec<String> = roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect();
+        return Err(invalid_path_error(
+            "path is outside the offline search paths",
+            &format!(
+                "Pass an existing absolute path that resolves under {} (ZFS_EXPLORER_OFFLINE_PATHS).",
+                roots.join(", ")
+            ),
+        ));
+    };
+    let result = crate::ffi::label_pool_name(&path)
+        .map_err(|message| invalid_path_error(message, "Remove NUL bytes from path."))?;
+    if !result.is_ok() {
+        if let Some(error) = result.error_msg().and_then(offline_scan_error) {
+            return Err(error);
+        }
+    }
+    json_from_result(result)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ObjsetReadBatchItem {
+    pub objid: u64,
+    pub offset: Option<u64>,
+    pub length: Option<u64>,
+}
+
+/// Resolve a read-batch body to `(objid, offset, length)` triples. Each length
+/// is clamped like `objset/.../data?limit=`; the item count and the summed
+/// lengths are capped so one request cannot pin the FFI lock indefinitely.
+fn plan_read_batch(items: &[ObjsetReadBatchItem]) -> Result<Vec<(u64, u64, u64)>, ApiError> {
+    if items.is_empty() || items.len() > READ_BATCH_MAX_ITEMS {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_QUERY",
+            format!(
+                "read-batch takes 1..={READ_BATCH_MAX_ITEMS} items, got {}",
+                items.len()
+            ),
+            Some("Split larger reconstructions across several requests.".to_string()),
+            true,
+        ));
+    }
+    let reads: Vec<(u64, u64, u64)> = items
+        .iter()
+        .map(|item| {
+            (
+                item.objid,
+                item.offset.unwrap_or(0),
+                normalize_objset_data_limit(item.length),
+            )
+        })
+        .collect();
+    let total: u64 = reads.iter().map(|&(_, _, length)| length).sum();
+    if total > READ_BATCH_MAX_BYTES {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_QUERY",
+            format!("read-batch requests {total} bytes, max {READ_BATCH_MAX_BYTES}"),
+            Some("Request smaller lengths or fewer items per batch.".to_string()),
+            true,
+        ));
+    }
+    Ok(reads)
+}
+
+/// One read-batch result: the data slice, or the error envelope the single
+/// `data` endpoint would have returned for the same read.
+fn read_batch_result(result: crate::ffi::ZdxResult, length: u64) -> Result<Value, ApiError> {
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_objset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+    let Json(value) = json_from_result(result)?;
+    if length == 0 {
+        return Ok(empty_data_slice(value));
+    }
+    Ok(value)
+}
+
+/// POST /api/pools/:pool/objset/:objset_id/read-batch
+pub async fn objset_read_batch(
+    State(state): State<AppState>,
+    Path((pool, objset_id)): Path<(String, u64)>,
+    Json(items): Json<Vec<ObjsetReadBatchItem>>,
+) -> ApiResult {
+    let reads = plan_read_batch(&items)?;
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let fetches: Vec<(u64, u64, u64)> = reads
+        .iter()
+        .map(|&(objid, offset, length)| (objid, offset, fetch_limit(length)))
+        .collect();
+    let results = crate::ffi::objset_read_data_batch(pool_ptr, objset_id, &fetches);
+
+    let mut total_bytes = 0;
+    let rows: Vec<Value> = reads
+        .iter()
+        .zip(results)
+        .map(|(&(objid, offset, length), result)| {
+            let result = read_batch_result(result, length);
+            if let Ok(value) = &result {
+                total_bytes += value["size"].as_u64().unwrap_or(0);
+            }
+            let mut row = overview_section(result);
+            row["objid"] = json!(objid);
+            row["offset"] = json!(offset);
+            row["length"] = json!(length);
+            row
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "objset_id": objset_id,
+        "total_bytes": total_bytes,
+        "results": rows,
+    })))
+}
+
+/// Restrict a DMU data slice to the object's logical size for
+/// `logical=true`. `dmu_read()` already returns decompressed bytes, but the
+/// DMU length is rounded up to whole blocks, so the tail past the ZPL size
+/// is padding rather than file content.
+fn trim_data_to_logical_size(
+    value: &mut Value,
+    offset: u64,
+    logical_size: u64,
+) -> Result<(), ApiError> {
+    if offset > logical_size {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_QUERY",
+            format!("offset {offset} exceeds object logical size {logical_size}"),
+            Some("Use an offset <= logical_size when logical=true.".to_string()),
+            false,
+        ));
+    }
+    let served = value["size"].as_u64().unwrap_or(0);
+    let keep = served.min(logical_size - offset);
+    if let Some(hex) = value["data_hex"].as_str() {
+        let hex_len = usize::try_from(keep * 2)
+            .unwrap_or(usize::MAX)
+            .min(hex.len());
+        value["data_hex"] = Value::String(hex[..hex_len].to_string());
+    }
+    value["size"] = json!(keep);
+    value["eof"] = json!(offset + keep >= logical_size);
+    value["logical"] = json!(true);
+    value["logical_size"] = json!(logical_size);
+    Ok(())
+}
+
+/// Size used to resolve `Range` requests: ZPL size when the object has SA
+/// attributes, otherwise the DMU data length.
+fn objset_object_size(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    objset_id: u64,
+    objid: u64,
+) -> Result<u64, ApiError> {
+    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, objid);
+    if stat_result.is_ok() {
+        if let Some(stat) = stat_result
+            .json()
+            .and_then(|json| serde_json::from_str::<ObjsetStatPayload>(json).ok())
+        {
+            return Ok(stat.size);
+        }
+    }
+
+    let probe = crate::ffi::objset_read_data(pool_ptr, objset_id, objid, 0, 1);
+    if !probe.is_ok() {
+        let err_msg = probe.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+    let json_str = probe
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    parse_json_value(json_str)?["max_offset"]
+        .as_u64()
+        .ok_or_else(|| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "object data payload missing max_offset",
+            )
+        })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DatasetCatalogEntry {
+    name: String,
+    #[serde(rename = "type")]
+    dataset_type: String,
+    mountpoint: Option<String>,
+    mounted: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjsetWalkPayload {
+    objid: u64,
+    found: bool,
+    remaining: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjsetStatPayload {
+    size: u64,
+    type_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjsetDataPayload {
+    data_hex: String,
+}
+
+#[derive(Debug, Clone)]
+struct ZplPathContext {
+    dataset_name: String,
+    objset_id: u64,
+    rel_path: String,
+    objid: u64,
+    file_size: u64,
+    filename: String,
+}
+
+#[derive(Debug, Clone)]
+struct ObjsetScopedZplPathContext {
+    objset_id: u64,
+    rel_path: String,
+    objid: u64,
+    file_size: u64,
+    filename: String,
+}
+
+fn decode_hex_bytes(data_hex: &str) -> Result<Vec<u8>, ApiError> {
+    let trimmed = data_hex.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    if trimmed.len() % 2 != 0 {
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "invalid hex payload length from backend read",
+        ));
+    }
+
+    fn nibble(byte: u8) -> Option<u8> {
+        match byte {
+            b'0'..=b'9' => Some(byte - b'0'),
+            b'a'..=b'f' => Some(byte - b'a' + 10),
+            b'A'..=b'F' => Some(byte - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let bytes = trimmed.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+    let mut idx = 0usize;
+    while idx < bytes.len() {
+        let hi = nibble(bytes[idx]).ok_or_else(|| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "invalid hex payload from backend read",
+            )
+        })?;
+        let lo = nibble(bytes[idx + 1]).ok_or_else(|| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "invalid hex payload from backend read",
+            )
+        })?;
+        out.push((hi << 4) | lo);
+        idx += 2;
+    }
+
+    Ok(out)
+}
+
+fn split_clean_path(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn invalid_path_error(message: impl Into<String>, hint: &str) -> ApiError {
+    api_error_with(
+        StatusCode::BAD_REQUEST,
+        "INVALID_PATH",
+        message,
+        Some(hint.to_string()),
+        true,
+    )
+}
+
+/// Normalize a client-supplied ZPL path to slash-joined components without a
+/// leading `/`, dropping empty and `.` segments. `..` components and embedded
+/// NULs are rejected up front so they never reach dataset matching or the
+/// native walk.
+fn normalize_client_zpl_path(path: &str) -> Result<String, ApiError> {
+    if path.contains('\0') {
+        return Err(invalid_path_error(
+            "path contains a NUL byte",
+            "Remove the NUL character from the path.",
+        ));
+    }
+    let segments: Vec<&str> = split_clean_path(path.trim())
+        .into_iter()
+        .filter(|segment| *segment != ".")
+        .collect();
+    if segments.contains(&"..") {
+        return Err(invalid_path_error(
+            "path must not contain '..' components",
+            "Address the file by its full path from the dataset or mount root.",
+        ));
+    }
+    Ok(segments.join("/"))
+}
+
+fn dataset_path_match(dataset: &str, path: &str) -> Option<String> {
+    if path == dataset {
+        return Some(String::new());
+    }
+
+    let prefix = format!("{dataset}/");
+    if path.starts_with(&prefix) {
+        return Some(path[prefix.len()..].to_string());
+    }
+
+    None
+}
+
+fn mountpoint_path_match(mountpoint: &str, absolute_path: &str) -> Option<String> {
+    if absolute_path == mountpoint {
+        return Some(String::new());
+    }
+
+    let prefix = format!("{mountpoint}/");
+    if absolute_path.starts_with(&prefix) {
+        return Some(absolute_path[prefix.len()..].to_string());
+    }
+
+    None
+}
+
+fn load_dataset_catalog(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+) -> Result<Vec<DatasetCatalogEntry>, ApiError> {
+    let datasets_result = crate::ffi::pool_datasets(pool_ptr);
+    if !datasets_result.is_ok() {
+        let err_msg = datasets_result.error_msg().unwrap_or("Unknown error");
+        let err_code = datasets_result.error_code();
+        let code = pool_open_error_code(err_code);
+        return Err(api_error_with(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            code,
+            format!("failed to list datasets: {err_msg}"),
+            None,
+            false,
+        ));
+    }
+
+    let datasets_json = datasets_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let datasets_value = parse_json_value(datasets_json)?;
+    serde_json::from_value::<Vec<DatasetCatalogEntry>>(datasets_value).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse dataset catalog: {err}"),
+        )
+    })
+}
+
+/// Dataset catalog cached per open pool for ZPL path resolution.
+#[derive(Debug)]
+pub struct DatasetCatalogCache {
+    pool: String,
+    loaded_at: Instant,
+    entries: Vec<DatasetCatalogEntry>,
+}
+
+fn dataset_catalog_ttl(mode: crate::PoolOpenMode) -> Option<Duration> {
+    match mode {
+        // Exported pools cannot change underneath us.
+        crate::PoolOpenMode::Offline => None,
+        crate::PoolOpenMode::Live => {
+            let secs = std::env::var("ZFS_EXPLORER_DATASET_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|raw| raw.trim().parse::<u64>().ok())
+                .unwrap_or(DATASET_CATALOG_DEFAULT_TTL_SECS);
+            Some(Duration::from_secs(secs))
+        }
+    }
+}
+
+fn cached_dataset_catalog(
+    state: &AppState,
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+    bypass_cache: bool,
+) -> Result<Vec<DatasetCatalogEntry>, ApiError> {
+    let ttl = dataset_catalog_ttl(pool_open_config(state).mode);
+    if !bypass_cache {
+        let guard = state.dataset_catalog.lock().unwrap();
+        if let Some(cache) = guard.as_ref() {
+            let fresh = match ttl {
+                Some(ttl) => cache.loaded_at.elapsed() < ttl,
+                None => true,
+            };
+            if cache.pool == pool_name && fresh {
+                return Ok(cache.entries.clone());
+            }
+        }
+    }
+
+    let entries = load_dataset_catalog(pool_ptr)?;
+    *state.dataset_catalog.lock().unwrap() = Some(DatasetCatalogCache {
+        pool: pool_name.to_string(),
+        loaded_at: Instant::now(),
+        entries: entries.clone(),
+    });
+    Ok(entries)
+}
+
+/// Sorted (txg, unix seconds) anchors used to estimate wall-clock times for txgs.
+#[derive(Debug)]
+pub struct TxgTimeIndex {
+    pool: String,
+    loaded_at: Instant,
+    anchors: Vec<(u64, u64)>,
+}
+
+impl TxgTimeIndex {
+    fn from_payload(pool: &str, payload: &Value) -> Self {
+        let mut anchors = payload["anchors"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|pair| Some((pair.get(0)?.as_u64()?, pair.get(1)?.as_u64()?)))
+            .collect::<Vec<_>>();
+        if let (Some(txg), Some(time)) = (
+            payload["uberblock"]["txg"].as_u64(),
+            payload["uberblock"]["timestamp"].as_u64(),
+        ) {
+            anchors.push((txg, time));
+        }
+        anchors.sort_unstable();
+        anchors.dedup_by_key(|(txg, _)| *txg);
+
+        // History timestamps come from the host clock; drop anchors that would
+        // make time run backwards rather than interpolate across the skew.
+        let mut monotonic: Vec<(u64, u64)> = Vec::with_capacity(anchors.len());
+        for (txg, time) in anchors {
+            if monotonic.last().is_none_or(|(_, last)| time >= *last) {
+                monotonic.push((txg, time));
+            }
+        }
+
+        Self {
+            pool: pool.to_string(),
+            loaded_at: Instant::now(),
+            anchors: monotonic,
+        }
+    }
+
+    /// Exact time for a known txg, linear interpolation between the nearest
+    /// known txgs on either side, or None outside the known range.
+    fn unix_sec_for(&self, txg: u64) -> Option<u64> {
+        match self.anchors.binary_search_by_key(&txg, |(t, _)| *t) {
+            Ok(idx) => Some(self.anchors[idx].1),
+            Err(idx) if idx > 0 && idx < self.anchors.len() => {
+                let (lo_txg, lo_time) = self.anchors[idx - 1];
+                let (hi_txg, hi_time) = self.anchors[idx];
+                let span = (hi_time - lo_time) as u128 * (txg - lo_txg) as u128;
+                Some(lo_time + (span / (hi_txg - lo_txg) as u128) as u64)
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+fn cached_txg_time_index(
+    state: &AppState,
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+) -> Option<Arc<TxgTimeIndex>> {
+    let ttl = dataset_catalog_ttl(pool_open_config(state).mode);
+    {
+        let guard = state.txg_time_index.lock().unwrap();
+        if let Some(index) = guard.as_ref() {
+            let fresh = match ttl {
+                Some(ttl) => index.loaded_at.elapsed() < ttl,
+                None => true,
+            };
+            if index.pool == pool_name && fresh {
+                return Some(index.clone());
+            }
+        }
+    }
+
+    let result = crate::ffi::txg_time_anchors(pool_ptr, TXG_TIME_MAX_ANCHORS);
+    if !result.is_ok() {
+        tracing::warn!(
+            "txg time index unavailable for {}: {}",
+            pool_name,
+            result.error_msg().unwrap_or("Unknown error")
+        );
+        return None;
+    }
+    let payload = serde_json::from_str::<Value>(result.json()?).ok()?;
+    if let Some(err) = payload["history_error"].as_str() {
+        tracing::warn!("pool history incomplete for {}: {}", pool_name, err);
+    }
+    let index = Arc::new(TxgTimeIndex::from_payload(pool_name, &payload));
+    *state.txg_time_index.lock().unwrap() = Some(index.clone());
+    Some(index)
+}
+
+/// Adds `birth_unix_sec` / `creation_unix_sec` next to every `birth_txg` /
+/// `creation_txg` field, preferring an on-disk `creation_time` when present.
+fn annotate_txg_times(value: &mut Value, index: &TxgTimeIndex) {
+    match value {
+        Value::Object(map) => {
+            if let Some(txg) = map.get("birth_txg").and_then(Value::as_u64) {
+                map.entry("birth_unix_sec")
+                    .or_insert_with(|| json!(index.unix_sec_for(txg)));
+            }
+            if let Some(txg) = map.get("creation_txg").and_then(Value::as_u64) {
+                let exact = map.get("creation_time").and_then(Value::as_u64);
+                map.entry("creation_unix_sec")
+                    .or_insert_with(|| json!(exact.or_else(|| index.unix_sec_for(txg))));
+            }
+            for child in map.values_mut() {
+                annotate_txg_times(child, index);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                annotate_txg_times(item, index);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn with_txg_times(
+    state: &AppState,
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+    mut value: Value,
+) -> Value {
+    if let Some(index) = cached_txg_time_index(state, pool_ptr, pool_name) {
+        annotate_txg_times(&mut value, &index);
+    }
+    value
+}
+
+/// Reverse map from MOS objid to the well-known name that reaches it: the
+/// object directory entries, plus the special children of `root_dataset`.
+#[derive(Debug)]
+pub struct KnownNameIndex {
+    pool: String,
+    loaded_at: Instant,
+    names: HashMap<u64, String>,
+}
+
+impl KnownNameIndex {
+    /// `root_children` are the `(name, dir_objid)` pairs under the root DSL
+    /// directory; only the `$`-prefixed internal ones are labelled.
+    fn from_parts(
+        pool: &str,
+        directory_entries: &[Value],
+        root_children: &[(String, u64)],
+    ) -> Self {
+        let mut named: Vec<(&str, u64)> = directory_entries
+            .iter()
+            .filter(|entry| entry["maybe_object_ref"].as_bool().unwrap_or(false))
+            .filter_map(|entry| Some((entry["name"].as_str()?, entry["target_obj"].as_u64()?)))
+            .filter(|(_, objid)| *objid != 0)
+            .collect();
+        // Several entries may share a target; keep the result independent of
+        // ZAP iteration order.
+        named.sort();
+
+        let mut names = HashMap::new();
+        names.insert(MOS_OBJECT_DIRECTORY, "object_directory".to_string());
+        for (name, objid) in named {
+            names.entry(objid).or_insert_with(|| name.to_string());
+        }
+        for (child, objid) in root_children {
+            if child.starts_with('$') {
+                names
+                    .entry(*objid)
+                    .or_insert_with(|| format!("root_dataset/{child}"));
+            }
+        }
+
+        Self {
+            pool: pool.to_string(),
+            loaded_at: Instant::now(),
+            names,
+        }
+    }
+
+    fn name_for(&self, objid: u64) -> Option<&str> {
+        self.names.get(&objid).map(String::as_str)
+    }
+}
+
+fn cached_known_names(
+    state: &AppState,
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+) -> Option<Arc<KnownNameIndex>> {
+    let ttl = dataset_catalog_ttl(pool_open_config(state).mode);
+    {
+        let guard = state.known_names.lock().unwrap();
+        if let Some(index) = guard.as_ref() {
+            let fresh = match ttl {
+                Some(ttl) => index.loaded_at.elapsed() < ttl,
+                None => true,
+            };
+            if index.pool == pool_name && fresh {
+                return Some(index.clone());
+            }
+        }
+    }
+
+    let directory_entries = match read_object_directory(pool_ptr) {
+        Ok(entries) => entries,
+        Err((_, Json(body))) => {
+            tracing::warn!(
+                "known-name index unavailable for {}: {}",
+                pool_name,
+                body["message"].as_str().unwrap_or("Unknown error")
+            );
+            return None;
+        }
+    };
+    let root_children = directory_entries
+        .iter()
+        .find(|entry| entry["name"].as_str() == Some("root_dataset"))
+        .and_then(|entry| entry["target_obj"].as_u64())
+        .and_then(|root_dir| {
+            let result = crate::ffi::dsl_dir_children(pool_ptr, root_dir);
+            let value = parse_json_value(result.json()?).ok()?;
+            Some(parse_dsl_children(&value))
+        })
+        .unwrap_or_default();
+
+    let index = Arc::new(KnownNameIndex::from_parts(
+        pool_name,
+        &directory_entries,
+        &root_children,
+    ));
+    *state.known_names.lock().unwrap() = Some(index.clone());
+    Some(index)
+}
+
+/// Adds `known_name` (null for anonymous objects) to a MOS object payload.
+fn with_known_name(
+    state: &AppState,
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+    objid: u64,
+    mut value: Value,
+) -> Value {
+    let index = cached_known_names(state, pool_ptr, pool_name);
+    if let Some(map) = value.as_object_mut() {
+        let name = index.as_deref().and_then(|index| index.name_for(objid));
+        map.insert("known_name".to_string(), json!(name));
+    }
+    value
+}
+
+fn resolve_pool_root_dir_obj(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+) -> Result<u64, ApiError> {
+    let root_result = crate::ffi::dsl_root_dir(pool_ptr);
+    if !root_result.is_ok() {
+        let err_msg = root_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to resolve DSL root: {err_msg}"),
+        ));
+    }
+    let root_json = root_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let root_value = parse_json_value(root_json)?;
+    let root_dir_obj = root_value["root_dir_obj"].as_u64().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "root_dir_obj missing in DSL root payload",
+        )
+    })?;
+
+    let children_result = crate::ffi::dsl_dir_children(pool_ptr, root_dir_obj);
+    if !children_result.is_ok() {
+        let err_msg = children_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to enumerate DSL children: {err_msg}"),
+        ));
+    }
+    let children_json = children_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let children_value = parse_json_value(children_json)?;
+    let children = parse_dsl_children(&children_value);
+    if let Some((_, objid)) = children.iter().find(|(name, _)| name == pool_name) {
+        return Ok(*objid);
+    }
+
+    let head_result = crate::ffi::dsl_dir_head(pool_ptr, root_dir_obj);
+    if head_result.is_ok() {
+        let head_json = head_result.json().ok_or_else(|| {
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+        })?;
+        let head_value = parse_json_value(head_json)?;
+        let head_obj = head_value["head_dataset_obj"].as_u64().unwrap_or(0);
+        if head_obj != 0 {
+            return Ok(root_dir_obj);
+        }
+    } else {
+        let err_msg = head_result.error_msg().unwrap_or("Unknown error");
+        tracing::debug!(
+            "dsl_dir_head failed for DSL root {} while resolving pool {}: {}",
+            root_dir_obj,
+            pool_name,
+            err_msg
+        );
+    }
+
+    tracing::debug!(
+        "using DSL root {} as pool root for {} (no matching child and no head dataset)",
+        root_dir_obj,
+        pool_name
+    );
+
+    Ok(root_dir_obj)
+}
+
+fn resolve_dataset_dir_obj_by_name(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+    dataset_name: &str,
+) -> Result<u64, ApiError> {
+    let pool_prefix = format!("{pool_name}/");
+    if dataset_name != pool_name && !dataset_name.starts_with(&pool_prefix) {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_DATASET_PATH",
+            format!("dataset '{dataset_name}' is not under pool '{pool_name}'"),
+            Some("Use paths rooted at the selected pool name.".to_string()),
+            true,
+        ));
+    }
+
+    let result = crate::ffi::dsl_dir_by_name(pool_ptr, dataset_name)
+        .map_err(|err| api_error(StatusCode::INTERNAL_SERVER_ERROR, err))?;
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("No such file or directory")
+            || err_msg.contains("dsl_dir_hold failed")
+            || err_msg.contains("not found")
+        {
+            return Err(api_error_with(
+                StatusCode::NOT_FOUND,
+                "DATASET_NOT_FOUND",
+                format!("dataset '{dataset_name}' not found"),
+                Some("Refresh dataset tree and verify the dataset path exists.".to_string()),
+                true,
+            ));
+        }
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to resolve dataset '{dataset_name}': {err_msg}"),
+        ));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    let head_obj = value["head_dataset_obj"].as_u64().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("head_dataset_obj missing in DSL payload for dataset '{dataset_name}'"),
+        )
+    })?;
+    if head_obj == 0 {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "DATASET_NO_HEAD",
+            format!(
+                "dataset '{}' has no head dataset (special/internal dataset)",
+                dataset_name
+            ),
+            Some("Use a user-visible filesystem dataset path.".to_string()),
+            true,
+        ));
+    }
+    Ok(head_obj)
+}
+
+fn resolve_dataset_candidates_from_pool_path_via_dsl(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+    normalized_path: &str,
+) -> Result<Vec<(String, String, u64)>, ApiError> {
+    let components = split_clean_path(normalized_path);
+    if components.is_empty() || components[0] != pool_name {
+        return Ok(Vec::new());
+    }
+
+    let mut candidates = Vec::new();
+    for dataset_depth in (1..=components.len()).rev() {
+        let dataset_name = components[..dataset_depth].join("/");
+        let rel_path = if dataset_depth < components.len() {
+            components[dataset_depth..].join("/")
+        } else {
+            String::new()
+        };
+        match resolve_dataset_dir_obj_by_name(pool_ptr, pool_name, &dataset_name) {
+            Ok(objset_id) => candidates.push((dataset_name, rel_path, objset_id)),
+            Err(err) if err.0 == StatusCode::NOT_FOUND => continue,
+            Err(err) if err.0 == StatusCode::BAD_REQUEST => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(candidates)
+}
+
+fn resolve_zpl_path_context<F>(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    pool_name: &str,
+    zpl_path: &str,
+    prefer_dsl_resolution: bool,
+    load_catalog: F,
+) -> Result<ZplPathContext, ApiError>
+where
+    F: FnOnce() -> Result<Vec<DatasetCatalogEntry>, ApiError>,
+{
+    let trimmed = zpl_path.trim();
+    if trimmed.is_empty() {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PATH",
+            "path is empty",
+            Some(
+                "Provide a dataset-relative path like pool/dataset/file or an absolute mount path."
+                    .to_string(),
+            ),
+            true,
+        ));
+    }
+
+    let normalized_path = normalize_client_zpl_path(trimmed)?;
+    let absolute_path = format!("/{normalized_path}");
+
+    let (dataset_name, rel_path, objset_id) = if prefer_dsl_resolution {
+        let dsl_candidates = resolve_dataset_candidates_from_pool_path_via_dsl(
+            pool_ptr,
+            pool_name,
+            &normalized_path,
+        )?;
+        if dsl_candidates.is_empty() {
+            return Err(api_error_with(
+                StatusCode::BAD_REQUEST,
+                "DATASET_PATH_UNRESOLVED",
+                format!("could not resolve dataset for path '{zpl_path}'"),
+                Some(
+                    "In offline mode, use pool/dataset-relative paths (for example: \
+pool/dataset/file)."
+                        .to_string(),
+                ),
+                true,
+            ));
+        }
+
+        let mut resolved: Option<(String, String, u64)> = None;
+        for (candidate_dataset_name, candidate_rel_path, candidate_objset_id) in dsl_candidates {
+            if candidate_objset_id == 0 {
+                continue;
+            }
+            resolved = Some((
+                candidate_dataset_name,
+                candidate_rel_path,
+                candidate_objset_id,
+            ));
+            break;
+        }
+
+        if let Some(result) = resolved {
+            result
+        } else {
+            return Err(api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to resolve dataset candidates for path '{zpl_path}'"),
+            ));
+        }
+    } else {
+        match load_catalog() {
+            Ok(catalog) => {
+                let mut candidates: Vec<(usize, String, String)> = Vec::new();
+                for entry in catalog
+                    .iter()
+                    .filter(|entry| entry.dataset_type == "filesystem")
+                {
+                    if let Some(rel) = dataset_path_match(&entry.name, &normalized_path) {
+                        candidates.push((entry.name.len(), entry.name.clone(), rel));
+                    }
+
+                    if let Some(mountpoint) = entry.mountpoint.as_deref() {
+                        if entry.mounted != Some(false) {
+                            if let Some(rel) = mountpoint_path_match(mountpoint, &absolute_path) {
+                                candidates.push((mountpoint.len(), entry.name.clone(), rel));
+                            }
+                        }
+                    }
+                }
+
+                candidates.sort_by(|a, b| b.0.cmp(&a.0));
+                if candidates.is_empty() {
+                    return Err(api_error_with(
+                    StatusCode::BAD_REQUEST,
+                    "DATASET_PATH_UNRESOLVED",
+                    format!("could not resolve dataset for path '{zpl_path}'"),
+                    Some(
+                        "Use either an absolute mounted path (/pool/dataset/file) or a dataset path \
+like pool/dataset/file."
+                            .to_string(),
+                    ),
+                    true,
+                ));
+                }
+
+                let mut resolved: Option<(String, String, u64)> = None;
+
+                for (_, candidate_dataset_name, candidate_rel_path) in candidates {
+                    let candidate_objset_id = match resolve_dataset_dir_obj_by_name(
+                        pool_ptr,
+                        pool_name,
+                        &candidate_dataset_name,
+                    ) {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+
+                    if candidate_objset_id == 0 {
+                        continue;
+                    }
+                    resolved = Some((
+                        candidate_dataset_name,
+                        candidate_rel_path,
+                        candidate_objset_id,
+                    ));
+                    break;
+                }
+
+                if let Some(result) = resolved {
+                    result
+                } else {
+                    return Err(api_error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("failed to resolve dataset candidates for path '{zpl_path}'"),
+                    ));
+                }
+            }
+            Err(catalog_err) => {
+                let dsl_candidates = resolve_dataset_candidates_from_pool_path_via_dsl(
+                    pool_ptr,
+                    pool_name,
+                    &normalized_path,
+                )?;
+                if dsl_candidates.is_empty() {
+                    return Err(catalog_err);
+                }
+
+                let mut resolved: Option<(String, String, u64)> = None;
+                for (candidate_dataset_name, candidate_rel_path, candidate_objset_id) in
+                    dsl_candidates
+                {
+                    if candidate_objset_id == 0 {
+                        continue;
+                    }
+                    resolved = Some((
+                        candidate_dataset_name,
+                        candidate_rel_path,
+                        candidate_objset_id,
+                    ));
+                    break;
+                }
+
+                if let Some(result) = resolved {
+                    result
+                } else {
+                    return Err(catalog_err);
+                }
+            }
+        }
+    };
+
+    let walk_path = if rel_path.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{rel_path}")
+    };
+    tracing::debug!(
+        "zpl context resolved: pool={} dataset={} objset_id={} rel_path={} walk_path={}",
+        pool_name,
+        dataset_name,
+        objset_id,
+        rel_path,
+        walk_path
+    );
+    let walk_result = crate::ffi::objset_walk(pool_ptr, objset_id, &walk_path)
+        .map_err(|err| api_error(StatusCode::BAD_REQUEST, err))?;
+    if !walk_result.is_ok() {
+        let err_msg = walk_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "ZPL_WALK_FAILED",
+            format!("failed to walk path '{walk_path}': {err_msg}"),
+            Some("Verify the file path and dataset context.".to_string()),
+            true,
+        ));
+    }
+    let walk_json = walk_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let walk_value = parse_json_value(walk_json)?;
+    let walk = serde_json::from_value::<ObjsetWalkPayload>(walk_value).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse walk payload: {err}"),
+        )
+    })?;
+
+    if !walk.found || !walk.remaining.is_empty() {
+        return Err(api_error_with(
+            StatusCode::NOT_FOUND,
+            "PATH_NOT_FOUND",
+            format!("path '{walk_path}' could not be fully resolved"),
+            Some("The requested file may not exist in this dataset or snapshot state.".to_string()),
+            true,
+        ));
+    }
+
+    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, walk.objid);
+    if !stat_result.is_ok() {
+        let err_msg = stat_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "OBJSET_STAT_FAILED",
+            format!("failed to stat object {}: {}", walk.objid, err_msg),
+            None,
+            true,
+        ));
+    }
+    let stat_json = stat_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let stat_value = parse_json_value(stat_json)?;
+    let stat = serde_json::from_value::<ObjsetStatPayload>(stat_value).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse stat payload: {err}"),
+        )
+    })?;
+
+    if stat.type_name != "file" {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "NOT_A_FILE",
+            format!(
+                "resolved path '{walk_path}' is a {} object, not a file",
+                stat.type_name
+            ),
+            Some("Use this endpoint only for file paths.".to_string()),
+            true,
+        ));
+    }
+
+    let filename = split_clean_path(&rel_path)
+        .last()
+        .map(|segment| (*segment).to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| format!("objset-{objset_id}-obj-{}", walk.objid));
+
+    Ok(ZplPathContext {
+        dataset_name,
+        objset_id,
+        rel_path,
+        objid: walk.objid,
+        file_size: stat.size,
+        filename,
+    })
+}
+
+fn normalize_objset_zpl_path(zpl_path: &str) -> Result<String, ApiError> {
+    let trimmed = zpl_path.trim();
+    if trimmed.is_empty() {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PATH",
+            "path is empty",
+            Some("Provide a file path rooted at the selected objset.".to_string()),
+            true,
+        ));
+    }
+
+    let normalized = normalize_client_zpl_path(trimmed)?;
+    if normalized.is_empty() {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PATH",
+            "path is empty",
+            Some("Provide a file path rooted at the selected objset.".to_string()),
+            true,
+        ));
+    }
+
+    Ok(format!("/{normalized}"))
+}
+
+fn resolve_objset_scoped_zpl_path_context(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    objset_id: u64,
+    zpl_path: &str,
+) -> Result<ObjsetScopedZplPathContext, ApiError> {
+    let walk_path = normalize_objset_zpl_path(zpl_path)?;
+
+    let walk_result = crate::ffi::objset_walk(pool_ptr, objset_id, &walk_path)
+        .map_err(|err| api_error(StatusCode::BAD_REQUEST, err))?;
+    if !walk_result.is_ok() {
+        let err_msg = walk_result.error_msg().unwrap_or("Unknown error");
+        let status = if err_msg.contains("No such file or directory") {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        let code = if status == StatusCode::NOT_FOUND {
+            "PATH_NOT_FOUND"
+        } else {
+            "ZPL_WALK_FAILED"
+        };
+        return Err(api_error_with(
+            status,
+            code,
+            format!("failed to walk path '{walk_path}': {err_msg}"),
+            Some("Verify the requested objset id and file path.".to_string()),
+            true,
+        ));
+    }
+
+    let walk_json = walk_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let walk_value = parse_json_value(walk_json)?;
+    let walk = serde_json::from_value::<ObjsetWalkPayload>(walk_value).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse walk payload: {err}"),
+        )
+    })?;
+
+    if !walk.found || !walk.remaining.is_empty() {
+        return Err(api_error_with(
+            StatusCode::NOT_FOUND,
+            "PATH_NOT_FOUND",
+            format!("path '{walk_path}' could not be fully resolved"),
+            Some("The requested file may not exist in the selected objset.".to_string()),
+            true,
+        ));
+    }
+
+    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, walk.objid);
+    if !stat_result.is_ok() {
+        let err_msg = stat_result.error_msg().unwrap_or("Unknown error");
+        let status = if is_objset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error_with(
+            status,
+            "OBJSET_STAT_FAILED",
+            format!("failed to stat object {}: {}", walk.objid, err_msg),
+            None,
+            status.is_client_error(),
+        ));
+    }
+
+    let stat_json = stat_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let stat_value = parse_json_value(stat_json)?;
+    let stat = serde_json::from_value::<ObjsetStatPayload>(stat_value).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse stat payload: {err}"),
+        )
+    })?;
+
+    if stat.type_name != "file" {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "NOT_A_FILE",
+            format!(
+                "resolved path '{walk_path}' is a {} object, not a file",
+                stat.type_name
+            ),
+            Some("Use this endpoint only for file paths.".to_string()),
+            true,
+        ));
+    }
+
+    let rel_path = walk_path.trim_start_matches('/').to_string();
+    let filename = split_clean_path(&walk_path)
+        .last()
+        .map(|segment| (*segment).to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| format!("objset-{objset_id}-obj-{}", walk.objid));
+
+    Ok(ObjsetScopedZplPathContext {
+        objset_id,
+        rel_path,
+        objid: walk.objid,
+        file_size: stat.size,
+        filename,
+    })
+}
+
+fn resolve_snapshot_objset_id(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    dsobj: u64,
+) -> Result<u64, ApiError> {
+    let result = crate::ffi::dataset_objset(pool_ptr, dsobj);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_dataset_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    value["objset_id"].as_u64().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "snapshot objset payload missing objset_id",
+        )
+    })
+}
+
+fn parse_range_header(headers: &HeaderMap, total_size: u64) -> Result<(u64, u64, bool), ApiError> {
+    let Some(range_header) = headers.get(RANGE) else {
+        if total_size == 0 {
+            return Ok((0, 0, false));
+        }
+        return Ok((0, total_size - 1, false));
+    };
+
+    let header_value = range_header.to_str().map_err(|_| {
+        api_error_with(
+            StatusCode::BAD_REQUEST,
+            "BAD_RANGE",
+            "invalid Range header",
+            None,
+            true,
+        )
+    })?;
+    let trimmed = header_value.trim();
+    if !trimmed.starts_with("bytes=") {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "BAD_RANGE",
+            format!("unsupported Range header '{trimmed}'"),
+            Some("Use a single byte range, for example: bytes=0-1048575".to_string()),
+            true,
+        ));
+    }
+
+    let range_expr = trimmed.trim_start_matches("bytes=").trim();
+    if range_expr.contains(',') {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "BAD_RANGE",
+            "multiple byte ranges are not supported",
+            Some("Use a single range request per call.".to_string()),
+            true,
+        ));
+    }
+
+    if total_size == 0 {
+        return Err(api_error_with(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            "RANGE_NOT_SATISFIABLE",
+            "cannot satisfy range for empty file",
+            None,
+            true,
+        ));
+    }
+
+    let parts: Vec<&str> = range_expr.splitn(2, '-').collect();
+    if parts.len() != 2 {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "BAD_RANGE",
+            format!("invalid Range header '{trimmed}'"),
+            None,
+            true,
+        ));
+    }
+
+    let start_raw = parts[0].trim();
+    let end_raw = parts[1].trim();
+
+    let (start, end) = if start_raw.is_empty() {
+        let suffix_len = u64::from_str(end_raw).map_err(|_| {
+            api_error_with(
+                StatusCode::BAD_REQUEST,
+                "BAD_RANGE",
+                format!("invalid suffix range '{trimmed}'"),
+                None,
+                true,
+            )
+        })?;
+        if suffix_len == 0 {
+            return Err(api_error_with(
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                "RANGE_NOT_SATISFIABLE",
+                "suffix length must be greater than zero",
+                None,
+                true,
+            ));
+        }
+        if suffix_len >= total_size {
+            (0, total_size - 1)
+        } else {
+            (total_size - suffix_len, total_size - 1)
+        }
+    } else {
+        let start = u64::from_str(start_raw).map_err(|_| {
+            api_error_with(
+                StatusCode::BAD_REQUEST,
+                "BAD_RANGE",
+                format!("invalid range start '{start_raw}'"),
+                None,
+                true,
+            )
+        })?;
+        let end = if end_raw.is_empty() {
+            total_size - 1
+        } else {
+            u64::from_str(end_raw).map_err(|_| {
+                api_error_with(
+                    StatusCode::BAD_REQUEST,
+                    "BAD_RANGE",
+                    format!("invalid range end '{end_raw}'"),
+                    None,
+                    true,
+                )
+            })?
+        };
+        if start >= total_size || start > end {
+            return Err(api_error_with(
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                "RANGE_NOT_SATISFIABLE",
+                format!("range {start}-{end} is outside object size {total_size}"),
+                None,
+                true,
+            ));
+        }
+        (start, end.min(total_size - 1))
+    };
+
+    Ok((start, end, true))
+}
+
+fn read_objset_bytes(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    objset_id: u64,
+    objid: u64,
+    start: u64,
+    end: u64,
+) -> Result<Vec<u8>, ApiError> {
+    if end < start {
+        return Ok(Vec::new());
+    }
+    let total = end - start + 1;
+    if total > ZPL_DOWNLOAD_MAX_BYTES {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "DOWNLOAD_TOO_LARGE",
+            format!(
+                "requested byte range is {} bytes; max per request is {} bytes",
+                total, ZPL_DOWNLOAD_MAX_BYTES
+            ),
+            Some("Use HTTP Range requests to download the file in chunks.".to_string()),
+            true,
+        ));
+    }
+
+    let mut out = Vec::with_capacity(total as usize);
+    let mut offset = start;
+    while offset <= end {
+        let remaining = end - offset + 1;
+        let chunk_size = remaining.min(OBJSET_DATA_MAX_LIMIT);
+        let chunk_result =
+            crate::ffi::objset_read_data(pool_ptr, objset_id, objid, offset, chunk_size);
+        if !chunk_result.is_ok() {
+            let err_msg = chunk_result.error_msg().unwrap_or("Unknown error");
+            let status = if is_objset_user_input_error(err_msg) {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            return Err(api_error(
+                status,
+                format!("failed to read object data at offset {offset}: {err_msg}"),
+            ));
+        }
+
+        let chunk_json = chunk_result.json().ok_or_else(|| {
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+        })?;
+        let chunk_value = parse_json_value(chunk_json)?;
+        let chunk = serde_json::from_value::<ObjsetDataPayload>(chunk_value).map_err(|err| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to parse object data payload: {err}"),
+            )
+        })?;
+
+        let mut bytes = decode_hex_bytes(&chunk.data_hex)?;
+        if bytes.is_empty() {
+            break;
+        }
+
+        if (bytes.len() as u64) > remaining {
+            bytes.truncate(remaining as usize);
+        }
+
+        let consumed = bytes.len() as u64;
+        out.extend_from_slice(&bytes);
+        if consumed == 0 {
+            break;
+        }
+        offset = offset.saturating_add(consumed);
+    }
+
+    if out.len() as u64 != total {
+        return Err(api_error_with(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "SHORT_READ",
+            format!(
+                "short read while exporting object data (expected {total} bytes, got {})",
+                out.len()
+            ),
+            Some(
+                "Try smaller range requests; the object may be sparse or partially unreadable."
+                    .to_string(),
+            ),
+            false,
+        ));
+    }
+
+    Ok(out)
+}
+
+fn sanitize_download_filename(raw: &str) -> String {
+    let mut cleaned = raw.replace(['"', '\\', '/'], "_");
+    if cleaned.is_empty() {
+        cleaned = "download.bin".to_string();
+    }
+    cleaned
+}
+
+fn build_file_download_response(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    headers: HeaderMap,
+    objset_id: u64,
+    objid: u64,
+    file_size: u64,
+    filename_raw: &str,
+    rel_path: &str,
+    dataset_name: Option<&str>,
+) -> Result<Response<Body>, ApiError> {
+    let filename = sanitize_download_filename(filename_raw);
+
+    if file_size == 0 {
+        let (content_type, _) = detect_content_type(&[], Some(&filename));
+        let mut response = Response::new(Body::from(Vec::<u8>::new()));
+        *response.status_mut() = StatusCode::OK;
+        response
+            .headers_mut()
+            .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_str(&content_type)
+                .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+        );
+        response
+            .headers_mut()
+            .insert(CONTENT_LENGTH, HeaderValue::from_static("0"));
+        response.headers_mut().insert(
+            CONTENT_DISPOSITION,
+            HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+                .unwrap_or(HeaderValue::from_static("attachment")),
+        );
+        response.headers_mut().insert(
+            HeaderName::from_static("x-zfs-objset-id"),
+            HeaderValue::from_str(&objset_id.to_string()).unwrap_or(HeaderValue::from_static("0")),
+        );
+        response.headers_mut().insert(
+            HeaderName::from_static("x-zfs-relpath"),
+            HeaderValue::from_str(rel_path).unwrap_or(HeaderValue::from_static("/")),
+        );
+        if let Some(dataset_name) = dataset_name {
+            response.headers_mut().insert(
+                HeaderName::from_static("x-zfs-dataset"),
+                HeaderValue::from_str(dataset_name).unwrap_or(HeaderValue::from_static("unknown")),
+            );
+        }
+        return Ok(response);
+    }
+
+    let (start, end, partial) = parse_range_header(&headers, file_size)?;
+    let bytes = read_objset_bytes(pool_ptr, objset_id, objid, start, end)?;
+    // The type comes from the head of the file, so every range of it gets
+    // the same Content-Type.
+    let sniff_len = file_size.min(SNIFF_BYTES);
+    let (content_type, _) = if start == 0 && bytes.len() as u64 >= sniff_len {
+        detect_content_type(&bytes[..sniff_len as usize], Some(&filename))
+    } else {
+        let head = read_objset_bytes(pool_ptr, objset_id, objid, 0, sniff_len - 1)?;
+        detect_content_type(&head, Some(&filename))
+    };
+
+    let mut response = Response::new(Body::from(bytes));
+    *response.status_mut() = if partial {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    };
+
+    response
+        .headers_mut()
+        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_str(&content_type)
+            .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+    );
+    response.headers_mut().insert(
+        CONTENT_LENGTH,
+        HeaderValue::from_str(&(end - start + 1).to_string())
+            .unwrap_or(HeaderValue::from_static("0")),
+    );
+    response.headers_mut().insert(
+        CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+            .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    response.headers_mut().insert(
+        HeaderName::from_static("x-zfs-objset-id"),
+        HeaderValue::from_str(&objset_id.to_string()).unwrap_or(HeaderValue::from_static("0")),
+    );
+    response.headers_mut().insert(
+        HeaderName::from_static("x-zfs-relpath"),
+        HeaderValue::from_str(rel_path).unwrap_or(HeaderValue::from_static("/")),
+    );
+    if let Some(dataset_name) = dataset_name {
+        response.headers_mut().insert(
+            HeaderName::from_static("x-zfs-dataset"),
+            HeaderValue::from_str(dataset_name).unwrap_or(HeaderValue::from_static("unknown")),
+        );
+    }
+
+    if partial {
+        response.headers_mut().insert(
+            CONTENT_RANGE,
+            HeaderValue::from_str(&format!("bytes {start}-{end}/{file_size}"))
+                .unwrap_or(HeaderValue::from_static("bytes */0")),
+        );
+    }
+
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ZplDownloadQuery {
+    pub no_cache: Option<bool>,
+}
+
+/// GET /api/pools/{pool}/zpl/path/{*zpl_path}
+/// (supports single HTTP Range request)
+pub async fn zpl_path_download(
+    State(state): State<AppState>,
+    Path((pool, zpl_path)): Path<(String, String)>,
+    ApiQuery(params): ApiQuery<ZplDownloadQuery>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let pool_open = pool_open_config(&state);
+    let bypass_cache = params.no_cache.unwrap_or(false);
+    let ctx = resolve_zpl_path_context(
+        pool_ptr,
+        &pool,
+        &zpl_path,
+        matches!(pool_open.mode, crate::PoolOpenMode::Offline),
+        || cached_dataset_catalog(&state, pool_ptr, &pool, bypass_cache),
+    )?;
+
+    build_file_download_response(
+        pool_ptr,
+        headers,
+        ctx.objset_id,
+        ctx.objid,
+        ctx.file_size,
+        &ctx.filename,
+        &ctx.rel_path,
+        Some(&ctx.dataset_name),
+    )
+}
+
+/// GET /api/pools/{pool}/objset/{objset_id}/zpl/path/{*zpl_path}
+/// (supports single HTTP Range request)
+pub async fn objset_zpl_path_download(
+    State(state): State<AppState>,
+    Path((pool, objset_id, zpl_path)): Path<(String, u64, String)>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let ctx = resolve_objset_scoped_zpl_path_context(pool_ptr, objset_id, &zpl_path)?;
+    build_file_download_response(
+        pool_ptr,
+        headers,
+        ctx.objset_id,
+        ctx.objid,
+        ctx.file_size,
+        &ctx.filename,
+        &ctx.rel_path,
+        None,
+    )
+}
+
+/// GET /api/pools/{pool}/objset/{objset_id}/obj/{objid}/download
+/// (supports single HTTP Range request)
+pub async fn objset_object_download(
+    State(state): State<AppState>,
+    Path((pool, objset_id, objid)): Path<(String, u64, u64)>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, objid);
+    if !stat_result.is_ok() {
+        let err_msg = stat_result.error_msg().unwrap_or("Unknown error");
+        return Err(api_error_for_objset(err_msg));
+    }
+
+    let stat_json = stat_result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let stat = serde_json::from_str::<ObjsetStatPayload>(stat_json).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse stat payload: {err}"),
+        )
+    })?;
+
+    if stat.type_name != "file" {
+        return Err(api_error_with(
+            StatusCode::BAD_REQUEST,
+            "NOT_A_FILE",
+            format!(
+                "object {objid} in objset {objset_id} is a {} object, not a file",
+                stat.type_name
+            ),
+            Some("Use this endpoint only for file objects.".to_string()),
+            true,
+        ));
+    }
+
+    let filename = format!("objset-{objset_id}-obj-{objid}.bin");
+    build_file_download_response(
+        pool_ptr, headers, objset_id, objid, stat.size, &filename, "", None,
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TarExportQuery {
+    pub max_entries: Option<u64>,
+    pub max_bytes: Option<u64>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TarStatPayload {
+    size: u64,
+    mode: u64,
+    uid: u64,
+    gid: u64,
+    mtime: TarStatTime,
+}
+
+#[derive(Debug, Deserialize)]
+struct TarStatTime {
+    sec: u64,
+}
+
+/// Adapts a channel receiver into a response body stream.
+struct ChannelBodyStream(tokio::sync::mpsc::Receiver<Result<Vec<u8>, std::io::Error>>);
+
+impl futures_core::Stream for ChannelBodyStream {
+    type Item = Result<Vec<u8>, std::io::Error>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.0.poll_recv(cx)
+    }
+}
+
+fn normalize_tar_export_max_entries(value: Option<u64>) -> Result<u64, ApiError> {
+    Ok(reject_zero("max_entries", value)?
+        .unwrap_or(TAR_EXPORT_DEFAULT_MAX_ENTRIES)
+        .min(TAR_EXPORT_MAX_ENTRIES))
+}
+
+fn normalize_tar_export_max_bytes(value: Option<u64>) -> Result<u64, ApiError> {
+    Ok(reject_zero("max_bytes", value)?
+        .unwrap_or(TAR_EXPORT_DEFAULT_MAX_BYTES)
+        .min(TAR_EXPORT_MAX_BYTES))
+}
+
+fn tar_octal_field(field: &mut [u8], value: u64) -> bool {
+    let digits = field.len() - 1;
+    let encoded = format!("{value:0digits$o}");
+    if encoded.len() > digits {
+        field[..digits].fill(b'0');
+        field[digits] = 0;
+        return false;
+    }
+    field[..digits].copy_from_slice(encoded.as_bytes());
+    field[digits] = 0;
+    true
+}
+
+fn tar_pax_record(key: &str, value: &str) -> String {
+    // The length prefix counts itself, so iterate until it stabilizes.
+    let base = key.len() + value.len() + 3;
+    let mut len = base + base.to_string().len();
+    while len != base + len.to_string().len() {
+        len = base + len.to_string().len();
+    }
+    format!("{len} {key}={value}\n")
+}
+
+fn tar_ustar_block(
+    name: &[u8],
+    size: u64,
+    mode: u64,
+    uid: u64,
+    gid: u64,
+    mtime: u64,
+    typeflag: u8,
+) -> ([u8; TAR_BLOCK_SIZE], Vec<(&'static str, String)>) {
+    let mut block = [0u8; TAR_BLOCK_SIZE];
+    let mut overflow = Vec::new();
+
+    let name_len = name.len().min(100);
+    block[..name_len].copy_from_slice(&name[..name_len]);
+    tar_octal_field(&mut block[100..108], mode & 0o7777);
+    if !tar_octal_field(&mut block[108..116], uid) {
+        overflow.push(("uid", uid.to_string()));
+    }
+    if !tar_octal_field(&mut block[116..124], gid) {
+        overflow.push(("gid", gid.to_string()));
+    }
+    if !tar_octal_field(&mut block[124..136], size) {
+        overflow.push(("size", size.to_string()));
+    }
+    if !tar_octal_field(&mut block[136..148], mtime) {
+        overflow.push(("mtime", mtime.to_string()));
+    }
+    block[156] = typeflag;
+    block[257..263].copy_from_slice(b"ustar\0");
+    block[263..265].copy_from_slice(b"00");
+
+    block[148..156].fill(b' ');
+    let checksum: u64 = block.iter().map(|b| u64::from(*b)).sum();
+    tar_octal_field(&mut block[148..155], checksum);
+    block[155] = b' ';
+
+    (block, overflow)
+}
+
+/// Encodes a tar entry header, preceded by a pax extended header when the
+/// path or numeric fields do not fit the ustar layout.
+fn tar_entry_header(
+    path: &str,
+    size: u64,
+    mode: u64,
+    uid: u64,
+    gid: u64,
+    mtime: u64,
+    typeflag: u8,
+) -> Vec<u8> {
+    let (block, overflow) = tar_ustar_block(path.as_bytes(), size, mode, uid, gid, mtime, typeflag);
+
+    let mut records = String::new();
+    if path.len() > 100 {
+        records.push_str(&tar_pax_record("path", path));
+    }
+    for (key, value) in &overflow {
+        records.push_str(&tar_pax_record(key, value));
+    }
+    if records.is_empty() {
+        return block.to_vec();
+    }
+
+    let pax_size = records.len() as u64;
+    let (pax_block, _) = tar_ustar_block(b"././@PaxHeader", pax_size, 0o644, 0, 0, mtime, b'x');
+    let mut out = pax_block.to_vec();
+    out.extend_from_slice(records.as_bytes());
+    out.resize(out.len() + tar_padding(pax_size), 0);
+    out.extend_from_slice(&block);
+    out
+}
+
+fn tar_padding(size: u64) -> usize {
+    let rem = (size % TAR_BLOCK_SIZE as u64) as usize;
+    if rem == 0 {
+        0
+    } else {
+        TAR_BLOCK_SIZE - rem
+    }
+}
+
+/// Runs `f` against the currently open pool handle, failing if another
+/// request has since switched the server to a different pool.
+fn with_open_pool<T>(
+    state: &AppState,
+    pool: &str,
+    f: impl FnOnce(*mut crate::ffi::zdx_pool_t) -> T,
+) -> Result<T, String> {
+    let handle = match state.pool.lock().unwrap().as_ref() {
+        Some(handle) if handle.name == pool => Arc::clone(handle),
+        _ => return Err(format!("pool '{pool}' was closed during export")),
+    };
+    Ok(f(handle.ptr))
+}
+
+/// An FFI result as parsed JSON, with failures flattened to their message.
+fn ffi_result_value(result: crate::ffi::ZdxResult) -> Result<Value, String> {
+    if !result.is_ok() {
+        return Err(result.error_msg().unwrap_or("Unknown error").to_string());
+    }
+    let json = result.json().ok_or("Missing JSON in result")?;
+    serde_json::from_str::<Value>(json).map_err(|err| format!("invalid JSON: {err}"))
+}
+
+/// One FFI call of a streaming response against a freshly ensured pool.
+fn stream_ffi_value(
+    state: &AppState,
+    pool: &str,
+    call: impl FnOnce(*mut crate::ffi::zdx_pool_t) -> crate::ffi::ZdxResult,
+) -> Result<Value, String> {
+    with_open_pool(state, pool, |pool_ptr| ffi_result_value(call(pool_ptr)))?
+}
+
+struct TarExportWriter {
+    state: AppState,
+    pool: String,
+    objset_id: u64,
+    /// None for a dry run: the walk records `manifest` instead of streaming.
+    tx: Option<tokio::sync::mpsc::Sender<Result<Vec<u8>, std::io::Error>>>,
+    manifest: Vec<Value>,
+    max_entries: u64,
+    max_bytes: u64,
+    entries: u64,
+    data_bytes: u64,
+    skipped: u64,
+    truncated: Option<String>,
+    errors: Vec<Value>,
+}
+
+impl TarExportWriter {
+    /// Sends a chunk to the client; returns false once the client has gone away.
+    fn emit(&self, bytes: Vec<u8>) -> bool {
+        match &self.tx {
+            Some(tx) => tx.blocking_send(Ok(bytes)).is_ok(),
+            None => true,
+        }
+    }
+
+    fn fail(&mut self, message: String) {
+        match &self.tx {
+            Some(tx) => {
+                let _ = tx.blocking_send(Err(std::io::Error::other(message)));
+            }
+            None => self.errors.push(json!({ "error": message })),
+        }
+    }
+
+    fn stat(&self, objid: u64) -> Result<TarStatPayload, String> {
+        let objset_id = self.objset_id;
+        let value = stream_ffi_value(&self.state, &self.pool, |pool_ptr| {
+            crate::ffi::objset_stat(pool_ptr, objset_id, objid)
+        })?;
+        serde_json::from_value::<TarStatPayload>(value)
+            .map_err(|err| format!("failed to parse stat payload: {err}"))
+    }
+
+    fn cap_reached(&mut self, size: u64) -> bool {
+        if self.entries >= self.max_entries {
+            self.truncated = Some(format!("max_entries ({}) reached", self.max_entries));
+        } else if self.data_bytes.saturating_add(size) > self.max_bytes {
+            self.truncated = Some(format!("max_bytes ({}) reached", self.max_bytes));
+        }
+        self.truncated.is_some()
+    }
+
+    /// Walks `root_obj` depth-first, streaming each entry as it is visited.
+    /// Returns false if the client disconnected.
+    fn run(&mut self, root_obj: u64, root_name: &str) -> bool {
+        let mut stack = vec![(root_obj, format!("{root_name}/"))];
+        let mut visited = HashSet::new();
+
+        while let Some((dir_obj, prefix)) = stack.pop() {
+            if !visited.insert(dir_obj) {
+                continue;
+            }
+            if let Err(err) = with_open_pool(&self.state, &self.pool, |_| ()) {
+                self.fail(err);
+                return false;
+            }
+            if self.cap_reached(0) {
+                break;
+            }
+            let stat = match self.stat(dir_obj) {
+                Ok(stat) => stat,
+                Err(err) => {
+                    self.errors
+                        .push(json!({ "path": prefix, "objid": dir_obj, "error": err }));
+                    continue;
+                }
+            };
+            self.entries += 1;
+            let header = tar_entry_header(
+                &prefix,
+                0,
+                stat.mode,
+                stat.uid,
+                stat.gid,
+                stat.mtime.sec,
+                b'5',
+            );
+            if !self.emit(header) {
+                return false;
+            }
+
+            let mut cursor = 0u64;
+            loop {
+                let objset_id = self.objset_id;
+                let page = match stream_ffi_value(&self.state, &self.pool, |pool_ptr| {
+                    crate::ffi::objset_dir_entries(
+                        pool_ptr,
+                        objset_id,
+                        dir_obj,
+                        cursor,
+                        TAR_EXPORT_DIR_PAGE,
+                    )
+                }) {
+                    Ok(page) => page,
+                    Err(err) => {
+                        self.errors
+                            .push(json!({ "path": prefix, "objid": dir_obj, "error": err }));
+                        break;
+                    }
+                };
+
+                for entry in page["entries"].as_array().into_iter().flatten() {
+                    let (Some(name), Some(objid)) =
+                        (entry["name"].as_str(), entry["objid"].as_u64())
+                    else {
+                        continue;
+                    };
+                    match entry["type_name"].as_str() {
+                        Some("dir") => stack.push((objid, format!("{prefix}{name}/"))),
+                        Some("file") => {
+                            let path = format!("{prefix}{name}");
+                            if !self.write_file(objid, &path) {
+                                return false;
+                            }
+                            if self.truncated.is_some() {
+                                return true;
+                            }
+                        }
+                        _ => self.skipped += 1,
+                    }
+                }
+
+                match page["next"].as_u64() {
+                    Some(next) if next != cursor => cursor = next,
+                    _ => break,
+                }
+            }
+
+            if self.truncated.is_some() {
+                break;
+            }
+        }
+        true
+    }
+
+    fn write_file(&mut self, objid: u64, path: &str) -> bool {
+        let stat = match self.stat(objid) {
+            Ok(stat) => stat,
+            Err(err) => {
+                self.errors
+                    .push(json!({ "path": path, "objid": objid, "error": err }));
+                return true;
+            }
+        };
+        if self.cap_reached(stat.size) {
+            return true;
+        }
+        self.entries += 1;
+        self.data_bytes += stat.size;
+        if self.tx.is_none() {
+            self.manifest
+                .push(json!({ "path": path, "size_bytes": stat.size }));
+            return true;
+        }
+
+        let header = tar_entry_header(
+            path,
+            stat.size,
+            stat.mode,
+            stat.uid,
+            stat.gid,
+            stat.mtime.sec,
+            b'0',
+        );
+        if !self.emit(header) {
+            return false;
+        }
+
+        let objset_id = self.objset_id;
+        let mut offset = 0u64;
+        while offset < stat.size {
+            let chunk_size = (stat.size - offset).min(OBJSET_DATA_MAX_LIMIT);
+            let chunk = stream_ffi_value(&self.state, &self.pool, |pool_ptr| {
+                crate::ffi::objset_read_data(pool_ptr, objset_id, objid, offset, chunk_size)
+            })
+            .and_then(|value| {
+                let hex = value["data_hex"].as_str().unwrap_or_default();
+                decode_hex_bytes(hex).map_err(|_| "invalid hex payload".to_string())
+            });
+
+            let mut bytes = match chunk {
+                Ok(bytes) if !bytes.is_empty() => bytes,
+                Ok(_) => {
+                    self.errors.push(json!({
+                        "path": path,
+                        "objid": objid,
+                        "error": format!("short read at offset {offset}; zero-filled {chunk_size} bytes"),
+                    }));
+                    Vec::new()
+                }
+                Err(err) => {
+                    self.errors.push(json!({
+                        "path": path,
+                        "objid": objid,
+                        "error": format!(
+                            "read failed at offset {offset}: {err}; zero-filled {chunk_size} bytes"
+                        ),
+                    }));
+                    Vec::new()
+                }
+            };
+            // Keep the archive well-formed: the header already promised `size`
+            // bytes, so a failed chunk becomes zeros and later chunks still read.
+            if bytes.is_empty() {
+                bytes = vec![0u8; chunk_size as usize];
+            }
+            bytes.truncate(chunk_size as usize);
+            offset += bytes.len() as u64;
+            if !self.emit(bytes) {
+                return false;
+            }
+        }
+
+        let padding = tar_padding(stat.size);
+        padding == 0 || self.emit(vec![0u8; padding])
+    }
+
+    fn finish(&mut self, root_name: &str) {
+        if self.truncated.is_some() || !self.errors.is_empty() {
+            let report = json!({
+                "truncated": self.truncated.is_some(),
+                "reason": self.truncated,
+                "entries": self.entries,
+                "data_bytes": self.data_bytes,
+                "skipped": self.skipped,
+                "errors": self.errors,
+            });
+            let body = serde_json::to_vec_pretty(&report).unwrap_or_default();
+            let size = body.len() as u64;
+            let mtime = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let path = format!("{root_name}/{TAR_EXPORT_SENTINEL}");
+            let mut out = tar_entry_header(&path, size, 0o644, 0, 0, mtime, b'0');
+            out.extend_from_slice(&body);
+            out.resize(out.len() + tar_padding(size), 0);
+            if !self.emit(out) {
+                return;
+            }
+        }
+        self.emit(vec![0u8; TAR_BLOCK_SIZE * 2]);
+    }
+}
+
+/// Runs the export walk without reading file data and returns what the
+/// archive would contain.
+async fn tar_export_manifest(
+    state: AppState,
+    pool: String,
+    objset_id: u64,
+    dir_obj: u64,
+    root_name: String,
+    max_entries: u64,
+    max_bytes: u64,
+) -> Result<Response<Body>, ApiError> {
+    let _inflight = reserve_inflight(&state, max_entries)?;
+    let mut writer = TarExportWriter {
+        state: state.clone(),
+        pool,
+        objset_id,
+        tx: None,
+        manifest: Vec::new(),
+        max_entries,
+        max_bytes,
+        entries: 0,
+        data_bytes: 0,
+        skipped: 0,
+        truncated: None,
+        errors: Vec::new(),
+    };
+    let writer = tokio::task::spawn_blocking(move || {
+        let completed = writer.run(dir_obj, &root_name);
+        (completed, writer)
+    })
+    .await
+    .map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("export manifest task failed: {err}"),
+        )
+    })?;
+    let (completed, writer) = writer;
+    if !completed {
+        let message = writer
+            .errors
+            .last()
+            .and_then(|err| err["error"].as_str())
+            .unwrap_or("export manifest aborted")
+            .to_string();
+        return Err(api_error(StatusCode::CONFLICT, message));
+    }
+
+    Ok(Json(json!({
+        "objset_id": objset_id,
+        "dir_obj": dir_obj,
+        "dry_run": true,
+        "max_entries": max_entries,
+        "max_bytes": max_bytes,
+        "total_files": writer.manifest.len(),
+        "total_bytes": writer.data_bytes,
+        "total_entries": writer.entries,
+        "truncated": writer.truncated.is_some(),
+        "reason": writer.truncated,
+        "skipped": writer.skipped,
+        "errors": writer.errors,
+        "files": writer.manifest,
+    }))
+    .into_response())
+}
+
+/// GET /api/pools/:pool/objset/:objset_id/dir/:dir_obj/export.tar
+pub async fn objset_dir_export_tar(
+    State(state): State<AppState>,
+    Path((pool, objset_id, dir_obj)): Path<(String, u64, u64)>,
+    ApiQuery(params): ApiQuery<TarExportQuery>,
+) -> Result<Response<Body>, ApiError> {
+    {
+        let pool_handle = ensure_pool(&state, &pool)?;
+        let pool_ptr = pool_handle.ptr;
+        let stat_result = crate::ffi::objset_stat(pool_ptr, objset_id, dir_obj);
+        if !stat_result.is_ok() {
+            let err_msg = stat_result.error_msg().unwrap_or("Unknown error");
+            return Err(api_error_for_objset(err_msg));
+        }
+        let stat_json = stat_result.json().ok_or_else(|| {
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+        })?;
+        let stat = serde_json::from_str::<ObjsetStatPayload>(stat_json).map_err(|err| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to parse stat payload: {err}"),
+            )
+        })?;
+        if stat.type_name != "dir" {
+            return Err(api_error_with(
+                StatusCode::BAD_REQUEST,
+                "NOT_A_DIRECTORY",
+                format!(
+                    "object {dir_obj} in objset {objset_id} is a {} object, not a directory",
+                    stat.type_name
+                ),
+                Some("Use this endpoint only for directory objects.".to_string()),
+                true,
+            ));
+        }
+    }
+
+    let max_entries = normalize_tar_export_max_entries(params.max_entries)?;
+    let max_bytes = normalize_tar_export_max_bytes(params.max_bytes)?;
+    let root_name = format!("objset-{objset_id}-dir-{dir_obj}");
+    let filename = format!("{root_name}.tar");
+
+    if params.dry_run.unwrap_or(false) {
+        return tar_export_manifest(
+            state,
+            pool,
+            objset_id,
+            dir_obj,
+            root_name,
+            max_entries,
+            max_bytes,
+        )
+        .await;
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_DEPTH);
+    let mut writer = TarExportWriter {
+        state: state.clone(),
+        pool: pool.clone(),
+        objset_id,
+        tx: Some(tx),
+        manifest: Vec::new(),
+        max_entries,
+        max_bytes,
+        entries: 0,
+        data_bytes: 0,
+        skipped: 0,
+        truncated: None,
+        errors: Vec::new(),
+    };
+    tokio::task::spawn_blocking(move || {
+        if !writer.run(dir_obj, &root_name) {
+            tracing::debug!("tar export of dir {dir_obj} aborted");
+            return;
+        }
+        if let Some(reason) = writer.truncated.as_deref() {
+            tracing::info!("tar export of dir {dir_obj} truncated: {reason}");
+        }
+        writer.finish(&root_name);
+    });
+
+    let mut response = Response::new(Body::from_stream(ChannelBodyStream(rx)));
+    *response.status_mut() = StatusCode::OK;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/x-tar"));
+    response.headers_mut().insert(
+        CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+            .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    response.headers_mut().insert(
+        HeaderName::from_static("x-zfs-objset-id"),
+        HeaderValue::from_str(&objset_id.to_string()).unwrap_or(HeaderValue::from_static("0")),
+    );
+    response.headers_mut().insert(
+        HeaderName::from_static("x-zfs-export-limits"),
+        HeaderValue::from_str(&format!("max_entries={max_entries}, max_bytes={max_bytes}"))
+            .unwrap_or(HeaderValue::from_static("unknown")),
+    );
+    Ok(response)
+}
+
+/// GET /api/pools/{pool}/snapshot/{dsobj}/zpl/path/{*zpl_path}
+/// (supports single HTTP Range request)
+pub async fn snapshot_zpl_path_download(
+    State(state): State<AppState>,
+    Path((pool, dsobj, zpl_path)): Path<(String, u64, String)>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let objset_id = resolve_snapshot_objset_id(pool_ptr, dsobj)?;
+    let ctx = resolve_objset_scoped_zpl_path_context(pool_ptr, objset_id, &zpl_path)?;
+    let mut response = build_file_download_response(
+        pool_ptr,
+        headers,
+        ctx.objset_id,
+        ctx.objid,
+        ctx.file_size,
+        &ctx.filename,
+        &ctx.rel_path,
+        None,
+    )?;
+    response.headers_mut().insert(
+        HeaderName::from_static("x-zfs-snapshot-dsobj"),
+        HeaderValue::from_str(&dsobj.to_string()).unwrap_or(HeaderValue::from_static("0")),
+    );
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ZvolDataQuery {
+    pub offset: Option<u64>,
+    pub length: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ZvolProbePayload {
+    objset_type: String,
+    max_offset: u64,
+}
+
+fn probe_zvol_data_object(
+    pool_ptr: *mut crate::ffi::zdx_pool_t,
+    dir_obj: u64,
+    objset_id: u64,
+) -> Result<ZvolProbePayload, ApiError> {
+    let result = crate::ffi::objset_read_data(pool_ptr, objset_id, ZVOL_DATA_OBJECT, 0, 1);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if err_msg.contains("objset is not ZFS") {
+            return Err(not_a_zvol_error(dir_obj));
+        }
+        return Err(api_error_for_objset(err_msg));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    let probe = serde_json::from_value::<ZvolProbePayload>(value).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to parse zvol data payload: {err}"),
+        )
+    })?;
+
+    if probe.objset_type != "zvol" {
+        return Err(not_a_zvol_error(dir_obj));
+    }
+    Ok(probe)
+}
+
+fn not_a_zvol_error(dir_obj: u64) -> ApiError {
+    api_error_with(
+        StatusCode::BAD_REQUEST,
+        "NOT_A_ZVOL",
+        format!("DSL dir {dir_obj} is not a volume dataset"),
+        Some("Use the objset or ZPL path endpoints for filesystem datasets.".to_string()),
+        true,
+    )
+}
+
+/// GET /api/pools/{pool}/zvol/{dir_obj}/data?offset=&length=
+/// (supports single HTTP Range request)
+pub async fn zvol_read_data(
+    State(state): State<AppState>,
+    Path((pool, dir_obj)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<ZvolDataQuery>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let objset_value = resolve_dataset_objset(pool_ptr, dir_obj)?;
+    let objset_id = objset_value["objset_id"].as_u64().ok_or_else(|| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "dataset objset payload missing objset_id",
+        )
+    })?;
+    let volume_size = probe_zvol_data_object(pool_ptr, dir_obj, objset_id)?.max_offset;
+
+    // Hex payloads are capped like objset data reads; larger ranges are
+    // served partially and the client continues from Content-Range.
+    let (offset, length, partial) = if headers.contains_key(RANGE) {
+        let (start, end, partial) = parse_range_header(&headers, volume_size)?;
+        (start, (end - start + 1).min(OBJSET_DATA_MAX_LIMIT), partial)
+    } else {
+        let offset = params.offset.unwrap_or(0);
+        let length =
+            normalize_objset_data_limit(params.length).min(volume_size.saturating_sub(offset));
+        (offset, length, false)
+    };
+
+    let data_hex = if length == 0 {
+        String::new()
+    } else {
+        let result =
+            crate::ffi::objset_read_data(pool_ptr, objset_id, ZVOL_DATA_OBJECT, offset, length);
+        if !result.is_ok() {
+            let err_msg = result.error_msg().unwrap_or("Unknown error");
+            return Err(api_error_for_objset(err_msg));
+        }
+        let json_str = result.json().ok_or_else(|| {
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result")
+        })?;
+        let chunk = serde_json::from_value::<ObjsetDataPayload>(parse_json_value(json_str)?)
+            .map_err(|err| {
+                api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("failed to parse zvol data payload: {err}"),
+                )
+            })?;
+        chunk.data_hex
+    };
+
+    let payload = json!({
+        "dsl_dir_obj": dir_obj,
+        "objset_id": objset_id,
+        "objid": ZVOL_DATA_OBJECT,
+        "volume_size": volume_size,
+        "offset": offset,
+        "length": data_hex.len() / 2,
+        "eof": offset.saturating_add(length) >= volume_size,
+        "data_hex": data_hex,
+    });
+    let body = serde_json::to_vec(&payload).map_err(|err| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to encode zvol data payload: {err}"),
+        )
+    })?;
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = if partial {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    };
+    response
+        .headers_mut()
+        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response.headers_mut().insert(
+        HeaderName::from_static("x-zfs-objset-id"),
+        HeaderValue::from_str(&objset_id.to_string()).unwrap_or(HeaderValue::from_static("0")),
+    );
+    if partial && length > 0 {
+        let end = offset + length - 1;
+        response.headers_mut().insert(
+            CONTENT_RANGE,
+            HeaderValue::from_str(&format!("bytes {offset}-{end}/{volume_size}"))
+                .unwrap_or(HeaderValue::from_static("bytes */0")),
+        );
+    }
+
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpacemapRangesQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+    pub op: Option<String>,
+    pub min_length: Option<u64>,
+    pub txg_min: Option<u64>,
+    pub txg_max: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpacemapBinsQuery {
+    pub bin_size: Option<u64>,
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+    pub op: Option<String>,
+    pub min_length: Option<u64>,
+    pub txg_min: Option<u64>,
+    pub txg_max: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpacemapListQuery {
+    pub cursor: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+/// GET /api/pools/:pool/spacemaps
+pub async fn pool_spacemaps(
+    State(state): State<AppState>,
+    Path(pool): Path<String>,
+    ApiQuery(params): ApiQuery<SpacemapListQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let result = crate::ffi::pool_spacemaps(pool_ptr, cursor, fetch_limit(limit));
+    finish_page(limit, "spacemaps", json_from_result(result))
+}
+
+/// GET /api/pools/:pool/vdev/:vdev/metaslabs
+pub async fn vdev_metaslabs(
+    State(state): State<AppState>,
+    Path((pool, vdev)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<SpacemapListQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_cursor_limit(params.cursor, params.limit);
+    let result = crate::ffi::vdev_metaslabs(pool_ptr, vdev, cursor, fetch_limit(limit));
+    finish_page(limit, "metaslabs", vdev_json_from_result(result))
+}
+
+/// GET /api/pools/:pool/vdev/:vdev/labels
+pub async fn vdev_labels(
+    State(state): State<AppState>,
+    Path((pool, vdev)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::vdev_labels(pool_ptr, vdev);
+    vdev_json_from_result(result)
+}
+
+/// `json_from_result` for per-vdev calls, with unknown vdev ids as 404.
+fn vdev_json_from_result(result: crate::ffi::ZdxResult) -> ApiResult {
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        if is_vdev_not_found_error(err_msg) {
+            return Err(api_error_with(
+                StatusCode::NOT_FOUND,
+                "VDEV_NOT_FOUND",
+                err_msg.to_string(),
+                Some("Use a top-level vdev id from /api/pools/{pool}/alloc-classes.".to_string()),
+                true,
+            ));
+        }
+    }
+    json_from_result(result)
+}
+
+/// GET /api/pools/:pool/spacemap/:objid/summary
+pub async fn spacemap_summary(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::spacemap_summary(pool_ptr, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_spacemap_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    Ok(Json(value))
+}
+
+/// GET /api/pools/:pool/spacemap/:objid/ranges?cursor=&limit=&op=&min_length=&txg_min=&txg_max=
+pub async fn spacemap_ranges(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<SpacemapRangesQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let (cursor, limit) = normalize_spacemap_cursor_limit(params.cursor, params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+    let op_filter = parse_spacemap_op_filter(params.op.as_deref())?;
+    let min_length = params.min_length.unwrap_or(0);
+    let txg_min = params.txg_min.unwrap_or(0);
+    let txg_max = params.txg_max.unwrap_or(0);
+    if txg_min != 0 && txg_max != 0 && txg_min > txg_max {
+        return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "txg_min must be <= txg_max",
+        ));
+    }
+
+    let result = crate::ffi::spacemap_ranges(
+        pool_ptr,
+        objid,
+        cursor,
+        fetch_limit(limit),
+        op_filter,
+        min_length,
+        txg_min,
+        txg_max,
+    );
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_spacemap_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    finish_page(limit, "ranges", Ok(Json(value)))
+}
+
+/// GET /api/pools/:pool/spacemap/:objid/bins?bin_size=&cursor=&limit=&op=&min_length=&txg_min=&txg_max=
+pub async fn spacemap_bins(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<SpacemapBinsQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let bin_size = normalize_spacemap_bin_size(params.bin_size)?;
+    let (cursor, limit) = normalize_spacemap_bins_cursor_limit(params.cursor, params.limit);
+    let _inflight = reserve_inflight(&state, limit)?;
+    let op_filter = parse_spacemap_op_filter(params.op.as_deref())?;
+    let min_length = params.min_length.unwrap_or(0);
+    let txg_min = params.txg_min.unwrap_or(0);
+    let txg_max = params.txg_max.unwrap_or(0);
+    if txg_min != 0 && txg_max != 0 && txg_min > txg_max {
+        return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "txg_min must be <= txg_max",
+        ));
+    }
+
+    let result = crate::ffi::spacemap_bins(
+        pool_ptr,
+        objid,
+        bin_size,
+        cursor,
+        fetch_limit(limit),
+        op_filter,
+        min_length,
+        txg_min,
+        txg_max,
+    );
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        let status = if is_spacemap_user_input_error(err_msg) {
+            StatusCode::BAD_REQUEST
+        } else {
+            tracing::error!("FFI error: {}", err_msg);
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        return Err(api_error(status, err_msg.to_string()));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+    finish_page(limit, "bins", Ok(Json(value)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQuery {
+    pub depth: Option<u8>,
+    pub include: Option<String>,
+}
+
+/// GET /api/pools/:pool/graph/from/:objid
+pub async fn graph_from(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+    ApiQuery(params): ApiQuery<GraphQuery>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let include = params
+        .include
+        .unwrap_or_else(|| "semantic,physical".to_string());
+    let _depth = params.depth.unwrap_or(1);
+    let (include_semantic, include_physical, include_zap) = parse_graph_include(Some(&include));
+
+    let result = crate::ffi::obj_get(pool_ptr, objid);
+    if !result.is_ok() {
+        let err_msg = result.error_msg().unwrap_or("Unknown error");
+        tracing::error!("FFI error: {}", err_msg);
+        return Err(api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            err_msg.to_string(),
+        ));
+    }
+
+    let json_str = result
+        .json()
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
+    let value = parse_json_value(json_str)?;
+
+    let object = &value["object"];
+    let source_obj = object["id"].as_u64().unwrap_or(objid);
+    let source_type = object["type"]["id"].as_u64();
+    let source_bonus = object["bonus_type"]["id"].as_u64();
+
+    let mut nodes = Vec::new();
+    let mut node_ids = HashSet::new();
+    let mut add_node = |objid: u64, type_id: Option<u64>, bonus_id: Option<u64>| {
+        if node_ids.insert(objid) {
+            nodes.push(serde_json::json!({
+                "objid": objid,
+                "type": type_id,
+                "bonus_type": bonus_id
+            }));
+        }
+    };
+
+    add_node(source_obj, source_type, source_bonus);
+
+    let mut edges: Vec<Value> = Vec::new();
+
+    if include_semantic {
+        if let Some(edge_list) = object["semantic_edges"].as_array() {
+            for edge in edge_list {
+                if let Some(target) = edge["target_obj"].as_u64() {
+                    add_node(target, None, None);
+                }
+                edges.push(edge.clone());
+            }
+        }
+    }
+
+    if include_zap {
+        if let Some(entries) = value["zap_entries"]["entries"].as_array() {
+            for entry in entries {
+                let maybe_ref = entry["maybe_object_ref"].as_bool().unwrap_or(false);
+                let target = entry["target_obj"].as_u64().unwrap_or(0);
+                let name = entry["name"].as_str().unwrap_or("zap");
+                if maybe_ref && target != 0 {
+                    add_node(target, None, None);
+                    edges.push(serde_json::json!({
+                        "source_obj": source_obj,
+                        "target_obj": target,
+                        "label": name,
+                        "kind": "zap",
+                        "confidence": 0.7
+                    }));
+                }
+            }
+        }
+    }
+
+    if include_physical {
+        if let Some(blkptrs) = value["blkptrs"]["blkptrs"].as_array() {
+            for (idx, bp) in blkptrs.iter().enumerate() {
+                let pseudo_id = (1u64 << 63) | (source_obj << 8) | (idx as u64);
+                add_node(pseudo_id, None, None);
+
+                edges.push(serde_json::json!({
+                    "source_obj": source_obj,
+                    "target_obj": pseudo_id,
+                    "label": format!("blkptr {}", idx),
+                    "kind": "blkptr",
+                    "confidence": 1.0,
+                    "notes": bp.get("dvas")
+                }));
+            }
+        }
+    }
+
+    let response = serde_json::json!({
+        "nodes": nodes,
+        "edges": edges
+    });
+
+    Ok(Json(response))
+}
+
+/// Well-known MOS object directory keys whose values are object ids.
+const MOS_DIRECTORY_ROLES: &[(&str, &str, &str)] = &[
+    (
+        "root_dataset",
+        "root_dsl_dir",
+        "Root DSL directory; every dataset in the pool hangs off this dir",
+    ),
+    (
+        "config",
+        "pool_config",
+        "Packed nvlist holding the pool configuration (vdev tree, guids)",
+    ),
+    (
+        "sync_bplist",
+        "deferred_free_list",
+        "Block pointer list of frees deferred to a later txg",
+    ),
+    (
+        "free_bpobj",
+        "deferred_free_list",
+        "bpobj of frees deferred until the next txg sync",
+    ),
+    (
+        "empty_bpobj",
+        "empty_bpobj",
+        "Shared empty bpobj referenced by datasets without deadlist entries",
+    ),
+    (
+        "history",
+        "pool_history",
+        "Ring buffer of pool command history (zpool history)",
+    ),
+    (
+        "errlog_last",
+        "error_log",
+        "Persistent error log from the last completed scrub",
+    ),
+    (
+        "errlog_scrub",
+        "error_log",
+        "Persistent error log for the scrub in progress",
+    ),
+    (
+        "features_for_read",
+        "feature_refcounts",
+        "Refcounts of features required to read the pool",
+    ),
+    (
+        "features_for_write",
+        "feature_refcounts",
+        "Refcounts of features required to write the pool",
+    ),
+    (
+        "feature_descriptions",
+        "feature_descriptions",
+        "Human-readable descriptions of enabled features",
+    ),
+    (
+        "feature_enabled_txg",
+        "feature_enabled_txg",
+        "TXG at which each feature was enabled",
+    ),
+    (
+        "com.delphix:vdev_zap_map",
+        "vdev_zap_map",
+        "Map of per-vdev ZAP objects",
+    ),
+    (
+        "com.delphix:obsolete_bpobj",
+        "obsolete_bpobj",
+        "Blocks made obsolete by device removal",
+    ),
+    (
+        "com.delphix:log_spacemap_zap",
+        "log_spacemap_zap",
+        "Index of log spacemaps (log_spacemap feature)",
+    ),
+];
+
+/// Type-name heuristics for MOS objects, most specific first.
+const MOS_TYPE_ROLES: &[(&str, &str, &str)] = &[
+    (
+        "object directory",
+        "object_directory",
+        "Pool-wide object directory ZAP; the entry point into the MOS",
+    ),
+    (
+        "packed nvlist",
+        "packed_nvlist",
+        "Packed nvlist payload such as the pool config",
+    ),
+    (
+        "space map",
+        "space_map",
+        "Allocation log for a metaslab or pool-wide space accounting",
+    ),
+    (
+        "dsl directory child map",
+        "dsl_child_map",
+        "ZAP mapping child dataset names to DSL directory objects",
+    ),
+    (
+        "dsl dataset snap map",
+        "snapshot_map",
+        "ZAP mapping snapshot names to DSL dataset objects",
+    ),
+    (
+        "next clones",
+        "clone_map",
+        "Set of clones created from this snapshot",
+    ),
+    (
+        "dir clones",
+        "clone_map",
+        "Set of clones of this DSL directory",
+    ),
+    (
+        "deadlist",
+        "deadlist",
+        "Blocks freed by this dataset but still referenced by snapshots",
+    ),
+    (
+        "dsl directory",
+        "dsl_dir",
+        "DSL directory: the named container for a dataset and its snapshots",
+    ),
+    (
+        "dsl dataset",
+        "dsl_dataset",
+        "DSL dataset: a filesystem, volume, or snapshot head",
+    ),
+    ("dsl props", "dsl_props", "Locally set dataset properties"),
+    (
+        "dsl permissions",
+        "dsl_permissions",
+        "Delegated administration permissions (zfs allow)",
+    ),
+    ("bpobj", "bpobj", "List of block pointers pending free"),
+    ("ddt", "dedup_table", "Deduplication table"),
+    ("spa history", "pool_history", "Pool command history"),
+    (
+        "persistent error log",
+        "error_log",
+        "Blocks with persistent read errors",
+    ),
+    ("pool properties", "pool_props", "Pool-level properties"),
+    ("scan", "scan_state", "Scrub/resilver bookkeeping"),
+    ("zap", "zap", "Generic ZAP key/value object"),
+];
+
+fn mos_directory_name_for(pool_ptr: *mut crate::ffi::zdx_pool_t, objid: u64) -> Option<String> {
+    let result = crate::ffi::zap_entries(pool_ptr, MOS_OBJECT_DIRECTORY, 0, MAX_PAGE_LIMIT);
+    if !result.is_ok() {
+        return None;
+    }
+    let value = parse_json_value(result.json()?).ok()?;
+    value["entries"].as_array()?.iter().find_map(|entry| {
+        let name = entry["name"].as_str()?;
+        let known =
+            MOS_DIRECTORY_ROLES.iter().any(|(key, _, _)| *key == name) || name.starts_with("DDT-");
+        let target = entry["target_obj"]
+            .as_u64()
+            .or_else(|| entry["value_u64"].as_u64())?;
+        (known && target == objid).then(|| name.to_string())
+    })
+}
+
+fn build_object_explanation(objid: u64, full: &Value, directory_name: Option<&str>) -> Value {
+    let object = &full["object"];
+    let type_name = object["type"]["name"].as_str().unwrap_or("unknown");
+    let type_lower = type_name.to_ascii_lowercase();
+
+    let mut notes: Vec<String> = Vec::new();
+    let (role, description, confidence) = if objid == MOS_OBJECT_DIRECTORY {
+        (
+            "object_directory",
+            "Pool-wide object directory ZAP; the entry point into the MOS",
+            1.0,
+        )
+    } else if let Some(name) = directory_name {
+        let (role, description) = MOS_DIRECTORY_ROLES
+            .iter()
+            .find(|(key, _, _)| *key == name)
+            .map(|(_, role, description)| (*role, *description))
+            .unwrap_or(("dedup_table", "Deduplication table for one checksum/class"));
+        notes.push(format!(
+            "referenced from the MOS object directory as '{name}'"
+        ));
+        (role, description, 0.95)
+    } else if let Some((_, role, description)) = MOS_TYPE_ROLES
+        .iter()
+        .find(|(needle, _, _)| type_lower.contains(needle))
+    {
+        (*role, *description, 0.7)
+    } else {
+        notes.push(format!(
+            "no heuristic matched type '{type_name}'; showing raw relationships only"
+        ));
+        ("unknown", "Object type has no built-in explanation", 0.2)
+    };
+
+    let mut outgoing_refs: Vec<Value> = Vec::new();
+    if let Some(edges) = object["semantic_edges"].as_array() {
+        for edge in edges {
+            outgoing_refs.push(json!({
+                "target_obj": edge["target_obj"],
+                "label": edge["label"],
+                "kind": edge["kind"],
+            }));
+        }
+    }
+    if let Some(entries) = full["zap_entries"]["entries"].as_array() {
+        for entry in entries {
+            let target = entry["target_obj"].as_u64().unwrap_or(0);
+            if entry["maybe_object_ref"].as_bool().unwrap_or(false) && target != 0 {
+                outgoing_refs.push(json!({
+                    "target_obj": target,
+                    "label": entry["name"],
+                    "kind": "zap",
+                }));
+            }
+        }
+        if !full["zap_entries"]["next"].is_null() {
+            notes.push(format!(
+                "only the first {} ZAP entries were inspected for references",
+                entries.len()
+            ));
+        }
+    }
+
+    if object["is_zap"].as_bool().unwrap_or(false) {
+        if let Some(count) = full["zap_info"]["num_entries"].as_u64() {
+            notes.push(format!("ZAP object with {count} entries"));
+        }
+    }
+    if let Some(bonus) = object["bonus_type"]["name"].as_str() {
+        if !bonus.is_empty() && !bonus.eq_ignore_ascii_case("none") {
+            notes.push(format!("bonus buffer type: {bonus}"));
+        }
+    }
+
+    let likely_purpose = match outgoing_refs.len() {
+        0 => description.to_string(),
+        n => format!("{description}; links to {n} other object(s)"),
+    };
+
+    json!({
+        "objid": objid,
+        "type_name": type_name,
+        "role": role,
+        "description": description,
+        "likely_purpose": likely_purpose,
+        "directory_name": directory_name,
+        "confidence": confidence,
+        "outgoing_refs": outgoing_refs,
+        "notes": notes,
+    })
+}
+
+/// GET /api/pools/:pool/obj/:objid/explain
+pub async fn obj_explain(
+    State(state): State<AppState>,
+    Path((pool, objid)): Path<(String, u64)>,
+) -> ApiResult {
+    let pool_handle = ensure_pool(&state, &pool)?;
+    let pool_ptr = pool_handle.ptr;
+    let result = crate::ffi::obj_get(pool_ptr, objid);
+    let Json(full) = json_from_result(result)?;
+    let directory_name = if objid == MOS_OBJECT_DIRECTORY {
+        None
+    } else {
+        mos_directory_name_for(pool_ptr, objid)
+    };
+
+    Ok(Json(build_object_explanation(
+        objid,
+        &full,
+        directory_name.as_deref(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn test_state(config: crate::PoolOpenConfig) -> crate::AppState {
+        crate::AppState {
+            pool: Arc::new(Mutex::new(None)),
+            pool_open: Arc::new(Mutex::new(config)),
+            dataset_catalog: Arc::new(Mutex::new(None)),
+            txg_time_index: Arc::new(Mutex::new(None)),
+            pool_timeline: Arc::new(Mutex::new(None)),
+            known_names: Arc::new(Mutex::new(None)),
+            inflight: Arc::new(InflightBudget::new(u64::MAX)),
+            max_body_bytes: 1024 * 1024,
+            max_lineage_entries: LINEAGE_MAX_ENTRIES,
+            session_marks: Arc::new(Mutex::new(Vec::new())),
+            ready: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            trust_proxy: false,
+            response_cache: Arc::new(ResponseCache::new(0)),
+        }
+    }
+
+    fn sample_txgs_payload() -> &'static str {
+        r#"
+2 1 0x01 6 288 1234
+txg birth state ndirty nread nwritten
+42 1770590000 C 0 0 0
+43 1770590001 O 4096 2 8192
+"#
+    }
+
+    #[test]
+    fn normalize_limit_uses_default_and_bounds() {
+        assert_eq!(normalize_limit(None), DEFAULT_PAGE_LIMIT);
+        assert_eq!(normalize_limit(Some(0)), 0);
+        assert_eq!(normalize_limit(Some(17)), 17);
+        assert_eq!(normalize_limit(Some(MAX_PAGE_LIMIT + 1)), MAX_PAGE_LIMIT);
+    }
+
+    #[test]
+    fn normalize_cursor_limit_defaults_cursor_and_limit() {
+        assert_eq!(normalize_cursor_limit(None, None), (0, DEFAULT_PAGE_LIMIT));
+        assert_eq!(normalize_cursor_limit(Some(42), Some(64)), (42, 64));
+    }
+
+    #[test]
+    fn normalize_spacemap_limit_uses_default_and_bounds() {
+        assert_eq!(normalize_spacemap_limit(None), SPACEMAP_DEFAULT_LIMIT);
+        assert_eq!(normalize_spacemap_limit(Some(0)), 0);
+        assert_eq!(normalize_spacemap_limit(Some(17)), 17);
+        assert_eq!(
+            normalize_spacemap_limit(Some(SPACEMAP_MAX_LIMIT + 1)),
+            SPACEMAP_MAX_LIMIT
+        );
+    }
+
+    #[test]
+    fn normalize_spacemap_bins_limit_uses_default_and_bounds() {
+        assert_eq!(
+            normalize_spacemap_bins_limit(None),
+            SPACEMAP_BINS_DEFAULT_LIMIT
+        );
+        assert_eq!(normalize_spacemap_bins_limit(Some(0)), 0);
+        assert_eq!(normalize_spacemap_bins_limit(Some(64)), 64);
+        assert_eq!(
+            normalize_spacemap_bins_limit(Some(SPACEMAP_BINS_MAX_LIMIT + 1)),
+            SPACEMAP_BINS_MAX_LIMIT
+        );
+    }
+
+    #[test]
+    fn normalize_spacemap_bin_size_uses_default_and_bounds() {
+        assert_eq!(
+            normalize_spacemap_bin_size(None).unwrap(),
+            SPACEMAP_BINS_DEFAULT_SIZE
+        );
+        assert_eq!(
+            normalize_spacemap_bin_size(Some(1)).unwrap(),
+            SPACEMAP_BINS_MIN_SIZE
+        );
+        assert_eq!(normalize_spacemap_bin_size(Some(4096)).unwrap(), 4096);
+        assert_eq!(
+            normalize_spacemap_bin_size(Some(SPACEMAP_BINS_MAX_SIZE + 1)).unwrap(),
+            SPACEMAP_BINS_MAX_SIZE
+        );
+        let (status, Json(body)) = normalize_spacemap_bin_size(Some(0)).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["parameter"], "bin_size");
+    }
+
+    #[test]
+    fn normalize_block_tree_depth_uses_default_and_bounds() {
+        assert_eq!(normalize_block_tree_depth(None), BLOCK_TREE_DEFAULT_DEPTH);
+        assert_eq!(normalize_block_tree_depth(Some(0)), 0);
+        assert_eq!(
+            normalize_block_tree_depth(Some(BLOCK_TREE_MAX_DEPTH + 3)),
+            BLOCK_TREE_MAX_DEPTH
+        );
+    }
+
+    #[test]
+    fn sorted_dir_entries_page_after_sorting() {
+        let entries = vec![
+            json!({"name": "zeta", "objid": 3}),
+            json!({"name": "Alpha", "objid": 9}),
+            json!({"name": "beta", "objid": 2}),
+        ];
+
+        let (page, next) = page_sorted_dir_entries(entries.clone(), DirEntrySort::Name, 0, 2);
+        let names: Vec<_> = page.iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["Alpha", "beta"]);
+        assert_eq!(next, Some(2));
+
+        let (page, next) = page_sorted_dir_entries(entries.clone(), DirEntrySort::Objid, 2, 2);
+        assert_eq!(page, vec![json!({"name": "Alpha", "objid": 9})]);
+        assert_eq!(next, None);
+
+        let (page, next) = page_sorted_dir_entries(entries, DirEntrySort::Name, 10, 2);
+        assert!(page.is_empty());
+        assert_eq!(next, None);
+        assert!(parse_dir_entry_sort(Some("size")).is_err());
+    }
+
+    #[test]
+    fn download_content_type_uses_the_sniff_detector() {
+        assert_eq!(
+            detect_content_type(b"%PDF-1.7\n", Some("report.txt")),
+            ("application/pdf".to_string(), "magic")
+        );
+        assert_eq!(
+            detect_content_type(b"plain words\n", Some("README")),
+            ("text/plain".to_string(), "text")
+        );
+        assert_eq!(
+            detect_content_type(&[], Some("empty")),
+            ("application/octet-stream".to_string(), "default")
+        );
+    }
+
+    #[test]
+    fn sniff_file_head_prefers_magic_then_extension_then_text() {
+        let png = sniff_file_head(b"\x89PNG\r\n\x1a\n\x00\x00", Some("notes.txt"));
+        assert_eq!(png["content_type_guess"], "image/png");
+        assert_eq!(png["source"], "magic");
+        assert_eq!(png["is_probably_text"], false);
+
+        let csv = sniff_file_head(b"a,b\n1,2\n", Some("data.csv"));
+        assert_eq!(csv["content_type_guess"], "text/csv");
+        assert_eq!(csv["detected_encoding"], "ascii");
+
+        // The trailing two-byte sequence is cut off by the sample boundary.
+        let text = sniff_file_head(b"caf\xc3\xa9 cr\xc3", None);
+        assert_eq!(text["content_type_guess"], "text/plain");
+        assert_eq!(text["detected_encoding"], "utf-8");
+
+        let binary = sniff_file_head(b"\x00\x01\x02\x03", None);
+        assert_eq!(binary["content_type_guess"], "application/octet-stream");
+        assert_eq!(binary["detected_encoding"], Value::Null);
+    }
+
+    #[test]
+    fn object_stream_chunks_form_a_valid_array_even_when_cut_short() {
+        let mut body = vec![b'['];
+        let mut written = 0;
+        let page = json!({"objects": [{"id": 1}, {"id": 2}], "next": 2});
+        assert_eq!(
+            append_object_stream_items(&page, &mut body, &mut written),
+            Some(2)
+        );
+        let page = json!({"objects": [{"id": 3}], "next": null});
+        assert_eq!(
+            append_object_stream_items(&page, &mut body, &mut written),
+            None
+        );
+        body.extend_from_slice(&object_stream_tail(written, None));
+        let parsed: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed, json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+
+        let mut body = vec![b'['];
+        body.extend_from_slice(&object_stream_tail(0, Some("pool closed")));
+        let parsed: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed, json!([{"truncated": true, "error": "pool closed"}]));
+    }
+
+    #[test]
+    fn snapshot_space_sorts_by_unique_descending_by_default() {
+        let mut entries = vec![
+            json!({"name": "b", "creation_txg": 20, "unique_bytes": 10, "written_bytes": 900}),
+            json!({"name": "a", "creation_txg": 30, "unique_bytes": 500, "written_bytes": 5}),
+            json!({"name": "c", "creation_txg": 10, "unique_bytes": 70, "written_bytes": 40}),
+        ];
+        let names = |entries: &[Value]| -> Vec<String> {
+            entries
+                .iter()
+                .map(|e| e["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        sort_snapshot_space(&mut entries, parse_snapshot_space_sort(None).unwrap());
+        assert_eq!(names(&entries), ["a", "c", "b"]);
+        sort_snapshot_space(&mut entries, "written");
+        assert_eq!(names(&entries), ["b", "c", "a"]);
+        sort_snapshot_space(&mut entries, "txg");
+        assert_eq!(names(&entries), ["c", "b", "a"]);
+        assert!(parse_snapshot_space_sort(Some("size")).is_err());
+    }
+
+    #[test]
+    fn lineage_budget_splits_and_reports_the_limiting_cap() {
+        assert_eq!(split_lineage_budget(64, 64, 1024), (64, 64));
+        assert_eq!(split_lineage_budget(4096, 4096, 1024), (512, 512));
+        assert_eq!(split_lineage_budget(10, 4096, 1024), (10, 1014));
+        assert_eq!(split_lineage_budget(4096, 100, 1024), (924, 100));
+
+        let value = json!({"prev_truncated": true, "next_truncated": false});
+        assert_eq!(
+            lineage_truncation_reason(&value, (4096, 4096), (512, 512)),
+            Some("max_entries")
+        );
+        assert_eq!(
+            lineage_truncation_reason(&value, (64, 64), (64, 64)),
+            Some("max_prev")
+        );
+        let done = json!({"prev_truncated": false, "next_truncated": false});
+        assert_eq!(lineage_truncation_reason(&done, (64, 64), (64, 64)), None);
+    }
+
+    #[test]
+    fn lineage_max_entries_clamps_to_the_server_ceiling() {
+        assert_eq!(
+            lineage_max_entries(None, LINEAGE_MAX_ENTRIES).unwrap(),
+            1024
+        );
+        assert_eq!(lineage_max_entries(None, 256).unwrap(), 256);
+        assert_eq!(lineage_max_entries(Some(5000), 2048).unwrap(), 2048);
+        assert_eq!(lineage_max_entries(Some(1), 2048).unwrap(), 2);
+        assert_eq!(
+            lineage_max_entries(Some(100_000), u64::MAX).unwrap(),
+            LINEAGE_MAX_ENTRIES
+        );
+        assert!(lineage_max_entries(Some(0), 2048).is_err());
+    }
+
+    #[test]
+    fn known_name_index_labels_directory_refs_and_internal_root_children() {
+        let entries = vec![
+            json!({"name": "root_dataset", "maybe_object_ref": true, "target_obj": 32}),
+            json!({"name": "config", "maybe_object_ref": true, "target_obj": 61}),
+            json!({"name": "DDT-sha256-zap-duplicate", "maybe_object_ref": true, "target_obj": 70}),
+            json!({"name": "version", "maybe_object_ref": false, "target_obj": 5000}),
+            json!({"name": "z_alias", "maybe_object_ref": true, "target_obj": 61}),
+            json!({"name": "empty", "maybe_object_ref": true, "target_obj": 0}),
+        ];
+        let root_children = vec![
+            ("$MOS".to_string(), 35),
+            ("$ORIGIN".to_string(), 54),
+            ("home".to_string(), 260),
+        ];
+        let index = KnownNameIndex::from_parts("tank", &entries, &root_children);
+
+        assert_eq!(
+            index.name_for(MOS_OBJECT_DIRECTORY),
+            Some("object_directory")
+        );
+        assert_eq!(index.name_for(32), Some("root_dataset"));
+        assert_eq!(index.name_for(61), Some("config"));
+        assert_eq!(index.name_for(70), Some("DDT-sha256-zap-duplicate"));
+        assert_eq!(index.name_for(35), Some("root_dataset/$MOS"));
+        assert_eq!(index.name_for(54), Some("root_dataset/$ORIGIN"));
+        assert_eq!(index.name_for(260), None);
+        assert_eq!(index.name_for(5000), None);
+        assert_eq!(index.name_for(0), None);
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison_and_lists() {
+        let etag = "W/\"obj-54-7-1234\"";
+
+        let mut headers = HeaderMap::new();
+        assert!(!if_none_match_hits(&headers, etag));
+
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("\"obj-54-7-1234\""));
+        assert!(if_none_match_hits(&headers, etag));
+
+        headers.insert(
+            IF_NONE_MATCH,
+            HeaderValue::from_static("W/\"obj-54-7-1200\", W/\"obj-54-7-1234\""),
+        );
+        assert!(if_none_match_hits(&headers, etag));
+
+        headers.insert(
+            IF_NONE_MATCH,
+            HeaderValue::from_static("W/\"obj-54-7-1235\""),
+        );
+        assert!(!if_none_match_hits(&headers, etag));
+
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(if_none_match_hits(&headers, etag));
+    }
+
+    #[test]
+    fn object_etag_tracks_dnode_changes_and_skips_unborn_objects() {
+        let object = json!({"birth_txg": 1234, "type": 19, "bonus_len": 168, "maxblkid": 3});
+        let etag = object_etag(54, 7, &object).expect("born object is tagged");
+        assert!(etag.starts_with("W/\"obj-54-7-1234-"));
+
+        let mut grown = object.clone();
+        grown["maxblkid"] = json!(4);
+        assert_ne!(object_etag(54, 7, &grown), Some(etag));
+
+        assert_eq!(object_etag(54, 7, &json!({"birth_txg": 0})), None);
+        assert_eq!(object_etag(54, 7, &json!({})), None);
+    }
+
+    #[test]
+    fn walk_recovery_dir_path_drops_non_directory_leaf() {
+        assert_eq!(walk_recovery_dir_path("/etc", Some("not_found")), "/etc");
+        assert_eq!(
+            walk_recovery_dir_path("/etc/passwd", Some("not_dir")),
+            "/etc"
+        );
+        assert_eq!(walk_recovery_dir_path("/motd", Some("not_dir")), "/");
+        assert_eq!(walk_recovery_dir_path("/", Some("unreadable")), "/");
+    }
+
+    #[test]
+    fn space_by_type_merges_batches_and_ranks_by_allocation() {
+        let mut totals = BTreeMap::new();
+        merge_space_by_type(
+            &mut totals,
+            &json!({"types": [
+                {"type": 19, "type_name": "ZFS plain file", "count": 2,
+                 "logical_bytes": 4096, "physical_bytes": 1024, "allocated_bytes": 1536},
+                {"type": 20, "type_name": "ZFS directory", "count": 1,
+                 "logical_bytes": 512, "physical_bytes": 512, "allocated_bytes": 512},
+            ]}),
+        );
+        merge_space_by_type(
+            &mut totals,
+            &json!({"types": [
+                {"type": 19, "type_name": "ZFS plain file", "count": 1,
+                 "logical_bytes": 2048, "physical_bytes": 2048, "allocated_bytes": 2048},
+                {"type": 46, "type_name": "SA master node", "count": 1,
+                 "logical_bytes": 0, "physical_bytes": 0, "allocated_bytes": 0},
+            ]}),
+        );
+
+        let rows = finish_space_by_type(totals);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["type_name"], "ZFS plain file");
+        assert_eq!(rows[0]["count"], 3);
+        assert_eq!(rows[0]["logical_bytes"], 6144);
+        assert_eq!(rows[0]["physical_bytes"], 3072);
+        assert_eq!(rows[0]["allocated_bytes"], 3584);
+        assert_eq!(rows[0]["compress_ratio"], 2.0);
+        assert_eq!(rows[1]["compress_ratio"], 1.0);
+        assert_eq!(rows[2]["compress_ratio"], Value::Null);
+    }
+
+    #[test]
+    fn merge_largest_files_keeps_earliest_on_ties() {
+        let mut top = vec![
+            json!({ "objid": 4, "size": 900 }),
+            json!({ "objid": 7, "size": 300 }),
+        ];
+        let batch = vec![
+            json!({ "objid": 12, "size": 300 }),
+            json!({ "objid": 15, "size": 500 }),
+        ];
+        merge_largest_files(&mut top, batch, 3);
+        let ids: Vec<u64> = top
+            .iter()
+            .map(|file| file["objid"].as_u64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![4, 15, 7]);
+    }
+
+    #[test]
+    fn normalize_largest_files_bounds() {
+        assert_eq!(
+            normalize_largest_files_limit(None),
+            LARGEST_FILES_DEFAULT_LIMIT
+        );
+        assert_eq!(normalize_largest_files_limit(Some(0)), 0);
+        assert_eq!(
+            normalize_largest_files_scan(Some(LARGEST_FILES_MAX_SCAN + 1)).unwrap(),
+            LARGEST_FILES_MAX_SCAN
+        );
+        let (_, Json(body)) = normalize_largest_files_scan(Some(0)).unwrap_err();
+        assert_eq!(body["code"], "INVALID_QUERY");
+        assert_eq!(body["parameter"], "scan_limit");
+    }
+
+    #[test]
+    fn normalize_block_tree_nodes_uses_default_and_bounds() {
+        assert_eq!(
+            normalize_block_tree_nodes(None).unwrap(),
+            BLOCK_TREE_DEFAULT_NODES
+        );
+        assert!(normalize_block_tree_nodes(Some(0)).is_err());
+        assert_eq!(normalize_block_tree_nodes(Some(77)).unwrap(), 77);
+        assert_eq!(
+            normalize_block_tree_nodes(Some(BLOCK_TREE_MAX_NODES + 1)).unwrap(),
+            BLOCK_TREE_MAX_NODES
+        );
+    }
+
+    #[test]
+    fn parse_spacemap_op_filter_accepts_expected_values() {
+        assert_eq!(parse_spacemap_op_filter(None).unwrap(), 0);
+        assert_eq!(parse_spacemap_op_filter(Some("all")).unwrap(), 0);
+        assert_eq!(parse_spacemap_op_filter(Some("alloc")).unwrap(), 1);
+        assert_eq!(parse_spacemap_op_filter(Some("free")).unwrap(), 2);
+    }
+
+    #[test]
+    fn parse_spacemap_op_filter_rejects_invalid_values() {
+        let err = parse_spacemap_op_filter(Some("bogus")).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn parse_userspace_type_accepts_user_and_group() {
+        assert!(!parse_userspace_type(None).unwrap());
+        assert!(!parse_userspace_type(Some("User")).unwrap());
+        assert!(parse_userspace_type(Some("group")).unwrap());
+        let err = parse_userspace_type(Some("project")).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn parse_graph_include_handles_defaults_and_flags() {
+        assert_eq!(parse_graph_include(None), (true, true, false));
+        assert_eq!(
+            parse_graph_include(Some("semantic,zap")),
+            (true, false, true)
+        );
+        assert_eq!(parse_graph_include(Some("physical")), (false, true, false));
+    }
+
+    #[test]
+    fn parse_json_value_maps_errors_to_502_ffi_bad_json() {
+        let err = parse_json_value("{bad json").unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_GATEWAY);
+        let body = err.1 .0;
+        assert_eq!(body["code"], "FFI_BAD_JSON");
+        assert!(body["message"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("native library returned unparseable JSON:"));
+        assert_eq!(body["details"]["truncated"], false);
+        assert_eq!(body["details"]["excerpt"], "{bad json");
+
+        let (_, Json(body)) = parse_json_value(r#"{"objid":5,"data_hex":"deadbeef"#).unwrap_err();
+        assert_eq!(body["details"]["truncated"], true);
+        assert_eq!(
+            body["details"]["excerpt"],
+            r#"{"objid":5,"data_hex":"<redacted>""#
+        );
+    }
+
+    #[test]
+    fn ffi_json_excerpt_caps_length_and_redacts_values() {
+        let long = format!(r#"{{"path":"/home/a\"b","x":"{}"}}"#, "y".repeat(1_000));
+        let excerpt = ffi_json_excerpt(&long);
+        assert!(excerpt.starts_with(r#"{"path":"<redacted>","x":"yyy"#));
+        assert!(excerpt.len() <= FFI_BAD_JSON_EXCERPT_BYTES);
+        assert_eq!(ffi_json_excerpt("a\nb"), "a.b");
+
+        let zap = r#"{"name":"secret","value":[1,{"k":"v"}],"int":7,"value":42}"#;
+        assert_eq!(
+            ffi_json_excerpt(zap),
+            r#"{"name":"<redacted>","value":"<redacted>","int":7,"value":"<redacted>"}"#
+        );
+
+        // A value that straddles the cap is redacted before the cut, so none
+        // of it reaches the excerpt.
+        let pad = "p".repeat(FFI_BAD_JSON_EXCERPT_BYTES - 20);
+        let straddling = format!(r#"{{"x":"{pad}","value":["abcdefgh","ijklmnop"]}}"#);
+        let excerpt = ffi_json_excerpt(&straddling);
+        assert!(!excerpt.contains("abc"), "{excerpt}");
+        assert!(excerpt.len() <= FFI_BAD_JSON_EXCERPT_BYTES);
+    }
+
+    #[test]
+    fn api_error_body_serializes_required_and_optional_keys() {
+        let value = ApiErrorBody::new("NOT_AN_ARRAY", "nope", None, true).into_value();
+        assert_eq!(
+            value,
+            json!({
+                "error": "nope",
+                "message": "nope",
+                "code": "NOT_AN_ARRAY",
+                "recoverable": true
+            })
+        );
+
+        let mut body = ApiErrorBody::new("INVALID_QUERY", "bad", Some("fix it".to_string()), true);
+        body.parameter = Some("limit".to_string());
+        let value = body.into_value();
+        assert_eq!(value["hint"], "fix it");
+        assert_eq!(value["parameter"], "limit");
+        assert!(value.get("request_id").is_none());
+    }
+
+    #[test]
+    fn api_response_normalizes_paged_payload_keys() {
+        let paged = ApiResponse::new(json!({
+            "start": 40,
+            "limit": 20,
+            "next": 60,
+            "objects": [1, 2, 3]
+        }));
+        assert_eq!(
+            serde_json::to_value(&paged).unwrap(),
+            json!({
+                "start": 40,
+                "cursor": 40,
+                "limit": 20,
+                "count": 3,
+                "next": 60,
+                "has_more": true,
+                "objects": [1, 2, 3]
+            })
+        );
+
+        let last =
+            ApiResponse::new(json!({ "cursor": 5, "count": 1, "next": null, "entries": [7] }));
+        assert_eq!(serde_json::to_value(&last).unwrap()["has_more"], false);
+
+        let plain = json!({ "id": 1, "blkptrs": [] });
+        assert_eq!(
+            serde_json::to_value(ApiResponse::new(plain.clone())).unwrap(),
+            plain
+        );
+    }
+
+    #[test]
+    fn strict_traversal_rejects_truncated_results_with_partial_counts() {
+        let partial = json!({
+            "root": 34,
+            "max_depth": 4,
+            "max_nodes": 2,
+            "count": 2,
+            "truncated": true,
+            "nodes": [{ "id": 0 }, { "id": 1 }]
+        });
+
+        let Json(lenient) = finish_traversal(None, partial.clone()).unwrap();
+        assert_eq!(lenient, partial);
+
+        let (status, Json(body)) = finish_traversal(Some(true), partial).unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "RESULT_TRUNCATED");
+        assert_eq!(body["recoverable"], true);
+        assert_eq!(
+            body["details"],
+            json!({
+                "root": 34,
+                "max_depth": 4,
+                "max_nodes": 2,
+                "count": 2,
+                "truncated": true,
+                "nodes_count": 2
+            })
+        );
+
+        let complete = json!({ "prev_truncated": false, "next_truncated": false, "prev": [] });
+        assert!(finish_traversal(Some(true), complete).is_ok());
+    }
+
+    #[test]
+    fn generated_at_stamp_is_additive_rfc3339() {
+        assert_eq!(rfc3339_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339_utc(951_825_600), "2000-02-29T12:00:00Z");
+        assert_eq!(rfc3339_utc(1_791_936_000), "2026-10-14T00:00:00Z");
+
+        let mut map = json!({ "id": 1, "generated_at_unix_sec": 5 })
+            .as_object()
+            .cloned()
+            .unwrap();
+        ApiResponse::stamp_generated_at(&mut map, 86_399);
+        assert_eq!(map["id"], 1);
+        assert_eq!(map["generated_at_unix_sec"], 5);
+        assert_eq!(map["generated_at"], "1970-01-01T23:59:59Z");
+    }
+
+    #[test]
+    fn cache_control_revalidates_only_offline_pool_successes() {
+        use crate::PoolOpenMode::{Live, Offline};
+        let get = axum::http::Method::GET;
+        let path = "/api/pools/tank/obj/34";
+
+        assert_eq!(
+            cache_control_for(Offline, &get, path, StatusCode::OK),
+            Some(OFFLINE_CACHE_CONTROL)
+        );
+        assert_eq!(
+            cache_control_for(Offline, &get, path, StatusCode::NOT_FOUND),
+            Some(LIVE_CACHE_CONTROL)
+        );
+        assert_eq!(
+            cache_control_for(Offline, &get, "/api/mode", StatusCode::OK),
+            Some(LIVE_CACHE_CONTROL)
+        );
+        assert_eq!(
+            cache_control_for(Live, &get, path, StatusCode::OK),
+            Some(LIVE_CACHE_CONTROL)
+        );
+        assert_eq!(
+            cache_control_for(Live, &get, "/api/perf/arc", StatusCode::OK),
+            None
+        );
+        assert_eq!(
+            cache_control_for(
+                Offline,
+                &axum::http::Method::PUT,
+                "/api/mode",
+                StatusCode::OK
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn offline_pool_etag_changes_with_generation_and_txg() {
+        let before = offline_pool_etag(None);
+        assert!(before.starts_with("W/\"offline-"));
+        assert_ne!(before, offline_pool_etag(Some(1234)));
+        bump_pool_generation();
+        assert_ne!(before, offline_pool_etag(None));
+    }
+
+    #[test]
+    fn api_error_returns_json_envelope() {
+        let err = api_error(StatusCode::BAD_REQUEST, "boom");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1 .0["error"], "boom");
+        assert_eq!(err.1 .0["message"], "boom");
+        assert_eq!(err.1 .0["code"], "HTTP_400");
+        assert_eq!(err.1 .0["recoverable"], true);
+    }
+
+    #[test]
+    fn pool_open_error_code_maps_libzfs_names() {
+        assert_eq!(pool_open_error_code(2009), "EZFS_NOENT");
+        assert_eq!(pool_open_error_code(libc::EACCES), "ERRNO_13");
+        assert_eq!(pool_open_error_code(-3), "ZDX_-3");
+    }
+
+    #[test]
+    fn pool_busy_codes_map_to_conflict() {
+        assert!(is_pool_busy_code(libc::EBUSY));
+        assert!(is_pool_busy_code(2007));
+        assert!(is_pool_busy_code(2020));
+        assert!(is_pool_busy_code(2065));
+        assert!(!is_pool_busy_code(libc::EIO));
+        assert!(!is_pool_busy_code(2009));
+
+        let (status, Json(body)) = pool_busy_error("EZFS_SCRUBBING", "zap read failed");
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "POOL_BUSY");
+        assert_eq!(body["recoverable"], true);
+        assert!(body["hint"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("EZFS_SCRUBBING"));
+    }
+
+    #[test]
+    fn offline_pool_open_hint_is_user_friendly() {
+        let noent = offline_pool_open_hint("tank", 2009).unwrap_or_default();
+        assert!(noent.contains("offline search paths"));
+        let perm = offline_pool_open_hint("tank", libc::EACCES).unwrap_or_default();
+        assert!(perm.contains("Run the backend as root"));
+        assert!(offline_pool_open_hint("tank", libc::EIO).is_none());
+    }
+
+    #[test]
+    fn dataset_objset_response_shape_is_stable() {
+        let payload = build_dataset_objset_response(
+            32,
+            54,
+            &json!({
+                "objset_id": 54,
+                "rootbp": {
+                    "ndvas": 2
+                }
+            }),
+        );
+
+        assert_eq!(payload["dsl_dir_obj"], 32);
+        assert_eq!(payload["head_dataset_obj"], 54);
+        assert_eq!(payload["objset_id"], 54);
+        assert_eq!(payload["rootbp"]["ndvas"], 2);
+    }
+
+    #[test]
+    fn zap_unreadable_error_detection_matches_invalid_exchange() {
+        assert!(is_zap_unreadable_error(
+            "zap_get_stats failed: Invalid exchange"
+        ));
+        assert!(is_zap_unreadable_error(
+            "zap_cursor_retrieve failed: Invalid exchange"
+        ));
+        assert!(!is_zap_unreadable_error(
+            "zap_get_stats failed: Invalid argument"
+        ));
+    }
+
+    #[test]
+    fn objset_error_maps_unallocated_object_to_not_found() {
+        let err = api_error_for_objset("object 4242 not allocated");
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+        assert_eq!(err.1 .0["code"], "OBJECT_NOT_FOUND");
+
+        let err = api_error_for_objset(
+            "dmu_object_info failed for object 4242: No such file or directory",
+        );
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+
+        let err = api_error_for_objset("dmu_object_info failed for object 7: Invalid argument");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn objset_error_maps_encrypted_zap_hint() {
+        let err = api_error_for_objset("zap_get_stats failed: Invalid exchange");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1 .0["code"], "ZAP_UNREADABLE");
+        let hint = err
+            .1
+             .0
+            .get("hint")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        assert!(hint.contains("encrypted dataset contents"));
+    }
+
+    #[test]
+    fn spacemap_user_input_error_detection() {
+        assert!(is_spacemap_user_input_error(
+            "object 265 is type \"object array\" (11); expected \"space map\""
+        ));
+        assert!(is_spacemap_user_input_error(
+            "object 265 bonus is too small for space map payload (bonus=0, need>=24)"
+        ));
+        assert!(is_spacemap_user_input_error(
+            "failed to inspect spacemap object 999999: No such file or directory"
+        ));
+        assert!(!is_spacemap_user_input_error(
+            "failed to iterate spacemap object 264"
+        ));
+    }
+
+    #[test]
+    fn parse_dsl_children_handles_missing_and_invalid_entries() {
+        let payload = json!({
+            "children": [
+                { "name": "local", "dir_objid": 3 },
+                { "name": "bad-zero", "dir_objid": 0 },
+                { "name": "bad-type", "dir_objid": "oops" },
+                { "dir_objid": 7 }
+            ]
+        });
+
+        let parsed = parse_dsl_children(&payload);
+        assert_eq!(
+            parsed,
+            vec![("local".to_string(), 3), ("dataset".to_string(), 7)]
+        );
+    }
+
+    #[test]
+    fn version_payload_includes_required_fields() {
+        let payload = build_version_payload(&crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Live,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        assert_eq!(payload["project"], "zfs-explorer");
+        assert_eq!(payload["project_url"], REPO_URL);
+        assert_eq!(payload["backend"]["name"], BACKEND_NAME);
+        assert_eq!(payload["backend"]["version"], BACKEND_VERSION);
+        assert!(payload["backend"]["git_sha"].as_str().is_some());
+        assert!(payload["backend"]["build_version"].as_str().is_some());
+        assert!(payload["openzfs"]["commit"].as_str().is_some());
+        assert_eq!(payload["openzfs"]["spa_version"], ZFS_SPA_VERSION);
+        assert_eq!(payload["openzfs"]["zpl_version"], ZFS_ZPL_VERSION);
+        assert!(payload["openzfs"]["kernel_module"]["source"]
+            .as_str()
+            .is_some());
+        assert_eq!(payload["runtime"]["os"], std::env::consts::OS);
+        assert_eq!(payload["runtime"]["arch"], std::env::consts::ARCH);
+        assert_eq!(payload["pool_open"]["mode"], "live");
+    }
+
+    #[test]
+    fn parse_pool_open_mode_accepts_expected_values() {
+        assert!(matches!(
+            parse_pool_open_mode("live"),
+            Some(crate::PoolOpenMode::Live)
+        ));
+        assert!(matches!(
+            parse_pool_open_mode("OFFLINE"),
+            Some(crate::PoolOpenMode::Offline)
         ));
+        assert!(parse_pool_open_mode("invalid").is_none());
     }
 
-    let json_str = result
-        .json()
-        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Missing JSON in result"))?;
-    let value = parse_json_value(json_str)?;
-
-    let object = &value["object"];
-    let source_obj = object["id"].as_u64().unwrap_or(objid);
-    let source_type = object["type"]["id"].as_u64();
-    let source_bonus = object["bonus_type"]["id"].as_u64();
-
-    let mut nodes = Vec::new();
-    let mut node_ids = HashSet::new();
-    let mut add_node = |objid: u64, type_id: Option<u64>, bonus_id: Option<u64>| {
-        if node_ids.insert(objid) {
-            nodes.push(serde_json::json!({
-                "objid": objid,
-                "type": type_id,
-                "bonus_type": bonus_id
-            }));
-        }
-    };
-
-    add_node(source_obj, source_type, source_bonus);
-
-    let mut edges: Vec<Value> = Vec::new();
+    #[test]
+    fn mode_payload_shape_is_stable() {
+        let payload = build_mode_payload(&crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Offline,
+            offline_search_paths: Some("/tmp/fixtures".to_string()),
+            offline_pool_names: vec!["tank".to_string(), "backup".to_string()],
+            offline_txg: Some(1234),
+        });
 
-    if include_semantic {
-        if let Some(edge_list) = object["semantic_edges"].as_array() {
-            for edge in edge_list {
-                if let Some(target) = edge["target_obj"].as_u64() {
-                    add_node(target, None, None);
-                }
-                edges.push(edge.clone());
-            }
-        }
+        assert_eq!(payload["mode"], "offline");
+        assert_eq!(payload["offline_txg"], 1234);
+        assert_eq!(payload["offline_search_paths"], "/tmp/fixtures");
+        assert_eq!(payload["offline_pools"][0], "tank");
+        assert_eq!(payload["offline_pools"][1], "backup");
     }
 
-    if include_zap {
-        if let Some(entries) = value["zap_entries"]["entries"].as_array() {
-            for entry in entries {
-                let maybe_ref = entry["maybe_object_ref"].as_bool().unwrap_or(false);
-                let target = entry["target_obj"].as_u64().unwrap_or(0);
-                let name = entry["name"].as_str().unwrap_or("zap");
-                if maybe_ref && target != 0 {
-                    add_node(target, None, None);
-                    edges.push(serde_json::json!({
-                        "source_obj": source_obj,
-                        "target_obj": target,
-                        "label": name,
-                        "kind": "zap",
-                        "confidence": 0.7
-                    }));
-                }
-            }
-        }
+    #[test]
+    fn tar_entry_header_encodes_ustar_and_pax_fields() {
+        let header = tar_entry_header("root/a.txt", 5, 0o100644, 1000, 1000, 1_700_000_000, b'0');
+        assert_eq!(header.len(), TAR_BLOCK_SIZE);
+        assert_eq!(&header[..10], b"root/a.txt");
+        assert_eq!(&header[124..136], b"00000000005\0");
+        assert_eq!(&header[100..108], b"0000644\0");
+        assert_eq!(&header[257..263], b"ustar\0");
+        let stored = std::str::from_utf8(&header[148..154]).unwrap();
+        let mut unsigned = header.clone();
+        unsigned[148..156].fill(b' ');
+        let expected: u64 = unsigned.iter().map(|b| u64::from(*b)).sum();
+        assert_eq!(u64::from_str_radix(stored, 8).unwrap(), expected);
+
+        let long_name = format!("root/{}", "n".repeat(150));
+        let header = tar_entry_header(&long_name, 0, 0o644, 0, 0, 0, b'0');
+        assert_eq!(header[156], b'x');
+        assert_eq!(header.len(), TAR_BLOCK_SIZE * 3);
+        let record = tar_pax_record("path", &long_name);
+        assert!(record.starts_with(&format!("{} path=", record.len())));
+        assert_eq!(
+            &header[TAR_BLOCK_SIZE..TAR_BLOCK_SIZE + record.len()],
+            record.as_bytes()
+        );
+        assert_eq!(tar_padding(512), 0);
+        assert_eq!(tar_padding(5), 507);
     }
 
-    if include_physical {
-        if let Some(blkptrs) = value["blkptrs"]["blkptrs"].as_array() {
-            for (idx, bp) in blkptrs.iter().enumerate() {
-                let pseudo_id = (1u64 << 63) | (source_obj << 8) | (idx as u64);
-                add_node(pseudo_id, None, None);
+    #[test]
+    fn dataset_tree_flags_special_dirs_without_head_resolution() {
+        let mut fetched = Vec::new();
+        let mut fetch_dir = |objid: u64| {
+            fetched.push(objid);
+            let children = match objid {
+                2 => json!({
+                    "child_dir_zapobj": 3,
+                    "children": [
+                        { "name": "$MOS", "dir_objid": 5 },
+                        { "name": "home", "dir_objid": 21 }
+                    ]
+                }),
+                _ => json!({ "child_dir_zapobj": 40, "children": [] }),
+            };
+            Ok((json!({ "head_dataset_obj": objid + 100 }), children))
+        };
+        let mut walk = DatasetTreeWalk {
+            limit: 10,
+            child_limit: usize::MAX,
+            seen: 0,
+            truncated: false,
+        };
 
-                edges.push(serde_json::json!({
-                    "source_obj": source_obj,
-                    "target_obj": pseudo_id,
-                    "label": format!("blkptr {}", idx),
-                    "kind": "blkptr",
-                    "confidence": 1.0,
-                    "notes": bp.get("dvas")
-                }));
-            }
-        }
-    }
+        let root = build_dataset_tree_node("tank".to_string(), 2, 4, 0, &mut walk, &mut fetch_dir)
+            .expect("tree builds from fixtures");
+        assert_eq!(fetched, vec![2, 21]);
+        assert_eq!(walk.seen, 2);
 
-    let response = serde_json::json!({
-        "nodes": nodes,
-        "edges": edges
-    });
+        let children = root["children"].as_array().unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0]["name"], "$MOS");
+        assert_eq!(children[0]["dsl_dir_obj"], 5);
+        assert_eq!(children[0]["special"], true);
+        assert!(children[0]["head_dataset_obj"].is_null());
+        assert_eq!(children[1]["name"], "home");
+        assert_eq!(children[1]["special"], false);
+        assert_eq!(children[1]["head_dataset_obj"], 121);
+    }
 
-    Ok(Json(response))
-}
+    #[test]
+    fn page_dsl_children_sorts_and_reports_next_cursor() {
+        let children = vec![
+            ("c".to_string(), 3),
+            ("a".to_string(), 1),
+            ("d".to_string(), 4),
+            ("b".to_string(), 2),
+        ];
+        let (page, next) = page_dsl_children(children.clone(), 0, 3);
+        assert_eq!(
+            page,
+            vec![
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+                ("c".to_string(), 3)
+            ]
+        );
+        assert_eq!(next, Some(3));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
-    use std::sync::{Arc, Mutex};
+        let (page, next) = page_dsl_children(children.clone(), 3, 3);
+        assert_eq!(page, vec![("d".to_string(), 4)]);
+        assert_eq!(next, None);
 
-    fn test_state(config: crate::PoolOpenConfig) -> crate::AppState {
-        crate::AppState {
-            pool: Arc::new(Mutex::new(None)),
-            pool_open: Arc::new(Mutex::new(config)),
-        }
+        let (page, next) = page_dsl_children(children, 10, 3);
+        assert!(page.is_empty());
+        assert_eq!(next, None);
     }
 
-    fn sample_txgs_payload() -> &'static str {
-        r#"
-2 1 0x01 6 288 1234
-txg birth state ndirty nread nwritten
-42 1770590000 C 0 0 0
-43 1770590001 O 4096 2 8192
-"#
+    #[test]
+    fn recursive_usage_totals_sum_visited_rows_and_keep_root_used() {
+        let root = json!({
+            "head_dataset_obj": 54,
+            "used_bytes": 1000,
+            "referenced_bytes": 100,
+            "used_breakdown": { "dataset": 100, "snapshots": 50, "children": 850, "refreservation": 0 }
+        });
+        let child = json!({
+            "head_dataset_obj": 0,
+            "used_bytes": 850,
+            "referenced_bytes": null,
+            "used_breakdown": null
+        });
+        let rows = vec![
+            recursive_usage_row(".", 32, 0, &root),
+            recursive_usage_row("projects", 40, 1, &child),
+        ];
+        assert_eq!(rows[1]["head_dataset_obj"], Value::Null);
+        assert_eq!(rows[1]["used_by_snapshots_bytes"], Value::Null);
+
+        let totals = recursive_usage_totals(&rows);
+        assert_eq!(totals["used_bytes"], 1000);
+        assert_eq!(totals["referenced_bytes"], 100);
+        assert_eq!(totals["used_by_snapshots_bytes"], 50);
+        assert_eq!(totals["used_by_datasets_bytes"], 100);
     }
 
     #[test]
-    fn normalize_limit_uses_default_and_bounds() {
-        assert_eq!(normalize_limit(None), DEFAULT_PAGE_LIMIT);
-        assert_eq!(normalize_limit(Some(0)), 1);
-        assert_eq!(normalize_limit(Some(17)), 17);
-        assert_eq!(normalize_limit(Some(MAX_PAGE_LIMIT + 1)), MAX_PAGE_LIMIT);
+    fn recursive_usage_treemap_nests_rows_and_sums_to_root_used() {
+        let row = |name: &str, depth: u64, used: u64| json!({ "name": name, "dsl_dir_obj": depth * 10, "depth": depth, "used_bytes": used });
+        // "b/deep" is absent, as if cut by depth: its space stays with "b".
+        let rows = vec![
+            row(".", 0, 1000),
+            row("a", 1, 300),
+            row("a/x", 2, 100),
+            row("b", 1, 500),
+        ];
+        let tree = recursive_usage_treemap(&rows);
+        assert_eq!(tree["path"], ".");
+        assert_eq!(tree["value"], 200);
+        assert_eq!(tree["used_bytes"], 1000);
+
+        let children = tree["children"].as_array().unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0]["name"], "a");
+        assert_eq!(children[0]["value"], 200);
+        assert_eq!(children[0]["children"][0]["name"], "x");
+        assert_eq!(children[0]["children"][0]["path"], "a/x");
+        assert_eq!(children[0]["children"][0]["value"], 100);
+        assert_eq!(children[1]["value"], 500);
+
+        fn sum(node: &Value) -> u64 {
+            node["value"].as_u64().unwrap()
+                + node["children"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(sum)
+                    .sum::<u64>()
+        }
+        assert_eq!(sum(&tree), 1000);
+        assert_eq!(recursive_usage_treemap(&[]), Value::Null);
+        assert!(parse_recursive_usage_format(Some("pie")).is_err());
     }
 
     #[test]
-    fn normalize_cursor_limit_defaults_cursor_and_limit() {
-        assert_eq!(normalize_cursor_limit(None, None), (0, DEFAULT_PAGE_LIMIT));
-        assert_eq!(normalize_cursor_limit(Some(42), Some(64)), (42, 64));
+    fn clone_origin_dsobj_only_follows_real_clones() {
+        let clone = json!({
+            "is_clone": true,
+            "origin_obj": 90,
+            "origin": { "snapshot_dsobj": 90, "snapshot_name": "tank/src@base" }
+        });
+        assert_eq!(clone_origin_dsobj(&clone), Some(90));
+
+        // Post-origin-feature datasets point at $ORIGIN@$ORIGIN.
+        let plain = json!({ "is_clone": false, "origin_obj": 54, "origin": null });
+        assert_eq!(clone_origin_dsobj(&plain), None);
+
+        let broken = json!({ "is_clone": true, "origin": { "snapshot_dsobj": 0 } });
+        assert_eq!(clone_origin_dsobj(&broken), None);
     }
 
     #[test]
-    fn normalize_spacemap_limit_uses_default_and_bounds() {
-        assert_eq!(normalize_spacemap_limit(None), SPACEMAP_DEFAULT_LIMIT);
-        assert_eq!(normalize_spacemap_limit(Some(0)), 1);
-        assert_eq!(normalize_spacemap_limit(Some(17)), 17);
-        assert_eq!(
-            normalize_spacemap_limit(Some(SPACEMAP_MAX_LIMIT + 1)),
-            SPACEMAP_MAX_LIMIT
+    fn server_timing_value_reports_millisecond_phases() {
+        let value = server_timing_value(
+            Duration::from_micros(12_340),
+            Duration::from_micros(1_060),
+            Duration::from_millis(14),
         );
+        assert_eq!(value, "ffi;dur=12.3, parse;dur=1.1, total;dur=14.0");
     }
 
     #[test]
-    fn normalize_spacemap_bins_limit_uses_default_and_bounds() {
+    fn metadata_export_document_is_valid_json_and_flags_truncation() {
+        let (summary, features, vdev_tree) = split_metadata_summary(json!({
+            "pool": { "name": "tank" },
+            "features_for_read": ["com.delphix:hole_birth"],
+            "compatibility": { "supported": true },
+            "vdev_tree": { "type": "root" },
+        }));
+        assert_eq!(summary, json!({ "pool": { "name": "tank" } }));
+        assert_eq!(features["features_for_read"][0], "com.delphix:hole_birth");
+        assert_eq!(vdev_tree["type"], "root");
+
+        let mut doc = JsonObjectFragments::default();
+        let mut out = doc.member("schema_version", &json!(1));
+        out.extend(doc.member("summary", &summary));
+
+        let mut errors = JsonObjectFragments::default();
+        out.extend(doc.key("errors"));
+        out.extend(errors.key("entries"));
+        out.push(b'[');
+        let (mut written, mut truncated) = (0u64, false);
+        let page = json!({ "entries": [{ "objset": 54 }, { "objset": 55 }], "next": 2 });
         assert_eq!(
-            normalize_spacemap_bins_limit(None),
-            SPACEMAP_BINS_DEFAULT_LIMIT
+            append_metadata_error_items(&page, &mut out, &mut written, 3, &mut truncated),
+            Some(2)
         );
-        assert_eq!(normalize_spacemap_bins_limit(Some(0)), 1);
-        assert_eq!(normalize_spacemap_bins_limit(Some(64)), 64);
         assert_eq!(
-            normalize_spacemap_bins_limit(Some(SPACEMAP_BINS_MAX_LIMIT + 1)),
-            SPACEMAP_BINS_MAX_LIMIT
+            append_metadata_error_items(&page, &mut out, &mut written, 3, &mut truncated),
+            None
         );
+        assert!(truncated);
+        out.push(b']');
+        out.extend(errors.member("count", &json!(written)));
+        out.extend(errors.member("truncated", &json!(truncated)));
+        out.extend(errors.close());
+        out.extend(doc.member("truncated_sections", &json!(["datasets", "errors"])));
+        out.extend(doc.close());
+
+        let doc: Value = serde_json::from_slice(&out).expect("export must be valid JSON");
+        assert_eq!(doc["schema_version"], 1);
+        assert_eq!(doc["errors"]["entries"].as_array().unwrap().len(), 3);
+        assert_eq!(doc["errors"]["count"], 3);
+        assert_eq!(doc["errors"]["truncated"], true);
+        assert_eq!(doc["truncated_sections"], json!(["datasets", "errors"]));
+
+        let empty = JsonObjectFragments::default().close();
+        assert_eq!(empty, b"{}");
     }
 
     #[test]
-    fn normalize_spacemap_bin_size_uses_default_and_bounds() {
+    fn block_tree_txg_range_defaults_open_and_rejects_inverted_bounds() {
+        let query = |min_txg, max_txg| BlockTreeQuery {
+            max_depth: None,
+            max_nodes: None,
+            min_txg,
+            max_txg,
+            strict: None,
+        };
         assert_eq!(
-            normalize_spacemap_bin_size(None),
-            SPACEMAP_BINS_DEFAULT_SIZE
+            block_tree_txg_range(&query(None, None)).unwrap(),
+            (0, u64::MAX)
         );
-        assert_eq!(normalize_spacemap_bin_size(Some(1)), SPACEMAP_BINS_MIN_SIZE);
-        assert_eq!(normalize_spacemap_bin_size(Some(4096)), 4096);
         assert_eq!(
-            normalize_spacemap_bin_size(Some(SPACEMAP_BINS_MAX_SIZE + 1)),
-            SPACEMAP_BINS_MAX_SIZE
+            block_tree_txg_range(&query(Some(10), Some(10))).unwrap(),
+            (10, 10)
         );
+        let err = block_tree_txg_range(&query(Some(11), Some(10))).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+
+        let value = finish_block_tree(&query(Some(5), None), json!({ "truncated": false }))
+            .unwrap()
+            .0;
+        assert_eq!(value["min_txg"], 5);
+        assert_eq!(value["max_txg"], Value::Null);
     }
 
     #[test]
-    fn normalize_block_tree_depth_uses_default_and_bounds() {
-        assert_eq!(normalize_block_tree_depth(None), BLOCK_TREE_DEFAULT_DEPTH);
-        assert_eq!(normalize_block_tree_depth(Some(0)), 0);
-        assert_eq!(
-            normalize_block_tree_depth(Some(BLOCK_TREE_MAX_DEPTH + 3)),
-            BLOCK_TREE_MAX_DEPTH
+    fn build_active_features_keeps_nonzero_refcounts_largest_first() {
+        let for_read = vec![
+            json!({ "name": "com.delphix:hole_birth", "value_u64": 1 }),
+            json!({ "name": "org.openzfs:zstd_compress", "value_u64": 0 }),
+        ];
+        let for_write = vec![
+            json!({ "name": "org.open-zfs:large_blocks", "value_u64": 3 }),
+            json!({ "name": "com.delphix:spacemap_histogram", "value_u64": 1 }),
+        ];
+        let enabled_txg = vec![json!({ "name": "org.open-zfs:large_blocks", "value_u64": 42 })];
+        let descriptions = vec![json!({
+            "name": "org.open-zfs:large_blocks",
+            "value_preview": "Support for blocks larger than 128KB."
+        })];
+        let mut holders = HashMap::new();
+        holders.insert(
+            "org.open-zfs:large_blocks".to_string(),
+            vec![json!({ "name": "tank/media", "dsobj": 260 })],
         );
-    }
 
-    #[test]
-    fn normalize_block_tree_nodes_uses_default_and_bounds() {
-        assert_eq!(normalize_block_tree_nodes(None), BLOCK_TREE_DEFAULT_NODES);
-        assert_eq!(normalize_block_tree_nodes(Some(0)), 1);
-        assert_eq!(normalize_block_tree_nodes(Some(77)), 77);
+        let features =
+            build_active_features(&for_read, &for_write, &enabled_txg, &descriptions, &holders);
+        let guids: Vec<&str> = features
+            .iter()
+            .map(|feature| feature["guid"].as_str().unwrap())
+            .collect();
         assert_eq!(
-            normalize_block_tree_nodes(Some(BLOCK_TREE_MAX_NODES + 1)),
-            BLOCK_TREE_MAX_NODES
+            guids,
+            vec![
+                "org.open-zfs:large_blocks",
+                "com.delphix:hole_birth",
+                "com.delphix:spacemap_histogram",
+            ]
         );
+        assert_eq!(features[0]["refcount"], 3);
+        assert_eq!(features[0]["required_for_read"], false);
+        assert_eq!(features[0]["enabled_txg"], 42);
+        assert_eq!(features[0]["holders"][0]["name"], "tank/media");
+        assert_eq!(features[1]["required_for_read"], true);
+        assert_eq!(features[1]["holders"], Value::Null);
+        assert_eq!(features[1]["description"], Value::Null);
     }
 
     #[test]
-    fn parse_spacemap_op_filter_accepts_expected_values() {
-        assert_eq!(parse_spacemap_op_filter(None).unwrap(), 0);
-        assert_eq!(parse_spacemap_op_filter(Some("all")).unwrap(), 0);
-        assert_eq!(parse_spacemap_op_filter(Some("alloc")).unwrap(), 1);
-        assert_eq!(parse_spacemap_op_filter(Some("free")).unwrap(), 2);
+    fn trim_data_to_logical_size_drops_block_padding() {
+        let mut value =
+            json!({"size": 8, "max_offset": 512, "eof": false, "data_hex": "6869210a00000000"});
+        trim_data_to_logical_size(&mut value, 0, 4).expect("in range");
+        assert_eq!(value["data_hex"], "6869210a");
+        assert_eq!(value["size"], 4);
+        assert_eq!(value["eof"], true);
+        assert_eq!(value["logical_size"], 4);
+
+        let mut value = json!({"size": 2, "eof": false, "data_hex": "6869"});
+        trim_data_to_logical_size(&mut value, 0, 4).expect("in range");
+        assert_eq!(value["data_hex"], "6869");
+        assert_eq!(value["eof"], false);
+
+        let mut value = json!({"size": 0, "eof": true, "data_hex": ""});
+        let (status, _) = trim_data_to_logical_size(&mut value, 5, 4).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
     #[test]
-    fn parse_spacemap_op_filter_rejects_invalid_values() {
-        let err = parse_spacemap_op_filter(Some("bogus")).unwrap_err();
+    fn failed_open_of_other_pool_keeps_active_handle() {
+        let state = test_state(crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Live,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        *state.pool.lock().unwrap() = Some(Arc::new(crate::ffi::PoolHandle {
+            name: "tank".to_string(),
+            ptr: std::ptr::null_mut(),
+        }));
+        *state.known_names.lock().unwrap() =
+            Some(Arc::new(KnownNameIndex::from_parts("tank", &[], &[])));
+
+        let mut attempts = Vec::new();
+        let err = open_pool_with(&state, "missing", false, |pool, _| {
+            attempts.push(pool.to_string());
+            Err(api_error(StatusCode::BAD_REQUEST, "no such pool"))
+        })
+        .expect_err("open of the other pool fails");
         assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(attempts, vec!["missing"]);
+        let active = state.pool.lock().unwrap().as_ref().map(|h| h.name.clone());
+        assert_eq!(active.as_deref(), Some("tank"));
+        assert!(state.known_names.lock().unwrap().is_some());
     }
 
     #[test]
-    fn parse_graph_include_handles_defaults_and_flags() {
-        assert_eq!(parse_graph_include(None), (true, true, false));
-        assert_eq!(
-            parse_graph_include(Some("semantic,zap")),
-            (true, false, true)
-        );
-        assert_eq!(parse_graph_include(Some("physical")), (false, true, false));
+    fn failed_reopen_restores_the_previous_handle() {
+        let state = test_state(crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        let null_handle = |name: &str| crate::ffi::PoolHandle {
+            name: name.to_string(),
+            ptr: std::ptr::null_mut(),
+        };
+        *state.pool.lock().unwrap() = Some(Arc::new(null_handle("tank")));
+
+        // A reopen is refused while another request holds the handle.
+        let held = ensure_pool(&state, "tank").expect("active handle");
+        let busy = open_pool_with(&state, "tank", true, |_, _| panic!("must not reopen"))
+            .expect_err("held handle blocks the reopen");
+        assert_eq!(busy.0, StatusCode::CONFLICT);
+        assert_eq!(busy.1["code"], "POOL_BUSY");
+        drop(held);
+
+        // The fresh open fails; the pool is opened again as before.
+        let mut attempts = 0;
+        let err = open_pool_with(&state, "tank", true, |pool, _| {
+            attempts += 1;
+            if attempts == 1 {
+                Err(api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "import failed",
+                ))
+            } else {
+                Ok(null_handle(pool))
+            }
+        })
+        .expect_err("the refresh itself fails");
+        assert_eq!(err.0, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(attempts, 2);
+        let active = state.pool.lock().unwrap().as_ref().map(|h| h.name.clone());
+        assert_eq!(active.as_deref(), Some("tank"));
     }
 
     #[test]
-    fn parse_json_value_maps_errors_to_http_500() {
-        let err = parse_json_value("{bad json").unwrap_err();
-        assert_eq!(err.0, StatusCode::INTERNAL_SERVER_ERROR);
-        let msg = err
-            .1
-             .0
-            .get("error")
-            .and_then(Value::as_str)
-            .unwrap_or_default();
-        assert!(msg.starts_with("JSON parse error:"));
+    fn vdev_not_found_error_matches_only_missing_vdev() {
+        assert!(is_vdev_not_found_error("top-level vdev 7 not found"));
+        assert!(!is_vdev_not_found_error("failed to encode metaslab list"));
+        assert!(!is_vdev_not_found_error("pool not open"));
     }
 
     #[test]
-    fn api_error_returns_json_envelope() {
-        let err = api_error(StatusCode::BAD_REQUEST, "boom");
-        assert_eq!(err.0, StatusCode::BAD_REQUEST);
-        assert_eq!(err.1 .0["error"], "boom");
-        assert_eq!(err.1 .0["message"], "boom");
-        assert_eq!(err.1 .0["code"], "HTTP_400");
-        assert_eq!(err.1 .0["recoverable"], true);
+    fn zero_limit_yields_empty_page_with_native_envelope() {
+        assert_eq!(fetch_limit(0), 1);
+        assert_eq!(fetch_limit(25), 25);
+
+        // A fetched item means more remains at the requested cursor.
+        let page = json!({"cursor": 40, "count": 1, "next": 41, "entries": [{"name": "a"}]});
+        let Json(value) = finish_page(0, "entries", Ok(Json(page))).expect("empty page");
+        assert_eq!(value["entries"], json!([]));
+        assert_eq!(value["count"], 0);
+        assert_eq!(value["next"], 40);
+        assert_eq!(value["has_more"], true);
+
+        // Object listings page by `start`; an exhausted listing stays done.
+        let page = json!({"start": 9, "limit": 1, "count": 0, "next": null, "objects": []});
+        let value = empty_page(page, "objects");
+        assert_eq!(value["limit"], 0);
+        assert!(value["next"].is_null());
+
+        // Other arrays in the payload are left alone.
+        let page = json!({"cursor": 0, "next": null, "pool_bpobjs": [1], "deadlists": [2]});
+        let value = empty_page(page, "deadlists");
+        assert_eq!(value["pool_bpobjs"], json!([1]));
+        assert_eq!(value["next"], 0);
+
+        // Non-zero limits pass through untouched.
+        let page = json!({"cursor": 0, "next": 1, "entries": [1]});
+        let Json(value) = finish_page(5, "entries", Ok(Json(page.clone()))).unwrap();
+        assert_eq!(value, page);
     }
 
     #[test]
-    fn pool_open_error_code_maps_libzfs_names() {
-        assert_eq!(pool_open_error_code(2009), "EZFS_NOENT");
-        assert_eq!(pool_open_error_code(libc::EACCES), "ERRNO_13");
-        assert_eq!(pool_open_error_code(-3), "ZDX_-3");
+    fn zero_data_limit_yields_empty_slice() {
+        let value = empty_data_slice(
+            json!({"offset": 0, "size": 1, "max_offset": 512, "eof": false, "data_hex": "ff"}),
+        );
+        assert_eq!(value["data_hex"], "");
+        assert_eq!(value["size"], 0);
+        assert_eq!(value["eof"], false);
+
+        let value = empty_data_slice(
+            json!({"offset": 512, "size": 0, "max_offset": 512, "eof": true, "data_hex": ""}),
+        );
+        assert_eq!(value["eof"], true);
+        assert_eq!(normalize_objset_data_limit(Some(0)), 0);
     }
 
     #[test]
-    fn offline_pool_open_hint_is_user_friendly() {
-        let noent = offline_pool_open_hint("tank", 2009).unwrap_or_default();
-        assert!(noent.contains("offline search paths"));
-        let perm = offline_pool_open_hint("tank", libc::EACCES).unwrap_or_default();
-        assert!(perm.contains("Run the backend as root"));
-        assert!(offline_pool_open_hint("tank", libc::EIO).is_none());
+    fn zero_budget_parameters_are_rejected() {
+        for (parameter, result) in [
+            ("max_entries", normalize_tar_export_max_entries(Some(0))),
+            ("max_bytes", normalize_tar_export_max_bytes(Some(0))),
+            ("max_nodes", normalize_block_tree_nodes(Some(0))),
+        ] {
+            let (status, Json(body)) = result.unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["code"], "INVALID_QUERY");
+            assert_eq!(body["parameter"], parameter);
+        }
+        assert_eq!(
+            normalize_tar_export_max_entries(None).unwrap(),
+            TAR_EXPORT_DEFAULT_MAX_ENTRIES
+        );
+        assert_eq!(reject_zero::<u64>("limit", None).unwrap(), None);
+        assert_eq!(reject_zero("limit", Some(3)).unwrap(), Some(3));
     }
 
     #[test]
-    fn dataset_objset_response_shape_is_stable() {
-        let payload = build_dataset_objset_response(
-            32,
-            54,
-            &json!({
-                "objset_id": 54,
-                "rootbp": {
-                    "ndvas": 2
-                }
-            }),
+    fn read_batch_plan_clamps_lengths_and_caps_totals() {
+        let item = |objid, length| ObjsetReadBatchItem {
+            objid,
+            offset: None,
+            length,
+        };
+        let reads = plan_read_batch(&[item(5, None), item(6, Some(u64::MAX))]).unwrap();
+        assert_eq!(
+            reads,
+            vec![
+                (5, 0, OBJSET_DATA_DEFAULT_LIMIT),
+                (6, 0, OBJSET_DATA_MAX_LIMIT)
+            ]
         );
 
-        assert_eq!(payload["dsl_dir_obj"], 32);
-        assert_eq!(payload["head_dataset_obj"], 54);
-        assert_eq!(payload["objset_id"], 54);
-        assert_eq!(payload["rootbp"]["ndvas"], 2);
-    }
+        let (status, Json(body)) = plan_read_batch(&[]).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "INVALID_QUERY");
 
-    #[test]
-    fn zap_unreadable_error_detection_matches_invalid_exchange() {
-        assert!(is_zap_unreadable_error(
-            "zap_get_stats failed: Invalid exchange"
-        ));
-        assert!(is_zap_unreadable_error(
-            "zap_cursor_retrieve failed: Invalid exchange"
-        ));
-        assert!(!is_zap_unreadable_error(
-            "zap_get_stats failed: Invalid argument"
-        ));
+        let too_many: Vec<_> = (0..=READ_BATCH_MAX_ITEMS as u64)
+            .map(|objid| item(objid, Some(1)))
+            .collect();
+        assert!(plan_read_batch(&too_many).is_err());
+
+        let too_large: Vec<_> = (0..9).map(|objid| item(objid, Some(u64::MAX))).collect();
+        let (status, _) = plan_read_batch(&too_large).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
     #[test]
-    fn objset_error_maps_encrypted_zap_hint() {
-        let err = api_error_for_objset("zap_get_stats failed: Invalid exchange");
-        assert_eq!(err.0, StatusCode::BAD_REQUEST);
-        assert_eq!(err.1 .0["code"], "ZAP_UNREADABLE");
-        let hint = err
-            .1
-             .0
-            .get("hint")
-            .and_then(Value::as_str)
-            .unwrap_or_default();
-        assert!(hint.contains("encrypted dataset contents"));
+    fn offline_scan_errors_map_to_bad_request() {
+        let (status, Json(body)) = offline_scan_error("no ZFS label found on /tmp/x").unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "NO_ZFS_LABEL");
+
+        let (status, Json(body)) = offline_scan_error("cannot open /dev/nope").unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "INVALID_PATH");
+
+        assert!(offline_scan_error("failed to allocate label buffer").is_none());
     }
 
     #[test]
-    fn spacemap_user_input_error_detection() {
-        assert!(is_spacemap_user_input_error(
-            "object 265 is type \"object array\" (11); expected \"space map\""
-        ));
-        assert!(is_spacemap_user_input_error(
-            "object 265 bonus is too small for space map payload (bonus=0, need>=24)"
-        ));
-        assert!(is_spacemap_user_input_error(
-            "failed to inspect spacemap object 999999: No such file or directory"
-        ));
-        assert!(!is_spacemap_user_input_error(
-            "failed to iterate spacemap object 264"
-        ));
+    fn offline_scan_paths_stay_inside_search_roots() {
+        let base =
+            std::env::temp_dir().join(format!("zfs-explorer-offline-scan-{}", std::process::id()));
+        let images = base.join("images");
+        let outside = base.join("imagesx");
+        std::fs::create_dir_all(&images).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(images.join("pool.img"), b"").unwrap();
+        std::fs::write(outside.join("pool.img"), b"").unwrap();
+        std::os::unix::fs::symlink(outside.join("pool.img"), images.join("escape.img")).unwrap();
+        let path = |rel: &str| base.join(rel).display().to_string();
+
+        let search = format!("/nonexistent: {}", images.display());
+        let roots = offline_scan_roots(Some(&search));
+        let allowed = offline_scan_allowed_path(&path("images/pool.img"), &roots);
+        assert!(allowed.is_some_and(|p| p.ends_with("/images/pool.img")));
+        assert!(offline_scan_allowed_path(&path("images/../imagesx/pool.img"), &roots).is_none());
+        assert!(offline_scan_allowed_path(&path("images/escape.img"), &roots).is_none());
+        assert!(offline_scan_allowed_path(&path("imagesx/pool.img"), &roots).is_none());
+        assert!(offline_scan_allowed_path(&path("images/missing.img"), &roots).is_none());
+        assert!(offline_scan_allowed_path("images/pool.img", &roots).is_none());
+        std::fs::remove_dir_all(&base).ok();
+
+        let defaults = offline_scan_roots(None);
+        assert!(offline_scan_allowed_path("/dev/null", &defaults).is_some());
+        assert!(offline_scan_allowed_path("/etc/passwd", &defaults).is_none());
     }
 
     #[test]
-    fn parse_dsl_children_handles_missing_and_invalid_entries() {
-        let payload = json!({
-            "children": [
-                { "name": "local", "dir_objid": 3 },
-                { "name": "bad-zero", "dir_objid": 0 },
-                { "name": "bad-type", "dir_objid": "oops" },
-                { "dir_objid": 7 }
+    fn timeline_merges_uberblocks_history_and_snapshots_in_time_order() {
+        let labels = json!({
+            "devices": [{
+                "labels": [
+                    { "uberblocks": [
+                        { "txg": 90, "timestamp": 1_000, "active": false },
+                        { "txg": 91, "timestamp": 1_005, "active": true }
+                    ] },
+                    { "uberblocks": [{ "txg": 90, "timestamp": 1_000, "active": false }] }
+                ]
+            }]
+        });
+        let history = json!({
+            "events": [
+                { "time": 900, "txg": null, "command": "zpool create tank sda" },
+                { "time": 1_002, "txg": 90, "command": null, "internal": "snapshot",
+                  "dataset": "tank/fs@a", "message": "", "ioctl": null }
             ]
         });
+        let snapshots = json!({
+            "entries": [{ "name": "a", "creation_txg": 90, "creation_time": 1_002 }]
+        });
 
-        let parsed = parse_dsl_children(&payload);
+        let uberblocks = uberblock_timeline_events(&labels);
+        assert_eq!(uberblocks.len(), 2);
+        assert_eq!(uberblocks[1]["detail"], "active uberblock");
+
+        let mut events = uberblocks;
+        events.extend(history_timeline_events(&history));
+        events.extend(snapshot_timeline_events("tank/fs", &snapshots));
+        sort_timeline(&mut events);
+
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|event| event["kind"].as_str().unwrap())
+            .collect();
         assert_eq!(
-            parsed,
-            vec![("local".to_string(), 3), ("dataset".to_string(), 7)]
+            kinds,
+            ["command", "uberblock", "internal", "snapshot", "uberblock"]
         );
+        assert_eq!(events[0]["detail"], "zpool create tank sda");
+        assert!(events[0]["txg"].is_null());
+        assert_eq!(events[2]["detail"], "snapshot tank/fs@a");
+        assert_eq!(events[3]["detail"], "tank/fs@a");
     }
 
     #[test]
-    fn version_payload_includes_required_fields() {
-        let payload = build_version_payload(&crate::PoolOpenConfig {
+    fn response_cache_evicts_least_recently_used() {
+        let cached = |body: &'static str| CachedResponse {
+            headers: HeaderMap::new(),
+            body: axum::body::Bytes::from_static(body.as_bytes()),
+        };
+        let cache = ResponseCache::new(2);
+        cache.insert("a".to_string(), cached("1"));
+        cache.insert("b".to_string(), cached("2"));
+        assert!(cache.get("a").is_some());
+        cache.insert("c".to_string(), cached("3"));
+
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().body, "1");
+        assert_eq!(cache.get("c").unwrap().body, "3");
+
+        cache.clear();
+        assert!(cache.get("a").is_none());
+        assert!(!ResponseCache::new(0).enabled());
+    }
+
+    #[test]
+    fn response_cache_evicts_to_fit_byte_budget() {
+        let cached = |body: &'static str| CachedResponse {
+            headers: HeaderMap::new(),
+            body: axum::body::Bytes::from_static(body.as_bytes()),
+        };
+        let cache = ResponseCache::with_byte_budget(10, 8);
+        cache.insert("a".to_string(), cached("aaaa"));
+        cache.insert("b".to_string(), cached("bbbb"));
+        cache.insert("c".to_string(), cached("cc"));
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+
+        cache.insert("huge".to_string(), cached("123456789"));
+        assert!(cache.get("huge").is_none());
+        assert!(cache.get("b").is_some());
+    }
+
+    #[test]
+    fn response_cache_key_covers_only_plain_offline_pool_reads() {
+        let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let offline = crate::PoolOpenMode::Offline;
+
+        assert_eq!(
+            response_cache_key(offline, &request("/api/pools/tank/obj/5?limit=10")).as_deref(),
+            Some("GET /api/pools/tank/obj/5?limit=10")
+        );
+        assert!(
+            response_cache_key(crate::PoolOpenMode::Live, &request("/api/pools/tank/obj/5"))
+                .is_none()
+        );
+        assert!(response_cache_key(offline, &request("/api/pools")).is_none());
+        assert!(
+            response_cache_key(offline, &request("/api/pools/tank/obj/5?debug=true")).is_none()
+        );
+
+        let mut ranged = request("/api/pools/tank/objset/54/obj/7/data");
+        ranged
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_static("bytes=0-9"));
+        assert!(response_cache_key(offline, &ranged).is_none());
+
+        let mut post = request("/api/pools/tank/blkptr/decode");
+        *post.method_mut() = axum::http::Method::POST;
+        assert!(response_cache_key(offline, &post).is_none());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn zero_limit_timeline_page_is_empty_envelope() {
+        let state = test_state(crate::PoolOpenConfig {
             mode: crate::PoolOpenMode::Live,
             offline_search_paths: None,
             offline_pool_names: Vec::new(),
+            offline_txg: None,
         });
-        assert_eq!(payload["project"], "zfs-explorer");
-        assert_eq!(payload["project_url"], REPO_URL);
-        assert_eq!(payload["backend"]["name"], BACKEND_NAME);
-        assert_eq!(payload["backend"]["version"], BACKEND_VERSION);
-        assert!(payload["backend"]["git_sha"].as_str().is_some());
-        assert!(payload["backend"]["build_version"].as_str().is_some());
-        assert!(payload["openzfs"]["commit"].as_str().is_some());
-        assert_eq!(payload["openzfs"]["spa_version"], ZFS_SPA_VERSION);
-        assert_eq!(payload["openzfs"]["zpl_version"], ZFS_ZPL_VERSION);
-        assert!(payload["openzfs"]["kernel_module"]["source"]
-            .as_str()
-            .is_some());
-        assert_eq!(payload["runtime"]["os"], std::env::consts::OS);
-        assert_eq!(payload["runtime"]["arch"], std::env::consts::ARCH);
-        assert_eq!(payload["pool_open"]["mode"], "live");
+        *state.pool_timeline.lock().unwrap() = Some(Arc::new(PoolTimeline {
+            pool: "tank".to_string(),
+            loaded_at: Instant::now(),
+            events: (0..3)
+                .map(|txg| timeline_event(100 + txg, Some(txg), "uberblock", String::new()))
+                .collect(),
+            truncated: false,
+            source_errors: serde_json::Map::new(),
+        }));
+
+        let Json(page) = pool_timeline(
+            State(state),
+            Path("tank".to_string()),
+            ApiQuery(TimelineQuery {
+                cursor: Some(1),
+                limit: Some(0),
+            }),
+        )
+        .await
+        .expect("cached timeline page");
+        assert_eq!(page["events"], json!([]));
+        assert_eq!(page["count"], 0);
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["next"], 1);
+        assert_eq!(page["has_more"], true);
     }
 
-    #[test]
-    fn parse_pool_open_mode_accepts_expected_values() {
-        assert!(matches!(
-            parse_pool_open_mode("live"),
-            Some(crate::PoolOpenMode::Live)
-        ));
-        assert!(matches!(
-            parse_pool_open_mode("OFFLINE"),
-            Some(crate::PoolOpenMode::Offline)
-        ));
-        assert!(parse_pool_open_mode("invalid").is_none());
+    #[tokio::test(flavor = "current_thread")]
+    async fn zero_limit_data_read_returns_empty_slice() {
+        let native = json!({
+            "offset": 4,
+            "max_offset": 10,
+            "size": 1,
+            "eof": false,
+            "data_hex": "41",
+        });
+        let response = objset_data_response(native, 4, 0, None, None).expect("data response");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[ACCEPT_RANGES], "bytes");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data_hex"], "");
+        assert_eq!(body["size"], 0);
+        assert_eq!(body["eof"], false);
     }
 
-    #[test]
-    fn mode_payload_shape_is_stable() {
-        let payload = build_mode_payload(&crate::PoolOpenConfig {
-            mode: crate::PoolOpenMode::Offline,
-            offline_search_paths: Some("/tmp/fixtures".to_string()),
-            offline_pool_names: vec!["tank".to_string(), "backup".to_string()],
+    #[tokio::test(flavor = "current_thread")]
+    async fn zero_budget_limits_are_rejected_before_the_pool_opens() {
+        let state = test_state(crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Live,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
         });
+        let (status, Json(body)) = dataset_recursive_usage(
+            State(state.clone()),
+            Path(("tank".to_string(), 34)),
+            ApiQuery(RecursiveUsageQuery {
+                depth: None,
+                limit: Some(0),
+                format: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "INVALID_QUERY");
+        assert_eq!(body["parameter"], "limit");
+        assert!(state.pool.lock().unwrap().is_none());
+    }
 
-        assert_eq!(payload["mode"], "offline");
-        assert_eq!(payload["offline_search_paths"], "/tmp/fixtures");
-        assert_eq!(payload["offline_pools"][0], "tank");
-        assert_eq!(payload["offline_pools"][1], "backup");
+    #[test]
+    fn file_history_orders_snapshots_by_listed_creation_txg() {
+        let mut snapshots: Vec<SnapshotListEntry> = serde_json::from_value(json!([
+            { "name": "old", "dsobj": 90, "creation_txg": 10, "creation_time": 100 },
+            { "name": "unreadable", "dsobj": 95, "creation_txg": null, "creation_time": null },
+            { "name": "new", "dsobj": 40, "creation_txg": 30, "creation_time": 300 },
+        ]))
+        .unwrap();
+        sort_snapshots_newest_first(&mut snapshots);
+        let names: Vec<&str> = snapshots.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["new", "old", "unreadable"]);
+    }
+
+    #[test]
+    fn raw_requested_matches_only_exact_flag() {
+        assert!(raw_requested(Some("limit=5&raw=true")));
+        assert!(raw_requested(Some("raw=1")));
+        assert!(!raw_requested(Some("raw=false")));
+        assert!(!raw_requested(Some("rawish=true")));
+        assert!(!raw_requested(None));
     }
 
     #[test]
@@ -4443,6 +12633,19 @@ c_max                           4    8192
         assert_eq!(rows[1].write_bytes, Some(8192));
     }
 
+    #[test]
+    fn split_vdev_iostat_samples_starts_block_at_pool_row() {
+        let sample = "tank\t100\t900\t1\t2\t4096\t8192\n mirror-0\t100\t900\t1\t2\t4096\t8192\n\
+                      tank\t100\t900\t7\t3\t512\t1024\n mirror-0\t100\t900\t7\t3\t512\t1024\n";
+        let samples = split_vdev_iostat_samples(parse_vdev_iostat_output(sample), "tank");
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].len(), 2);
+        let rate = vdev_iostat_rate(&samples[1][1]);
+        assert_eq!(rate["name"], "mirror-0");
+        assert_eq!(rate["read_ops_per_sec"], 7);
+        assert_eq!(rate["write_bytes_per_sec"], 1024);
+    }
+
     #[test]
     fn parse_iostat_counter_handles_dash_values() {
         assert_eq!(parse_iostat_counter("1234"), Some(1234));
@@ -4466,6 +12669,51 @@ c_max                           4    8192
         assert_eq!((start, end, partial), (80, 99, true));
     }
 
+    fn resolve_zpl_path_error(path: &str) -> (StatusCode, Value) {
+        let result = resolve_zpl_path_context(std::ptr::null_mut(), "tank", path, false, || {
+            panic!("catalog must not load for rejected paths")
+        });
+        let (status, Json(body)) = result.expect_err("path should be rejected");
+        (status, body)
+    }
+
+    #[test]
+    fn zpl_paths_reject_parent_components_and_nul() {
+        for path in ["tank/data/../../other/secret", "/tank/data/../secret", ".."] {
+            let (status, body) = resolve_zpl_path_error(path);
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{path}");
+            assert_eq!(body["code"], "INVALID_PATH", "{path}");
+        }
+
+        let (status, body) = resolve_zpl_path_error("tank/data/fi\0le");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "INVALID_PATH");
+        assert_eq!(body["message"], "path contains a NUL byte");
+
+        let (_, Json(body)) = normalize_objset_zpl_path("dir/../etc").expect_err("rejected");
+        assert_eq!(body["code"], "INVALID_PATH");
+    }
+
+    #[test]
+    fn zpl_paths_normalize_leading_slash_and_dot_segments() {
+        assert_eq!(
+            normalize_client_zpl_path("/tank/data/./file.bin").unwrap(),
+            "tank/data/file.bin"
+        );
+        assert_eq!(
+            normalize_client_zpl_path("tank//data/file.bin").unwrap(),
+            "tank/data/file.bin"
+        );
+        assert_eq!(
+            normalize_objset_zpl_path("/dir/./file.bin").unwrap(),
+            "/dir/file.bin"
+        );
+        assert_eq!(
+            normalize_client_zpl_path("tank/data/..file").unwrap(),
+            "tank/data/..file"
+        );
+    }
+
     #[test]
     fn dataset_and_mountpoint_path_match_handles_prefixes() {
         assert_eq!(
@@ -4581,6 +12829,7 @@ txg birth state ndirty nread nwritten
             mode: crate::PoolOpenMode::Offline,
             offline_search_paths: Some("/fixtures".to_string()),
             offline_pool_names: vec!["tank".to_string()],
+            offline_txg: None,
         });
 
         let Json(payload) = api_version(State(state))
@@ -4597,6 +12846,7 @@ txg birth state ndirty nread nwritten
             mode: crate::PoolOpenMode::Offline,
             offline_search_paths: None,
             offline_pool_names: Vec::new(),
+            offline_txg: None,
         });
 
         let err = pool_dedup_summary(State(state), Path("tank".to_string()))
@@ -4625,6 +12875,29 @@ txg birth state ndirty nread nwritten
         );
     }
 
+    #[test]
+    fn pool_metrics_render_help_type_and_pool_labels() {
+        let sample = concat!(
+            "tank\t1000\t400\t600\t12%\t1.50x\tONLINE\n",
+            "scratch\t-\t-\t-\t-\t1.00x\tDEGRADED\n"
+        );
+        let rows = parse_zpool_metrics_rows(sample);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name, "tank");
+        assert_eq!(rows[0].allocated_bytes, Some(400));
+        assert_eq!(rows[1].size_bytes, None);
+
+        let text = render_pool_metrics(&rows);
+        assert!(text.contains("# HELP zfs_pool_size_bytes "));
+        assert!(text.contains("# TYPE zfs_pool_size_bytes gauge\n"));
+        assert!(text.contains("zfs_pool_size_bytes{pool=\"tank\"} 1000\n"));
+        assert!(!text.contains("zfs_pool_size_bytes{pool=\"scratch\"}"));
+        assert!(text.contains("zfs_pool_frag_ratio{pool=\"tank\"} 0.12\n"));
+        assert!(text.contains("zfs_pool_dedup_ratio{pool=\"tank\"} 1.5\n"));
+        assert!(text.contains("zfs_pool_state{pool=\"scratch\",state=\"DEGRADED\"} 1\n"));
+        assert_eq!(prometheus_label_value("a\"b"), "a\\\"b");
+    }
+
     #[test]
     fn parse_zfs_space_rows_handles_optional_values() {
         let sample = concat!(
@@ -4716,4 +12989,292 @@ refcnt   blocks   LSIZE   PSIZE   DSIZE   blocks   LSIZE   PSIZE   DSIZE
             Some(240 * 1024 * 1024)
         );
     }
+
+    #[test]
+    fn parse_zpool_status_health_counts_vdev_states_and_errors() {
+        let sample = "  pool: tank
+ state: DEGRADED
+status: One or more devices could not be used because the label is missing or
+\tinvalid.  Sufficient replicas exist for the pool to continue
+\tfunctioning in a degraded state.
+action: Replace the device using 'zpool replace'.
+   see: https://openzfs.github.io/openzfs-docs/msg/ZFS-8000-4J
+  scan: scrub repaired 0B in 00:00:01 with 0 errors on Sun Oct 11 00:24:01 2026
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        DEGRADED     0     0     0
+\t  mirror-0  DEGRADED     0     0     0
+\t    sda     ONLINE       0     0     3
+\t    sdb     UNAVAIL      2     1     0  was /dev/sdb1
+\tspares
+\t  sdc       AVAIL
+
+errors: No known data errors
+";
+
+        let health = parse_zpool_status_health(sample);
+        assert_eq!(health["source"], "zpool status");
+        assert_eq!(health["state"], "DEGRADED");
+        assert_eq!(health["vdev_states"]["DEGRADED"], 1);
+        assert_eq!(health["vdev_states"]["ONLINE"], 1);
+        assert_eq!(health["vdev_states"]["UNAVAIL"], 1);
+        assert_eq!(health["vdev_states"]["AVAIL"], 1);
+        assert_eq!(health["read_errors"], 2);
+        assert_eq!(health["write_errors"], 1);
+        assert_eq!(health["cksum_errors"], 3);
+        assert_eq!(
+            health["status"],
+            "One or more devices could not be used because the label is missing or \
+             invalid.  Sufficient replicas exist for the pool to continue \
+             functioning in a degraded state."
+        );
+        assert_eq!(
+            health["action"],
+            "Replace the device using 'zpool replace'."
+        );
+        assert_eq!(health["errors"], "No known data errors");
+    }
+
+    #[test]
+    fn error_export_lines_stop_at_cap_with_truncated_marker() {
+        let page = json!({
+            "entries": [{"objset": 1}, {"objset": 2}, {"objset": 3}],
+            "next": 3,
+        });
+        let mut out = Vec::new();
+        let mut written = 0;
+        assert_eq!(
+            append_error_export_lines(&page, &mut out, &mut written, 10),
+            Some(3)
+        );
+        assert_eq!(written, 3);
+
+        let mut capped = Vec::new();
+        let mut capped_written = 0;
+        assert_eq!(
+            append_error_export_lines(&page, &mut capped, &mut capped_written, 2),
+            None
+        );
+        assert_eq!(
+            String::from_utf8(capped).unwrap(),
+            "{\"objset\":1}\n{\"objset\":2}\n{\"truncated\":true}\n"
+        );
+
+        let last = json!({"entries": [{"objset": 4}], "next": null});
+        let mut tail = Vec::new();
+        assert_eq!(
+            append_error_export_lines(&last, &mut tail, &mut written, 4),
+            None
+        );
+        assert_eq!(String::from_utf8(tail).unwrap(), "{\"objset\":4}\n");
+    }
+
+    #[test]
+    fn invalid_query_error_names_offending_parameter() {
+        let (status, Json(payload)) = invalid_query_error(
+            "Failed to deserialize query string: limit: invalid digit found in string",
+        );
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(payload["code"], "INVALID_QUERY");
+        assert_eq!(payload["recoverable"], true);
+        assert_eq!(payload["parameter"], "limit");
+        assert_eq!(
+            payload["message"],
+            "invalid query parameter 'limit': invalid digit found in string"
+        );
+
+        let (_, Json(missing)) =
+            invalid_query_error("Failed to deserialize query string: missing field `path`");
+        assert_eq!(missing["parameter"], "path");
+
+        let (_, Json(opaque)) = invalid_query_error("Failed to deserialize query string: oops");
+        assert_eq!(opaque["code"], "INVALID_QUERY");
+        assert!(opaque.get("parameter").is_none());
+    }
+
+    #[test]
+    fn object_explanation_prefers_directory_name_over_type() {
+        let full = json!({
+            "object": {
+                "id": 61,
+                "type": {"id": 11, "name": "packed nvlist"},
+                "bonus_type": {"id": 12, "name": "packed nvlist size"},
+                "is_zap": false,
+                "semantic_edges": []
+            },
+            "zap_info": null,
+            "zap_entries": null
+        });
+        let explained = build_object_explanation(61, &full, Some("config"));
+        assert_eq!(explained["role"], "pool_config");
+        assert_eq!(explained["confidence"], 0.95);
+
+        let by_type = build_object_explanation(61, &full, None);
+        assert_eq!(by_type["role"], "packed_nvlist");
+        assert_eq!(by_type["confidence"], 0.7);
+
+        let unknown =
+            build_object_explanation(62, &json!({"object": {"type": {"name": "mystery"}}}), None);
+        assert_eq!(unknown["role"], "unknown");
+        assert_eq!(unknown["outgoing_refs"], json!([]));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn set_mode_change_invalidates_dataset_catalog_cache() {
+        let state = test_state(crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        *state.dataset_catalog.lock().unwrap() = Some(DatasetCatalogCache {
+            pool: "tank".to_string(),
+            loaded_at: Instant::now(),
+            entries: Vec::new(),
+        });
+
+        let _ = set_mode(
+            State(state.clone()),
+            Json(SetModeRequest {
+                mode: "offline".to_string(),
+            }),
+        )
+        .await
+        .expect("same-mode set should succeed");
+        assert!(state.dataset_catalog.lock().unwrap().is_some());
+
+        let _ = set_mode(
+            State(state.clone()),
+            Json(SetModeRequest {
+                mode: "live".to_string(),
+            }),
+        )
+        .await
+        .expect("mode switch should succeed");
+        assert!(state.dataset_catalog.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn inflight_budget_rejects_and_releases_reservations() {
+        let mut state = test_state(crate::PoolOpenConfig {
+            mode: crate::PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        state.inflight = Arc::new(InflightBudget::new(10 * INFLIGHT_BYTES_PER_ITEM));
+
+        let first = reserve_inflight(&state, 6).expect("first reservation fits");
+        let err = match reserve_inflight(&state, 6) {
+            Ok(_) => panic!("second reservation should exceed the budget"),
+            Err(err) => err,
+        };
+        assert_eq!(err.0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(err.1 .0["code"], "MEMORY_PRESSURE");
+
+        drop(first);
+        assert!(reserve_inflight(&state, 6).is_ok());
+        assert_eq!(state.inflight.used.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn dataset_type_filter_keeps_matching_entries() {
+        let catalog = json!([
+            { "name": "tank", "type": "filesystem" },
+            { "name": "tank/vol", "type": "volume" },
+            { "name": "tank@snap", "type": "snapshot" },
+        ]);
+        let filter = parse_dataset_type_filter(Some("volume")).expect("valid filter");
+        let filtered = filter_dataset_catalog(catalog.clone(), filter);
+        assert_eq!(filtered, json!([{ "name": "tank/vol", "type": "volume" }]));
+
+        let all = parse_dataset_type_filter(Some("all")).expect("valid filter");
+        assert_eq!(
+            filter_dataset_catalog(catalog, all)
+                .as_array()
+                .map(Vec::len),
+            Some(3)
+        );
+
+        let err = parse_dataset_type_filter(Some("bookmark")).expect_err("invalid filter");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1 .0["code"], "INVALID_QUERY");
+    }
+
+    #[test]
+    fn vdev_rollup_counts_unhealthy_leaves() {
+        let tree = json!({
+            "type": "root",
+            "children": [
+                {
+                    "type": "mirror",
+                    "children": [
+                        { "type": "disk", "path": "/dev/sda" },
+                        { "type": "disk", "path": "/dev/sdb", "faulted": 1 }
+                    ]
+                },
+                { "type": "disk", "path": "/dev/sdc", "offline": 1 }
+            ]
+        });
+        let rollup = summarize_vdev_tree(&tree);
+        assert_eq!(rollup["total"], 5);
+        assert_eq!(rollup["leaves"], 3);
+        assert_eq!(rollup["faulted"], 1);
+        assert_eq!(rollup["offline"], 1);
+        assert_eq!(rollup["unhealthy_leaves"], 2);
+        assert_eq!(rollup["by_type"]["disk"], 3);
+    }
+
+    #[test]
+    fn txg_time_index_interpolates_between_known_txgs() {
+        let payload = json!({
+            "uberblock": { "txg": 300, "timestamp": 3000 },
+            "anchors": [[100, 1000], [200, 1500], [250, 1400]]
+        });
+        let index = TxgTimeIndex::from_payload("tank", &payload);
+        // The skewed (250, 1400) anchor is dropped.
+        assert_eq!(index.anchors, vec![(100, 1000), (200, 1500), (300, 3000)]);
+        assert_eq!(index.unix_sec_for(200), Some(1500));
+        assert_eq!(index.unix_sec_for(150), Some(1250));
+        assert_eq!(index.unix_sec_for(99), None);
+        assert_eq!(index.unix_sec_for(301), None);
+
+        let mut value = json!({
+            "blkptrs": [{ "birth_txg": 250 }, { "birth_txg": 5 }],
+            "bonus_decoded": { "creation_txg": 150, "creation_time": 1234 },
+            "lineage": [{ "creation_txg": 150 }]
+        });
+        annotate_txg_times(&mut value, &index);
+        assert_eq!(value["blkptrs"][0]["birth_unix_sec"], 2250);
+        assert!(value["blkptrs"][1]["birth_unix_sec"].is_null());
+        assert_eq!(value["bonus_decoded"]["creation_unix_sec"], 1234);
+        assert_eq!(value["lineage"][0]["creation_unix_sec"], 1250);
+    }
+
+    #[test]
+    fn parse_blkptr_hex_accepts_hexdump_spacing_and_rejects_bad_input() {
+        let mut dump = String::from("0x");
+        for row in 0..8 {
+            let line: Vec<String> = (0..16)
+                .map(|col| format!("{:02X}", row * 16 + col))
+                .collect();
+            dump.push_str(&line.join(" "));
+            dump.push('\n');
+        }
+        let bytes = parse_blkptr_hex(&dump).expect("hexdump should parse");
+        assert_eq!(bytes.len(), BLKPTR_SIZE);
+        assert_eq!(bytes[0], 0);
+        assert_eq!(bytes[127], 127);
+
+        let short = parse_blkptr_hex("abcd").expect_err("short input must fail");
+        assert!(short.contains("exactly 128 bytes"));
+        let bad = "zz".repeat(BLKPTR_SIZE);
+        assert!(parse_blkptr_hex(&bad).is_err());
+    }
+
+    #[test]
+    fn dataset_catalog_ttl_is_unbounded_offline() {
+        assert_eq!(dataset_catalog_ttl(crate::PoolOpenMode::Offline), None);
+    }
 }
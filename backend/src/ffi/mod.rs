@@ -6,8 +6,11 @@
 mod bindings;
 
 use std::ffi::{CStr, CString};
+use std::future::Future;
 use std::os::raw::c_char;
-use std::sync::{Mutex, Once};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+use std::time::Instant;
 
 pub use bindings::*;
 
@@ -26,6 +29,84 @@ static FFI_MUTEX: Mutex<()> = Mutex::new(());
 /// Ensure zdx_init() is called exactly once
 static INIT: Once = Once::new();
 
+tokio::task_local! {
+    /// Per-request FFI call counter, installed by `with_ffi_call_counter`.
+    static FFI_CALL_COUNTER: Arc<AtomicU64>;
+}
+
+/// Run `fut` with a fresh FFI call counter in scope.
+pub async fn with_ffi_call_counter<F: Future>(counter: Arc<AtomicU64>, fut: F) -> F::Output {
+    FFI_CALL_COUNTER.scope(counter, fut).await
+}
+
+/// Upper bound on FFI payloads kept per request by `with_ffi_raw_capture`.
+const FFI_RAW_CAPTURE_MAX: usize = 64;
+
+tokio::task_local! {
+    /// Per-request copies of FFI JSON payloads, installed by `with_ffi_raw_capture`.
+    static FFI_RAW_CAPTURE: Arc<Mutex<Vec<String>>>;
+}
+
+/// Run `fut` recording every successful FFI JSON payload into `capture`.
+pub async fn with_ffi_raw_capture<F: Future>(
+    capture: Arc<Mutex<Vec<String>>>,
+    fut: F,
+) -> F::Output {
+    FFI_RAW_CAPTURE.scope(capture, fut).await
+}
+
+/// Per-request phase durations in nanoseconds, for the `Server-Timing` header.
+#[derive(Debug, Default)]
+pub struct PhaseTimings {
+    /// Time spent inside FFI calls, including waits on the FFI mutex.
+    pub ffi_nanos: AtomicU64,
+    /// Time spent parsing FFI JSON payloads.
+    pub parse_nanos: AtomicU64,
+}
+
+tokio::task_local! {
+    /// Per-request phase timings, installed by `with_phase_timings`.
+    static PHASE_TIMINGS: Arc<PhaseTimings>;
+}
+
+/// Run `fut` accumulating FFI and parse durations into `timings`.
+pub async fn with_phase_timings<F: Future>(timings: Arc<PhaseTimings>, fut: F) -> F::Output {
+    PHASE_TIMINGS.scope(timings, fut).await
+}
+
+fn add_nanos(slot: impl FnOnce(&PhaseTimings) -> &AtomicU64, started: Instant) {
+    let nanos = u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    let _ = PHASE_TIMINGS.try_with(|timings| slot(timings).fetch_add(nanos, Ordering::Relaxed));
+}
+
+/// Charge the time since `started` to the current request's parse phase.
+pub fn record_parse_time(started: Instant) {
+    add_nanos(|timings| &timings.parse_nanos, started);
+}
+
+/// Held for the duration of one FFI call; charges its time to the current
+/// request (if any) when dropped.
+struct FfiCallGuard {
+    _lock: MutexGuard<'static, ()>,
+    started: Instant,
+}
+
+impl Drop for FfiCallGuard {
+    fn drop(&mut self) {
+        add_nanos(|timings| &timings.ffi_nanos, self.started);
+    }
+}
+
+/// Take the FFI mutex, counting the call against the current request (if any)
+fn ffi_lock() -> FfiCallGuard {
+    let _ = FFI_CALL_COUNTER.try_with(|counter| counter.fetch_add(1, Ordering::Relaxed));
+    let started = Instant::now();
+    FfiCallGuard {
+        _lock: FFI_MUTEX.lock().unwrap(),
+        started,
+    }
+}
+
 fn errno_hint(code: i32) -> String {
     if code > 0 {
         format!(" ({})", std::io::Error::from_raw_os_error(code))
@@ -57,7 +138,16 @@ pub struct ZdxResult {
 impl ZdxResult {
     /// Create from raw zdx_result_t (takes ownership)
     pub fn from_raw(raw: zdx_result_t) -> Self {
-        ZdxResult { inner: raw }
+        let result = ZdxResult { inner: raw };
+        if let Some(json) = result.json() {
+            let _ = FFI_RAW_CAPTURE.try_with(|capture| {
+                let mut capture = capture.lock().unwrap();
+                if capture.len() < FFI_RAW_CAPTURE_MAX {
+                    capture.push(json.to_string());
+                }
+            });
+        }
+        result
     }
 
     /// Check if result is successful
@@ -94,6 +184,8 @@ impl Drop for ZdxResult {
     }
 }
 
+/// An open native pool. The handle is closed when dropped, so callers that
+/// share it (via `Arc`) keep the pointer valid for as long as they hold it.
 #[derive(Debug)]
 pub struct PoolHandle {
     pub name: String,
@@ -103,42 +195,108 @@ pub struct PoolHandle {
 unsafe impl Send for PoolHandle {}
 unsafe impl Sync for PoolHandle {}
 
+impl Drop for PoolHandle {
+    fn drop(&mut self) {
+        pool_close(self.ptr);
+    }
+}
+
 /// List all pools (behind mutex)
 pub fn list_pools() -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_list_pools() };
     ZdxResult::from_raw(raw)
 }
 
+/// Pool name, GUID, state and txg from a device's labels, without importing it.
+/// Reads only the device, never pool state, so it runs without the FFI mutex.
+pub fn label_pool_name(path: &str) -> Result<ZdxResult, String> {
+    let c_path = CString::new(path).map_err(|_| "path contains NUL".to_string())?;
+    let raw = unsafe { zdx_label_pool_name(c_path.as_ptr()) };
+    Ok(ZdxResult::from_raw(raw))
+}
+
 /// List datasets in an open pool (behind mutex)
 pub fn pool_datasets(pool: *mut zdx_pool_t) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_pool_datasets(pool) };
     ZdxResult::from_raw(raw)
 }
 
 /// Fetch structured pool summary (zdb-like config view)
 pub fn pool_summary(pool: *mut zdx_pool_t) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_pool_summary(pool) };
     ZdxResult::from_raw(raw)
 }
 
-/// Fetch paginated persistent pool error-log entries.
+/// Per-allocation-class (normal/special/dedup/log) space usage
+pub fn pool_alloc_classes(pool: *mut zdx_pool_t) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_pool_alloc_classes(pool) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Pool checkpoint state (checkpointed uberblock and the space it pins)
+pub fn pool_checkpoint(pool: *mut zdx_pool_t) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_pool_checkpoint(pool) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Cache (L2ARC) devices with their persistent L2ARC header state
+pub fn pool_l2arc(pool: *mut zdx_pool_t) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_pool_l2arc(pool) };
+    ZdxResult::from_raw(raw)
+}
+
+/// On-disk format summary: SPA version, active features, ZPL versions, bootfs
+pub fn pool_version_info(pool: *mut zdx_pool_t) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_pool_version_info(pool) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Fetch paginated persistent pool error-log entries; `since_txg` of 0 disables
+/// the birth-txg filter.
 pub fn pool_errors(
     pool: *mut zdx_pool_t,
     cursor: u64,
     limit: u64,
     resolve_paths: bool,
+    since_txg: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
-    let raw = unsafe { zdx_pool_errors(pool, cursor, limit, if resolve_paths { 1 } else { 0 }) };
+    let _lock = ffi_lock();
+    let raw = unsafe {
+        zdx_pool_errors(
+            pool,
+            cursor,
+            limit,
+            if resolve_paths { 1 } else { 0 },
+            since_txg,
+        )
+    };
+    ZdxResult::from_raw(raw)
+}
+
+/// Txg-to-wall-clock anchors from the uberblock and pool history
+pub fn txg_time_anchors(pool: *mut zdx_pool_t, max_records: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_txg_time_anchors(pool, max_records) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Pool history records (commands, internal events), oldest first
+pub fn pool_history(pool: *mut zdx_pool_t, max_records: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_pool_history(pool, max_records) };
     ZdxResult::from_raw(raw)
 }
 
 /// Open a pool (behind mutex)
 pub fn pool_open(name: &str) -> Result<PoolHandle, (i32, String)> {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let c_name = CString::new(name).map_err(|e| (-1, e.to_string()))?;
     let mut err: i32 = 0;
     let ptr = unsafe { zdx_pool_open(c_name.as_ptr(), &mut err) };
@@ -155,12 +313,14 @@ pub fn pool_open(name: &str) -> Result<PoolHandle, (i32, String)> {
 
 /// Open a pool from offline/exported media by scanning provided paths.
 /// `search_paths` is a colon-separated list of directories/devices, or `None`
-/// to use OpenZFS default import search paths.
+/// to use OpenZFS default import search paths. `txg` rewinds to the newest
+/// uberblock at or below it.
 pub fn pool_open_offline(
     name: &str,
     search_paths: Option<&str>,
+    txg: Option<u64>,
 ) -> Result<PoolHandle, (i32, String)> {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let c_name = CString::new(name).map_err(|e| (-1, e.to_string()))?;
     let c_paths = match search_paths {
         Some(v) => Some(CString::new(v).map_err(|e| (-1, e.to_string()))?),
@@ -172,7 +332,8 @@ pub fn pool_open_offline(
         .map(|s| s.as_ptr())
         .unwrap_or(std::ptr::null());
     let mut err: i32 = 0;
-    let ptr = unsafe { zdx_pool_open_offline(c_name.as_ptr(), path_ptr, &mut err) };
+    let ptr =
+        unsafe { zdx_pool_open_offline_txg(c_name.as_ptr(), path_ptr, txg.unwrap_or(0), &mut err) };
     if ptr.is_null() {
         let msg = format!(
             "zdx_pool_open_offline failed with code {}{}",
@@ -189,11 +350,11 @@ pub fn pool_open_offline(
 }
 
 /// Close a pool (behind mutex)
-pub fn pool_close(ptr: *mut zdx_pool_t) {
+fn pool_close(ptr: *mut zdx_pool_t) {
     if ptr.is_null() {
         return;
     }
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     unsafe { zdx_pool_close(ptr) };
 }
 
@@ -204,82 +365,168 @@ pub fn mos_list_objects(
     start: u64,
     limit: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_mos_list_objects(pool, type_filter, start, limit) };
     ZdxResult::from_raw(raw)
 }
 
 /// Get MOS object info
 pub fn mos_get_object(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_mos_get_object(pool, objid) };
     ZdxResult::from_raw(raw)
 }
 
 /// Get MOS object blkptrs
 pub fn mos_get_blkptrs(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_mos_get_blkptrs(pool, objid) };
     ZdxResult::from_raw(raw)
 }
 
-/// Get MOS object block tree
+/// Decode a caller-supplied raw blkptr
+pub fn decode_blkptr(pool: *mut zdx_pool_t, data: &[u8]) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_decode_blkptr(pool, data.as_ptr(), data.len()) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Get MOS object block tree, pruned to blocks born in `[min_txg, max_txg]`
 pub fn mos_block_tree(
     pool: *mut zdx_pool_t,
     objid: u64,
     max_depth: u64,
     max_nodes: u64,
+    min_txg: u64,
+    max_txg: u64,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_mos_block_tree(pool, objid, max_depth, max_nodes, min_txg, max_txg) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Search one batch of objects (MOS when objset_id is 0) for the block
+/// pointer holding a DVA
+#[allow(clippy::too_many_arguments)]
+pub fn dva_owner(
+    pool: *mut zdx_pool_t,
+    objset_id: u64,
+    vdev: u64,
+    offset: u64,
+    asize: u64,
+    start: u64,
+    scan_limit: u64,
+    max_blkptrs: u64,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe {
+        zdx_dva_owner(
+            pool,
+            objset_id,
+            vdev,
+            offset,
+            asize,
+            start,
+            scan_limit,
+            max_blkptrs,
+        )
+    };
+    ZdxResult::from_raw(raw)
+}
+
+/// Decode a MOS object's data as a packed nvlist
+pub fn decode_nvlist(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_decode_nvlist(pool, objid) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Read logical object data from a MOS object
+pub fn mos_read_data(pool: *mut zdx_pool_t, objid: u64, offset: u64, limit: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_mos_read_data(pool, objid, offset, limit) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Space accounting for a bpobj or deadlist object
+pub fn bpobj_stats(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_bpobj_stats(pool, objid) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Page of block pointers stored directly in a bpobj (sub-bpobjs not followed)
+pub fn bpobj_iter(pool: *mut zdx_pool_t, objid: u64, cursor: u64, limit: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_bpobj_iter(pool, objid, cursor, limit) };
+    ZdxResult::from_raw(raw)
+}
+
+/// MOS object array entries
+pub fn obj_array(
+    pool: *mut zdx_pool_t,
+    objid: u64,
+    cursor: u64,
+    limit: u64,
+    with_types: bool,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
-    let raw = unsafe { zdx_mos_block_tree(pool, objid, max_depth, max_nodes) };
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_obj_array(pool, objid, cursor, limit, if with_types { 1 } else { 0 }) };
     ZdxResult::from_raw(raw)
 }
 
 /// Unified object fetch
 pub fn obj_get(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_obj_get(pool, objid) };
     ZdxResult::from_raw(raw)
 }
 
 /// List DMU object types
 pub fn list_dmu_types() -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_list_dmu_types() };
     ZdxResult::from_raw(raw)
 }
 
 /// Get ZAP info
 pub fn zap_info(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_zap_info(pool, objid) };
     ZdxResult::from_raw(raw)
 }
 
 /// Get ZAP entries
 pub fn zap_entries(pool: *mut zdx_pool_t, objid: u64, cursor: u64, limit: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_zap_entries(pool, objid, cursor, limit) };
     ZdxResult::from_raw(raw)
 }
 
 /// DSL dir children
 pub fn dsl_dir_children(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dsl_dir_children(pool, objid) };
     ZdxResult::from_raw(raw)
 }
 
 /// DSL dir head dataset
 pub fn dsl_dir_head(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dsl_dir_head(pool, objid) };
     ZdxResult::from_raw(raw)
 }
 
+/// DSL dir accounting (used, compressed/uncompressed, quota, reservation)
+pub fn dsl_dir_props(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_dsl_dir_props(pool, objid) };
+    ZdxResult::from_raw(raw)
+}
+
 /// DSL root dir discovery
 pub fn dsl_root_dir(pool: *mut zdx_pool_t) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dsl_root_dir(pool) };
     ZdxResult::from_raw(raw)
 }
@@ -287,32 +534,46 @@ pub fn dsl_root_dir(pool: *mut zdx_pool_t) -> ZdxResult {
 /// Resolve a DSL directory by full dataset name
 pub fn dsl_dir_by_name(pool: *mut zdx_pool_t, name: &str) -> Result<ZdxResult, String> {
     let c_name = CString::new(name).map_err(|_| "dataset name contains NUL".to_string())?;
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dsl_dir_by_name_compat(pool, c_name.as_ptr()) };
     Ok(ZdxResult::from_raw(raw))
 }
 
 /// Dataset -> objset mapping
 pub fn dataset_objset(pool: *mut zdx_pool_t, dsobj: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dataset_objset(pool, dsobj) };
     ZdxResult::from_raw(raw)
 }
 
 /// DSL dir -> snapshots list
 pub fn dataset_snapshots(pool: *mut zdx_pool_t, dir_obj: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dataset_snapshots(pool, dir_obj) };
     ZdxResult::from_raw(raw)
 }
 
 /// DSL dir -> snapshot count
 pub fn dataset_snapshot_count(pool: *mut zdx_pool_t, dir_obj: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dataset_snapshot_count(pool, dir_obj) };
     ZdxResult::from_raw(raw)
 }
 
+/// Snapshot dataset -> user holds
+pub fn snapshot_holds(pool: *mut zdx_pool_t, dsobj: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_snapshot_holds(pool, dsobj) };
+    ZdxResult::from_raw(raw)
+}
+
+/// DSL dir -> per-snapshot space accounting, one page at a time
+pub fn snapshot_space(pool: *mut zdx_pool_t, dir_obj: u64, cursor: u64, limit: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_snapshot_space(pool, dir_obj, cursor, limit) };
+    ZdxResult::from_raw(raw)
+}
+
 /// Snapshot lineage chain around a DSL dataset object
 pub fn dataset_lineage(
     pool: *mut zdx_pool_t,
@@ -320,14 +581,42 @@ pub fn dataset_lineage(
     max_prev: u64,
     max_next: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_dataset_lineage(pool, dsobj, max_prev, max_next) };
     ZdxResult::from_raw(raw)
 }
 
+/// Clone origin and clone list for a DSL dir
+pub fn dataset_origin(pool: *mut zdx_pool_t, dir_obj: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_dataset_origin(pool, dir_obj) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Dataset ZIL header and bounded log chain walk
+pub fn dataset_zil(pool: *mut zdx_pool_t, dsobj: u64, max_records: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_dataset_zil(pool, dsobj, max_records) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Per-user or per-group space accounting (userused/groupused + quotas)
+pub fn dataset_userspace(
+    pool: *mut zdx_pool_t,
+    dsobj: u64,
+    group: bool,
+    cursor: u64,
+    limit: u64,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw =
+        unsafe { zdx_dataset_userspace(pool, dsobj, if group { 1 } else { 0 }, cursor, limit) };
+    ZdxResult::from_raw(raw)
+}
+
 /// Objset root lookup
 pub fn objset_root(pool: *mut zdx_pool_t, objset_id: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_root(pool, objset_id) };
     ZdxResult::from_raw(raw)
 }
@@ -339,9 +628,21 @@ pub fn objset_list_objects(
     type_filter: i32,
     start: u64,
     limit: u64,
+    min_size: u64,
+    allocated_only: bool,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
-    let raw = unsafe { zdx_objset_list_objects(pool, objset_id, type_filter, start, limit) };
+    let _lock = ffi_lock();
+    let raw = unsafe {
+        zdx_objset_list_objects(
+            pool,
+            objset_id,
+            type_filter,
+            start,
+            limit,
+            min_size,
+            if allocated_only { 1 } else { 0 },
+        )
+    };
     ZdxResult::from_raw(raw)
 }
 
@@ -353,7 +654,7 @@ pub fn objset_dir_entries(
     cursor: u64,
     limit: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_dir_entries(pool, objset_id, dir_obj, cursor, limit) };
     ZdxResult::from_raw(raw)
 }
@@ -361,48 +662,111 @@ pub fn objset_dir_entries(
 /// Walk a path within a ZPL objset
 pub fn objset_walk(pool: *mut zdx_pool_t, objset_id: u64, path: &str) -> Result<ZdxResult, String> {
     let c_path = CString::new(path).map_err(|_| "path contains NUL".to_string())?;
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_walk(pool, objset_id, c_path.as_ptr()) };
     Ok(ZdxResult::from_raw(raw))
 }
 
 /// Stat a ZPL znode object
 pub fn objset_stat(pool: *mut zdx_pool_t, objset_id: u64, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_stat(pool, objset_id, objid) };
     ZdxResult::from_raw(raw)
 }
 
 /// Objset object metadata (dnode view)
 pub fn objset_get_object(pool: *mut zdx_pool_t, objset_id: u64, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_get_object(pool, objset_id, objid) };
     ZdxResult::from_raw(raw)
 }
 
+/// Objset object raw dnode fields
+pub fn objset_dnode(pool: *mut zdx_pool_t, objset_id: u64, objid: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_objset_dnode(pool, objset_id, objid) };
+    ZdxResult::from_raw(raw)
+}
+
 /// Objset object blkptrs
 pub fn objset_get_blkptrs(pool: *mut zdx_pool_t, objset_id: u64, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_get_blkptrs(pool, objset_id, objid) };
     ZdxResult::from_raw(raw)
 }
 
-/// Objset object block tree
+/// Objset object block tree, pruned to blocks born in `[min_txg, max_txg]`
 pub fn objset_block_tree(
     pool: *mut zdx_pool_t,
     objset_id: u64,
     objid: u64,
     max_depth: u64,
     max_nodes: u64,
+    min_txg: u64,
+    max_txg: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
-    let raw = unsafe { zdx_objset_block_tree(pool, objset_id, objid, max_depth, max_nodes) };
+    let _lock = ffi_lock();
+    let raw = unsafe {
+        zdx_objset_block_tree(
+            pool, objset_id, objid, max_depth, max_nodes, min_txg, max_txg,
+        )
+    };
+    ZdxResult::from_raw(raw)
+}
+
+/// Objset meta-dnode block tree rooted at the objset rootbp, pruned to
+/// blocks born in `[min_txg, max_txg]`
+pub fn objset_rootbp_tree(
+    pool: *mut zdx_pool_t,
+    objset_id: u64,
+    max_depth: u64,
+    max_nodes: u64,
+    min_txg: u64,
+    max_txg: u64,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw =
+        unsafe { zdx_objset_rootbp_tree(pool, objset_id, max_depth, max_nodes, min_txg, max_txg) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Largest plain files in an objset (one bounded batch of an object scan)
+pub fn objset_largest_files(
+    pool: *mut zdx_pool_t,
+    objset_id: u64,
+    start: u64,
+    limit: u64,
+    scan_limit: u64,
+    min_size: u64,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw =
+        unsafe { zdx_objset_largest_files(pool, objset_id, start, limit, scan_limit, min_size) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Per-DMU-type space rollup over a bounded run of objset objects
+pub fn objset_space_by_type(
+    pool: *mut zdx_pool_t,
+    objset_id: u64,
+    start: u64,
+    scan_limit: u64,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_objset_space_by_type(pool, objset_id, start, scan_limit) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Objset SA attribute registry and layouts
+pub fn objset_sa_layouts(pool: *mut zdx_pool_t, objset_id: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_objset_sa_layouts(pool, objset_id) };
     ZdxResult::from_raw(raw)
 }
 
 /// Objset object ZAP metadata
 pub fn objset_zap_info(pool: *mut zdx_pool_t, objset_id: u64, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_zap_info(pool, objset_id, objid) };
     ZdxResult::from_raw(raw)
 }
@@ -415,7 +779,7 @@ pub fn objset_zap_entries(
     cursor: u64,
     limit: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_zap_entries(pool, objset_id, objid, cursor, limit) };
     ZdxResult::from_raw(raw)
 }
@@ -428,14 +792,53 @@ pub fn objset_read_data(
     offset: u64,
     limit: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_objset_read_data(pool, objset_id, objid, offset, limit) };
     ZdxResult::from_raw(raw)
 }
 
+/// Read several `(objid, offset, limit)` slices of one objset while holding
+/// the FFI lock once, so the batch is not interleaved with other requests
+pub fn objset_read_data_batch(
+    pool: *mut zdx_pool_t,
+    objset_id: u64,
+    reads: &[(u64, u64, u64)],
+) -> Vec<ZdxResult> {
+    let _lock = ffi_lock();
+    reads
+        .iter()
+        .map(|&(objid, offset, limit)| {
+            let raw = unsafe { zdx_objset_read_data(pool, objset_id, objid, offset, limit) };
+            ZdxResult::from_raw(raw)
+        })
+        .collect()
+}
+
+/// Paged index of every space map object in the pool
+pub fn pool_spacemaps(pool: *mut zdx_pool_t, cursor: u64, limit: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_pool_spacemaps(pool, cursor, limit) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Raw labels (config nvlist and uberblock ring) of each leaf device under a
+/// top-level vdev
+pub fn vdev_labels(pool: *mut zdx_pool_t, vdev: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_vdev_labels(pool, vdev) };
+    ZdxResult::from_raw(raw)
+}
+
+/// Paged per-metaslab allocation summary for one top-level vdev
+pub fn vdev_metaslabs(pool: *mut zdx_pool_t, vdev: u64, cursor: u64, limit: u64) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_vdev_metaslabs(pool, vdev, cursor, limit) };
+    ZdxResult::from_raw(raw)
+}
+
 /// Space-map summary for a specific MOS space-map object
 pub fn spacemap_summary(pool: *mut zdx_pool_t, objid: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe { zdx_spacemap_summary(pool, objid) };
     ZdxResult::from_raw(raw)
 }
@@ -451,7 +854,7 @@ pub fn spacemap_ranges(
     txg_min: u64,
     txg_max: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe {
         zdx_spacemap_ranges(
             pool, objid, cursor, limit, op_filter, min_length, txg_min, txg_max,
@@ -472,7 +875,7 @@ pub fn spacemap_bins(
     txg_min: u64,
     txg_max: u64,
 ) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let raw = unsafe {
         zdx_spacemap_bins(
             pool, objid, bin_size, cursor, limit, op_filter, min_length, txg_min, txg_max,
@@ -481,16 +884,22 @@ pub fn spacemap_bins(
     ZdxResult::from_raw(raw)
 }
 
-/// Read raw block by vdev + offset
-pub fn read_block(pool: *mut zdx_pool_t, vdev: u64, offset: u64, size: u64) -> ZdxResult {
-    let _lock = FFI_MUTEX.lock().unwrap();
-    let raw = unsafe { zdx_read_block(pool, vdev, offset, size) };
+/// Read raw block by vdev + offset, optionally following a gang header
+pub fn read_block(
+    pool: *mut zdx_pool_t,
+    vdev: u64,
+    offset: u64,
+    size: u64,
+    follow_gang: bool,
+) -> ZdxResult {
+    let _lock = ffi_lock();
+    let raw = unsafe { zdx_read_block(pool, vdev, offset, size, if follow_gang { 1 } else { 0 }) };
     ZdxResult::from_raw(raw)
 }
 
 /// Get version string
 pub fn version() -> &'static str {
-    let _lock = FFI_MUTEX.lock().unwrap();
+    let _lock = ffi_lock();
     let cstr = unsafe { CStr::from_ptr(zdx_version()) };
     cstr.to_str().unwrap_or("unknown")
 }
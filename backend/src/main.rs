@@ -1,12 +1,27 @@
 mod api;
 mod ffi;
 
-use axum::{routing::get, Router};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, DefaultBodyLimit, State},
+    http::{HeaderMap, HeaderValue, Request, Response},
+    middleware::{self, Next},
+    routing::{delete, get, post},
+    Router,
+};
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use std::hash::{BuildHasher, Hasher};
 use std::net::SocketAddr;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tower_http::add_extension::AddExtension;
 use tower_http::cors::CorsLayer;
-use tracing_subscriber;
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::Instrument;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum PoolOpenMode {
@@ -19,14 +34,39 @@ pub struct PoolOpenConfig {
     pub mode: PoolOpenMode,
     pub offline_search_paths: Option<String>,
     pub offline_pool_names: Vec<String>,
+    /// Uberblock txg to rewind to when opening offline pools.
+    pub offline_txg: Option<u64>,
 }
 
 #[derive(Clone)]
 pub struct AppState {
-    pub pool: Arc<Mutex<Option<ffi::PoolHandle>>>,
+    pub pool: Arc<Mutex<Option<Arc<ffi::PoolHandle>>>>,
     pub pool_open: Arc<Mutex<PoolOpenConfig>>,
+    pub dataset_catalog: Arc<Mutex<Option<api::DatasetCatalogCache>>>,
+    pub txg_time_index: Arc<Mutex<Option<Arc<api::TxgTimeIndex>>>>,
+    pub pool_timeline: Arc<Mutex<Option<Arc<api::PoolTimeline>>>>,
+    pub known_names: Arc<Mutex<Option<Arc<api::KnownNameIndex>>>>,
+    pub inflight: Arc<api::InflightBudget>,
+    pub max_body_bytes: usize,
+    /// Server ceiling on a snapshot lineage `max_entries`
+    /// (`ZFS_EXPLORER_MAX_LINEAGE_ENTRIES`).
+    pub max_lineage_entries: u64,
+    pub session_marks: Arc<Mutex<Vec<api::SessionMark>>>,
+    /// Set once startup initialization (`ffi::init`) has finished; until
+    /// then every route except `/api/ready` answers 503 `NOT_READY`.
+    pub ready: Arc<AtomicBool>,
+    /// Honor `X-Forwarded-For/-Proto/-Host` from a reverse proxy
+    /// (`ZFS_EXPLORER_TRUST_PROXY=1`); ignored otherwise.
+    pub trust_proxy: bool,
+    /// Offline-mode response cache (`ZFS_EXPLORER_RESPONSE_CACHE` entries).
+    pub response_cache: Arc<api::ResponseCache>,
 }
 
+const DEFAULT_MAX_INFLIGHT_BYTES: u64 = 256 * 1024 * 1024;
+const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+const DEFAULT_HTTP2_MAX_STREAMS: u32 = 256;
+const DEFAULT_KEEPALIVE_SECS: u64 = 75;
+const HTTP2_KEEPALIVE_ACK_TIMEOUT: Duration = Duration::from_secs(20);
 const REPO_URL: &str = "https://github.com/mminkus/zfs-explorer";
 const ZFS_SPA_VERSION: u64 = 5000;
 const ZFS_ZPL_VERSION: u64 = 5;
@@ -50,6 +90,251 @@ fn parse_pool_open_mode() -> Result<PoolOpenMode, String> {
     }
 }
 
+fn parse_max_inflight_bytes() -> Result<u64, String> {
+    let Ok(raw) = std::env::var("ZFS_EXPLORER_MAX_INFLIGHT_BYTES") else {
+        return Ok(DEFAULT_MAX_INFLIGHT_BYTES);
+    };
+    match raw.trim().parse::<u64>() {
+        Ok(0) => Ok(u64::MAX),
+        Ok(bytes) => Ok(bytes),
+        Err(_) => Err(format!(
+            "invalid ZFS_EXPLORER_MAX_INFLIGHT_BYTES '{}'; expected a byte count (0 disables)",
+            raw
+        )),
+    }
+}
+
+fn parse_response_cache_entries() -> Result<usize, String> {
+    let Ok(raw) = std::env::var("ZFS_EXPLORER_RESPONSE_CACHE") else {
+        return Ok(0);
+    };
+    raw.trim().parse::<usize>().map_err(|_| {
+        format!(
+            "invalid ZFS_EXPLORER_RESPONSE_CACHE '{}'; expected an entry count (0 disables)",
+            raw
+        )
+    })
+}
+
+fn parse_max_body_bytes() -> Result<usize, String> {
+    let Ok(raw) = std::env::var("ZFS_EXPLORER_MAX_BODY_BYTES") else {
+        return Ok(DEFAULT_MAX_BODY_BYTES);
+    };
+    match raw.trim().parse::<usize>() {
+        Ok(bytes) if bytes > 0 => Ok(bytes),
+        _ => Err(format!(
+            "invalid ZFS_EXPLORER_MAX_BODY_BYTES '{}'; expected a positive byte count",
+            raw
+        )),
+    }
+}
+
+fn parse_max_lineage_entries() -> Result<u64, String> {
+    let Ok(raw) = std::env::var("ZFS_EXPLORER_MAX_LINEAGE_ENTRIES") else {
+        return Ok(api::LINEAGE_MAX_ENTRIES);
+    };
+    match raw.trim().parse::<u64>() {
+        Ok(entries) if (2..=api::LINEAGE_MAX_ENTRIES).contains(&entries) => Ok(entries),
+        _ => Err(format!(
+            "invalid ZFS_EXPLORER_MAX_LINEAGE_ENTRIES '{}'; expected an entry count from 2 to {}",
+            raw,
+            api::LINEAGE_MAX_ENTRIES
+        )),
+    }
+}
+
+fn parse_offline_txg() -> Result<Option<u64>, String> {
+    let Ok(raw) = std::env::var("ZFS_EXPLORER_OFFLINE_TXG") else {
+        return Ok(None);
+    };
+    if raw.trim().is_empty() {
+        return Ok(None);
+    }
+    match raw.trim().parse::<u64>() {
+        Ok(txg) if txg > 0 => Ok(Some(txg)),
+        _ => Err(format!(
+            "invalid ZFS_EXPLORER_OFFLINE_TXG '{}'; expected a positive txg",
+            raw
+        )),
+    }
+}
+
+/// Connection tuning for the HTTP/1.1 + h2c listener.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct HttpServerConfig {
+    http2_max_streams: u32,
+    /// Idle keep-alive window; `None` closes HTTP/1.1 connections after each
+    /// response and disables HTTP/2 pings. For HTTP/1.1 this is hyper's
+    /// header-read timeout, which starts once the previous response is done
+    /// and runs until the next request's headers are in: one deadline covers
+    /// both the idle gap and a slow request head.
+    keepalive: Option<Duration>,
+}
+
+fn parse_http_server_config() -> Result<HttpServerConfig, String> {
+    let http2_max_streams = match std::env::var("ZFS_EXPLORER_HTTP2_MAX_STREAMS") {
+        Err(_) => DEFAULT_HTTP2_MAX_STREAMS,
+        Ok(raw) => match raw.trim().parse::<u32>() {
+            Ok(streams) if streams > 0 => streams,
+            _ => {
+                return Err(format!(
+                    "invalid ZFS_EXPLORER_HTTP2_MAX_STREAMS '{}'; expected a positive stream count",
+                    raw
+                ))
+            }
+        },
+    };
+    let keepalive_secs = match std::env::var("ZFS_EXPLORER_KEEPALIVE_SECS") {
+        Err(_) => DEFAULT_KEEPALIVE_SECS,
+        Ok(raw) => raw.trim().parse::<u64>().map_err(|_| {
+            format!(
+                "invalid ZFS_EXPLORER_KEEPALIVE_SECS '{}'; expected seconds (0 disables keep-alive)",
+                raw
+            )
+        })?,
+    };
+    Ok(HttpServerConfig {
+        http2_max_streams,
+        keepalive: (keepalive_secs > 0).then(|| Duration::from_secs(keepalive_secs)),
+    })
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum OfflineSelftest {
+    Off,
+    Report,
+    Strict,
+}
+
+fn parse_offline_selftest() -> Result<OfflineSelftest, String> {
+    let raw = std::env::var("ZFS_EXPLORER_OFFLINE_SELFTEST").unwrap_or_default();
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "false" | "off" => Ok(OfflineSelftest::Off),
+        "1" | "true" | "on" => Ok(OfflineSelftest::Report),
+        "strict" => Ok(OfflineSelftest::Strict),
+        other => Err(format!(
+            "invalid ZFS_EXPLORER_OFFLINE_SELFTEST '{}'; expected '0', '1', or 'strict'",
+            other
+        )),
+    }
+}
+
+fn parse_trust_proxy() -> Result<bool, String> {
+    let raw = std::env::var("ZFS_EXPLORER_TRUST_PROXY").unwrap_or_default();
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "false" | "off" => Ok(false),
+        "1" | "true" | "on" => Ok(true),
+        other => Err(format!(
+            "invalid ZFS_EXPLORER_TRUST_PROXY '{}'; expected '0' or '1'",
+            other
+        )),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+fn parse_log_format() -> Result<LogFormat, String> {
+    let raw = std::env::var("ZFS_EXPLORER_LOG_FORMAT").unwrap_or_else(|_| "text".to_string());
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" | "text" => Ok(LogFormat::Text),
+        "json" => Ok(LogFormat::Json),
+        other => Err(format!(
+            "invalid ZFS_EXPLORER_LOG_FORMAT '{}'; expected 'text' or 'json'",
+            other
+        )),
+    }
+}
+
+/// Shared libraries the backend links against dynamically (see build.rs).
+const BUNDLED_LIBRARIES: [&str; 4] = ["zdbdecode", "zpool", "zfs", "nvpair"];
+
+#[cfg(target_os = "macos")]
+const SHARED_LIBRARY_SUFFIX: &str = "dylib";
+#[cfg(not(target_os = "macos"))]
+const SHARED_LIBRARY_SUFFIX: &str = "so";
+
+/// Return the expected file names of bundled libraries absent from `dir`.
+/// Versioned names such as `libzfs.so.6` count as present.
+fn missing_bundled_libraries(dir: &std::path::Path) -> Vec<String> {
+    let present: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    BUNDLED_LIBRARIES
+        .iter()
+        .map(|name| format!("lib{}.{}", name, SHARED_LIBRARY_SUFFIX))
+        .filter(|expected| {
+            let versioned = format!("{}.", expected);
+            !present
+                .iter()
+                .any(|file| file == expected || file.starts_with(&versioned))
+        })
+        .collect()
+}
+
+/// Startup check behind `run-backend.sh`, which exports the directory it
+/// validated. The loader has already resolved the libraries by the time this
+/// runs, so it only catches a directory that is incomplete while the loader
+/// found the libraries elsewhere; missing libraries never reach `main`.
+fn check_bundled_library_dir() -> Result<(), String> {
+    let Some(dir) = std::env::var("ZFS_EXPLORER_LIB_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(());
+    };
+
+    let path = std::path::Path::new(&dir);
+    if !path.is_dir() {
+        return Err(format!(
+            "ZFS_EXPLORER_LIB_DIR '{}' is not a directory; point it at the directory containing libzdbdecode and the OpenZFS libraries",
+            dir
+        ));
+    }
+
+    let missing = missing_bundled_libraries(path);
+    if missing.is_empty() {
+        tracing::info!("Bundled libraries found in {}", dir);
+        return Ok(());
+    }
+
+    for file in &missing {
+        tracing::error!(
+            "Missing bundled library {} in ZFS_EXPLORER_LIB_DIR {}",
+            file,
+            dir
+        );
+    }
+    Err(format!(
+        "ZFS_EXPLORER_LIB_DIR '{}' is missing {}; copy them there (run `build/build.sh`) or unset ZFS_EXPLORER_LIB_DIR and use LD_LIBRARY_PATH",
+        dir,
+        missing.join(", ")
+    ))
+}
+
+fn init_tracing(format: LogFormat) {
+    // INFO level by default; RUST_LOG overrides.
+    let builder = tracing_subscriber::fmt().with_env_filter(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+    );
+
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_target(true).init(),
+    }
+}
+
 fn parse_offline_pool_names() -> Vec<String> {
     std::env::var("ZFS_EXPLORER_OFFLINE_POOLS")
         .ok()
@@ -157,10 +442,150 @@ fn check_runtime_privileges(_mode: PoolOpenMode) -> Result<(), String> {
     Ok(())
 }
 
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const REQUEST_ID_MAX_LEN: usize = 128;
+
+/// Random UUIDv4-formatted request ID. Uniqueness, not unpredictability, is
+/// what matters here, so std's randomly keyed hasher is enough.
+fn generate_request_id() -> String {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let seq = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+
+    let keys = std::collections::hash_map::RandomState::new();
+    let mut hasher = keys.build_hasher();
+    hasher.write_u64(seq);
+    hasher.write_u64(nanos);
+    let hi = hasher.finish();
+    hasher.write_u64(hi);
+    let lo = hasher.finish();
+
+    let mut bits = (u128::from(hi) << 64) | u128::from(lo);
+    bits = (bits & !(0xF << 76)) | (0x4 << 76); // version 4
+    bits = (bits & !(0x3 << 62)) | (0x2 << 62); // RFC 4122 variant
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        (bits >> 96) as u32,
+        (bits >> 80) as u16,
+        (bits >> 64) as u16,
+        (bits >> 48) as u16,
+        bits & 0xFFFF_FFFF_FFFF
+    )
+}
+
+fn inbound_request_id(request: &Request<Body>) -> Option<String> {
+    let raw = request
+        .headers()
+        .get(REQUEST_ID_HEADER)?
+        .to_str()
+        .ok()?
+        .trim();
+    let valid = !raw.is_empty()
+        && raw.len() <= REQUEST_ID_MAX_LEN
+        && raw.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| raw.to_string())
+}
+
+const FORWARDED_HEADER_MAX_LEN: usize = 256;
+
+/// Where a request came from, as logged: the TCP peer and `Host` header, or
+/// the original client, scheme and host reported by a trusted proxy.
+#[derive(Debug, Eq, PartialEq)]
+struct RequestOrigin {
+    client: String,
+    scheme: String,
+    host: String,
+}
+
+/// Right-most comma-separated value of a header (across repeated headers),
+/// when it is short printable ASCII. For `X-Forwarded-For` that is the
+/// address the trusted proxy itself saw; entries to its left came from the
+/// client and can be forged.
+fn last_header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    let raw = headers.get_all(name).iter().next_back()?.to_str().ok()?;
+    let last = raw.rsplit(',').next()?.trim();
+    let valid = !last.is_empty()
+        && last.len() <= FORWARDED_HEADER_MAX_LEN
+        && last.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| last.to_string())
+}
+
+fn request_origin(
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+    trust_proxy: bool,
+) -> RequestOrigin {
+    let forwarded = |name| {
+        trust_proxy
+            .then(|| last_header_value(headers, name))
+            .flatten()
+    };
+    let client = forwarded("x-forwarded-for")
+        .or_else(|| peer.map(|peer| peer.ip().to_string()))
+        .unwrap_or_else(|| "-".to_string());
+    let scheme = forwarded("x-forwarded-proto")
+        .map(|proto| proto.to_ascii_lowercase())
+        .filter(|proto| proto == "http" || proto == "https")
+        .unwrap_or_else(|| "http".to_string());
+    let host = forwarded("x-forwarded-host")
+        .or_else(|| last_header_value(headers, "host"))
+        .unwrap_or_else(|| "-".to_string());
+    RequestOrigin {
+        client,
+        scheme,
+        host,
+    }
+}
+
+/// Tag each request with an ID (the client's `X-Request-Id` when usable), echo
+/// it in the response header and error envelopes, and scope log lines to it.
+async fn request_id_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response<Body> {
+    let request_id = inbound_request_id(&request).unwrap_or_else(generate_request_id);
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(peer)| *peer);
+    let origin = request_origin(request.headers(), peer, state.trust_proxy);
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+        client = %origin.client,
+        scheme = %origin.scheme,
+        host = %origin.host
+    );
+
+    let mut response = api::with_request_id(request_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
 fn build_router(state: AppState) -> Router {
+    let max_body_bytes = state.max_body_bytes;
+    let request_state = state.clone();
     Router::new()
+        .route("/api/ready", get(api::api_ready))
         .route("/api/version", get(api::api_version))
         .route("/api/mode", get(api::get_mode).put(api::set_mode))
+        .route(
+            "/api/session/marks",
+            get(api::list_session_marks).post(api::create_session_mark),
+        )
+        .route("/api/session/marks/{id}", delete(api::delete_session_mark))
+        .route("/metrics", get(api::metrics))
+        .route("/api/offline/scan", get(api::offline_scan))
         .route("/api/perf/arc", get(api::perf_arc))
         .route("/api/perf/vdev_iostat", get(api::perf_vdev_iostat))
         .route("/api/perf/txg", get(api::perf_txg))
@@ -171,11 +596,53 @@ fn build_router(state: AppState) -> Router {
         )
         .route("/api/pools", get(api::list_pools))
         .route("/api/pools/{pool}/summary", get(api::pool_summary))
+        .route("/api/pools/{pool}/refresh", post(api::refresh_pool))
+        .route(
+            "/api/pools/{pool}/alloc-classes",
+            get(api::pool_alloc_classes),
+        )
+        .route("/api/pools/{pool}/checkpoint", get(api::pool_checkpoint))
+        .route("/api/pools/{pool}/l2arc", get(api::pool_l2arc))
+        .route(
+            "/api/pools/{pool}/version-info",
+            get(api::pool_version_info),
+        )
+        .route("/api/pools/{pool}/overview", get(api::pool_overview))
+        .route("/api/pools/{pool}/timeline", get(api::pool_timeline))
+        .route(
+            "/api/pools/{pool}/features/active",
+            get(api::pool_active_features),
+        )
         .route("/api/pools/{pool}/errors", get(api::pool_errors))
+        .route(
+            "/api/pools/{pool}/errors/export",
+            get(api::pool_errors_export),
+        )
+        .route(
+            "/api/pools/{pool}/export/metadata.json",
+            get(api::pool_metadata_export),
+        )
         .route("/api/pools/{pool}/datasets", get(api::list_pool_datasets))
         .route("/api/pools/{pool}/mos/objects", get(api::mos_list_objects))
+        .route("/api/pools/{pool}/mos/directory", get(api::mos_directory))
         .route("/api/pools/{pool}/obj/{objid}", get(api::mos_get_object))
         .route("/api/pools/{pool}/obj/{objid}/full", get(api::obj_get_full))
+        .route(
+            "/api/pools/{pool}/obj/{objid}/nvlist",
+            get(api::mos_decode_nvlist),
+        )
+        .route(
+            "/api/pools/{pool}/obj/{objid}/data",
+            get(api::mos_read_data),
+        )
+        .route(
+            "/api/pools/{pool}/obj/{objid}/array",
+            get(api::mos_obj_array),
+        )
+        .route(
+            "/api/pools/{pool}/obj/{objid}/explain",
+            get(api::obj_explain),
+        )
         .route(
             "/api/pools/{pool}/obj/{objid}/block-tree",
             get(api::mos_block_tree),
@@ -184,6 +651,12 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/obj/{objid}/blkptrs",
             get(api::mos_get_blkptrs),
         )
+        .route("/api/pools/{pool}/blkptr/decode", post(api::decode_blkptr))
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/read-batch",
+            post(api::objset_read_batch),
+        )
+        .route("/api/pools/{pool}/dva/owner", get(api::dva_owner))
         .route("/api/pools/{pool}/obj/{objid}/zap/info", get(api::zap_info))
         .route("/api/pools/{pool}/obj/{objid}/zap", get(api::zap_entries))
         .route(
@@ -194,8 +667,16 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/dsl/dir/{objid}/head",
             get(api::dsl_dir_head),
         )
+        .route(
+            "/api/pools/{pool}/dsl/dir/{objid}/props",
+            get(api::dsl_dir_props),
+        )
         .route("/api/pools/{pool}/dsl/root", get(api::dsl_root_dir))
         .route("/api/pools/{pool}/datasets/tree", get(api::dataset_tree))
+        .route(
+            "/api/pools/{pool}/dataset/{objid}/recursive-usage",
+            get(api::dataset_recursive_usage),
+        )
         .route(
             "/api/pools/{pool}/dataset/{objid}/head",
             get(api::dataset_head),
@@ -212,6 +693,26 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/dataset/{objid}/snapshot-count",
             get(api::dataset_snapshot_count),
         )
+        .route(
+            "/api/pools/{pool}/dataset/{objid}/snapshot-space",
+            get(api::dataset_snapshot_space),
+        )
+        .route(
+            "/api/pools/{pool}/dataset/{objid}/file-history",
+            get(api::dataset_file_history),
+        )
+        .route(
+            "/api/pools/{pool}/dataset/{objid}/origin",
+            get(api::dataset_origin),
+        )
+        .route(
+            "/api/pools/{pool}/dataset/{objid}/zil",
+            get(api::dataset_zil),
+        )
+        .route(
+            "/api/pools/{pool}/dataset/{objid}/userspace",
+            get(api::dataset_userspace),
+        )
         .route(
             "/api/pools/{pool}/snapshot/{dsobj}/objset",
             get(api::snapshot_objset),
@@ -220,6 +721,10 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/snapshot/{dsobj}/lineage",
             get(api::snapshot_lineage),
         )
+        .route(
+            "/api/pools/{pool}/snapshot/{dsobj}/holds",
+            get(api::snapshot_holds),
+        )
         .route(
             "/api/pools/{pool}/objset/{objset_id}/root",
             get(api::objset_root),
@@ -228,14 +733,38 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/objset/{objset_id}/objects",
             get(api::objset_list_objects),
         )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/objects/stream",
+            get(api::objset_objects_stream),
+        )
         .route(
             "/api/pools/{pool}/objset/{objset_id}/dir/{dir_obj}/entries",
             get(api::objset_dir_entries),
         )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/dir/{dir_obj}/export.tar",
+            get(api::objset_dir_export_tar),
+        )
         .route(
             "/api/pools/{pool}/objset/{objset_id}/walk",
             get(api::objset_walk),
         )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/sa",
+            get(api::objset_sa_layouts),
+        )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/largest",
+            get(api::objset_largest_files),
+        )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/space-by-type",
+            get(api::objset_space_by_type),
+        )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/rootbp/tree",
+            get(api::objset_rootbp_tree),
+        )
         .route(
             "/api/pools/{pool}/objset/{objset_id}/stat/{objid}",
             get(api::objset_stat),
@@ -244,6 +773,14 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/objset/{objset_id}/obj/{objid}",
             get(api::objset_get_object),
         )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/obj/{objid}/download",
+            get(api::objset_object_download),
+        )
+        .route(
+            "/api/pools/{pool}/objset/{objset_id}/obj/{objid}/dnode",
+            get(api::objset_dnode),
+        )
         .route(
             "/api/pools/{pool}/objset/{objset_id}/obj/{objid}/blkptrs",
             get(api::objset_get_blkptrs),
@@ -280,6 +817,24 @@ fn build_router(state: AppState) -> Router {
             "/api/pools/{pool}/snapshot/{dsobj}/zpl/path/{*zpl_path}",
             get(api::snapshot_zpl_path_download),
         )
+        .route(
+            "/api/pools/{pool}/zvol/{dir_obj}/data",
+            get(api::zvol_read_data),
+        )
+        .route("/api/pools/{pool}/spacemaps", get(api::pool_spacemaps))
+        .route(
+            "/api/pools/{pool}/vdev/{vdev}/metaslabs",
+            get(api::vdev_metaslabs),
+        )
+        .route(
+            "/api/pools/{pool}/vdev/{vdev}/labels",
+            get(api::vdev_labels),
+        )
+        .route("/api/pools/{pool}/deadlists", get(api::pool_deadlists))
+        .route(
+            "/api/pools/{pool}/bpobj/{objid}/blkptrs",
+            get(api::bpobj_blkptrs),
+        )
         .route(
             "/api/pools/{pool}/spacemap/{objid}/summary",
             get(api::spacemap_summary),
@@ -295,27 +850,99 @@ fn build_router(state: AppState) -> Router {
         .route("/api/pools/{pool}/block", get(api::read_block))
         .route("/api/pools/{pool}/graph/from/{objid}", get(api::graph_from))
         .route("/api/mos/types", get(api::list_dmu_types))
+        .layer(middleware::from_fn(api::generated_at_middleware))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::response_cache_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::cache_control_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::readiness_middleware,
+        ))
         .with_state(state)
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(max_body_bytes))
+        .layer(middleware::from_fn(api::payload_too_large_middleware))
+        .layer(middleware::from_fn(api::ffi_debug_middleware))
+        .layer(middleware::from_fn(api::ffi_raw_middleware))
         .layer(CorsLayer::permissive())
+        .layer(middleware::from_fn_with_state(
+            request_state,
+            request_id_middleware,
+        ))
+}
+
+/// Serve `app` on `listener`, negotiating HTTP/1.1 or cleartext HTTP/2
+/// (prior knowledge) per connection. Browsers only speak HTTP/2 over TLS, so a
+/// UI loaded straight from this listener stays on HTTP/1.1; h2c serves API
+/// clients and reverse proxies that forward h2c upstream.
+async fn serve(listener: tokio::net::TcpListener, app: Router, config: HttpServerConfig) {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .keep_alive(config.keepalive.is_some());
+    builder
+        .http2()
+        .timer(TokioTimer::new())
+        .max_concurrent_streams(config.http2_max_streams);
+    if let Some(idle) = config.keepalive {
+        builder.http1().header_read_timeout(idle);
+        builder
+            .http2()
+            .keep_alive_interval(idle)
+            .keep_alive_timeout(HTTP2_KEEPALIVE_ACK_TIMEOUT);
+    }
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                // Typically EMFILE; back off instead of spinning.
+                tracing::warn!("accept failed: {}", err);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let service = TowerToHyperService::new(AddExtension::new(app.clone(), ConnectInfo(peer)));
+        let builder = builder.clone();
+        tokio::spawn(async move {
+            if let Err(err) = builder
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("connection from {} ended: {}", peer, err);
+            }
+        });
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing with INFO level by default
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
+    // Initialize tracing before any startup logging so every line uses the
+    // selected format.
+    init_tracing(parse_log_format()?);
 
     let mode = parse_pool_open_mode()?;
+    let max_inflight_bytes = parse_max_inflight_bytes()?;
+    let max_body_bytes = parse_max_body_bytes()?;
+    let max_lineage_entries = parse_max_lineage_entries()?;
+    let http_config = parse_http_server_config()?;
     let offline_search_paths = std::env::var("ZFS_EXPLORER_OFFLINE_PATHS")
         .ok()
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty());
     let offline_pool_names = parse_offline_pool_names();
+    let offline_txg = parse_offline_txg()?;
+    let offline_selftest = parse_offline_selftest()?;
+    let trust_proxy = parse_trust_proxy()?;
+    let response_cache_entries = parse_response_cache_entries()?;
     check_runtime_privileges(mode)?;
+    check_bundled_library_dir()?;
 
     let (kernel_module_version, kernel_module_source) = detect_kernel_module_version();
     tracing::info!(
@@ -326,8 +953,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("{}", REPO_URL);
 
     // Initialize ZFS
+    let ready = Arc::new(AtomicBool::new(false));
     tracing::info!("Initializing ZFS library...");
     ffi::init()?;
+    ready.store(true, Ordering::Release);
 
     match kernel_module_version {
         Some(version) => tracing::info!(
@@ -351,6 +980,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if offline_search_paths.is_some() {
                 tracing::warn!("ZFS_EXPLORER_OFFLINE_PATHS is set but ignored in live mode");
             }
+            if offline_selftest != OfflineSelftest::Off {
+                tracing::warn!("ZFS_EXPLORER_OFFLINE_SELFTEST is set but ignored in live mode");
+            }
+            if offline_txg.is_some() {
+                tracing::warn!("ZFS_EXPLORER_OFFLINE_TXG is set but ignored in live mode");
+            }
         }
         PoolOpenMode::Offline => {
             tracing::info!("Pool open mode: offline (exported pools)");
@@ -361,11 +996,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             if offline_pool_names.is_empty() {
                 tracing::warn!(
-                    "ZFS_EXPLORER_OFFLINE_POOLS is empty; /api/pools will only show imported pools"
+                    "ZFS_EXPLORER_OFFLINE_POOLS is empty; /api/pools will list no pools"
                 );
             } else {
                 tracing::info!("Offline pool names: {}", offline_pool_names.join(", "));
             }
+            if let Some(txg) = offline_txg {
+                tracing::warn!("Offline pools will be rewound to txg {} or earlier", txg);
+            }
+            if offline_selftest != OfflineSelftest::Off {
+                let opened = api::offline_selftest(
+                    &offline_pool_names,
+                    offline_search_paths.as_deref(),
+                    offline_txg,
+                );
+                tracing::info!(
+                    "Offline self-test: {}/{} configured pools opened",
+                    opened,
+                    offline_pool_names.len()
+                );
+                if opened == 0 && offline_selftest == OfflineSelftest::Strict {
+                    return Err(
+                        "offline self-test failed: no configured pool could be opened".into(),
+                    );
+                }
+            }
         }
     }
 
@@ -375,7 +1030,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             mode,
             offline_search_paths,
             offline_pool_names,
+            offline_txg,
         })),
+        dataset_catalog: Arc::new(Mutex::new(None)),
+        txg_time_index: Arc::new(Mutex::new(None)),
+        pool_timeline: Arc::new(Mutex::new(None)),
+        known_names: Arc::new(Mutex::new(None)),
+        inflight: Arc::new(api::InflightBudget::new(max_inflight_bytes)),
+        max_body_bytes,
+        max_lineage_entries,
+        session_marks: Arc::new(Mutex::new(Vec::new())),
+        ready,
+        trust_proxy,
+        response_cache: Arc::new(api::ResponseCache::new(response_cache_entries)),
     };
 
     // Build the router
@@ -383,11 +1050,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Bind to localhost only (per security model in plan)
     let addr = SocketAddr::from(([127, 0, 0, 1], 9000));
-    tracing::info!("ZDX API server listening on {}", addr);
+    tracing::info!(
+        "ZDX API server listening on {} (HTTP/1.1 and h2c, {} streams/connection, keep-alive {})",
+        addr,
+        http_config.http2_max_streams,
+        http_config
+            .keepalive
+            .map(|idle| format!("{}s", idle.as_secs()))
+            .unwrap_or_else(|| "off".to_string())
+    );
 
     // Start the server
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    serve(listener, app, http_config).await;
 
     Ok(())
 }
@@ -395,15 +1070,51 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::body::{to_bytes, Body};
-    use axum::http::{Method, Request, StatusCode};
+    use axum::body::to_bytes;
+    use axum::http::{Method, StatusCode};
     use serde_json::{json, Value};
     use tower::util::ServiceExt;
 
+    #[test]
+    fn missing_bundled_libraries_accepts_versioned_names() {
+        let dir = std::env::temp_dir().join(format!(
+            "zdx-libdir-test-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in [
+            format!("libzdbdecode.{}", SHARED_LIBRARY_SUFFIX),
+            format!("libzfs.{}.6", SHARED_LIBRARY_SUFFIX),
+            format!("libnvpair.{}.3.0.0", SHARED_LIBRARY_SUFFIX),
+            format!("libzpoolx.{}", SHARED_LIBRARY_SUFFIX),
+        ] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+
+        let missing = missing_bundled_libraries(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(missing, vec![format!("libzpool.{}", SHARED_LIBRARY_SUFFIX)]);
+    }
+
     fn test_state(config: PoolOpenConfig) -> AppState {
         AppState {
             pool: Arc::new(Mutex::new(None)),
             pool_open: Arc::new(Mutex::new(config)),
+            dataset_catalog: Arc::new(Mutex::new(None)),
+            txg_time_index: Arc::new(Mutex::new(None)),
+            pool_timeline: Arc::new(Mutex::new(None)),
+            known_names: Arc::new(Mutex::new(None)),
+            inflight: Arc::new(api::InflightBudget::new(DEFAULT_MAX_INFLIGHT_BYTES)),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_lineage_entries: api::LINEAGE_MAX_ENTRIES,
+            session_marks: Arc::new(Mutex::new(Vec::new())),
+            ready: Arc::new(AtomicBool::new(true)),
+            trust_proxy: false,
+            response_cache: Arc::new(api::ResponseCache::new(0)),
         }
     }
 
@@ -413,6 +1124,7 @@ mod tests {
             mode: PoolOpenMode::Offline,
             offline_search_paths: Some("/fixtures".to_string()),
             offline_pool_names: vec!["tank".to_string()],
+            offline_txg: None,
         }));
 
         let response = app
@@ -434,6 +1146,296 @@ mod tests {
         assert_eq!(payload["pool_open"]["mode"], "offline");
         assert_eq!(payload["pool_open"]["offline_search_paths"], "/fixtures");
         assert_eq!(payload["pool_open"]["offline_pools"][0], "tank");
+        assert!(payload["generated_at_unix_sec"].as_u64().unwrap_or(0) > 0);
+        assert!(payload["generated_at"]
+            .as_str()
+            .is_some_and(|stamp| stamp.ends_with('Z')));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_rejects_oversized_body_with_envelope() {
+        let mut state = test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        state.max_body_bytes = 64;
+        let app = build_router(state);
+
+        let body = format!("{{\"hex\":\"{}\"}}", "00".repeat(128));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/pools/tank/blkptr/decode")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+        assert_eq!(payload["code"], "PAYLOAD_TOO_LARGE");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_session_marks_round_trip_scoped_by_pool() {
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+
+        async fn call(
+            app: &Router,
+            method: Method,
+            uri: &str,
+            body: Option<Value>,
+        ) -> (StatusCode, Value) {
+            let mut request = Request::builder().method(method).uri(uri);
+            let body = match body {
+                Some(body) => {
+                    request = request.header("content-type", "application/json");
+                    Body::from(body.to_string())
+                }
+                None => Body::empty(),
+            };
+            let response = app
+                .clone()
+                .oneshot(request.body(body).expect("request build should succeed"))
+                .await
+                .expect("router should respond");
+            let status = response.status();
+            let bytes = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("response body should be readable");
+            (
+                status,
+                serde_json::from_slice(&bytes).expect("valid JSON payload"),
+            )
+        }
+
+        let (status, created) = call(
+            &app,
+            Method::POST,
+            "/api/session/marks",
+            Some(json!({ "pool": "tank", "label": "suspect", "kind": "object", "ref": { "objset_id": 54, "objid": 7 } })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(created["ref"]["objid"], 7);
+        let id = created["id"].as_u64().expect("mark id");
+
+        call(
+            &app,
+            Method::POST,
+            "/api/session/marks",
+            Some(json!({ "pool": "other", "label": "x", "kind": "path", "ref": "/etc" })),
+        )
+        .await;
+
+        let (_, listed) = call(&app, Method::GET, "/api/session/marks?pool=tank", None).await;
+        assert_eq!(listed["count"], 1);
+        assert_eq!(listed["marks"][0]["label"], "suspect");
+
+        let (status, bad) = call(
+            &app,
+            Method::POST,
+            "/api/session/marks",
+            Some(json!({ "pool": "tank", "label": " ", "kind": "object", "ref": 1 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(bad["code"], "INVALID_MARK");
+
+        let uri = format!("/api/session/marks/{id}");
+        let (status, _) = call(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, missing) = call(&app, Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(missing["code"], "MARK_NOT_FOUND");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_offline_pool_list_without_config_explains_itself() {
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/pools")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response
+            .headers()
+            .get("x-zfs-note")
+            .and_then(|note| note.to_str().ok())
+            .is_some_and(|note| note.contains("ZFS_EXPLORER_OFFLINE_POOLS")));
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let payload: Value = serde_json::from_slice(&bytes).expect("valid JSON payload");
+        assert_eq!(payload, json!([]));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_matches_decoded_pool_names_and_rejects_invalid_ones() {
+        let state = test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        });
+        let app = build_router(state.clone());
+
+        // dva/owner resolves the pool before checking `scan_limit`, so a zero
+        // limit answers 400 without any FFI call once the name matches the
+        // active handle; a mismatch would instead try to open the pool.
+        for (stored, uri) in [
+            (
+                "my pool",
+                "/api/pools/my%20pool/dva/owner?vdev=0&offset=0&scan_limit=0",
+            ),
+            (
+                "tank-2",
+                "/api/pools/tank%2D2/dva/owner?vdev=0&offset=0&scan_limit=0",
+            ),
+        ] {
+            *state.pool.lock().unwrap() = Some(Arc::new(ffi::PoolHandle {
+                name: stored.to_string(),
+                ptr: std::ptr::null_mut(),
+            }));
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .expect("router should respond");
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let payload: Value = serde_json::from_slice(&bytes).expect("valid JSON payload");
+            assert_eq!(payload["code"], "INVALID_QUERY", "{uri}");
+            assert_eq!(payload["parameter"], "scan_limit", "{uri}");
+            // A mismatch would have closed the handle and tried to reopen it.
+            let active = state.pool.lock().unwrap().as_ref().map(|h| h.name.clone());
+            assert_eq!(active.as_deref(), Some(stored), "{uri}");
+        }
+
+        for uri in ["/api/pools/%00/mos/objects", "/api/pools/%20/mos/objects"] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .expect("router should respond");
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let payload: Value = serde_json::from_slice(&bytes).expect("valid JSON payload");
+            assert_eq!(payload["code"], "INVALID_POOL_NAME");
+        }
+        // Invalid names are rejected without closing the active handle.
+        assert!(state.pool.lock().unwrap().is_some());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn server_speaks_h2c_with_configured_stream_limit() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind should succeed");
+        let addr = listener.local_addr().expect("local addr");
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+        let server = tokio::spawn(serve(
+            listener,
+            app,
+            HttpServerConfig {
+                http2_max_streams: 77,
+                keepalive: None,
+            },
+        ));
+
+        // Client preface plus an empty SETTINGS frame; the server answers
+        // with its own SETTINGS frame first.
+        let mut conn = tokio::net::TcpStream::connect(addr)
+            .await
+            .expect("connect should succeed");
+        conn.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\x00\x00\x00\x04\x00\x00\x00\x00\x00")
+            .await
+            .expect("preface should be written");
+        let mut header = [0u8; 9];
+        conn.read_exact(&mut header).await.expect("frame header");
+        assert_eq!(header[3], 0x4, "first frame should be SETTINGS");
+        let len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+        let mut payload = vec![0u8; len];
+        conn.read_exact(&mut payload)
+            .await
+            .expect("settings payload");
+        let max_streams = payload
+            .chunks_exact(6)
+            .find(|setting| u16::from_be_bytes([setting[0], setting[1]]) == 0x3)
+            .map(|setting| u32::from_be_bytes([setting[2], setting[3], setting[4], setting[5]]));
+        assert_eq!(max_streams, Some(77));
+        server.abort();
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn server_closes_idle_http1_connection_after_keepalive_window() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind should succeed");
+        let addr = listener.local_addr().expect("local addr");
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+        let server = tokio::spawn(serve(
+            listener,
+            app,
+            HttpServerConfig {
+                http2_max_streams: DEFAULT_HTTP2_MAX_STREAMS,
+                keepalive: Some(Duration::from_millis(200)),
+            },
+        ));
+
+        let mut conn = tokio::net::TcpStream::connect(addr)
+            .await
+            .expect("connect should succeed");
+        conn.write_all(b"GET /api/mode HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .expect("request should be written");
+        let mut buf = vec![0u8; 4096];
+        let read = conn.read(&mut buf).await.expect("response should arrive");
+        assert!(buf[..read].starts_with(b"HTTP/1.1 "), "keep-alive response");
+
+        // The connection stays open across the response and is closed once
+        // it has sat idle for the keep-alive window.
+        let idle = tokio::time::timeout(Duration::from_secs(5), conn.read(&mut buf))
+            .await
+            .expect("server should close the idle connection");
+        assert_eq!(idle.expect("clean close"), 0);
+        server.abort();
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -442,6 +1444,7 @@ mod tests {
             mode: PoolOpenMode::Offline,
             offline_search_paths: None,
             offline_pool_names: Vec::new(),
+            offline_txg: None,
         }));
 
         let response = app
@@ -449,6 +1452,7 @@ mod tests {
                 Request::builder()
                     .method(Method::GET)
                     .uri("/api/perf/txg?pool=tank")
+                    .header(REQUEST_ID_HEADER, "req-offline-1")
                     .body(Body::empty())
                     .expect("request build should succeed"),
             )
@@ -466,8 +1470,267 @@ mod tests {
                 "code": "HTTP_400",
                 "error": "runtime telemetry is unavailable in offline mode",
                 "message": "runtime telemetry is unavailable in offline mode",
-                "recoverable": true
+                "recoverable": true,
+                "request_id": "req-offline-1"
             })
         );
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_invalid_numeric_query_returns_json_envelope() {
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/api/pools/tank/spacemap/7/ranges?limit=abc")
+                    .body(Body::empty())
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+        assert_eq!(payload["code"], "INVALID_QUERY");
+        assert_eq!(payload["parameter"], "limit");
+        assert_eq!(payload["recoverable"], true);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_dva_owner_requires_scan_limit() {
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/api/pools/tank/dva/owner?vdev=0&offset=4194304&asize=4096")
+                    .body(Body::empty())
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+        assert_eq!(payload["code"], "INVALID_QUERY");
+        assert_eq!(payload["parameter"], "scan_limit");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_tags_responses_and_errors_with_request_id() {
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/api/pools/tank/spacemap/7/ranges?limit=abc")
+                    .body(Body::empty())
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        let generated = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+            .expect("request id header should be set");
+        assert_eq!(generated.len(), 36);
+        assert_eq!(&generated[14..15], "4");
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+        assert_eq!(payload["request_id"], generated.as_str());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/api/pools/tank/spacemap/7/ranges?limit=abc")
+                    .header(REQUEST_ID_HEADER, "client-trace-42")
+                    .body(Body::empty())
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(
+            response
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok()),
+            Some("client-trace-42")
+        );
+        assert_ne!(generate_request_id(), generate_request_id());
+    }
+
+    #[test]
+    fn request_origin_uses_forwarded_headers_only_when_trusted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("127.0.0.1:9000"));
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.9, 203.0.113.7"),
+        );
+        headers.insert("x-forwarded-proto", HeaderValue::from_static("HTTPS"));
+        headers.insert(
+            "x-forwarded-host",
+            HeaderValue::from_static("zfs.example.org"),
+        );
+        let peer = Some(SocketAddr::from(([10, 0, 0, 2], 51000)));
+
+        assert_eq!(
+            request_origin(&headers, peer, false),
+            RequestOrigin {
+                client: "10.0.0.2".to_string(),
+                scheme: "http".to_string(),
+                host: "127.0.0.1:9000".to_string(),
+            }
+        );
+        assert_eq!(
+            request_origin(&headers, peer, true),
+            RequestOrigin {
+                client: "203.0.113.7".to_string(),
+                scheme: "https".to_string(),
+                host: "zfs.example.org".to_string(),
+            }
+        );
+
+        headers.insert("x-forwarded-proto", HeaderValue::from_static("gopher"));
+        assert_eq!(request_origin(&headers, None, true).scheme, "http");
+
+        // A client-supplied X-Forwarded-For is followed by the proxy's own
+        // header; only the proxy's right-most entry counts.
+        headers.append("x-forwarded-for", HeaderValue::from_static("192.0.2.44"));
+        assert_eq!(request_origin(&headers, None, true).client, "192.0.2.44");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_debug_flag_reports_ffi_call_count() {
+        let app = build_router(test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: Vec::new(),
+            offline_txg: None,
+        }));
+
+        let plain = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/api/pools/tank/spacemap/7/ranges?limit=abc")
+                    .body(Body::empty())
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        assert!(plain.headers().get("x-zfs-ffi-calls").is_none());
+        assert!(plain.headers().get("server-timing").is_none());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/api/pools/tank/spacemap/7/ranges?limit=abc&debug=true")
+                    .body(Body::empty())
+                    .expect("request build should succeed"),
+            )
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response
+                .headers()
+                .get("x-zfs-ffi-calls")
+                .and_then(|value| value.to_str().ok()),
+            Some("0")
+        );
+        let server_timing = response
+            .headers()
+            .get("server-timing")
+            .and_then(|value| value.to_str().ok())
+            .expect("debug responses carry Server-Timing");
+        assert!(server_timing.starts_with("ffi;dur=0.0, parse;dur=0.0, total;dur="));
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+        assert_eq!(payload["code"], "INVALID_QUERY");
+        assert_eq!(payload["_debug"]["ffi_calls"], 0);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn router_answers_not_ready_until_init_completes() {
+        let state = test_state(PoolOpenConfig {
+            mode: PoolOpenMode::Offline,
+            offline_search_paths: None,
+            offline_pool_names: vec!["tank".to_string()],
+            offline_txg: None,
+        });
+        state.ready.store(false, Ordering::Release);
+        let app = build_router(state.clone());
+
+        let get = |uri: &str| {
+            Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .body(Body::empty())
+                .expect("request build should succeed")
+        };
+
+        for uri in ["/api/ready", "/api/pools"] {
+            let response = app
+                .clone()
+                .oneshot(get(uri))
+                .await
+                .expect("router should respond");
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE, "{uri}");
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("response body should be readable");
+            let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+            assert_eq!(payload["code"], "NOT_READY", "{uri}");
+            assert_eq!(payload["recoverable"], true, "{uri}");
+        }
+
+        state.ready.store(true, Ordering::Release);
+        let response = app
+            .oneshot(get("/api/ready"))
+            .await
+            .expect("router should respond");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("response body should be readable");
+        let payload: Value = serde_json::from_slice(&body).expect("valid JSON payload");
+        assert_eq!(payload["ready"], true);
+    }
 }
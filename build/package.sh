@@ -252,7 +252,24 @@ cat > "$BACKEND_BUNDLE_DIR/run-backend.sh" <<'EOF'
 #!/usr/bin/env bash
 set -euo pipefail
 HERE="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
-export LD_LIBRARY_PATH="$HERE/lib:${LD_LIBRARY_PATH:-}"
+LIB_DIR="${ZFS_EXPLORER_LIB_DIR:-$HERE/lib}"
+
+# The loader fails before the backend's own checks can run, so verify the
+# bundled libraries here and name any that are missing.
+missing=()
+for lib in libzdbdecode.so libzpool.so libzfs.so libnvpair.so; do
+  if ! compgen -G "$LIB_DIR/$lib" >/dev/null && ! compgen -G "$LIB_DIR/$lib.*" >/dev/null; then
+    missing+=("$lib")
+  fi
+done
+if (( ${#missing[@]} > 0 )); then
+  echo "error: $LIB_DIR is missing ${missing[*]}" >&2
+  echo "hint: copy the bundle's lib/ directory there, or set ZFS_EXPLORER_LIB_DIR to where the libraries live." >&2
+  exit 1
+fi
+
+export ZFS_EXPLORER_LIB_DIR="$LIB_DIR"
+export LD_LIBRARY_PATH="$LIB_DIR:${LD_LIBRARY_PATH:-}"
 exec "$HERE/bin/zfs-explorer" "$@"
 EOF
 chmod +x "$BACKEND_BUNDLE_DIR/run-backend.sh"
@@ -31,22 +31,49 @@ zdx_result_t zdx_list_pools(void);
 zdx_pool_t *zdx_pool_open(const char *name, int *err);
 zdx_pool_t *zdx_pool_open_offline(const char *name, const char *search_paths,
                                   int *err);
+zdx_pool_t *zdx_pool_open_offline_txg(const char *name,
+                                      const char *search_paths, uint64_t txg,
+                                      int *err);
 void zdx_pool_close(zdx_pool_t *pool);
 zdx_result_t zdx_pool_info(zdx_pool_t *pool);
 zdx_result_t zdx_pool_vdevs(zdx_pool_t *pool);
 zdx_result_t zdx_pool_datasets(zdx_pool_t *pool);
 zdx_result_t zdx_pool_summary(zdx_pool_t *pool);
+zdx_result_t zdx_pool_alloc_classes(zdx_pool_t *pool);
+zdx_result_t zdx_vdev_labels(zdx_pool_t *pool, uint64_t vdev);
+zdx_result_t zdx_label_pool_name(const char *path);
+zdx_result_t zdx_pool_checkpoint(zdx_pool_t *pool);
+zdx_result_t zdx_pool_l2arc(zdx_pool_t *pool);
+zdx_result_t zdx_pool_version_info(zdx_pool_t *pool);
 zdx_result_t zdx_pool_errors(zdx_pool_t *pool, uint64_t cursor,
-                             uint64_t limit, int resolve_paths);
+                             uint64_t limit, int resolve_paths,
+                             uint64_t since_txg);
+zdx_result_t zdx_txg_time_anchors(zdx_pool_t *pool, uint64_t max_records);
+zdx_result_t zdx_pool_history(zdx_pool_t *pool, uint64_t max_records);
 
 /* === MOS object operations === */
 zdx_result_t zdx_mos_list_objects(zdx_pool_t *pool, int type_filter,
                                    uint64_t start, uint64_t limit);
 zdx_result_t zdx_mos_get_object(zdx_pool_t *pool, uint64_t objid);
 zdx_result_t zdx_mos_get_blkptrs(zdx_pool_t *pool, uint64_t objid);
+zdx_result_t zdx_decode_blkptr(zdx_pool_t *pool, const uint8_t *data,
+                               size_t len);
 zdx_result_t zdx_obj_get(zdx_pool_t *pool, uint64_t objid);
 zdx_result_t zdx_mos_block_tree(zdx_pool_t *pool, uint64_t objid,
-                                uint64_t max_depth, uint64_t max_nodes);
+                                uint64_t max_depth, uint64_t max_nodes,
+                                uint64_t min_txg, uint64_t max_txg);
+zdx_result_t zdx_dva_owner(zdx_pool_t *pool, uint64_t objset_id,
+                           uint64_t vdev, uint64_t offset, uint64_t asize,
+                           uint64_t start, uint64_t scan_limit,
+                           uint64_t max_blkptrs);
+zdx_result_t zdx_decode_nvlist(zdx_pool_t *pool, uint64_t objid);
+zdx_result_t zdx_mos_read_data(zdx_pool_t *pool, uint64_t objid,
+                               uint64_t offset, uint64_t limit);
+zdx_result_t zdx_obj_array(zdx_pool_t *pool, uint64_t objid, uint64_t cursor,
+                           uint64_t limit, int with_types);
+zdx_result_t zdx_bpobj_stats(zdx_pool_t *pool, uint64_t objid);
+zdx_result_t zdx_bpobj_iter(zdx_pool_t *pool, uint64_t objid, uint64_t cursor,
+                            uint64_t limit);
 
 /* === DMU type catalog === */
 zdx_result_t zdx_list_dmu_types(void);
@@ -58,24 +85,34 @@ zdx_result_t zdx_zap_entries(zdx_pool_t *pool, uint64_t objid,
 
 /* === Raw block read === */
 zdx_result_t zdx_read_block(zdx_pool_t *pool, uint64_t vdev,
-                            uint64_t offset, uint64_t size);
+                            uint64_t offset, uint64_t size, int follow_gang);
 
 /* === DSL traversal === */
 zdx_result_t zdx_dsl_dir_children(zdx_pool_t *pool, uint64_t objid);
 zdx_result_t zdx_dsl_dir_head(zdx_pool_t *pool, uint64_t objid);
+zdx_result_t zdx_dsl_dir_props(zdx_pool_t *pool, uint64_t objid);
 zdx_result_t zdx_dsl_root_dir(zdx_pool_t *pool);
 zdx_result_t zdx_dsl_dir_by_name(zdx_pool_t *pool, const char *name);
 
 /* === Dataset / Objset === */
 zdx_result_t zdx_dataset_snapshots(zdx_pool_t *pool, uint64_t dir_obj);
 zdx_result_t zdx_dataset_snapshot_count(zdx_pool_t *pool, uint64_t dir_obj);
+zdx_result_t zdx_snapshot_holds(zdx_pool_t *pool, uint64_t dsobj);
+zdx_result_t zdx_snapshot_space(zdx_pool_t *pool, uint64_t dir_obj,
+                                uint64_t cursor, uint64_t limit);
 zdx_result_t zdx_dataset_objset(zdx_pool_t *pool, uint64_t dsobj);
 zdx_result_t zdx_dataset_lineage(zdx_pool_t *pool, uint64_t dsobj,
                                  uint64_t max_prev, uint64_t max_next);
+zdx_result_t zdx_dataset_origin(zdx_pool_t *pool, uint64_t dir_obj);
+zdx_result_t zdx_dataset_zil(zdx_pool_t *pool, uint64_t dsobj,
+                             uint64_t max_records);
+zdx_result_t zdx_dataset_userspace(zdx_pool_t *pool, uint64_t objset_id,
+                                   int group, uint64_t cursor, uint64_t limit);
 zdx_result_t zdx_objset_root(zdx_pool_t *pool, uint64_t objset_id);
 zdx_result_t zdx_objset_list_objects(zdx_pool_t *pool, uint64_t objset_id,
                                      int type_filter, uint64_t start,
-                                     uint64_t limit);
+                                     uint64_t limit, uint64_t min_size,
+                                     int allocated_only);
 zdx_result_t zdx_objset_dir_entries(zdx_pool_t *pool, uint64_t objset_id,
                                     uint64_t dir_obj, uint64_t cursor,
                                     uint64_t limit);
@@ -85,11 +122,17 @@ zdx_result_t zdx_objset_stat(zdx_pool_t *pool, uint64_t objset_id,
                              uint64_t objid);
 zdx_result_t zdx_objset_get_object(zdx_pool_t *pool, uint64_t objset_id,
                                    uint64_t objid);
+zdx_result_t zdx_objset_dnode(zdx_pool_t *pool, uint64_t objset_id,
+                              uint64_t objid);
 zdx_result_t zdx_objset_get_blkptrs(zdx_pool_t *pool, uint64_t objset_id,
                                     uint64_t objid);
 zdx_result_t zdx_objset_block_tree(zdx_pool_t *pool, uint64_t objset_id,
                                    uint64_t objid, uint64_t max_depth,
-                                   uint64_t max_nodes);
+                                   uint64_t max_nodes, uint64_t min_txg,
+                                   uint64_t max_txg);
+zdx_result_t zdx_objset_rootbp_tree(zdx_pool_t *pool, uint64_t objset_id,
+                                    uint64_t max_depth, uint64_t max_nodes,
+                                    uint64_t min_txg, uint64_t max_txg);
 zdx_result_t zdx_objset_zap_info(zdx_pool_t *pool, uint64_t objset_id,
                                  uint64_t objid);
 zdx_result_t zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id,
@@ -98,8 +141,18 @@ zdx_result_t zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id,
 zdx_result_t zdx_objset_read_data(zdx_pool_t *pool, uint64_t objset_id,
                                   uint64_t objid, uint64_t offset,
                                   uint64_t limit);
+zdx_result_t zdx_objset_sa_layouts(zdx_pool_t *pool, uint64_t objset_id);
+zdx_result_t zdx_objset_largest_files(zdx_pool_t *pool, uint64_t objset_id,
+                                     uint64_t start, uint64_t limit,
+                                     uint64_t scan_limit, uint64_t min_size);
+zdx_result_t zdx_objset_space_by_type(zdx_pool_t *pool, uint64_t objset_id,
+                                      uint64_t start, uint64_t scan_limit);
 
 /* === Spacemap inspection === */
+zdx_result_t zdx_pool_spacemaps(zdx_pool_t *pool, uint64_t cursor,
+                                uint64_t limit);
+zdx_result_t zdx_vdev_metaslabs(zdx_pool_t *pool, uint64_t vdev,
+                                uint64_t cursor, uint64_t limit);
 zdx_result_t zdx_spacemap_summary(zdx_pool_t *pool, uint64_t objid);
 zdx_result_t zdx_spacemap_ranges(zdx_pool_t *pool, uint64_t objid,
                                  uint64_t cursor, uint64_t limit,
@@ -3,6 +3,7 @@
 #include <errno.h>
 #include <limits.h>
 #include <stddef.h>
+#include <string.h>
 
 uint64_t
 zdx_u64_add_sat(uint64_t a, uint64_t b)
@@ -96,3 +97,42 @@ zdx_normalize_errno(int err)
 
     return EINVAL;
 }
+
+const char *
+zdx_redundancy_label(int ndvas)
+{
+    switch (ndvas) {
+    case 0:
+        return "no copies";
+    case 1:
+        return "1 copy";
+    case 2:
+        return "2 copies (ditto)";
+    case 3:
+        return "3 copies (ditto)";
+    default:
+        return "unknown";
+    }
+}
+
+/*
+ * Check whether buf starts with a gang-header-shaped block: 1 when the
+ * embedded checksum magic sits at the end of the first 512 bytes in host
+ * byte order, -1 when it is byteswapped, 0 otherwise.
+ */
+int
+zdx_gang_header_byteorder(const uint8_t *buf, size_t len)
+{
+    uint64_t magic;
+
+    if (buf == NULL || len < ZDX_GANG_HEADER_SIZE)
+        return 0;
+
+    memcpy(&magic, buf + ZDX_GANG_HEADER_SIZE - ZDX_EMBEDDED_CKSUM_TAIL_SIZE,
+        sizeof (magic));
+    if (magic == ZDX_EMBEDDED_CKSUM_MAGIC)
+        return 1;
+    if (magic == __builtin_bswap64(ZDX_EMBEDDED_CKSUM_MAGIC))
+        return -1;
+    return 0;
+}
@@ -1,6 +1,7 @@
 #ifndef ZDX_HELPERS_H
 #define ZDX_HELPERS_H
 
+#include <stddef.h>
 #include <stdint.h>
 
 uint64_t zdx_u64_add_sat(uint64_t a, uint64_t b);
@@ -9,5 +10,23 @@ uint64_t zdx_u64_clamp(uint64_t value, uint64_t min_value, uint64_t max_value);
 int zdx_parse_u64_token_base(const char *s, int base, const char **next,
     uint64_t *value);
 int zdx_normalize_errno(int err);
+const char *zdx_redundancy_label(int ndvas);
+
+/*
+ * Legacy gang headers are 512-byte blocks ending in an embedded checksum
+ * tail (zio_eck_t: magic + 256-bit checksum).
+ */
+#define ZDX_GANG_HEADER_SIZE 512
+#define ZDX_EMBEDDED_CKSUM_MAGIC 0x210da7ab10c7a11ULL
+#define ZDX_EMBEDDED_CKSUM_TAIL_SIZE 40
+int zdx_gang_header_byteorder(const uint8_t *buf, size_t len);
+
+/*
+ * Surfaced alongside gang blkptrs: a raw read of a gang DVA returns the
+ * gang header block, not the logical data it describes.
+ */
+#define ZDX_GANG_BLOCK_WARNING \
+    "gang block: DVAs point at a gang header; raw block reads return " \
+    "the header, not the logical data"
 
 #endif /* ZDX_HELPERS_H */
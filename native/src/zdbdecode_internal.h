@@ -33,6 +33,7 @@
 #include <sys/zfs_refcount.h>
 #include <sys/vdev.h>
 #include <sys/zio.h>
+#include <sys/zil.h>
 #include <sys/abd.h>
 #include <libzfs.h>
 #include <libzutil.h>
@@ -59,6 +60,7 @@ struct zdx_pool {
     spa_t *spa;
     boolean_t offline_mode;
     boolean_t imported_transient;
+    uint64_t requested_txg;     /* rewind target; 0 = latest uberblock */
 };
 
 extern libzfs_handle_t *g_zfs;
@@ -67,11 +69,19 @@ zdx_result_t make_success(char *json);
 const char *dmu_ot_name_safe(dmu_object_type_t type);
 char *bytes_to_hex(const uint8_t *data, size_t len);
 char *numbers_preview(const void *data, uint64_t count, int int_len);
+const char *zap_value_type(const void *data, int int_len, uint64_t count);
+char *zap_value_json(const void *data, int int_len, uint64_t count);
+char *zap_structure_json(const zap_stats_t *zs);
 const char *dirent_type_name(uint64_t type);
 uint64_t mode_to_dirent_type(uint64_t mode);
 char *dup_range(const char *start, size_t len);
 int append_semantic_edge(char **array, int *count, uint64_t source,
     uint64_t target, const char *label, const char *kind, double confidence);
 int zdx_sa_setup(objset_t *os, sa_attr_type_t **tablep);
+int zdx_hold_objset_by_dsobj(spa_t *spa, uint64_t dsobj, dsl_dataset_t **dsp,
+    objset_t **osp, const void *tag);
+uint64_t dnode_top_birth_txg(const dnode_phys_t *dnp);
+char *rootbp_json(const blkptr_t *bp);
+char *nvlist_to_json_string(nvlist_t *nvl);
 
 #endif /* ZDBDECODE_INTERNAL_H */
@@ -1,11 +1,168 @@
 #include "zdbdecode_internal.h"
 
+#define ZDX_GANG_HEADER_NBLKPTRS \
+    ((ZDX_GANG_HEADER_SIZE - ZDX_EMBEDDED_CKSUM_TAIL_SIZE) / sizeof (blkptr_t))
+
+/*
+ * Read a block through its own blkptr, returning the physical payload
+ * (checksummed, not decompressed).
+ */
+static int
+read_bp_physical(spa_t *spa, const blkptr_t *bp, void *out)
+{
+    uint64_t psize = BP_GET_PSIZE(bp);
+    abd_t *abd = abd_alloc(psize, B_FALSE);
+    if (!abd)
+        return ENOMEM;
+
+    spa_config_enter(spa, SCL_STATE, FTAG, RW_READER);
+    int err = zio_wait(zio_read(NULL, spa, bp, abd, psize, NULL, NULL,
+        ZIO_PRIORITY_SYNC_READ, ZIO_FLAG_CANFAIL | ZIO_FLAG_RAW, NULL));
+    spa_config_exit(spa, SCL_STATE, FTAG);
+    if (err == 0)
+        abd_copy_to_buf(out, abd, psize);
+    abd_free(abd);
+    return err;
+}
+
+/*
+ * Recognize a legacy gang header by its embedded checksum tail and list
+ * its child blkptrs. Children must point at existing top-level vdevs, so
+ * other blocks that happen to end in the same magic are not misread. With
+ * follow_gang, the children are read in order and their payloads
+ * concatenated: that is the gang block's physical (possibly compressed)
+ * data. Returns "null" when buf is not a gang header.
+ */
+static char *
+gang_header_json(spa_t *spa, const uint8_t *buf, uint64_t size,
+    boolean_t follow_gang, uint64_t max_data)
+{
+    int order = zdx_gang_header_byteorder(buf, (size_t)size);
+    if (order == 0)
+        return strdup("null");
+
+    blkptr_t children[ZDX_GANG_HEADER_NBLKPTRS];
+    memcpy(children, buf, sizeof (children));
+    if (order < 0)
+        byteswap_uint64_array(children, sizeof (children));
+
+    uint64_t nvdevs = spa->spa_root_vdev->vdev_children;
+    uint64_t total = 0;
+    int live = 0;
+    for (int i = 0; i < (int)ZDX_GANG_HEADER_NBLKPTRS; i++) {
+        const blkptr_t *bp = &children[i];
+        if (BP_IS_HOLE(bp))
+            continue;
+        if (BP_IS_EMBEDDED(bp) || DVA_GET_VDEV(&bp->blk_dva[0]) >= nvdevs ||
+            BP_GET_PSIZE(bp) == 0)
+            return strdup("null");
+        total += BP_GET_PSIZE(bp);
+        live++;
+    }
+    if (live == 0)
+        return strdup("null");
+
+    char *array = json_array_start();
+    if (!array)
+        return NULL;
+    int count = 0;
+    for (int i = 0; i < (int)ZDX_GANG_HEADER_NBLKPTRS; i++) {
+        const blkptr_t *bp = &children[i];
+        if (BP_IS_HOLE(bp))
+            continue;
+        const dva_t *dva = &bp->blk_dva[0];
+        char *bp_json = rootbp_json(bp);
+        char *item = bp_json ? json_format(
+            "{"
+            "\"index\":%d,"
+            "\"dva\":{\"vdev\":%llu,\"offset\":%llu,\"asize\":%llu,"
+            "\"is_gang\":%s},"
+            "\"size\":%llu,"
+            "\"blkptr\":%s"
+            "}",
+            i,
+            (unsigned long long)DVA_GET_VDEV(dva),
+            (unsigned long long)DVA_GET_OFFSET(dva),
+            (unsigned long long)DVA_GET_ASIZE(dva),
+            DVA_GET_GANG(dva) ? "true" : "false",
+            (unsigned long long)BP_GET_PSIZE(bp),
+            bp_json) : NULL;
+        free(bp_json);
+        char *next = item ? json_array_append(array, item) : NULL;
+        free(item);
+        free(array);
+        array = next;
+        if (!array)
+            return NULL;
+        count++;
+    }
+    char *children_json = json_array_end(array, count > 0);
+    free(array);
+    if (!children_json)
+        return NULL;
+
+    char *data_json = strdup("null");
+    const char *data_error = NULL;
+    if (data_json && follow_gang) {
+        if (total > max_data) {
+            data_error = "gang data exceeds the read cap";
+        } else {
+            uint8_t *data = malloc(total);
+            uint64_t used = 0;
+            int err = data ? 0 : ENOMEM;
+            for (int i = 0; err == 0 && i < (int)ZDX_GANG_HEADER_NBLKPTRS;
+                i++) {
+                if (BP_IS_HOLE(&children[i]))
+                    continue;
+                err = read_bp_physical(spa, &children[i], data + used);
+                used += BP_GET_PSIZE(&children[i]);
+            }
+            if (err == 0) {
+                char *hex = bytes_to_hex(data, (size_t)total);
+                free(data_json);
+                data_json = hex ? json_string(hex) : NULL;
+                free(hex);
+            } else {
+                data_error = strerror(err);
+            }
+            free(data);
+        }
+    }
+    char *error_json = data_error ? json_string(data_error) : strdup("null");
+    if (!data_json || !error_json) {
+        free(children_json);
+        free(data_json);
+        free(error_json);
+        return NULL;
+    }
+
+    char *json = json_format(
+        "{"
+        "\"byteswapped\":%s,"
+        "\"children\":%s,"
+        "\"data_size\":%llu,"
+        "\"followed\":%s,"
+        "\"data_hex\":%s,"
+        "\"follow_error\":%s"
+        "}",
+        order < 0 ? "true" : "false",
+        children_json,
+        (unsigned long long)total,
+        follow_gang ? "true" : "false",
+        data_json,
+        error_json);
+    free(children_json);
+    free(data_json);
+    free(error_json);
+    return json;
+}
+
 /*
  * Read a raw block by vdev + offset.
  */
 zdx_result_t
 zdx_read_block(zdx_pool_t *pool, uint64_t vdev_id,
-    uint64_t offset, uint64_t size)
+    uint64_t offset, uint64_t size, int follow_gang)
 {
     if (!pool || !pool->spa)
         return make_error(EINVAL, "pool not open");
@@ -80,28 +237,41 @@ zdx_read_block(zdx_pool_t *pool, uint64_t vdev_id,
     }
 
     char *hex = bytes_to_hex((const uint8_t *)buf, (size_t)size);
+    char *gang_json = gang_header_json(spa, (const uint8_t *)buf, size,
+        follow_gang != 0, max_read);
     abd_return_buf_copy(abd, buf, size);
     abd_free(abd);
-    if (!hex)
+    if (!hex || !gang_json) {
+        free(hex);
+        free(gang_json);
         return make_error(ENOMEM, "failed to encode hex");
+    }
 
     char *hex_json = json_string(hex);
     free(hex);
-    if (!hex_json)
+    if (!hex_json) {
+        free(gang_json);
         return make_error(ENOMEM, "failed to allocate JSON string");
+    }
 
+    boolean_t is_gang = strcmp(gang_json, "null") != 0;
     char *result = json_format(
         "{"
         "\"vdev\":%llu,"
         "\"offset\":%llu,"
         "\"size\":%llu,"
-        "\"data_hex\":%s"
+        "\"data_hex\":%s,"
+        "\"gang\":%s,"
+        "\"warning\":%s"
         "}",
         (unsigned long long)vdev_id,
         (unsigned long long)offset,
         (unsigned long long)size,
-        hex_json);
+        hex_json,
+        gang_json,
+        is_gang ? "\"" ZDX_GANG_BLOCK_WARNING "\"" : "null");
     free(hex_json);
+    free(gang_json);
 
     if (!result)
         return make_error(ENOMEM, "failed to allocate JSON result");
@@ -7,6 +7,8 @@ typedef struct zdx_block_tree_ctx {
     uint64_t next_id;
     uint64_t max_depth;
     uint64_t max_nodes;
+    uint64_t min_txg;
+    uint64_t max_txg;
     boolean_t truncated;
 } zdx_block_tree_ctx_t;
 
@@ -79,8 +81,20 @@ zdx_block_tree_append_bp(zdx_block_tree_ctx_t *ctx, dnode_t *dn,
         return (0);
     }
 
-    uint64_t node_id = ctx->next_id++;
+    /*
+     * An indirect block is rewritten whenever any child is, so its birth is
+     * the newest in its subtree: anything born before min_txg can be pruned
+     * whole. Indirects born after max_txg may still cover in-range children,
+     * so they stay as context nodes (in_txg_range=false); only level-0
+     * blocks outside the range are dropped.
+     */
+    uint64_t birth = BP_GET_BIRTH(bp);
     int level = BP_GET_LEVEL(bp);
+    boolean_t in_txg_range = birth >= ctx->min_txg && birth <= ctx->max_txg;
+    if (birth < ctx->min_txg || (!in_txg_range && (level == 0 || is_spill)))
+        return (0);
+
+    uint64_t node_id = ctx->next_id++;
     int child_slots = level > 0 ? EPB(dn->dn_indblkshift, SPA_BLKPTRSHIFT) : 0;
     boolean_t can_descend = !BP_IS_HOLE(bp) && !BP_IS_EMBEDDED(bp) &&
         level > 0 && depth < ctx->max_depth && !is_spill;
@@ -115,14 +129,17 @@ zdx_block_tree_append_bp(zdx_block_tree_ctx_t *ctx, dnode_t *dn,
         "\"birth_txg\":%llu,"
         "\"logical_birth\":%llu,"
         "\"physical_birth\":%llu,"
+        "\"in_txg_range\":%s,"
         "\"fill\":%llu,"
         "\"checksum\":%d,"
         "\"compression\":%d,"
         "\"dedup\":%s,"
         "\"ndvas\":%d,"
+        "\"redundancy\":\"%s\","
         "\"is_hole\":%s,"
         "\"is_embedded\":%s,"
         "\"is_gang\":%s,"
+        "\"gang_warning\":%s,"
         "\"child_slots\":%d,"
         "\"dvas\":%s"
         "}",
@@ -136,17 +153,20 @@ zdx_block_tree_append_bp(zdx_block_tree_ctx_t *ctx, dnode_t *dn,
         (unsigned long long)BP_GET_LSIZE(bp),
         (unsigned long long)BP_GET_PSIZE(bp),
         (unsigned long long)BP_GET_ASIZE(bp),
-        (unsigned long long)BP_GET_BIRTH(bp),
+        (unsigned long long)birth,
         (unsigned long long)BP_GET_LOGICAL_BIRTH(bp),
         (unsigned long long)BP_GET_PHYSICAL_BIRTH(bp),
+        in_txg_range ? "true" : "false",
         (unsigned long long)BP_GET_FILL(bp),
         BP_GET_CHECKSUM(bp),
         BP_GET_COMPRESS(bp),
         BP_GET_DEDUP(bp) ? "true" : "false",
         BP_GET_NDVAS(bp),
+        zdx_redundancy_label(BP_GET_NDVAS(bp)),
         BP_IS_HOLE(bp) ? "true" : "false",
         BP_IS_EMBEDDED(bp) ? "true" : "false",
         BP_IS_GANG(bp) ? "true" : "false",
+        BP_IS_GANG(bp) ? "\"" ZDX_GANG_BLOCK_WARNING "\"" : "null",
         child_slots,
         dvas_json);
     free(dvas_json);
@@ -188,7 +208,8 @@ zdx_block_tree_append_bp(zdx_block_tree_ctx_t *ctx, dnode_t *dn,
 static zdx_result_t
 zdx_block_tree_from_dnode(const char *scope, uint64_t objset_id,
     boolean_t has_objset_id, uint64_t objid, dnode_t *dn, uint64_t max_depth,
-    uint64_t max_nodes)
+    uint64_t max_nodes, uint64_t min_txg, uint64_t max_txg,
+    const blkptr_t *rootbp)
 {
     if (dn == NULL || dn->dn_phys == NULL)
         return make_error(EINVAL, "missing dnode");
@@ -199,6 +220,8 @@ zdx_block_tree_from_dnode(const char *scope, uint64_t objset_id,
         .next_id = 1,
         .max_depth = max_depth,
         .max_nodes = max_nodes,
+        .min_txg = min_txg,
+        .max_txg = max_txg,
         .truncated = B_FALSE,
     };
     if (ctx.nodes == NULL)
@@ -283,13 +306,25 @@ zdx_block_tree_from_dnode(const char *scope, uint64_t objset_id,
         objset_json = objset_buf;
     }
 
+    char *rootbp_str = NULL;
+    if (rootbp != NULL) {
+        rootbp_str = rootbp_json(rootbp);
+        if (rootbp_str == NULL) {
+            free(nodes_json);
+            return make_error(ENOMEM, "failed to encode objset rootbp");
+        }
+    }
+
     char *result = json_format(
         "{"
         "\"scope\":\"%s\","
         "\"objset_id\":%s,"
         "\"object\":%llu,"
+        "\"rootbp\":%s,"
         "\"max_depth\":%llu,"
         "\"max_nodes\":%llu,"
+        "\"min_txg\":%llu,"
+        "\"max_txg\":%llu,"
         "\"count\":%d,"
         "\"truncated\":%s,"
         "\"nodes\":%s"
@@ -297,11 +332,15 @@ zdx_block_tree_from_dnode(const char *scope, uint64_t objset_id,
         scope,
         objset_json,
         (unsigned long long)objid,
+        rootbp_str ? rootbp_str : "null",
         (unsigned long long)max_depth,
         (unsigned long long)max_nodes,
+        (unsigned long long)min_txg,
+        (unsigned long long)max_txg,
         ctx.count,
         ctx.truncated ? "true" : "false",
         nodes_json);
+    free(rootbp_str);
     free(nodes_json);
     if (result == NULL)
         return make_error(ENOMEM, "failed to allocate JSON result");
@@ -311,7 +350,7 @@ zdx_block_tree_from_dnode(const char *scope, uint64_t objset_id,
 
 zdx_result_t
 zdx_mos_block_tree(zdx_pool_t *pool, uint64_t objid, uint64_t max_depth,
-    uint64_t max_nodes)
+    uint64_t max_nodes, uint64_t min_txg, uint64_t max_txg)
 {
     if (!pool || !pool->spa)
         return make_error(EINVAL, "pool not open");
@@ -327,14 +366,14 @@ zdx_mos_block_tree(zdx_pool_t *pool, uint64_t objid, uint64_t max_depth,
             (unsigned long long)objid);
 
     zdx_result_t result = zdx_block_tree_from_dnode("mos", 0, B_FALSE, objid,
-        dn, max_depth, max_nodes);
+        dn, max_depth, max_nodes, min_txg, max_txg, NULL);
     dnode_rele(dn, FTAG);
     return (result);
 }
 
 zdx_result_t
 zdx_objset_block_tree(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
-    uint64_t max_depth, uint64_t max_nodes)
+    uint64_t max_depth, uint64_t max_nodes, uint64_t min_txg, uint64_t max_txg)
 {
     if (!pool || !pool->spa)
         return make_error(EINVAL, "pool not open");
@@ -370,9 +409,439 @@ zdx_objset_block_tree(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
     }
 
     zdx_result_t result = zdx_block_tree_from_dnode("objset", objset_id,
-        B_TRUE, objid, dn, max_depth, max_nodes);
+        B_TRUE, objid, dn, max_depth, max_nodes, min_txg, max_txg, NULL);
     dnode_rele(dn, FTAG);
     dsl_dataset_rele(ds, FTAG);
     dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
     return (result);
 }
+
+/*
+ * Walk the objset's meta-dnode, i.e. the tree hanging off the objset rootbp.
+ * This works even when individual objects in the objset cannot be held.
+ */
+zdx_result_t
+zdx_objset_rootbp_tree(zdx_pool_t *pool, uint64_t objset_id,
+    uint64_t max_depth, uint64_t max_nodes, uint64_t min_txg, uint64_t max_txg)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    int err;
+
+    dsl_pool_config_enter(spa->spa_dsl_pool, FTAG);
+    err = dsl_dataset_hold_obj(spa->spa_dsl_pool, objset_id, FTAG, &ds);
+    if (err != 0) {
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+        return make_error(err, "dsl_dataset_hold_obj failed: %s",
+            strerror(err));
+    }
+
+    err = dmu_objset_from_ds(ds, &os);
+    if (err != 0) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+        return make_error(err, "dmu_objset_from_ds failed: %s",
+            strerror(err));
+    }
+
+    zdx_result_t result = zdx_block_tree_from_dnode("objset_meta", objset_id,
+        B_TRUE, DMU_META_DNODE_OBJECT, DMU_META_DNODE(os), max_depth,
+        max_nodes, min_txg, max_txg, os->os_rootbp);
+    dsl_dataset_rele(ds, FTAG);
+    dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    return (result);
+}
+
+typedef struct zdx_dva_owner_ctx {
+    uint64_t vdev;
+    uint64_t offset;
+    uint64_t end;
+    uint64_t max_blkptrs;
+    uint64_t blkptrs;
+    boolean_t truncated;
+    boolean_t found;
+    uint64_t objid;
+    int level;
+    uint64_t blkid;
+    boolean_t is_spill;
+    int dva_index;
+    dva_t dva;
+} zdx_dva_owner_ctx_t;
+
+/*
+ * Depth-first search of one block pointer subtree for a DVA overlapping
+ * [offset, end) on the requested vdev.
+ */
+static void
+zdx_dva_owner_visit(zdx_dva_owner_ctx_t *ctx, dnode_t *dn, const blkptr_t *bp,
+    uint64_t blkid, boolean_t is_spill)
+{
+    if (ctx->found || ctx->truncated)
+        return;
+    if (ctx->blkptrs >= ctx->max_blkptrs) {
+        ctx->truncated = B_TRUE;
+        return;
+    }
+    ctx->blkptrs++;
+
+    if (BP_IS_HOLE(bp) || BP_IS_EMBEDDED(bp))
+        return;
+
+    for (int i = 0; i < SPA_DVAS_PER_BP; i++) {
+        const dva_t *dva = &bp->blk_dva[i];
+        if (!DVA_IS_VALID(dva) || DVA_GET_VDEV(dva) != ctx->vdev)
+            continue;
+        uint64_t start = DVA_GET_OFFSET(dva);
+        if (start < ctx->end && ctx->offset < start + DVA_GET_ASIZE(dva)) {
+            ctx->found = B_TRUE;
+            ctx->level = BP_GET_LEVEL(bp);
+            ctx->blkid = blkid;
+            ctx->is_spill = is_spill;
+            ctx->dva_index = i;
+            ctx->dva = *dva;
+            return;
+        }
+    }
+
+    int level = BP_GET_LEVEL(bp);
+    if (level == 0 || is_spill)
+        return;
+
+    int child_slots = EPB(dn->dn_indblkshift, SPA_BLKPTRSHIFT);
+    for (int i = 0; i < child_slots && !ctx->found && !ctx->truncated; i++) {
+        uint64_t child_blkid = blkid * (uint64_t)child_slots + (uint64_t)i;
+        blkptr_t child_bp;
+        uint16_t datablkszsec = 0;
+        uint8_t indblkshift = 0;
+        if (dbuf_dnode_findbp(dn, (uint64_t)(level - 1), child_blkid,
+            &child_bp, &datablkszsec, &indblkshift) != 0)
+            continue;
+        zdx_dva_owner_visit(ctx, dn, &child_bp, child_blkid, B_FALSE);
+    }
+}
+
+static void
+zdx_dva_owner_dnode(zdx_dva_owner_ctx_t *ctx, dnode_t *dn, uint64_t objid)
+{
+    dnode_phys_t *dnp = dn->dn_phys;
+    if (dnp == NULL)
+        return;
+
+    rw_enter(&dn->dn_struct_rwlock, RW_READER);
+    for (int i = 0; i < dnp->dn_nblkptr && !ctx->found; i++)
+        zdx_dva_owner_visit(ctx, dn, &dnp->dn_blkptr[i], i, B_FALSE);
+    if (!ctx->found && (dnp->dn_flags & DNODE_FLAG_SPILL_BLKPTR) != 0)
+        zdx_dva_owner_visit(ctx, dn, DN_SPILL_BLKPTR(dnp), 0, B_TRUE);
+    rw_exit(&dn->dn_struct_rwlock);
+
+    if (ctx->found)
+        ctx->objid = objid;
+}
+
+/*
+ * Find the object whose block tree holds a DVA overlapping
+ * vdev:[offset, offset + asize). objset_id 0 searches the MOS. Scans at most
+ * scan_limit objects from `start` (object 0, the meta-dnode holding the
+ * dnode blocks themselves, included) and visits at most max_blkptrs block
+ * pointers; `next` is the object to resume from when the object budget ran
+ * out first, null otherwise.
+ */
+zdx_result_t
+zdx_dva_owner(zdx_pool_t *pool, uint64_t objset_id, uint64_t vdev,
+    uint64_t offset, uint64_t asize, uint64_t start, uint64_t scan_limit,
+    uint64_t max_blkptrs)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    zdx_result_t result;
+    int err;
+
+    if (objset_id == 0) {
+        os = spa_meta_objset(spa);
+        if (os == NULL)
+            return make_error(EINVAL, "failed to access MOS");
+    } else {
+        err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
+        if (err != 0)
+            return make_error(err, "dva_owner: failed to hold objset: %s",
+                strerror(err));
+    }
+
+    zdx_dva_owner_ctx_t ctx = {
+        .vdev = vdev,
+        .offset = offset,
+        .end = offset + (asize > 0 ? asize : 1),
+        .max_blkptrs = max_blkptrs,
+    };
+
+    uint64_t scanned = 0;
+    uint64_t object = start;
+    boolean_t more = B_FALSE;
+
+    if (start == 0) {
+        zdx_dva_owner_dnode(&ctx, DMU_META_DNODE(os), DMU_META_DNODE_OBJECT);
+        scanned++;
+    } else {
+        object = start - 1;
+    }
+
+    while (!ctx.found && !ctx.truncated &&
+        dmu_object_next(os, &object, B_FALSE, 0) == 0) {
+        if (scanned >= scan_limit) {
+            more = B_TRUE;
+            break;
+        }
+        scanned++;
+
+        dnode_t *dn = NULL;
+        if (dnode_hold(os, object, FTAG, &dn) != 0)
+            continue;
+        zdx_dva_owner_dnode(&ctx, dn, object);
+        dnode_rele(dn, FTAG);
+    }
+
+    char next_json[32] = "null";
+    if (more)
+        snprintf(next_json, sizeof (next_json), "%llu",
+            (unsigned long long)object);
+
+    char *match_json;
+    if (ctx.found) {
+        match_json = json_format(
+            "{"
+            "\"objid\":%llu,"
+            "\"level\":%d,"
+            "\"block_id\":%llu,"
+            "\"is_spill\":%s,"
+            "\"dva_index\":%d,"
+            "\"dva\":{\"vdev\":%llu,\"offset\":%llu,\"asize\":%llu,"
+            "\"is_gang\":%s}"
+            "}",
+            (unsigned long long)ctx.objid,
+            ctx.level,
+            (unsigned long long)ctx.blkid,
+            ctx.is_spill ? "true" : "false",
+            ctx.dva_index,
+            (unsigned long long)DVA_GET_VDEV(&ctx.dva),
+            (unsigned long long)DVA_GET_OFFSET(&ctx.dva),
+            (unsigned long long)DVA_GET_ASIZE(&ctx.dva),
+            DVA_GET_GANG(&ctx.dva) ? "true" : "false");
+    } else {
+        match_json = strdup("null");
+    }
+    if (match_json == NULL) {
+        result = make_error(ENOMEM, "failed to allocate match JSON");
+        goto out;
+    }
+
+    char *json = json_format(
+        "{"
+        "\"start\":%llu,"
+        "\"next\":%s,"
+        "\"scanned\":%llu,"
+        "\"blkptrs_visited\":%llu,"
+        "\"blkptr_budget_exhausted\":%s,"
+        "\"match\":%s"
+        "}",
+        (unsigned long long)start,
+        next_json,
+        (unsigned long long)scanned,
+        (unsigned long long)ctx.blkptrs,
+        ctx.truncated ? "true" : "false",
+        match_json);
+    free(match_json);
+    if (json == NULL) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+    result = make_success(json);
+
+out:
+    if (ds) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    }
+    return result;
+}
+
+typedef struct zdx_type_space {
+    uint64_t count;
+    uint64_t logical;
+    uint64_t physical;
+    uint64_t allocated;
+} zdx_type_space_t;
+
+/*
+ * Sum one block pointer subtree into `ts`: every block's allocated size, and
+ * the logical and physical (compressed) size of level-0 data blocks.
+ */
+static void
+zdx_type_space_visit(zdx_type_space_t *ts, dnode_t *dn, const blkptr_t *bp,
+    uint64_t blkid, boolean_t is_spill)
+{
+    if (BP_IS_HOLE(bp))
+        return;
+
+    int level = BP_GET_LEVEL(bp);
+    if (!BP_IS_EMBEDDED(bp))
+        ts->allocated += BP_GET_ASIZE(bp);
+    if (level == 0 || is_spill || BP_IS_EMBEDDED(bp)) {
+        ts->logical += BP_GET_LSIZE(bp);
+        ts->physical += BP_GET_PSIZE(bp);
+        return;
+    }
+
+    int child_slots = EPB(dn->dn_indblkshift, SPA_BLKPTRSHIFT);
+    for (int i = 0; i < child_slots; i++) {
+        uint64_t child_blkid = blkid * (uint64_t)child_slots + (uint64_t)i;
+        blkptr_t child_bp;
+        uint16_t datablkszsec = 0;
+        uint8_t indblkshift = 0;
+        if (dbuf_dnode_findbp(dn, (uint64_t)(level - 1), child_blkid,
+            &child_bp, &datablkszsec, &indblkshift) != 0)
+            continue;
+        zdx_type_space_visit(ts, dn, &child_bp, child_blkid, B_FALSE);
+    }
+}
+
+/*
+ * Per-DMU-type space rollup of a ZFS objset, walking every block pointer of
+ * up to scan_limit objects from `start`. `next` is the object to resume from
+ * when the object budget ran out, null once the objset is exhausted.
+ */
+zdx_result_t
+zdx_objset_space_by_type(zdx_pool_t *pool, uint64_t objset_id,
+    uint64_t start, uint64_t scan_limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    zdx_type_space_t *types = NULL;
+    char *array = NULL;
+    zdx_result_t result;
+
+    int err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
+    if (err != 0)
+        return make_error(err, "space_by_type: failed to hold objset: %s",
+            strerror(err));
+
+    types = calloc(UINT8_MAX + 1, sizeof (*types));
+    if (types == NULL) {
+        result = make_error(ENOMEM, "failed to allocate type table");
+        goto out;
+    }
+
+    uint64_t object = start > 0 ? start - 1 : 0;
+    uint64_t scanned = 0;
+    boolean_t more = B_FALSE;
+    while (dmu_object_next(os, &object, B_FALSE, 0) == 0) {
+        if (scanned >= scan_limit) {
+            more = B_TRUE;
+            break;
+        }
+        scanned++;
+
+        dnode_t *dn = NULL;
+        if (dnode_hold(os, object, FTAG, &dn) != 0)
+            continue;
+        dnode_phys_t *dnp = dn->dn_phys;
+        if (dnp != NULL) {
+            zdx_type_space_t *ts = &types[(uint8_t)dnp->dn_type];
+            ts->count++;
+            rw_enter(&dn->dn_struct_rwlock, RW_READER);
+            for (int i = 0; i < dnp->dn_nblkptr; i++)
+                zdx_type_space_visit(ts, dn, &dnp->dn_blkptr[i], i, B_FALSE);
+            if ((dnp->dn_flags & DNODE_FLAG_SPILL_BLKPTR) != 0)
+                zdx_type_space_visit(ts, dn, DN_SPILL_BLKPTR(dnp), 0, B_TRUE);
+            rw_exit(&dn->dn_struct_rwlock);
+        }
+        dnode_rele(dn, FTAG);
+    }
+
+    array = json_array_start();
+    if (array == NULL) {
+        result = make_error(ENOMEM, "failed to allocate JSON array");
+        goto out;
+    }
+    int count = 0;
+    for (int t = 0; t <= UINT8_MAX; t++) {
+        const zdx_type_space_t *ts = &types[t];
+        if (ts->count == 0)
+            continue;
+        char *name = json_string(dmu_ot_name_safe((dmu_object_type_t)t));
+        char *item = name ? json_format(
+            "{"
+            "\"type\":%d,"
+            "\"type_name\":%s,"
+            "\"count\":%llu,"
+            "\"logical_bytes\":%llu,"
+            "\"physical_bytes\":%llu,"
+            "\"allocated_bytes\":%llu"
+            "}",
+            t,
+            name,
+            (unsigned long long)ts->count,
+            (unsigned long long)ts->logical,
+            (unsigned long long)ts->physical,
+            (unsigned long long)ts->allocated) : NULL;
+        free(name);
+        char *next = item ? json_array_append(array, item) : NULL;
+        free(item);
+        if (next == NULL) {
+            result = make_error(ENOMEM, "failed to append JSON item");
+            goto out;
+        }
+        free(array);
+        array = next;
+        count++;
+    }
+
+    char *types_json = json_array_end(array, count > 0);
+    if (types_json == NULL) {
+        result = make_error(ENOMEM, "failed to finalize JSON array");
+        goto out;
+    }
+
+    char next_json[32] = "null";
+    if (more)
+        snprintf(next_json, sizeof (next_json), "%llu",
+            (unsigned long long)object);
+
+    char *json = json_format(
+        "{"
+        "\"objset_id\":%llu,"
+        "\"start\":%llu,"
+        "\"next\":%s,"
+        "\"scanned\":%llu,"
+        "\"types\":%s"
+        "}",
+        (unsigned long long)objset_id,
+        (unsigned long long)start,
+        next_json,
+        (unsigned long long)scanned,
+        types_json);
+    free(types_json);
+    if (json == NULL) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+    result = make_success(json);
+
+out:
+    free(array);
+    free(types);
+    dsl_dataset_rele(ds, FTAG);
+    dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    return result;
+}
@@ -231,6 +231,103 @@ numbers_preview(const void *data, uint64_t count, int int_len)
     return out;
 }
 
+static uint64_t
+zap_integer_at(const void *data, int int_len, uint64_t i)
+{
+    switch (int_len) {
+    case 1:
+        return ((const uint8_t *)data)[i];
+    case 2:
+        return ((const uint16_t *)data)[i];
+    case 4:
+        return ((const uint32_t *)data)[i];
+    default:
+        return ((const uint64_t *)data)[i];
+    }
+}
+
+static boolean_t
+zap_bytes_printable(const uint8_t *u8, uint64_t count)
+{
+    for (uint64_t i = 0; i < count; i++) {
+        if (u8[i] == 0) {
+            if (i + 1 != count)
+                return B_FALSE;
+            continue;
+        }
+        if (!isprint(u8[i]) && !isspace(u8[i]))
+            return B_FALSE;
+    }
+    return B_TRUE;
+}
+
+/*
+ * Classify a ZAP value from its entry metadata: 1-byte values are "string"
+ * when printable (NUL-terminated at most) and "bytes" otherwise; wider
+ * integers are "uint64" or "uint64_array" by count. Pass data == NULL when
+ * the value was not read; byte values are then reported as "bytes".
+ */
+const char *
+zap_value_type(const void *data, int int_len, uint64_t count)
+{
+    if (int_len == 1 || count == 0) {
+        if (data != NULL && count > 0 &&
+            zap_bytes_printable((const uint8_t *)data, count))
+            return "string";
+        return "bytes";
+    }
+    return count == 1 ? "uint64" : "uint64_array";
+}
+
+/*
+ * Render a ZAP value as JSON according to zap_value_type(): a number, an
+ * array of numbers (narrower integers widened), a string with the trailing
+ * NUL dropped, or a hex string for raw bytes.
+ */
+char *
+zap_value_json(const void *data, int int_len, uint64_t count)
+{
+    const char *type = zap_value_type(data, int_len, count);
+
+    if (strcmp(type, "string") == 0) {
+        size_t slen = (size_t)count;
+        if (slen > 0 && ((const uint8_t *)data)[slen - 1] == 0)
+            slen--;
+        char *tmp = dup_range((const char *)data, slen);
+        if (!tmp)
+            return NULL;
+        char *out = json_string(tmp);
+        free(tmp);
+        return out;
+    }
+    if (strcmp(type, "bytes") == 0) {
+        char *hex = bytes_to_hex((const uint8_t *)data, (size_t)count);
+        if (!hex)
+            return NULL;
+        char *out = json_string(hex);
+        free(hex);
+        return out;
+    }
+    if (count == 1)
+        return json_format("%llu",
+            (unsigned long long)zap_integer_at(data, int_len, 0));
+
+    size_t cap = 2 + (size_t)count * 21;
+    char *out = malloc(cap + 1);
+    if (!out)
+        return NULL;
+    size_t used = 0;
+    out[used++] = '[';
+    for (uint64_t i = 0; i < count; i++) {
+        used += (size_t)snprintf(out + used, cap + 1 - used, "%s%llu",
+            i > 0 ? "," : "",
+            (unsigned long long)zap_integer_at(data, int_len, i));
+    }
+    out[used++] = ']';
+    out[used] = '\0';
+    return out;
+}
+
 const char *
 dirent_type_name(uint64_t type)
 {
@@ -324,6 +421,25 @@ append_semantic_edge(char **array, int *count, uint64_t source,
     return 0;
 }
 
+/*
+ * Newest logical birth txg across a dnode's top-level block pointers, i.e.
+ * the last txg that rewrote any part of the object. 0 for holes.
+ */
+uint64_t
+dnode_top_birth_txg(const dnode_phys_t *dnp)
+{
+    uint64_t birth = 0;
+
+    for (int i = 0; i < dnp->dn_nblkptr; i++) {
+        const blkptr_t *bp = &dnp->dn_blkptr[i];
+        if (BP_IS_HOLE(bp))
+            continue;
+        if (BP_GET_LOGICAL_BIRTH(bp) > birth)
+            birth = BP_GET_LOGICAL_BIRTH(bp);
+    }
+    return birth;
+}
+
 int
 zdx_sa_setup(objset_t *os, sa_attr_type_t **tablep)
 {
@@ -566,6 +682,18 @@ zdx_pool_open(const char *name, int *err)
  */
 zdx_pool_t *
 zdx_pool_open_offline(const char *name, const char *search_paths, int *err)
+{
+    return zdx_pool_open_offline_txg(name, search_paths, 0, err);
+}
+
+/*
+ * Like zdx_pool_open_offline(), but when txg is non-zero rewind to the newest
+ * uberblock at or below it (the metadata-only equivalent of zpool import -T).
+ * A pool already imported in-process keeps whatever txg it was loaded at.
+ */
+zdx_pool_t *
+zdx_pool_open_offline_txg(const char *name, const char *search_paths,
+    uint64_t txg, int *err)
 {
     if (err)
         *err = 0;
@@ -605,6 +733,25 @@ zdx_pool_open_offline(const char *name, const char *search_paths, int *err)
         return NULL;
     }
 
+    if (txg != 0) {
+        nvlist_t *policy = NULL;
+        rc = nvlist_alloc(&policy, NV_UNIQUE_NAME, 0);
+        if (rc == 0)
+            rc = nvlist_add_uint64(policy, ZPOOL_LOAD_REQUEST_TXG, txg);
+        if (rc == 0)
+            rc = nvlist_add_uint32(policy, ZPOOL_LOAD_REWIND_POLICY,
+                ZPOOL_DO_REWIND | ZPOOL_EXTREME_REWIND);
+        if (rc == 0)
+            rc = nvlist_add_nvlist(cfg, ZPOOL_LOAD_POLICY, policy);
+        nvlist_free(policy);
+        if (rc != 0) {
+            nvlist_free(cfg);
+            if (err)
+                *err = rc;
+            return NULL;
+        }
+    }
+
     boolean_t imported_offline = B_FALSE;
     char *import_name = strdup(name);
     if (!import_name) {
@@ -659,6 +806,7 @@ zdx_pool_open_offline(const char *name, const char *search_paths, int *err)
     pool->spa = spa;
     pool->offline_mode = B_TRUE;
     pool->imported_transient = imported_offline;
+    pool->requested_txg = txg;
     return pool;
 }
 
@@ -320,7 +320,7 @@ out_unlock:
 }
 
 /*
- * DSL dir head dataset
+ * DSL dir head dataset and space accounting
  */
 zdx_result_t
 zdx_dsl_dir_head(zdx_pool_t *pool, uint64_t objid)
@@ -351,14 +351,134 @@ zdx_dsl_dir_head(zdx_pool_t *pool, uint64_t objid)
             (unsigned long long)objid);
     }
 
-    head = dsl_dir_phys(dd)->dd_head_dataset_obj;
+    dsl_dir_phys_t *ddp = dsl_dir_phys(dd);
+    head = ddp->dd_head_dataset_obj;
+    uint64_t used = ddp->dd_used_bytes;
+
+    /* Per-category space; only present once the breakdown flag is set. */
+    char breakdown[256] = "null";
+    if (ddp->dd_flags & DD_FLAG_USED_BREAKDOWN) {
+        (void) snprintf(breakdown, sizeof (breakdown),
+            "{"
+            "\"dataset\":%llu,"
+            "\"snapshots\":%llu,"
+            "\"children\":%llu,"
+            "\"refreservation\":%llu,"
+            "\"children_refreservation\":%llu"
+            "}",
+            (unsigned long long)ddp->dd_used_breakdown[DD_USED_HEAD],
+            (unsigned long long)ddp->dd_used_breakdown[DD_USED_SNAP],
+            (unsigned long long)ddp->dd_used_breakdown[DD_USED_CHILD],
+            (unsigned long long)ddp->dd_used_breakdown[DD_USED_REFRSRV],
+            (unsigned long long)ddp->dd_used_breakdown[DD_USED_CHILD_RSRV]);
+    }
+    dsl_dir_rele(dd, FTAG);
+
+    char referenced[32] = "null";
+    if (head != 0) {
+        dsl_dataset_t *ds = NULL;
+        if (dsl_dataset_hold_obj(dp, head, FTAG, &ds) == 0) {
+            (void) snprintf(referenced, sizeof (referenced), "%llu",
+                (unsigned long long)
+                dsl_dataset_phys(ds)->ds_referenced_bytes);
+            dsl_dataset_rele(ds, FTAG);
+        }
+    }
+    dsl_pool_config_exit(dp, FTAG);
+
+    char *result = json_format(
+        "{"
+        "\"dir_objid\":%llu,"
+        "\"head_dataset_obj\":%llu,"
+        "\"used_bytes\":%llu,"
+        "\"referenced_bytes\":%s,"
+        "\"used_breakdown\":%s"
+        "}",
+        (unsigned long long)objid,
+        (unsigned long long)head,
+        (unsigned long long)used,
+        referenced,
+        breakdown);
+
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * A DSL dir's own accounting from dd_phys: space charged to the subtree,
+ * the quota and reservation set at this level (0 when unset), and the number
+ * of child dirs in dd_child_dir_zapobj.
+ */
+zdx_result_t
+zdx_dsl_dir_props(zdx_pool_t *pool, uint64_t objid)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    dsl_pool_t *dp = pool->spa->spa_dsl_pool;
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+    dsl_dir_t *dd = NULL;
+    int err;
+
+    dsl_pool_config_enter(dp, FTAG);
+    err = zdx_check_is_dsl_dir_obj(mos, objid);
+    if (err != 0) {
+        dsl_pool_config_exit(dp, FTAG);
+        /* EINVAL is a wrong bonus type (or an objid no dnode can have). */
+        if (err == EINVAL)
+            return make_error(EINVAL, "object %llu is not DSL dir",
+                (unsigned long long)objid);
+        return make_error(err, "failed to read object %llu: %s",
+            (unsigned long long)objid, strerror(err));
+    }
+
+    err = dsl_dir_hold_obj(dp, objid, NULL, FTAG, &dd);
+    if (err != 0) {
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(err, "dsl_dir_hold_obj failed for object %llu",
+            (unsigned long long)objid);
+    }
+
+    dsl_dir_phys_t ddp = *dsl_dir_phys(dd);
     dsl_dir_rele(dd, FTAG);
+
+    char child_count[32] = "null";
+    uint64_t count = 0;
+    if (ddp.dd_child_dir_zapobj != 0 &&
+        zap_count(mos, ddp.dd_child_dir_zapobj, &count) == 0)
+        (void) snprintf(child_count, sizeof (child_count), "%llu",
+            (unsigned long long)count);
     dsl_pool_config_exit(dp, FTAG);
 
     char *result = json_format(
-        "{\"dir_objid\":%llu,\"head_dataset_obj\":%llu}",
+        "{"
+        "\"dir_objid\":%llu,"
+        "\"head_dataset_obj\":%llu,"
+        "\"parent_dir_obj\":%llu,"
+        "\"props_zapobj\":%llu,"
+        "\"creation_time\":%llu,"
+        "\"used_bytes\":%llu,"
+        "\"compressed_bytes\":%llu,"
+        "\"uncompressed_bytes\":%llu,"
+        "\"quota_bytes\":%llu,"
+        "\"reserved_bytes\":%llu,"
+        "\"child_count\":%s"
+        "}",
         (unsigned long long)objid,
-        (unsigned long long)head);
+        (unsigned long long)ddp.dd_head_dataset_obj,
+        (unsigned long long)ddp.dd_parent_obj,
+        (unsigned long long)ddp.dd_props_zapobj,
+        (unsigned long long)ddp.dd_creation_time,
+        (unsigned long long)ddp.dd_used_bytes,
+        (unsigned long long)ddp.dd_compressed_bytes,
+        (unsigned long long)ddp.dd_uncompressed_bytes,
+        (unsigned long long)ddp.dd_quota,
+        (unsigned long long)ddp.dd_reserved,
+        child_count);
 
     if (!result)
         return make_error(ENOMEM, "failed to allocate JSON result");
@@ -467,7 +587,8 @@ zdx_dsl_dir_by_name(zdx_pool_t *pool, const char *name)
 }
 
 /*
- * Snapshot list for a DSL directory
+ * Snapshot list for a DSL directory, with each snapshot's creation txg and
+ * time read straight from its dataset bonus (null if it cannot be held).
  */
 zdx_result_t
 zdx_dataset_snapshots(zdx_pool_t *pool, uint64_t dir_obj)
@@ -560,10 +681,31 @@ zdx_dataset_snapshots(zdx_pool_t *pool, uint64_t dir_obj)
                 return make_error(ENOMEM, "failed to allocate snapshot name");
             }
 
+            char creation_txg[32] = "null";
+            char creation_time[32] = "null";
+            dnode_t *snap_dn = NULL;
+            if (dnode_hold(mos, snap_dsobj, FTAG, &snap_dn) == 0) {
+                dmu_object_info_t snap_doi;
+                dmu_object_info_from_dnode(snap_dn, &snap_doi);
+                if (snap_doi.doi_bonus_type == DMU_OT_DSL_DATASET &&
+                    snap_dn->dn_bonuslen >= sizeof (dsl_dataset_phys_t)) {
+                    dsl_dataset_phys_t *snap =
+                        (dsl_dataset_phys_t *)DN_BONUS(snap_dn->dn_phys);
+                    (void) snprintf(creation_txg, sizeof (creation_txg),
+                        "%llu", (unsigned long long)snap->ds_creation_txg);
+                    (void) snprintf(creation_time, sizeof (creation_time),
+                        "%llu", (unsigned long long)snap->ds_creation_time);
+                }
+                dnode_rele(snap_dn, FTAG);
+            }
+
             char *item = json_format(
-                "{\"name\":%s,\"dsobj\":%llu}",
+                "{\"name\":%s,\"dsobj\":%llu,\"creation_txg\":%s,"
+                "\"creation_time\":%s}",
                 name_json,
-                (unsigned long long)snap_dsobj);
+                (unsigned long long)snap_dsobj,
+                creation_txg,
+                creation_time);
             free(name_json);
             if (!item) {
                 zap_attribute_free(attrp);
@@ -625,6 +767,337 @@ zdx_dataset_snapshots(zdx_pool_t *pool, uint64_t dir_obj)
     return make_success(result);
 }
 
+/*
+ * User holds (zfs hold) on a snapshot: the ds_userrefs_obj ZAP maps each tag
+ * to the hold's creation time in seconds.
+ */
+zdx_result_t
+zdx_snapshot_holds(zdx_pool_t *pool, uint64_t dsobj)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dnode_t *ds_dn = NULL;
+    int err = dnode_hold(mos, dsobj, FTAG, &ds_dn);
+    if (err != 0) {
+        return make_error(err, "dnode_hold failed for dataset %llu",
+            (unsigned long long)dsobj);
+    }
+
+    dmu_object_info_t ds_doi;
+    dmu_object_info_from_dnode(ds_dn, &ds_doi);
+    if (ds_doi.doi_bonus_type != DMU_OT_DSL_DATASET ||
+        ds_dn->dn_bonuslen < sizeof (dsl_dataset_phys_t)) {
+        dnode_rele(ds_dn, FTAG);
+        return make_error(EINVAL, "object %llu is not a DSL dataset",
+            (unsigned long long)dsobj);
+    }
+
+    dsl_dataset_phys_t *ds = (dsl_dataset_phys_t *)DN_BONUS(ds_dn->dn_phys);
+    uint64_t num_children = ds->ds_num_children;
+    uint64_t userrefs_obj = ds->ds_userrefs_obj;
+    dnode_rele(ds_dn, FTAG);
+
+    /* Only snapshots carry ds_num_children != 0. */
+    if (num_children == 0) {
+        return make_error(EINVAL, "dataset %llu is not a snapshot",
+            (unsigned long long)dsobj);
+    }
+
+    char *holds = json_array_start();
+    if (!holds)
+        return make_error(ENOMEM, "failed to allocate holds array");
+    int count = 0;
+
+    if (userrefs_obj != 0) {
+        zap_cursor_t zc;
+        zap_cursor_init(&zc, mos, userrefs_obj);
+        zap_attribute_t *attrp = zap_attribute_long_alloc();
+        if (!attrp) {
+            zap_cursor_fini(&zc);
+            free(holds);
+            return make_error(ENOMEM, "failed to allocate zap attribute");
+        }
+
+        while ((err = zap_cursor_retrieve(&zc, attrp)) == 0) {
+            char created[32] = "null";
+            if (attrp->za_integer_length == 8 && attrp->za_num_integers == 1)
+                (void) snprintf(created, sizeof (created), "%llu",
+                    (unsigned long long)attrp->za_first_integer);
+
+            char *tag_json = json_string(attrp->za_name);
+            char *item = tag_json ? json_format(
+                "{\"tag\":%s,\"creation_unix_sec\":%s}",
+                tag_json, created) : NULL;
+            free(tag_json);
+            char *next = item ? json_array_append(holds, item) : NULL;
+            free(item);
+            if (!next) {
+                zap_attribute_free(attrp);
+                zap_cursor_fini(&zc);
+                free(holds);
+                return make_error(ENOMEM, "failed to append hold item");
+            }
+            free(holds);
+            holds = next;
+            count++;
+            zap_cursor_advance(&zc);
+        }
+
+        zap_attribute_free(attrp);
+        zap_cursor_fini(&zc);
+        if (err != ENOENT) {
+            free(holds);
+            return make_error(err, "user hold ZAP traversal failed: %s",
+                strerror(err));
+        }
+    }
+
+    char *holds_json = json_array_end(holds, count > 0);
+    free(holds);
+    if (!holds_json)
+        return make_error(ENOMEM, "failed to finalize holds array");
+
+    char *result = json_format(
+        "{"
+        "\"dsobj\":%llu,"
+        "\"userrefs_obj\":%llu,"
+        "\"count\":%d,"
+        "\"holds\":%s"
+        "}",
+        (unsigned long long)dsobj,
+        (unsigned long long)userrefs_obj,
+        count,
+        holds_json);
+    free(holds_json);
+
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * Snapshots per snapshot-space page: each one costs a deadlist walk
+ * (dsl_dataset_space_written) under the pool config lock.
+ */
+#define ZDX_SNAPSHOT_SPACE_MAX_PAGE 1000
+
+/*
+ * Space figures for one snapshot, as zfs list -o space reports them: used is
+ * the unique space destroying it would free, written is what it references
+ * that was born after the previous snapshot (all of referenced for the first).
+ */
+static char *
+snapshot_space_item_json(dsl_pool_t *dp, const char *name, uint64_t snap_obj,
+    int *errp)
+{
+    dsl_dataset_t *ds = NULL;
+    *errp = dsl_dataset_hold_obj(dp, snap_obj, FTAG, &ds);
+    if (*errp != 0)
+        return (NULL);
+
+    dsl_dataset_phys_t *phys = dsl_dataset_phys(ds);
+    uint64_t written = phys->ds_referenced_bytes;
+    if (phys->ds_prev_snap_obj != 0) {
+        dsl_dataset_t *prev = NULL;
+        *errp = dsl_dataset_hold_obj(dp, phys->ds_prev_snap_obj, FTAG, &prev);
+        if (*errp == 0) {
+            uint64_t comp = 0, uncomp = 0;
+            *errp = dsl_dataset_space_written(prev, ds, &written, &comp,
+                &uncomp);
+            dsl_dataset_rele(prev, FTAG);
+        }
+        if (*errp != 0) {
+            dsl_dataset_rele(ds, FTAG);
+            return (NULL);
+        }
+    }
+
+    char *name_json = json_string(name);
+    char *item = name_json ? json_format(
+        "{"
+        "\"name\":%s,"
+        "\"dsobj\":%llu,"
+        "\"creation_txg\":%llu,"
+        "\"creation_time\":%llu,"
+        "\"used_bytes\":%llu,"
+        "\"unique_bytes\":%llu,"
+        "\"referenced_bytes\":%llu,"
+        "\"written_bytes\":%llu"
+        "}",
+        name_json,
+        (unsigned long long)snap_obj,
+        (unsigned long long)phys->ds_creation_txg,
+        (unsigned long long)phys->ds_creation_time,
+        (unsigned long long)phys->ds_unique_bytes,
+        (unsigned long long)phys->ds_unique_bytes,
+        (unsigned long long)phys->ds_referenced_bytes,
+        (unsigned long long)written) : NULL;
+    free(name_json);
+    dsl_dataset_rele(ds, FTAG);
+    if (!item)
+        *errp = ENOMEM;
+    return (item);
+}
+
+/*
+ * Per-snapshot space accounting for the snapshots of a DSL directory, in
+ * snapshot-name ZAP order, one page at a time: cursor is a serialized ZAP
+ * cursor (0 to start) and next is null once the listing is exhausted.
+ */
+zdx_result_t
+zdx_snapshot_space(zdx_pool_t *pool, uint64_t dir_obj, uint64_t cursor,
+    uint64_t limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dnode_t *dir_dn = NULL;
+    int err = dnode_hold(mos, dir_obj, FTAG, &dir_dn);
+    if (err != 0) {
+        return make_error(err, "dnode_hold failed for DSL dir %llu",
+            (unsigned long long)dir_obj);
+    }
+
+    dmu_object_info_t dir_doi;
+    dmu_object_info_from_dnode(dir_dn, &dir_doi);
+    if (dir_doi.doi_bonus_type != DMU_OT_DSL_DIR ||
+        dir_dn->dn_bonuslen < sizeof (dsl_dir_phys_t)) {
+        dnode_rele(dir_dn, FTAG);
+        return make_error(EINVAL, "object %llu is not DSL dir",
+            (unsigned long long)dir_obj);
+    }
+
+    dsl_dir_phys_t *dd = (dsl_dir_phys_t *)DN_BONUS(dir_dn->dn_phys);
+    uint64_t head_dataset_obj = dd->dd_head_dataset_obj;
+    dnode_rele(dir_dn, FTAG);
+
+    if (head_dataset_obj == 0) {
+        return make_error(EINVAL, "DSL dir %llu has no head dataset",
+            (unsigned long long)dir_obj);
+    }
+
+    dsl_pool_t *dp = pool->spa->spa_dsl_pool;
+    dsl_pool_config_enter(dp, FTAG);
+
+    dsl_dataset_t *head = NULL;
+    err = dsl_dataset_hold_obj(dp, head_dataset_obj, FTAG, &head);
+    if (err != 0) {
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(err, "dsl_dataset_hold_obj failed: %s",
+            strerror(err));
+    }
+    uint64_t snapnames_zapobj = dsl_dataset_phys(head)->ds_snapnames_zapobj;
+    dsl_dataset_rele(head, FTAG);
+
+    char *entries = json_array_start();
+    if (!entries) {
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(ENOMEM, "failed to allocate snapshot space array");
+    }
+    uint64_t count = 0;
+    boolean_t done = B_TRUE;
+    uint64_t next = 0;
+    limit = MIN(limit, ZDX_SNAPSHOT_SPACE_MAX_PAGE);
+
+    if (snapnames_zapobj != 0) {
+        zap_cursor_t zc;
+        zap_cursor_init_serialized(&zc, mos, snapnames_zapobj, cursor);
+        zap_attribute_t *attrp = zap_attribute_long_alloc();
+        if (!attrp) {
+            zap_cursor_fini(&zc);
+            free(entries);
+            dsl_pool_config_exit(dp, FTAG);
+            return make_error(ENOMEM, "failed to allocate zap attribute");
+        }
+
+        while ((err = zap_cursor_retrieve(&zc, attrp)) == 0) {
+            if (count >= limit) {
+                done = B_FALSE;
+                next = zap_cursor_serialize(&zc);
+                break;
+            }
+            if (attrp->za_integer_length != 8 || attrp->za_num_integers != 1) {
+                zap_cursor_advance(&zc);
+                continue;
+            }
+
+            char *item = snapshot_space_item_json(dp, attrp->za_name,
+                attrp->za_first_integer, &err);
+            char *next = item ? json_array_append(entries, item) : NULL;
+            free(item);
+            if (!next) {
+                uint64_t snap_obj = attrp->za_first_integer;
+                zap_attribute_free(attrp);
+                zap_cursor_fini(&zc);
+                free(entries);
+                dsl_pool_config_exit(dp, FTAG);
+                if (err == 0)
+                    err = ENOMEM;
+                return make_error(err, "snapshot %llu space failed: %s",
+                    (unsigned long long)snap_obj, strerror(err));
+            }
+            free(entries);
+            entries = next;
+            count++;
+            zap_cursor_advance(&zc);
+        }
+
+        zap_attribute_free(attrp);
+        zap_cursor_fini(&zc);
+        if (err != 0 && err != ENOENT) {
+            free(entries);
+            dsl_pool_config_exit(dp, FTAG);
+            return make_error(err, "snapshot ZAP traversal failed: %s",
+                strerror(err));
+        }
+    }
+    dsl_pool_config_exit(dp, FTAG);
+
+    char *entries_json = json_array_end(entries, count > 0);
+    free(entries);
+    if (!entries_json)
+        return make_error(ENOMEM, "failed to finalize snapshot space array");
+
+    char next_buf[32] = "null";
+    if (!done)
+        (void) snprintf(next_buf, sizeof (next_buf), "%llu",
+            (unsigned long long)next);
+
+    char *result = json_format(
+        "{"
+        "\"dsl_dir_obj\":%llu,"
+        "\"head_dataset_obj\":%llu,"
+        "\"cursor\":%llu,"
+        "\"limit\":%llu,"
+        "\"next\":%s,"
+        "\"count\":%llu,"
+        "\"entries\":%s"
+        "}",
+        (unsigned long long)dir_obj,
+        (unsigned long long)head_dataset_obj,
+        (unsigned long long)cursor,
+        (unsigned long long)limit,
+        next_buf,
+        (unsigned long long)count,
+        entries_json);
+    free(entries_json);
+
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
 /*
  * Snapshot count for a DSL directory (cheap metadata-only query).
  */
@@ -989,3 +1462,468 @@ zdx_dataset_lineage(zdx_pool_t *pool, uint64_t dsobj, uint64_t max_prev,
 
     return make_success(result);
 }
+
+/*
+ * Resolve a DSL dataset's full name and DSL dir. Caller holds the DSL pool
+ * config lock. On failure the name is left empty.
+ */
+static uint64_t
+zdx_dataset_name_by_obj(dsl_pool_t *dp, uint64_t dsobj, char *name,
+    size_t name_len)
+{
+    dsl_dataset_t *ds = NULL;
+    uint64_t dir_obj = 0;
+
+    name[0] = '\0';
+    if (name_len < ZFS_MAX_DATASET_NAME_LEN)
+        return 0;
+
+    if (dsl_dataset_hold_obj(dp, dsobj, FTAG, &ds) == 0) {
+        dsl_dataset_name(ds, name);
+        dir_obj = dsl_dataset_phys(ds)->ds_dir_obj;
+        dsl_dataset_rele(ds, FTAG);
+    }
+    return dir_obj;
+}
+
+/*
+ * Clone topology for a DSL dir: the origin snapshot (when the head dataset
+ * is a clone) and the clones recorded in dd_clones.
+ */
+zdx_result_t
+zdx_dataset_origin(zdx_pool_t *pool, uint64_t dir_obj)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    dsl_pool_t *dp = pool->spa->spa_dsl_pool;
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dsl_dir_t *dd = NULL;
+    int err;
+
+    dsl_pool_config_enter(dp, FTAG);
+    err = zdx_check_is_dsl_dir_obj(mos, dir_obj);
+    if (err != 0) {
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(EINVAL, "object %llu is not DSL dir",
+            (unsigned long long)dir_obj);
+    }
+
+    err = dsl_dir_hold_obj(dp, dir_obj, NULL, FTAG, &dd);
+    if (err != 0) {
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(err, "dsl_dir_hold_obj failed for object %llu",
+            (unsigned long long)dir_obj);
+    }
+
+    uint64_t origin_obj = dsl_dir_phys(dd)->dd_origin_obj;
+    uint64_t clones_obj = dsl_dir_phys(dd)->dd_clones;
+    dsl_dir_rele(dd, FTAG);
+
+    /*
+     * Datasets created after the origin feature point at $ORIGIN@$ORIGIN;
+     * only a user-visible origin snapshot makes this dataset a clone.
+     */
+    boolean_t is_clone = B_FALSE;
+    if (origin_obj != 0) {
+        dsl_dataset_t *origin_ds = NULL;
+        err = dsl_dataset_hold_obj(dp, origin_obj, FTAG, &origin_ds);
+        if (err == 0) {
+            is_clone = !zdx_dataset_in_special_dir(origin_ds,
+                ORIGIN_DIR_NAME);
+            dsl_dataset_rele(origin_ds, FTAG);
+        }
+    }
+
+    char *origin_json = NULL;
+    if (is_clone) {
+        char snap_name[ZFS_MAX_DATASET_NAME_LEN];
+        (void) zdx_dataset_name_by_obj(dp, origin_obj, snap_name,
+            sizeof (snap_name));
+        char *snap_json = json_string(snap_name);
+
+        char *at = strchr(snap_name, '@');
+        if (at != NULL)
+            *at = '\0';
+        char *origin_ds_json = json_string(snap_name);
+
+        if (snap_json && origin_ds_json) {
+            origin_json = json_format(
+                "{"
+                "\"snapshot_dsobj\":%llu,"
+                "\"snapshot_name\":%s,"
+                "\"origin_dataset_name\":%s"
+                "}",
+                (unsigned long long)origin_obj,
+                snap_json,
+                origin_ds_json);
+        }
+        free(snap_json);
+        free(origin_ds_json);
+    } else {
+        origin_json = strdup("null");
+    }
+    if (!origin_json) {
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(ENOMEM, "failed to allocate origin JSON");
+    }
+
+    char *clones = json_array_start();
+    if (!clones) {
+        free(origin_json);
+        dsl_pool_config_exit(dp, FTAG);
+        return make_error(ENOMEM, "failed to allocate clones array");
+    }
+    int clone_count = 0;
+
+    if (clones_obj != 0) {
+        zap_cursor_t zc;
+        zap_attribute_t *attrp = zap_attribute_long_alloc();
+        if (!attrp) {
+            free(clones);
+            free(origin_json);
+            dsl_pool_config_exit(dp, FTAG);
+            return make_error(ENOMEM, "failed to allocate ZAP attribute");
+        }
+
+        zap_cursor_init(&zc, mos, clones_obj);
+        while ((err = zap_cursor_retrieve(&zc, attrp)) == 0) {
+            uint64_t clone_obj = attrp->za_first_integer;
+            char clone_name[ZFS_MAX_DATASET_NAME_LEN];
+            uint64_t clone_dir = zdx_dataset_name_by_obj(dp, clone_obj,
+                clone_name, sizeof (clone_name));
+            char *name_json = json_string(clone_name);
+            char *item = name_json ? json_format(
+                "{\"dsobj\":%llu,\"dir_obj\":%llu,\"name\":%s}",
+                (unsigned long long)clone_obj,
+                (unsigned long long)clone_dir,
+                name_json) : NULL;
+            free(name_json);
+            char *next = item ? json_array_append(clones, item) : NULL;
+            free(item);
+            if (!next) {
+                zap_attribute_free(attrp);
+                zap_cursor_fini(&zc);
+                free(clones);
+                free(origin_json);
+                dsl_pool_config_exit(dp, FTAG);
+                return make_error(ENOMEM, "failed to append clone entry");
+            }
+            free(clones);
+            clones = next;
+            clone_count++;
+            zap_cursor_advance(&zc);
+        }
+        zap_attribute_free(attrp);
+        zap_cursor_fini(&zc);
+
+        if (err != 0 && err != ENOENT) {
+            free(clones);
+            free(origin_json);
+            dsl_pool_config_exit(dp, FTAG);
+            return make_error(err, "failed to iterate clones ZAP %llu: %s",
+                (unsigned long long)clones_obj, strerror(err));
+        }
+    }
+    dsl_pool_config_exit(dp, FTAG);
+
+    char *clones_json = json_array_end(clones, clone_count > 0);
+    free(clones);
+    if (!clones_json) {
+        free(origin_json);
+        return make_error(ENOMEM, "failed to finalize clones array");
+    }
+
+    char *result = json_format(
+        "{"
+        "\"dir_obj\":%llu,"
+        "\"is_clone\":%s,"
+        "\"origin_obj\":%llu,"
+        "\"origin\":%s,"
+        "\"clones_obj\":%llu,"
+        "\"clone_count\":%d,"
+        "\"clones\":%s"
+        "}",
+        (unsigned long long)dir_obj,
+        is_clone ? "true" : "false",
+        (unsigned long long)origin_obj,
+        origin_json,
+        (unsigned long long)clones_obj,
+        clone_count,
+        clones_json);
+    free(origin_json);
+    free(clones_json);
+
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+#define ZDX_VERSION_INFO_MAX_DIRS 100000
+#define ZDX_VERSION_INFO_MAX_ZPL 16
+
+typedef struct zdx_zpl_version_count {
+    uint64_t version;
+    uint64_t datasets;
+} zdx_zpl_version_count_t;
+
+static void
+zpl_version_tally(zdx_zpl_version_count_t *tally, int *ntally,
+    uint64_t version)
+{
+    int i;
+    for (i = 0; i < *ntally; i++) {
+        if (tally[i].version == version) {
+            tally[i].datasets++;
+            return;
+        }
+    }
+    if (*ntally >= ZDX_VERSION_INFO_MAX_ZPL)
+        return;
+
+    /* Keep the list sorted by version. */
+    for (i = *ntally; i > 0 && tally[i - 1].version > version; i--)
+        tally[i] = tally[i - 1];
+    tally[i].version = version;
+    tally[i].datasets = 1;
+    (*ntally)++;
+}
+
+/*
+ * Feature GUIDs with a non-zero refcount in one of the MOS feature ZAPs
+ * (features_for_read or features_for_write).
+ */
+static char *
+active_feature_names_json(objset_t *mos, uint64_t obj)
+{
+    char *array = json_array_start();
+    if (!array)
+        return NULL;
+    if (obj == 0) {
+        char *empty = json_array_end(array, 0);
+        free(array);
+        return empty;
+    }
+
+    zap_cursor_t zc;
+    zap_attribute_t *za = zap_attribute_alloc();
+    if (!za) {
+        free(array);
+        return NULL;
+    }
+
+    int count = 0;
+    boolean_t failed = B_FALSE;
+    for (zap_cursor_init(&zc, mos, obj);
+        zap_cursor_retrieve(&zc, za) == 0;
+        zap_cursor_advance(&zc)) {
+        if (za->za_integer_length != sizeof (uint64_t) ||
+            za->za_first_integer == 0)
+            continue;
+
+        char *guid_json = json_string(za->za_name);
+        char *next = guid_json ? json_array_append(array, guid_json) : NULL;
+        free(guid_json);
+        if (!next) {
+            failed = B_TRUE;
+            break;
+        }
+        free(array);
+        array = next;
+        count++;
+    }
+    zap_cursor_fini(&zc);
+    zap_attribute_free(za);
+
+    if (failed) {
+        free(array);
+        return NULL;
+    }
+    char *result = json_array_end(array, count > 0);
+    free(array);
+    return result;
+}
+
+/*
+ * On-disk format summary: SPA version, active feature GUIDs, the ZPL
+ * versions in use across head datasets, and the bootfs property.
+ */
+zdx_result_t
+zdx_pool_version_info(zdx_pool_t *pool)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_pool_t *dp = spa->spa_dsl_pool;
+    objset_t *mos = spa_meta_objset(spa);
+    if (!mos || !dp)
+        return make_error(EINVAL, "failed to access MOS");
+
+    uint64_t version = spa_version(spa);
+    boolean_t feature_flags = version >= SPA_VERSION_FEATURES;
+    char *read_json = active_feature_names_json(mos,
+        feature_flags ? spa->spa_feat_for_read_obj : 0);
+    char *compat_json = active_feature_names_json(mos,
+        feature_flags ? spa->spa_feat_for_write_obj : 0);
+    uint64_t *stack = malloc(ZDX_VERSION_INFO_MAX_DIRS * sizeof (uint64_t));
+    if (!read_json || !compat_json || !stack) {
+        free(read_json);
+        free(compat_json);
+        free(stack);
+        return make_error(ENOMEM, "failed to allocate version info");
+    }
+
+    dsl_pool_config_enter(dp, FTAG);
+
+    uint64_t bootfs_obj = spa->spa_bootfs;
+    char *bootfs_name_json = NULL;
+    if (bootfs_obj != 0) {
+        dsl_dataset_t *ds = NULL;
+        if (dsl_dataset_hold_obj(dp, bootfs_obj, FTAG, &ds) == 0) {
+            char name[ZFS_MAX_DATASET_NAME_LEN];
+            dsl_dataset_name(ds, name);
+            dsl_dataset_rele(ds, FTAG);
+            bootfs_name_json = json_string(name);
+        }
+    }
+    if (!bootfs_name_json)
+        bootfs_name_json = strdup("null");
+
+    zdx_zpl_version_count_t tally[ZDX_VERSION_INFO_MAX_ZPL];
+    int ntally = 0;
+    uint64_t zpl_datasets = 0;
+    uint64_t dirs_scanned = 0;
+    boolean_t truncated = B_FALSE;
+    int depth = 0;
+    int err = 0;
+    zap_attribute_t *za = zap_attribute_alloc();
+    if (!za)
+        err = ENOMEM;
+
+    stack[depth++] = dp->dp_root_dir_obj;
+    while (err == 0 && depth > 0) {
+        uint64_t dir_obj = stack[--depth];
+        if (dirs_scanned >= ZDX_VERSION_INFO_MAX_DIRS) {
+            truncated = B_TRUE;
+            break;
+        }
+        if (zdx_check_is_dsl_dir_obj(mos, dir_obj) != 0)
+            continue;
+
+        dsl_dir_t *dd = NULL;
+        if (dsl_dir_hold_obj(dp, dir_obj, NULL, FTAG, &dd) != 0)
+            continue;
+        uint64_t head_obj = dsl_dir_phys(dd)->dd_head_dataset_obj;
+        uint64_t zapobj = dsl_dir_phys(dd)->dd_child_dir_zapobj;
+        dsl_dir_rele(dd, FTAG);
+        dirs_scanned++;
+
+        if (head_obj != 0) {
+            dsl_dataset_t *ds = NULL;
+            objset_t *os = NULL;
+            if (dsl_dataset_hold_obj(dp, head_obj, FTAG, &ds) == 0) {
+                uint64_t zpl_version = 0;
+                if (dmu_objset_from_ds(ds, &os) == 0 &&
+                    dmu_objset_type(os) == DMU_OST_ZFS &&
+                    zap_lookup(os, MASTER_NODE_OBJ, ZPL_VERSION_STR, 8, 1,
+                    &zpl_version) == 0) {
+                    zpl_version_tally(tally, &ntally, zpl_version);
+                    zpl_datasets++;
+                }
+                dsl_dataset_rele(ds, FTAG);
+            }
+        }
+
+        if (zapobj == 0)
+            continue;
+
+        zap_cursor_t zc;
+        for (zap_cursor_init(&zc, mos, zapobj);
+            zap_cursor_retrieve(&zc, za) == 0;
+            zap_cursor_advance(&zc)) {
+            /* Skip $MOS, $FREE and $ORIGIN. */
+            if (za->za_name[0] == '$' ||
+                za->za_integer_length != sizeof (uint64_t) ||
+                za->za_first_integer == 0)
+                continue;
+            if (depth >= ZDX_VERSION_INFO_MAX_DIRS) {
+                truncated = B_TRUE;
+                break;
+            }
+            stack[depth++] = za->za_first_integer;
+        }
+        zap_cursor_fini(&zc);
+    }
+
+    if (za)
+        zap_attribute_free(za);
+    dsl_pool_config_exit(dp, FTAG);
+    free(stack);
+
+    char *zpl_json = NULL;
+    char *array = err == 0 ? json_array_start() : NULL;
+    for (int i = 0; array && i < ntally; i++) {
+        char *item = json_format("{\"version\":%llu,\"datasets\":%llu}",
+            (unsigned long long)tally[i].version,
+            (unsigned long long)tally[i].datasets);
+        char *next = item ? json_array_append(array, item) : NULL;
+        free(item);
+        free(array);
+        array = next;
+    }
+    if (array) {
+        zpl_json = json_array_end(array, ntally > 0);
+        free(array);
+    }
+
+    char bootfs_obj_buf[32] = "null";
+    if (bootfs_obj != 0) {
+        (void) snprintf(bootfs_obj_buf, sizeof (bootfs_obj_buf), "%llu",
+            (unsigned long long)bootfs_obj);
+    }
+
+    char *result = NULL;
+    if (zpl_json && bootfs_name_json) {
+        result = json_format(
+            "{"
+            "\"spa_version\":%llu,"
+            "\"feature_flags\":%s,"
+            "\"supported_by_build\":%s,"
+            "\"read_features\":%s,"
+            "\"readonly_compat_features\":%s,"
+            "\"zpl_versions\":%s,"
+            "\"zpl_datasets\":%llu,"
+            "\"dirs_scanned\":%llu,"
+            "\"truncated\":%s,"
+            "\"bootfs_objid\":%s,"
+            "\"bootfs_dataset\":%s"
+            "}",
+            (unsigned long long)version,
+            feature_flags ? "true" : "false",
+            SPA_VERSION_IS_SUPPORTED(version) ? "true" : "false",
+            read_json,
+            compat_json,
+            zpl_json,
+            (unsigned long long)zpl_datasets,
+            (unsigned long long)dirs_scanned,
+            truncated ? "true" : "false",
+            bootfs_obj_buf,
+            bootfs_name_json);
+    }
+    free(read_json);
+    free(compat_json);
+    free(zpl_json);
+    free(bootfs_name_json);
+
+    if (err != 0)
+        return make_error(err, "failed to walk DSL directories: %s",
+            strerror(err));
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+    return make_success(result);
+}
@@ -1,4 +1,8 @@
 #include "zdbdecode_internal.h"
+#include <sys/zio_checksum.h>
+#include <sys/zio_compress.h>
+#include <sys/bpobj.h>
+#include <sys/dsl_deadlist.h>
 
 /*
  * List MOS objects with optional type filter + pagination
@@ -434,6 +438,9 @@ zdx_mos_get_object(zdx_pool_t *pool, uint64_t objid)
         "\"id\":%llu,"
         "\"type\":{\"id\":%u,\"name\":%s},"
         "\"bonus_type\":{\"id\":%u,\"name\":%s},"
+        "\"bonus_type_name\":%s,"
+        "\"gen_txg\":null,"
+        "\"birth_txg\":%llu,"
         "\"is_zap\":%s,"
         "\"bonus_decoded\":%s,"
         "\"semantic_edges\":%s,"
@@ -461,6 +468,8 @@ zdx_mos_get_object(zdx_pool_t *pool, uint64_t objid)
         type_name,
         (unsigned)doi.doi_bonus_type,
         bonus_name,
+        bonus_name,
+        (unsigned long long)dnode_top_birth_txg(dnp),
         is_zap ? "true" : "false",
         bonus_decoded,
         edges_json,
@@ -577,6 +586,8 @@ blkptr_to_json(const blkptr_t *bp, int index, int is_spill)
         "\"compression\":%d,"
         "\"dedup\":%s,"
         "\"ndvas\":%d,"
+        "\"redundancy\":\"%s\","
+        "\"gang_warning\":%s,"
         "\"dvas\":%s"
         "}",
         index,
@@ -597,6 +608,8 @@ blkptr_to_json(const blkptr_t *bp, int index, int is_spill)
         compress,
         dedup ? "true" : "false",
         ndvas,
+        zdx_redundancy_label(ndvas),
+        is_gang ? "\"" ZDX_GANG_BLOCK_WARNING "\"" : "null",
         dvas_json);
 
     free(dvas_json);
@@ -708,6 +721,69 @@ zdx_mos_get_blkptrs(zdx_pool_t *pool, uint64_t objid)
     return make_success(result);
 }
 
+/*
+ * Decode a caller-supplied raw blkptr (native byte order)
+ */
+zdx_result_t
+zdx_decode_blkptr(zdx_pool_t *pool, const uint8_t *data, size_t len)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+    if (!data || len != sizeof (blkptr_t))
+        return make_error(EINVAL, "blkptr must be exactly %zu bytes, got %zu",
+            sizeof (blkptr_t), len);
+
+    blkptr_t bp;
+    memcpy(&bp, data, sizeof (bp));
+
+    char *bp_json = blkptr_to_json(&bp, 0, 0);
+    if (!bp_json)
+        return make_error(ENOMEM, "failed to allocate blkptr JSON");
+
+    int checksum = BP_GET_CHECKSUM(&bp);
+    int compress = BP_GET_COMPRESS(&bp);
+    const char *checksum_name = checksum < ZIO_CHECKSUM_FUNCTIONS ?
+        zio_checksum_table[checksum].ci_name : "unknown";
+    const char *compress_name = compress < ZIO_COMPRESS_FUNCTIONS ?
+        zio_compress_table[compress].ci_name : "unknown";
+
+    char *embedded_json = NULL;
+    if (BP_IS_EMBEDDED(&bp)) {
+        embedded_json = json_format(
+            "{\"etype\":%d,\"lsize\":%llu,\"psize\":%llu}",
+            (int)BPE_GET_ETYPE(&bp),
+            (unsigned long long)BPE_GET_LSIZE(&bp),
+            (unsigned long long)BPE_GET_PSIZE(&bp));
+    } else {
+        embedded_json = strdup("null");
+    }
+    if (!embedded_json) {
+        free(bp_json);
+        return make_error(ENOMEM, "failed to allocate embedded JSON");
+    }
+
+    char *result = json_format(
+        "{"
+        "\"blkptr\":%s,"
+        "\"type_name\":\"%s\","
+        "\"checksum_name\":\"%s\","
+        "\"compression_name\":\"%s\","
+        "\"embedded\":%s"
+        "}",
+        bp_json,
+        dmu_ot_name_safe(BP_GET_TYPE(&bp)),
+        checksum_name ? checksum_name : "unknown",
+        compress_name ? compress_name : "unknown",
+        embedded_json);
+    free(bp_json);
+    free(embedded_json);
+
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
 /*
  * Unified object fetch: dnode + blkptrs + optional ZAP
  */
@@ -774,3 +850,566 @@ zdx_obj_get(zdx_pool_t *pool, uint64_t objid)
 
     return make_success(result);
 }
+
+/* Largest packed nvlist we are willing to unpack in one request. */
+#define ZDX_NVLIST_MAX_PACKED (16ULL << 20)
+
+/*
+ * Unpack a MOS object's data as an XDR/native packed nvlist. The packed
+ * length comes from a DMU_OT_PACKED_NVLIST_SIZE bonus when present, and
+ * from the object's data length otherwise.
+ */
+zdx_result_t
+zdx_decode_nvlist(zdx_pool_t *pool, uint64_t objid)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dmu_object_info_t doi;
+    int err = dmu_object_info(mos, objid, &doi);
+    if (err != 0)
+        return make_error(err, "dmu_object_info failed for object %llu: %s",
+            (unsigned long long)objid, strerror(err));
+
+    uint64_t packed_size = doi.doi_max_offset;
+    boolean_t size_from_bonus = B_FALSE;
+    if (doi.doi_bonus_type == DMU_OT_PACKED_NVLIST_SIZE &&
+        doi.doi_bonus_size >= sizeof (uint64_t)) {
+        dmu_buf_t *db = NULL;
+        err = dmu_bonus_hold(mos, objid, FTAG, &db);
+        if (err != 0)
+            return make_error(err, "dmu_bonus_hold failed for object %llu: %s",
+                (unsigned long long)objid, strerror(err));
+        packed_size = *(uint64_t *)db->db_data;
+        dmu_buf_rele(db, FTAG);
+        size_from_bonus = B_TRUE;
+    }
+
+    if (packed_size == 0 || packed_size > doi.doi_max_offset)
+        return make_error(EINVAL,
+            "object %llu is not a packed nvlist: packed size %llu, data size %llu",
+            (unsigned long long)objid, (unsigned long long)packed_size,
+            (unsigned long long)doi.doi_max_offset);
+    if (packed_size > ZDX_NVLIST_MAX_PACKED)
+        return make_error(EFBIG, "object %llu nvlist too large (%llu bytes)",
+            (unsigned long long)objid, (unsigned long long)packed_size);
+
+    char *packed = malloc(packed_size);
+    if (!packed)
+        return make_error(ENOMEM, "failed to allocate nvlist buffer");
+
+    err = dmu_read(mos, objid, 0, packed_size, packed, DMU_READ_PREFETCH);
+    if (err != 0) {
+        free(packed);
+        return make_error(err, "dmu_read failed for object %llu: %s",
+            (unsigned long long)objid, strerror(err));
+    }
+
+    nvlist_t *nvl = NULL;
+    err = nvlist_unpack(packed, packed_size, &nvl, 0);
+    free(packed);
+    if (err != 0)
+        return make_error(EINVAL, "object %llu is not a packed nvlist: %s",
+            (unsigned long long)objid, strerror(err));
+
+    char *nvlist_json = nvlist_to_json_string(nvl);
+    nvlist_free(nvl);
+    if (!nvlist_json)
+        return make_error(ENOMEM, "failed to encode nvlist as JSON");
+
+    char *type_name = json_string(dmu_ot_name_safe(doi.doi_type));
+    if (!type_name) {
+        free(nvlist_json);
+        return make_error(ENOMEM, "failed to allocate JSON strings");
+    }
+
+    char *result = json_format(
+        "{"
+        "\"object\":%llu,"
+        "\"type_name\":%s,"
+        "\"packed_size\":%llu,"
+        "\"size_from_bonus\":%s,"
+        "\"nvlist\":%s"
+        "}",
+        (unsigned long long)objid,
+        type_name,
+        (unsigned long long)packed_size,
+        size_from_bonus ? "true" : "false",
+        nvlist_json);
+    free(type_name);
+    free(nvlist_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * Read a logical byte range of a MOS object as hex. Mirrors
+ * zdx_objset_read_data() (same 1 MiB cap and payload shape) against the
+ * meta-objset, for inspecting packed structures directly.
+ */
+zdx_result_t
+zdx_mos_read_data(zdx_pool_t *pool, uint64_t objid, uint64_t offset,
+    uint64_t limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+    if (limit == 0)
+        return make_error(EINVAL, "limit must be > 0");
+
+    const uint64_t max_read = 1ULL << 20; /* 1 MiB hard cap per request */
+    uint64_t request_limit = limit;
+    if (request_limit > max_read)
+        request_limit = max_read;
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dmu_object_info_t doi;
+    int err = dmu_object_info(mos, objid, &doi);
+    if (err != 0)
+        return make_error(err, "dmu_object_info failed for object %llu: %s",
+            (unsigned long long)objid, strerror(err));
+
+    uint64_t max_offset = doi.doi_max_offset;
+    uint64_t read_size = 0;
+    if (offset < max_offset) {
+        read_size = max_offset - offset;
+        if (read_size > request_limit)
+            read_size = request_limit;
+    }
+
+    char *hex = NULL;
+    if (read_size > 0) {
+        void *buf = malloc((size_t)read_size);
+        if (!buf)
+            return make_error(ENOMEM, "failed to allocate read buffer");
+
+        err = dmu_read(mos, objid, offset, read_size, buf, 0);
+        if (err != 0) {
+            free(buf);
+            return make_error(err, "dmu_read failed for object %llu: %s",
+                (unsigned long long)objid, strerror(err));
+        }
+
+        hex = bytes_to_hex((const uint8_t *)buf, (size_t)read_size);
+        free(buf);
+    } else {
+        hex = strdup("");
+    }
+    if (!hex)
+        return make_error(ENOMEM, "failed to encode read buffer to hex");
+
+    char *hex_json = json_string(hex);
+    free(hex);
+    char *type_name = json_string(dmu_ot_name_safe(doi.doi_type));
+    if (!hex_json || !type_name) {
+        free(hex_json);
+        free(type_name);
+        return make_error(ENOMEM, "failed to allocate JSON strings");
+    }
+
+    boolean_t eof = (offset >= max_offset) || (offset + read_size >= max_offset);
+    char *result = json_format(
+        "{"
+        "\"id\":%llu,"
+        "\"type_name\":%s,"
+        "\"offset\":%llu,"
+        "\"requested\":%llu,"
+        "\"size\":%llu,"
+        "\"max_offset\":%llu,"
+        "\"eof\":%s,"
+        "\"data_hex\":%s"
+        "}",
+        (unsigned long long)objid,
+        type_name,
+        (unsigned long long)offset,
+        (unsigned long long)request_limit,
+        (unsigned long long)read_size,
+        (unsigned long long)max_offset,
+        eof ? "true" : "false",
+        hex_json);
+    free(type_name);
+    free(hex_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * Page through a MOS object array (DMU_OT_OBJECT_ARRAY / DMU_OT_BPOBJ_SUBOBJ),
+ * optionally resolving each referenced object's type.
+ */
+zdx_result_t
+zdx_obj_array(zdx_pool_t *pool, uint64_t objid, uint64_t cursor,
+    uint64_t limit, int with_types)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dmu_object_info_t doi;
+    int err = dmu_object_info(mos, objid, &doi);
+    if (err != 0)
+        return make_error(err, "dmu_object_info failed for object %llu: %s",
+            (unsigned long long)objid, strerror(err));
+
+    if (doi.doi_type != DMU_OT_OBJECT_ARRAY &&
+        doi.doi_type != DMU_OT_BPOBJ_SUBOBJ)
+        return make_error(EINVAL, "object %llu is not an object array (type %s)",
+            (unsigned long long)objid, dmu_ot_name_safe(doi.doi_type));
+
+    uint64_t total = doi.doi_max_offset / sizeof (uint64_t);
+    uint64_t count = 0;
+    if (cursor < total)
+        count = MIN(limit, total - cursor);
+
+    uint64_t *ids = NULL;
+    if (count > 0) {
+        ids = malloc(count * sizeof (uint64_t));
+        if (!ids)
+            return make_error(ENOMEM, "failed to allocate array buffer");
+        err = dmu_read(mos, objid, cursor * sizeof (uint64_t),
+            count * sizeof (uint64_t), ids, DMU_READ_PREFETCH);
+        if (err != 0) {
+            free(ids);
+            return make_error(err, "dmu_read failed for object %llu: %s",
+                (unsigned long long)objid, strerror(err));
+        }
+    }
+
+    char *array = json_array_start();
+    if (!array) {
+        free(ids);
+        return make_error(ENOMEM, "failed to allocate JSON array");
+    }
+
+    for (uint64_t i = 0; i < count; i++) {
+        char *type_json = NULL;
+        dmu_object_info_t child;
+        if (!with_types || ids[i] == 0 ||
+            dmu_object_info(mos, ids[i], &child) != 0) {
+            type_json = strdup("null");
+        } else {
+            char *name = json_string(dmu_ot_name_safe(child.doi_type));
+            type_json = name
+                ? json_format("{\"id\":%u,\"name\":%s}",
+                    (unsigned)child.doi_type, name)
+                : NULL;
+            free(name);
+        }
+        if (!type_json) {
+            free(array);
+            free(ids);
+            return make_error(ENOMEM, "failed to allocate JSON strings");
+        }
+
+        char *item = json_format("{\"index\":%llu,\"objid\":%llu,\"type\":%s}",
+            (unsigned long long)(cursor + i), (unsigned long long)ids[i],
+            type_json);
+        free(type_json);
+        if (!item) {
+            free(array);
+            free(ids);
+            return make_error(ENOMEM, "failed to allocate JSON item");
+        }
+
+        char *new_array = json_array_append(array, item);
+        free(item);
+        free(array);
+        if (!new_array) {
+            free(ids);
+            return make_error(ENOMEM, "failed to append JSON item");
+        }
+        array = new_array;
+    }
+    free(ids);
+
+    char *entries_json = json_array_end(array, count > 0);
+    free(array);
+    if (!entries_json)
+        return make_error(ENOMEM, "failed to finalize JSON array");
+
+    char *next_json = (cursor + count < total)
+        ? json_format("%llu", (unsigned long long)(cursor + count))
+        : strdup("null");
+    char *type_name = json_string(dmu_ot_name_safe(doi.doi_type));
+    if (!next_json || !type_name) {
+        free(entries_json);
+        free(next_json);
+        free(type_name);
+        return make_error(ENOMEM, "failed to allocate JSON strings");
+    }
+
+    char *result = json_format(
+        "{"
+        "\"object\":%llu,"
+        "\"type_name\":%s,"
+        "\"total\":%llu,"
+        "\"cursor\":%llu,"
+        "\"count\":%llu,"
+        "\"next\":%s,"
+        "\"entries\":%s"
+        "}",
+        (unsigned long long)objid,
+        type_name,
+        (unsigned long long)total,
+        (unsigned long long)cursor,
+        (unsigned long long)count,
+        next_json,
+        entries_json);
+    free(entries_json);
+    free(next_json);
+    free(type_name);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * Space accounting for a bpobj or deadlist object, read straight from its
+ * bonus header. Deadlists report the number of per-txg sub-bpobjs as
+ * entries; bpobjs report their blkptr count. Fields absent from older
+ * bpobj header versions are null.
+ */
+zdx_result_t
+zdx_bpobj_stats(zdx_pool_t *pool, uint64_t objid)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dmu_object_info_t doi;
+    int err = dmu_object_info(mos, objid, &doi);
+    if (err != 0) {
+        return make_error(err, "dmu_object_info %llu failed: %s",
+            (unsigned long long)objid, strerror(err));
+    }
+
+    if (doi.doi_type != DMU_OT_BPOBJ && doi.doi_type != DMU_OT_DEADLIST) {
+        return make_error(EINVAL,
+            "object %llu is type \"%s\" (%u); expected bpobj or deadlist",
+            (unsigned long long)objid, dmu_ot_name_safe(doi.doi_type),
+            doi.doi_type);
+    }
+
+    dmu_buf_t *db = NULL;
+    err = dmu_bonus_hold(mos, objid, FTAG, &db);
+    if (err != 0) {
+        return make_error(err, "dmu_bonus_hold %llu failed: %s",
+            (unsigned long long)objid, strerror(err));
+    }
+
+    const char *kind;
+    uint64_t entries = 0, bytes = 0;
+    char comp_buf[32], uncomp_buf[32], subobjs_buf[32], nsub_buf[32];
+    const char *comp_json = "null";
+    const char *uncomp_json = "null";
+    const char *subobjs_json = "null";
+    const char *nsub_json = "null";
+
+    if (doi.doi_type == DMU_OT_DEADLIST) {
+        kind = "deadlist";
+        dsl_deadlist_phys_t dlp = { 0 };
+        (void) memcpy(&dlp, db->db_data,
+            MIN(db->db_size, sizeof (dlp)));
+        bytes = dlp.dl_used;
+        (void) snprintf(comp_buf, sizeof (comp_buf), "%llu",
+            (unsigned long long)dlp.dl_comp);
+        (void) snprintf(uncomp_buf, sizeof (uncomp_buf), "%llu",
+            (unsigned long long)dlp.dl_uncomp);
+        comp_json = comp_buf;
+        uncomp_json = uncomp_buf;
+    } else {
+        kind = "bpobj";
+        bpobj_phys_t bpp = { 0 };
+        (void) memcpy(&bpp, db->db_data,
+            MIN(db->db_size, sizeof (bpp)));
+        entries = bpp.bpo_num_blkptrs;
+        bytes = bpp.bpo_bytes;
+        if (db->db_size >= BPOBJ_SIZE_V1) {
+            (void) snprintf(comp_buf, sizeof (comp_buf), "%llu",
+                (unsigned long long)bpp.bpo_comp);
+            (void) snprintf(uncomp_buf, sizeof (uncomp_buf), "%llu",
+                (unsigned long long)bpp.bpo_uncomp);
+            comp_json = comp_buf;
+            uncomp_json = uncomp_buf;
+        }
+        if (db->db_size >= BPOBJ_SIZE_V2) {
+            (void) snprintf(subobjs_buf, sizeof (subobjs_buf), "%llu",
+                (unsigned long long)bpp.bpo_subobjs);
+            (void) snprintf(nsub_buf, sizeof (nsub_buf), "%llu",
+                (unsigned long long)bpp.bpo_num_subobjs);
+            subobjs_json = subobjs_buf;
+            nsub_json = nsub_buf;
+        }
+    }
+    dmu_buf_rele(db, FTAG);
+
+    if (doi.doi_type == DMU_OT_DEADLIST) {
+        err = zap_count(mos, objid, &entries);
+        if (err != 0) {
+            return make_error(err, "zap_count %llu failed: %s",
+                (unsigned long long)objid, strerror(err));
+        }
+    }
+
+    char *result = json_format(
+        "{"
+        "\"objid\":%llu,"
+        "\"kind\":\"%s\","
+        "\"entries\":%llu,"
+        "\"bytes\":%llu,"
+        "\"comp\":%s,"
+        "\"uncomp\":%s,"
+        "\"subobjs\":%s,"
+        "\"num_subobjs\":%s"
+        "}",
+        (unsigned long long)objid,
+        kind,
+        (unsigned long long)entries,
+        (unsigned long long)bytes,
+        comp_json,
+        uncomp_json,
+        subobjs_json,
+        nsub_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * Page through the block pointers stored directly in a bpobj. Sub-bpobjs
+ * are not followed; the subobj list object and count are reported so the
+ * caller can recurse with further requests.
+ */
+zdx_result_t
+zdx_bpobj_iter(zdx_pool_t *pool, uint64_t objid, uint64_t cursor,
+    uint64_t limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    objset_t *mos = spa_meta_objset(pool->spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    dmu_object_info_t doi;
+    int err = dmu_object_info(mos, objid, &doi);
+    if (err != 0) {
+        return make_error(err, "dmu_object_info %llu failed: %s",
+            (unsigned long long)objid, strerror(err));
+    }
+    if (doi.doi_type != DMU_OT_BPOBJ) {
+        return make_error(EINVAL,
+            "object %llu is type \"%s\" (%u); expected bpobj",
+            (unsigned long long)objid, dmu_ot_name_safe(doi.doi_type),
+            doi.doi_type);
+    }
+
+    dmu_buf_t *db = NULL;
+    err = dmu_bonus_hold(mos, objid, FTAG, &db);
+    if (err != 0) {
+        return make_error(err, "dmu_bonus_hold %llu failed: %s",
+            (unsigned long long)objid, strerror(err));
+    }
+    bpobj_phys_t bpp = { 0 };
+    (void) memcpy(&bpp, db->db_data, MIN(db->db_size, sizeof (bpp)));
+    boolean_t has_subobj_fields = db->db_size >= BPOBJ_SIZE_V2;
+    dmu_buf_rele(db, FTAG);
+
+    uint64_t total = bpp.bpo_num_blkptrs;
+    uint64_t subobjs = has_subobj_fields ? bpp.bpo_subobjs : 0;
+    uint64_t num_subobjs = has_subobj_fields ? bpp.bpo_num_subobjs : 0;
+    if (cursor > total)
+        cursor = total;
+    uint64_t count = MIN(limit, total - cursor);
+
+    blkptr_t *bps = NULL;
+    if (count > 0) {
+        bps = malloc(count * sizeof (blkptr_t));
+        if (!bps)
+            return make_error(ENOMEM, "failed to allocate blkptr buffer");
+        err = dmu_read(mos, objid, cursor * sizeof (blkptr_t),
+            count * sizeof (blkptr_t), bps, DMU_READ_PREFETCH);
+        if (err != 0) {
+            free(bps);
+            return make_error(err, "dmu_read bpobj %llu failed: %s",
+                (unsigned long long)objid, strerror(err));
+        }
+    }
+
+    char *array = json_array_start();
+    if (!array) {
+        free(bps);
+        return make_error(ENOMEM, "failed to allocate JSON array");
+    }
+    for (uint64_t i = 0; i < count; i++) {
+        char *item = blkptr_to_json(&bps[i], (int)(cursor + i), 0);
+        char *next = item ? json_array_append(array, item) : NULL;
+        free(item);
+        if (!next) {
+            free(array);
+            free(bps);
+            return make_error(ENOMEM, "failed to append blkptr JSON");
+        }
+        free(array);
+        array = next;
+    }
+    free(bps);
+
+    char *blkptrs_json = json_array_end(array, count > 0);
+    free(array);
+    if (!blkptrs_json)
+        return make_error(ENOMEM, "failed to finalize blkptr array");
+
+    char next_buf[32] = "null";
+    if (cursor + count < total) {
+        (void) snprintf(next_buf, sizeof (next_buf), "%llu",
+            (unsigned long long)(cursor + count));
+    }
+
+    char *result = json_format(
+        "{"
+        "\"objid\":%llu,"
+        "\"cursor\":%llu,"
+        "\"next\":%s,"
+        "\"count\":%llu,"
+        "\"total_blkptrs\":%llu,"
+        "\"bytes\":%llu,"
+        "\"subobjs\":%llu,"
+        "\"num_subobjs\":%llu,"
+        "\"has_subobjs\":%s,"
+        "\"blkptrs\":%s"
+        "}",
+        (unsigned long long)objid,
+        (unsigned long long)cursor,
+        next_buf,
+        (unsigned long long)count,
+        (unsigned long long)total,
+        (unsigned long long)bpp.bpo_bytes,
+        (unsigned long long)subobjs,
+        (unsigned long long)num_subobjs,
+        num_subobjs > 0 ? "true" : "false",
+        blkptrs_json);
+    free(blkptrs_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
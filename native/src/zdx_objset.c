@@ -1,4 +1,7 @@
 #include "zdbdecode_internal.h"
+#include <sys/sa_impl.h>
+#include <pwd.h>
+#include <grp.h>
 
 /*
  * Master node contains a mix of object references and scalar config values.
@@ -35,7 +38,7 @@ master_node_key_is_object_ref(const char *name)
  * On failure: the dataset hold and config lock are already released; the
  * caller receives only the error code.
  */
-static int
+int
 zdx_hold_objset_by_dsobj(spa_t *spa, uint64_t dsobj, dsl_dataset_t **dsp,
     objset_t **osp, const void *tag)
 {
@@ -164,6 +167,8 @@ objset_blkptr_to_json(const blkptr_t *bp, int index, int is_spill)
         "\"compression\":%d,"
         "\"dedup\":%s,"
         "\"ndvas\":%d,"
+        "\"redundancy\":\"%s\","
+        "\"gang_warning\":%s,"
         "\"dvas\":%s"
         "}",
         index,
@@ -184,6 +189,8 @@ objset_blkptr_to_json(const blkptr_t *bp, int index, int is_spill)
         compress,
         dedup ? "true" : "false",
         ndvas,
+        zdx_redundancy_label(ndvas),
+        is_gang ? "\"" ZDX_GANG_BLOCK_WARNING "\"" : "null",
         dvas_json);
 
     free(dvas_json);
@@ -191,11 +198,14 @@ objset_blkptr_to_json(const blkptr_t *bp, int index, int is_spill)
 }
 
 /*
- * List objects from a ZFS objset with optional type filter + pagination.
+ * List objects from a ZFS objset with optional type/size filters +
+ * pagination. The size filters use the allocated bytes already reported by
+ * dmu_object_info(), so they add no per-object reads; like the type filter,
+ * a sparse match can walk many objects to fill one page.
  */
 zdx_result_t
 zdx_objset_list_objects(zdx_pool_t *pool, uint64_t objset_id, int type_filter,
-    uint64_t start, uint64_t limit)
+    uint64_t start, uint64_t limit, uint64_t min_size, int allocated_only)
 {
     if (!pool || !pool->spa)
         return make_error(EINVAL, "pool not open");
@@ -253,6 +263,10 @@ zdx_objset_list_objects(zdx_pool_t *pool, uint64_t objset_id, int type_filter,
             doi.doi_type != (dmu_object_type_t)type_filter)
             continue;
 
+        uint64_t used_bytes = doi.doi_physical_blocks_512 << 9;
+        if (used_bytes < min_size || (allocated_only && used_bytes == 0))
+            continue;
+
         char *type_name = json_string(dmu_ot_name_safe(doi.doi_type));
         char *bonus_name = json_string(dmu_ot_name_safe(doi.doi_bonus_type));
         if (!type_name || !bonus_name) {
@@ -270,13 +284,17 @@ zdx_objset_list_objects(zdx_pool_t *pool, uint64_t objset_id, int type_filter,
             "\"type\":%u,"
             "\"type_name\":%s,"
             "\"bonus_type\":%u,"
-            "\"bonus_type_name\":%s"
+            "\"bonus_type_name\":%s,"
+            "\"used_bytes\":%llu,"
+            "\"logical_bytes\":%llu"
             "}",
             (unsigned long long)object,
             (unsigned)doi.doi_type,
             type_name,
             (unsigned)doi.doi_bonus_type,
-            bonus_name);
+            bonus_name,
+            (unsigned long long)used_bytes,
+            (unsigned long long)doi.doi_max_offset);
         free(type_name);
         free(bonus_name);
 
@@ -607,6 +625,86 @@ zdx_objset_dir_entries(zdx_pool_t *pool, uint64_t objset_id,
     return make_success(result);
 }
 
+typedef enum zdx_walk_match {
+    ZDX_WALK_EXACT,
+    ZDX_WALK_NORMALIZE,
+    ZDX_WALK_CASE_INSENSITIVE,
+    ZDX_WALK_MIXED,
+} zdx_walk_match_t;
+
+static const char *
+walk_match_name(zdx_walk_match_t mode)
+{
+    switch (mode) {
+    case ZDX_WALK_NORMALIZE:
+        return "normalize";
+    case ZDX_WALK_CASE_INSENSITIVE:
+        return "case_insensitive";
+    case ZDX_WALK_MIXED:
+        return "mixed";
+    default:
+        return "exact";
+    }
+}
+
+/*
+ * Pick the directory matching mode from the ZPL casesensitivity and
+ * normalization properties stored in the master node. Datasets that predate
+ * these properties have neither entry and keep exact matching.
+ */
+static zdx_walk_match_t
+walk_match_mode(objset_t *os, uint64_t *case_out, uint64_t *norm_out)
+{
+    uint64_t casesens = ZFS_CASE_SENSITIVE;
+    uint64_t norm = 0;
+
+    (void) zap_lookup(os, MASTER_NODE_OBJ, zfs_prop_to_name(ZFS_PROP_CASE),
+        8, 1, &casesens);
+    (void) zap_lookup(os, MASTER_NODE_OBJ,
+        zfs_prop_to_name(ZFS_PROP_NORMALIZE), 8, 1, &norm);
+    *case_out = casesens;
+    *norm_out = norm;
+
+    if (casesens == ZFS_CASE_INSENSITIVE)
+        return ZDX_WALK_CASE_INSENSITIVE;
+    if (casesens == ZFS_CASE_MIXED)
+        return ZDX_WALK_MIXED;
+    if (norm != 0)
+        return ZDX_WALK_NORMALIZE;
+    return ZDX_WALK_EXACT;
+}
+
+/*
+ * Look up one path component the way the ZPL would for this dataset.
+ * The directory ZAPs carry the dataset's normalization flags, so
+ * MT_NORMALIZE folds case only where the dataset asked for it. Mixed
+ * datasets prefer an exact hit and fall back to a case-insensitive one.
+ * realname receives the on-disk spelling of the matched entry.
+ */
+static int
+walk_lookup_component(objset_t *os, uint64_t dir, const char *name,
+    zdx_walk_match_t mode, uint64_t *dirent, char *realname, size_t rnlen)
+{
+    int err;
+
+    if (mode == ZDX_WALK_EXACT || mode == ZDX_WALK_MIXED) {
+        err = zap_lookup(os, dir, name, 8, 1, dirent);
+        if (err == 0) {
+            (void) strlcpy(realname, name, rnlen);
+            return 0;
+        }
+        if (mode == ZDX_WALK_EXACT || err != ENOENT)
+            return err;
+    }
+
+    matchtype_t mt = MT_NORMALIZE;
+    if (mode == ZDX_WALK_NORMALIZE)
+        mt |= MT_MATCH_CASE;
+
+    return zap_lookup_norm(os, dir, name, 8, 1, dirent, mt, realname, rnlen,
+        NULL);
+}
+
 /*
  * Path walk within a ZPL objset.
  */
@@ -634,6 +732,7 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
     char *resolved_json = NULL;
     char *remaining_json = NULL;
     char *error_json = NULL;
+    char *match_json = NULL;
     const char *error_kind = NULL;
 
     err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
@@ -656,6 +755,21 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
         goto out;
     }
 
+    uint64_t casesens = 0, norm = 0;
+    zdx_walk_match_t match_mode = walk_match_mode(os, &casesens, &norm);
+    const char *case_name = "unknown";
+    const char *norm_name = "unknown";
+    (void) zfs_prop_index_to_string(ZFS_PROP_CASE, casesens, &case_name);
+    (void) zfs_prop_index_to_string(ZFS_PROP_NORMALIZE, norm, &norm_name);
+    match_json = json_format(
+        "{\"mode\":\"%s\",\"casesensitivity\":\"%s\","
+        "\"normalization\":\"%s\"}",
+        walk_match_name(match_mode), case_name, norm_name);
+    if (!match_json) {
+        result = make_error(ENOMEM, "failed to allocate match JSON");
+        goto out;
+    }
+
     const char *p = input_path;
     while (*p == '/')
         p++;
@@ -688,7 +802,10 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
             "\"type\":%llu,"
             "\"type_name\":\"%s\","
             "\"found\":true,"
-            "\"error\":null"
+            "\"error\":null,"
+            "\"lookup_errno\":0,"
+            "\"last_dir_obj\":%llu,"
+            "\"match\":%s"
             "}",
             (unsigned long long)objset_id,
             path_json,
@@ -697,7 +814,9 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
             remaining_json,
             (unsigned long long)root_obj,
             (unsigned long long)dtype,
-            dtype_name);
+            dtype_name,
+            (unsigned long long)root_obj,
+            match_json);
         if (!json) {
             result = make_error(ENOMEM, "failed to allocate JSON result");
             goto out;
@@ -720,6 +839,9 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
     uint64_t current_type = 4;
     const char *current_type_name = dirent_type_name(current_type);
     int found = 1;
+    /* Deepest directory reached, for recovery when the walk stops early. */
+    uint64_t last_dir = root_obj;
+    int lookup_errno = 0;
 
     while (*p) {
         while (*p == '/')
@@ -740,14 +862,20 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
         }
 
         uint64_t dirent = 0;
-        err = zap_lookup(os, current, name, 8, 1, &dirent);
+        char realname[ZAP_MAXNAMELEN];
+        err = walk_lookup_component(os, current, name, match_mode, &dirent,
+            realname, sizeof (realname));
         if (err != 0) {
             free(name);
             found = 0;
-            error_kind = "not_found";
+            error_kind = err == ENOENT ? "not_found" : "unreadable";
+            lookup_errno = err;
             remaining = strdup(start);
             break;
         }
+        free(name);
+        /* Report the on-disk spelling, which may differ in case or form. */
+        len = strlen(realname);
 
         uint64_t child_obj = ZFS_DIRENT_OBJ(dirent);
         uint64_t dtype = ZFS_DIRENT_TYPE(dirent);
@@ -757,7 +885,6 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
             size_t new_cap = resolved_len + len + 2;
             char *new_resolved = realloc(resolved, new_cap);
             if (!new_resolved) {
-                free(name);
                 result = make_error(ENOMEM, "failed to grow resolved path");
                 goto out;
             }
@@ -769,12 +896,10 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
             resolved[resolved_len] = '/';
             resolved_len++;
         }
-        (void)memcpy(resolved + resolved_len, name, len);
+        (void)memcpy(resolved + resolved_len, realname, len);
         resolved_len += len;
         resolved[resolved_len] = '\0';
 
-        free(name);
-
         const char *next = p;
         while (*next == '/')
             next++;
@@ -782,6 +907,8 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
         current = child_obj;
         current_type = dtype;
         current_type_name = dtype_name;
+        if (dtype == 4)
+            last_dir = child_obj;
 
         if (*next != '\0' && dtype != 4) {
             found = 0;
@@ -831,7 +958,10 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
         "\"type\":%llu,"
         "\"type_name\":\"%s\","
         "\"found\":%s,"
-        "\"error\":%s"
+        "\"error\":%s,"
+        "\"lookup_errno\":%d,"
+        "\"last_dir_obj\":%llu,"
+        "\"match\":%s"
         "}",
         (unsigned long long)objset_id,
         path_json,
@@ -842,7 +972,10 @@ zdx_objset_walk(zdx_pool_t *pool, uint64_t objset_id, const char *path)
         (unsigned long long)current_type,
         current_type_name,
         found ? "true" : "false",
-        error_field);
+        error_field,
+        lookup_errno,
+        (unsigned long long)last_dir,
+        match_json);
     if (!json) {
         result = make_error(ENOMEM, "failed to allocate JSON result");
         goto out;
@@ -861,6 +994,7 @@ out:
     free(resolved_json);
     free(remaining_json);
     free(error_json);
+    free(match_json);
     return result;
 }
 
@@ -1057,6 +1191,9 @@ zdx_objset_get_object(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
     if (err != 0) {
         dsl_dataset_rele(ds, FTAG);
         dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+        if (err == ENOENT)
+            return make_error(err, "object %llu not allocated",
+                (unsigned long long)objid);
         return make_error(err, "dnode_hold failed for object %llu",
             (unsigned long long)objid);
     }
@@ -1088,12 +1225,33 @@ zdx_objset_get_object(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
     uint64_t indirect_block_size = 1ULL << dnp->dn_indblkshift;
     int is_zap = (DMU_OT_BYTESWAP(doi.doi_type) == DMU_BSWAP_ZAP);
 
+    /*
+     * The creation txg lives in the ZPL generation attribute, so only
+     * znode/SA-bonus objects have one; everything else reports null.
+     */
+    char gen_json[32] = "null";
+    if (doi.doi_bonus_type == DMU_OT_SA || doi.doi_bonus_type == DMU_OT_ZNODE) {
+        sa_attr_type_t *sa_table = NULL;
+        sa_handle_t *hdl = NULL;
+        uint64_t gen = 0;
+        if (zdx_sa_setup(os, &sa_table) == 0 &&
+            sa_handle_get(os, objid, NULL, SA_HDL_PRIVATE, &hdl) == 0) {
+            if (sa_lookup(hdl, sa_table[ZPL_GEN], &gen, sizeof (gen)) == 0)
+                snprintf(gen_json, sizeof (gen_json), "%llu",
+                    (unsigned long long)gen);
+            sa_handle_destroy(hdl);
+        }
+    }
+
     char *result = json_format(
         "{"
         "\"objset_id\":%llu,"
         "\"id\":%llu,"
         "\"type\":{\"id\":%u,\"name\":%s},"
         "\"bonus_type\":{\"id\":%u,\"name\":%s},"
+        "\"bonus_type_name\":%s,"
+        "\"gen_txg\":%s,"
+        "\"birth_txg\":%llu,"
         "\"is_zap\":%s,"
         "\"bonus_decoded\":null,"
         "\"semantic_edges\":[],"
@@ -1122,6 +1280,9 @@ zdx_objset_get_object(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
         type_name,
         (unsigned)doi.doi_bonus_type,
         bonus_name,
+        bonus_name,
+        gen_json,
+        (unsigned long long)dnode_top_birth_txg(dnp),
         is_zap ? "true" : "false",
         (unsigned)dnp->dn_nlevels,
         (unsigned)dnp->dn_nblkptr,
@@ -1154,6 +1315,329 @@ zdx_objset_get_object(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
     return make_success(result);
 }
 
+static const char *zdx_zil_txtype_names[] = {
+    "TX_COMMIT", "TX_CREATE", "TX_MKDIR", "TX_MKXATTR", "TX_SYMLINK",
+    "TX_REMOVE", "TX_RMDIR", "TX_LINK", "TX_RENAME", "TX_WRITE",
+    "TX_TRUNCATE", "TX_SETATTR", "TX_ACL_V0", "TX_ACL", "TX_CREATE_ACL",
+    "TX_CREATE_ATTR", "TX_CREATE_ACL_ATTR", "TX_MKDIR_ACL", "TX_MKDIR_ATTR",
+    "TX_MKDIR_ACL_ATTR", "TX_WRITE2", "TX_SETSAXATTR", "TX_RENAME_EXCHANGE",
+    "TX_RENAME_WHITEOUT", "TX_CLONE_RANGE",
+};
+
+typedef struct zdx_zil_walk {
+    char *blocks;
+    char *records;
+    uint64_t block_count;
+    uint64_t record_count;
+    uint64_t max_records;
+    boolean_t truncated;
+    int oom;
+} zdx_zil_walk_t;
+
+static int
+zdx_zil_append(char **array, char *item, int *oom)
+{
+    if (!item) {
+        *oom = 1;
+        return (ENOMEM);
+    }
+    char *next = json_array_append(*array, item);
+    free(item);
+    if (!next) {
+        *oom = 1;
+        return (ENOMEM);
+    }
+    free(*array);
+    *array = next;
+    return (0);
+}
+
+static int
+zdx_zil_parse_blk(zilog_t *zilog, const blkptr_t *bp, void *arg,
+    uint64_t claim_txg)
+{
+    (void) zilog;
+    zdx_zil_walk_t *walk = arg;
+
+    if (walk->block_count + walk->record_count >= walk->max_records) {
+        walk->truncated = B_TRUE;
+        return (EFBIG);
+    }
+
+    uint64_t birth = BP_GET_LOGICAL_BIRTH(bp);
+    char *item = json_format(
+        "{\"birth_txg\":%llu,\"lsize\":%llu,\"seq\":%llu,\"claimed\":%s}",
+        (unsigned long long)birth,
+        (unsigned long long)BP_GET_LSIZE(bp),
+        (unsigned long long)bp->blk_cksum.zc_word[ZIL_ZC_SEQ],
+        (claim_txg != 0 && birth >= claim_txg) ? "true" : "false");
+    if (zdx_zil_append(&walk->blocks, item, &walk->oom) != 0)
+        return (ENOMEM);
+    walk->block_count++;
+    return (0);
+}
+
+static int
+zdx_zil_parse_lr(zilog_t *zilog, const lr_t *lr, void *arg, uint64_t claim_txg)
+{
+    (void) zilog, (void) claim_txg;
+    zdx_zil_walk_t *walk = arg;
+
+    if (walk->block_count + walk->record_count >= walk->max_records) {
+        walk->truncated = B_TRUE;
+        return (EFBIG);
+    }
+
+    uint64_t txtype = lr->lrc_txtype & ~TX_CI;
+    const char *name = txtype < ARRAY_SIZE(zdx_zil_txtype_names)
+        ? zdx_zil_txtype_names[txtype] : "unknown";
+    char *item = json_format(
+        "{\"txtype\":%llu,\"txtype_name\":\"%s\",\"case_insensitive\":%s,"
+        "\"reclen\":%llu,\"txg\":%llu,\"seq\":%llu}",
+        (unsigned long long)txtype, name,
+        (lr->lrc_txtype & TX_CI) ? "true" : "false",
+        (unsigned long long)lr->lrc_reclen,
+        (unsigned long long)lr->lrc_txg,
+        (unsigned long long)lr->lrc_seq);
+    if (zdx_zil_append(&walk->records, item, &walk->oom) != 0)
+        return (ENOMEM);
+    walk->record_count++;
+    return (0);
+}
+
+/*
+ * ZIL header for a dataset and, when the log is non-empty, a bounded walk of
+ * its log blocks and records.
+ */
+zdx_result_t
+zdx_dataset_zil(zdx_pool_t *pool, uint64_t dsobj, uint64_t max_records)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    char *log_bp = NULL;
+    zdx_zil_walk_t walk = { 0 };
+    zdx_result_t result;
+    int err;
+
+    err = zdx_hold_objset_by_dsobj(spa, dsobj, &ds, &os, FTAG);
+    if (err != 0) {
+        result = make_error(err, "dataset_zil: failed to hold objset: %s",
+            strerror(err));
+        goto out;
+    }
+
+    zilog_t *zilog = dmu_objset_zil(os);
+    const zil_header_t *zh = zilog ? zilog->zl_header : NULL;
+    if (!zh) {
+        result = make_error(EINVAL, "dataset %llu has no intent log",
+            (unsigned long long)dsobj);
+        goto out;
+    }
+
+    log_bp = rootbp_json(&zh->zh_log);
+    walk.blocks = json_array_start();
+    walk.records = json_array_start();
+    if (!log_bp || !walk.blocks || !walk.records) {
+        result = make_error(ENOMEM, "failed to allocate JSON buffers");
+        goto out;
+    }
+
+    boolean_t present = !BP_IS_HOLE(&zh->zh_log);
+    char *parse_error = NULL;
+    walk.max_records = max_records;
+    if (present) {
+        err = zil_parse(zilog, zdx_zil_parse_blk, zdx_zil_parse_lr, &walk,
+            zh->zh_claim_txg, B_FALSE);
+        if (walk.oom) {
+            result = make_error(ENOMEM, "failed to build ZIL chain JSON");
+            goto out;
+        }
+        /* zil_parse stops at the first unreadable block; that ends the chain. */
+        if (err != 0 && !walk.truncated)
+            parse_error = json_format("\"%s\"", strerror(err));
+    }
+
+    char *blocks_json = json_array_end(walk.blocks, walk.block_count > 0);
+    char *records_json = json_array_end(walk.records, walk.record_count > 0);
+    char *json = (blocks_json && records_json) ? json_format(
+        "{"
+        "\"dsobj\":%llu,"
+        "\"present\":%s,"
+        "\"header\":{"
+            "\"claim_txg\":%llu,"
+            "\"replay_seq\":%llu,"
+            "\"claim_blk_seq\":%llu,"
+            "\"claim_lr_seq\":%llu,"
+            "\"flags\":%llu,"
+            "\"log_bp\":%s"
+        "},"
+        "\"max_records\":%llu,"
+        "\"block_count\":%llu,"
+        "\"record_count\":%llu,"
+        "\"truncated\":%s,"
+        "\"parse_error\":%s,"
+        "\"blocks\":%s,"
+        "\"records\":%s"
+        "}",
+        (unsigned long long)dsobj,
+        present ? "true" : "false",
+        (unsigned long long)zh->zh_claim_txg,
+        (unsigned long long)zh->zh_replay_seq,
+        (unsigned long long)zh->zh_claim_blk_seq,
+        (unsigned long long)zh->zh_claim_lr_seq,
+        (unsigned long long)zh->zh_flags,
+        log_bp,
+        (unsigned long long)max_records,
+        (unsigned long long)walk.block_count,
+        (unsigned long long)walk.record_count,
+        walk.truncated ? "true" : "false",
+        parse_error ? parse_error : "null",
+        blocks_json,
+        records_json) : NULL;
+    free(blocks_json);
+    free(records_json);
+    free(parse_error);
+
+    if (!json) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+    result = make_success(json);
+
+out:
+    free(log_bp);
+    free(walk.blocks);
+    free(walk.records);
+    if (ds) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    }
+    return result;
+}
+
+/*
+ * Raw dnode_phys_t fields for an objset object, without any semantic decode.
+ */
+zdx_result_t
+zdx_objset_dnode(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    dnode_t *dn = NULL;
+    char *type_name = NULL;
+    char *bonus_name = NULL;
+    char *json = NULL;
+    zdx_result_t result;
+    int err;
+
+    err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
+    if (err != 0) {
+        result = make_error(err, "objset_dnode: failed to hold objset: %s",
+            strerror(err));
+        goto out;
+    }
+
+    err = dnode_hold(os, objid, FTAG, &dn);
+    if (err != 0) {
+        dn = NULL;
+        if (err == ENOENT)
+            result = make_error(err, "object %llu not allocated",
+                (unsigned long long)objid);
+        else
+            result = make_error(err, "dnode_hold failed for object %llu",
+                (unsigned long long)objid);
+        goto out;
+    }
+
+    dnode_phys_t *dnp = dn->dn_phys;
+    if (!dnp) {
+        result = make_error(EIO, "missing dnode phys for object %llu",
+            (unsigned long long)objid);
+        goto out;
+    }
+
+    type_name = json_string(dmu_ot_name_safe(dnp->dn_type));
+    bonus_name = json_string(dmu_ot_name_safe(dnp->dn_bonustype));
+    if (!type_name || !bonus_name) {
+        result = make_error(ENOMEM, "failed to allocate JSON strings");
+        goto out;
+    }
+
+    json = json_format(
+        "{"
+        "\"objset_id\":%llu,"
+        "\"objid\":%llu,"
+        "\"dn_type\":{\"id\":%u,\"name\":%s},"
+        "\"bonus_type\":{\"id\":%u,\"name\":%s},"
+        "\"slots\":%u,"
+        "\"extra_slots\":%u,"
+        "\"dnode_size\":%llu,"
+        "\"indblkshift\":%u,"
+        "\"nlevels\":%u,"
+        "\"nblkptr\":%u,"
+        "\"bonuslen\":%u,"
+        "\"checksum\":%u,"
+        "\"compress\":%u,"
+        "\"flags\":%u,"
+        "\"datablkszsec\":%u,"
+        "\"data_block_size\":%llu,"
+        "\"max_block_id\":%llu,"
+        "\"dn_used\":%llu,"
+        "\"used_is_bytes\":%s,"
+        "\"used_bytes\":%llu,"
+        "\"has_spill\":%s"
+        "}",
+        (unsigned long long)objset_id,
+        (unsigned long long)objid,
+        (unsigned)dnp->dn_type,
+        type_name,
+        (unsigned)dnp->dn_bonustype,
+        bonus_name,
+        (unsigned)dnp->dn_extra_slots + 1,
+        (unsigned)dnp->dn_extra_slots,
+        (unsigned long long)(dnp->dn_extra_slots + 1) << DNODE_SHIFT,
+        (unsigned)dnp->dn_indblkshift,
+        (unsigned)dnp->dn_nlevels,
+        (unsigned)dnp->dn_nblkptr,
+        (unsigned)dnp->dn_bonuslen,
+        (unsigned)dnp->dn_checksum,
+        (unsigned)dnp->dn_compress,
+        (unsigned)dnp->dn_flags,
+        (unsigned)dnp->dn_datablkszsec,
+        (unsigned long long)dnp->dn_datablkszsec << SPA_MINBLOCKSHIFT,
+        (unsigned long long)dnp->dn_maxblkid,
+        (unsigned long long)dnp->dn_used,
+        (dnp->dn_flags & DNODE_FLAG_USED_BYTES) ? "true" : "false",
+        (unsigned long long)DN_USED_BYTES(dnp),
+        (dnp->dn_flags & DNODE_FLAG_SPILL_BLKPTR) ? "true" : "false");
+
+    if (!json) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+
+    result = make_success(json);
+
+out:
+    free(type_name);
+    free(bonus_name);
+    if (dn)
+        dnode_rele(dn, FTAG);
+    if (ds) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    }
+    return result;
+}
+
 /*
  * Get objset object block pointers.
  */
@@ -1349,6 +1833,13 @@ zdx_objset_zap_info(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
         return make_error(err, "zap_get_stats failed: %s", strerror(err));
     }
 
+    char *structure = zap_structure_json(&zs);
+    if (!structure) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+        return make_error(ENOMEM, "failed to allocate ZAP structure");
+    }
+
     int is_micro = (zs.zs_ptrtbl_len == 0);
     char *result = json_format(
         "{"
@@ -1367,7 +1858,8 @@ zdx_objset_zap_info(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
         "\"ptrtbl_nextblk\":%llu,"
         "\"zap_block_type\":%llu,"
         "\"zap_magic\":%llu,"
-        "\"zap_salt\":%llu"
+        "\"zap_salt\":%llu,"
+        "\"structure\":%s"
         "}",
         (unsigned long long)objset_id,
         (unsigned long long)objid,
@@ -1384,7 +1876,9 @@ zdx_objset_zap_info(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid)
         (unsigned long long)zs.zs_ptrtbl_nextblk,
         (unsigned long long)zs.zs_block_type,
         (unsigned long long)zs.zs_magic,
-        (unsigned long long)zs.zs_salt);
+        (unsigned long long)zs.zs_salt,
+        structure);
+    free(structure);
 
     dsl_dataset_rele(ds, FTAG);
     dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
@@ -1523,6 +2017,9 @@ zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
         int maybe_ref = 0;
         uint64_t target_obj = 0;
         char *value_preview = NULL;
+        char *typed_json = NULL;
+        const char *value_type = zap_value_type(NULL,
+            attrp->za_integer_length, attrp->za_num_integers);
         int truncated = 0;
 
         if (attrp->za_num_integers > 0) {
@@ -1635,17 +2132,26 @@ zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
                         attrp->za_num_integers, attrp->za_integer_length);
                 }
 
+                value_type = zap_value_type(prop,
+                    attrp->za_integer_length, attrp->za_num_integers);
+                typed_json = zap_value_json(prop,
+                    attrp->za_integer_length, attrp->za_num_integers);
                 free(prop);
             }
         } else {
             value_preview = strdup("");
+            typed_json = strdup("\"\"");
         }
 
         if (!value_preview)
             value_preview = strdup("");
+        if (!typed_json)
+            typed_json = strdup("null");
 
         char *value_json = json_string(value_preview);
-        if (!value_json) {
+        if (!value_json || !typed_json) {
+            free(value_json);
+            free(typed_json);
             free(value_preview);
             free(name_json);
             free(array);
@@ -1710,6 +2216,10 @@ zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
             "\"key_u64\":%s,"
             "\"integer_length\":%d,"
             "\"num_integers\":%llu,"
+            "\"value_type\":\"%s\","
+            "\"int_length\":%d,"
+            "\"int_count\":%llu,"
+            "\"value\":%s,"
             "\"value_preview\":%s,"
             "\"value_u64\":%s,"
             "\"raw_value_u64\":%s,"
@@ -1725,6 +2235,10 @@ zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
             key_json,
             attrp->za_integer_length,
             (unsigned long long)attrp->za_num_integers,
+            value_type,
+            attrp->za_integer_length,
+            (unsigned long long)attrp->za_num_integers,
+            typed_json,
             value_json,
             value_u64_json,
             raw_value_u64_json,
@@ -1737,6 +2251,7 @@ zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
             truncated ? "true" : "false");
 
         free(value_preview);
+        free(typed_json);
         free(value_json);
         free(dirent_type_name_json);
         free(name_json);
@@ -1819,7 +2334,7 @@ zdx_objset_zap_entries(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
 }
 
 /*
- * Read logical object data from a ZFS objset object.
+ * Read logical object data from a ZFS or ZVOL objset object.
  * This uses dmu_read() (logical view), not raw DVA reads.
  */
 zdx_result_t
@@ -1856,7 +2371,12 @@ zdx_objset_read_data(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
             strerror(err));
     }
 
-    if (dmu_objset_type(os) != DMU_OST_ZFS) {
+    /*
+     * ZVOL objsets keep the volume contents in a single data object, which
+     * dmu_read() serves the same way as a ZPL file object.
+     */
+    if (dmu_objset_type(os) != DMU_OST_ZFS &&
+        dmu_objset_type(os) != DMU_OST_ZVOL) {
         result = make_error(EINVAL, "objset is not ZFS (type %d)",
             dmu_objset_type(os));
         goto out;
@@ -1922,6 +2442,7 @@ zdx_objset_read_data(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
     result_json = json_format(
         "{"
         "\"objset_id\":%llu,"
+        "\"objset_type\":\"%s\","
         "\"id\":%llu,"
         "\"offset\":%llu,"
         "\"requested\":%llu,"
@@ -1931,6 +2452,7 @@ zdx_objset_read_data(zdx_pool_t *pool, uint64_t objset_id, uint64_t objid,
         "\"data_hex\":%s"
         "}",
         (unsigned long long)objset_id,
+        dmu_objset_type(os) == DMU_OST_ZVOL ? "zvol" : "zfs",
         (unsigned long long)objid,
         (unsigned long long)offset,
         (unsigned long long)request_limit,
@@ -1956,3 +2478,645 @@ out:
     dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
     return result;
 }
+
+/*
+ * Append one SA registry entry per attribute in the REGISTRY ZAP.
+ */
+static int
+zdx_sa_registry_json(objset_t *os, uint64_t registry_obj, char **out)
+{
+    char *array = json_array_start();
+    if (!array)
+        return ENOMEM;
+
+    int count = 0;
+    int err = 0;
+    if (registry_obj != 0) {
+        zap_cursor_t zc;
+        zap_attribute_t *attrp = zap_attribute_long_alloc();
+        if (!attrp) {
+            free(array);
+            return ENOMEM;
+        }
+
+        zap_cursor_init(&zc, os, registry_obj);
+        while ((err = zap_cursor_retrieve(&zc, attrp)) == 0) {
+            uint64_t packed = attrp->za_first_integer;
+            char *name_json = json_string(attrp->za_name);
+            char *item = name_json ? json_format(
+                "{"
+                "\"name\":%s,"
+                "\"attr_num\":%llu,"
+                "\"length\":%llu,"
+                "\"byteswap\":%llu"
+                "}",
+                name_json,
+                (unsigned long long)ATTR_NUM(packed),
+                (unsigned long long)ATTR_LENGTH(packed),
+                (unsigned long long)ATTR_BSWAP(packed)) : NULL;
+            free(name_json);
+            char *next = item ? json_array_append(array, item) : NULL;
+            free(item);
+            if (!next) {
+                err = ENOMEM;
+                break;
+            }
+            free(array);
+            array = next;
+            count++;
+            zap_cursor_advance(&zc);
+        }
+        zap_attribute_free(attrp);
+        zap_cursor_fini(&zc);
+
+        if (err != 0 && err != ENOENT) {
+            free(array);
+            return err;
+        }
+    }
+
+    *out = json_array_end(array, count > 0);
+    free(array);
+    return *out ? 0 : ENOMEM;
+}
+
+/*
+ * Append one entry per layout in the LAYOUTS ZAP. Keys are layout numbers,
+ * values are uint16 arrays of attribute numbers in on-disk order.
+ */
+static int
+zdx_sa_layouts_json(objset_t *os, uint64_t layouts_obj, char **out)
+{
+    char *array = json_array_start();
+    if (!array)
+        return ENOMEM;
+
+    int count = 0;
+    int err = 0;
+    if (layouts_obj != 0) {
+        zap_cursor_t zc;
+        zap_attribute_t *attrp = zap_attribute_long_alloc();
+        if (!attrp) {
+            free(array);
+            return ENOMEM;
+        }
+
+        zap_cursor_init(&zc, os, layouts_obj);
+        while ((err = zap_cursor_retrieve(&zc, attrp)) == 0) {
+            uint64_t nattrs = attrp->za_num_integers;
+            if (attrp->za_integer_length != 2 || nattrs == 0) {
+                zap_cursor_advance(&zc);
+                continue;
+            }
+
+            uint16_t *attrs = calloc(nattrs, sizeof (uint16_t));
+            if (!attrs) {
+                err = ENOMEM;
+                break;
+            }
+            err = zap_lookup(os, layouts_obj, attrp->za_name, 2, nattrs,
+                attrs);
+            if (err != 0) {
+                free(attrs);
+                break;
+            }
+
+            char *nums = json_array_start();
+            int num_count = 0;
+            for (uint64_t i = 0; nums && i < nattrs; i++) {
+                char num_buf[16];
+                (void) snprintf(num_buf, sizeof (num_buf), "%u",
+                    (unsigned)attrs[i]);
+                char *next_nums = json_array_append(nums, num_buf);
+                free(nums);
+                nums = next_nums;
+                num_count++;
+            }
+            free(attrs);
+            char *nums_json = nums ? json_array_end(nums, num_count > 0) :
+                NULL;
+            free(nums);
+
+            uint64_t layout_id = strtoull(attrp->za_name, NULL, 10);
+            char *item = nums_json ? json_format(
+                "{\"layout_id\":%llu,\"attrs\":%s}",
+                (unsigned long long)layout_id,
+                nums_json) : NULL;
+            free(nums_json);
+            char *next = item ? json_array_append(array, item) : NULL;
+            free(item);
+            if (!next) {
+                err = ENOMEM;
+                break;
+            }
+            free(array);
+            array = next;
+            count++;
+            zap_cursor_advance(&zc);
+        }
+        zap_attribute_free(attrp);
+        zap_cursor_fini(&zc);
+
+        if (err != 0 && err != ENOENT) {
+            free(array);
+            return err;
+        }
+    }
+
+    *out = json_array_end(array, count > 0);
+    free(array);
+    return *out ? 0 : ENOMEM;
+}
+
+/*
+ * System attribute registry and layouts from the objset's SA master node.
+ */
+zdx_result_t
+zdx_objset_sa_layouts(zdx_pool_t *pool, uint64_t objset_id)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    char *registry_json = NULL;
+    char *layouts_json = NULL;
+    uint64_t sa_attrs = 0;
+    uint64_t registry_obj = 0;
+    uint64_t layouts_obj = 0;
+    zdx_result_t result;
+    int err;
+
+    err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
+    if (err != 0) {
+        return make_error(err, "objset_sa_layouts: failed to hold objset: %s",
+            strerror(err));
+    }
+
+    if (dmu_objset_type(os) != DMU_OST_ZFS) {
+        result = make_error(EINVAL, "objset is not ZFS (type %d)",
+            dmu_objset_type(os));
+        goto out;
+    }
+
+    /* Pre-SA ZPL versions have no SA_ATTRS entry; report empty tables. */
+    (void) zap_lookup(os, MASTER_NODE_OBJ, ZFS_SA_ATTRS, 8, 1, &sa_attrs);
+    if (sa_attrs != 0) {
+        (void) zap_lookup(os, sa_attrs, SA_REGISTRY, 8, 1, &registry_obj);
+        (void) zap_lookup(os, sa_attrs, SA_LAYOUTS, 8, 1, &layouts_obj);
+    }
+
+    err = zdx_sa_registry_json(os, registry_obj, &registry_json);
+    if (err != 0) {
+        result = make_error(err, "zap_cursor_retrieve failed for SA "
+            "registry %llu: %s", (unsigned long long)registry_obj,
+            strerror(err));
+        goto out;
+    }
+
+    err = zdx_sa_layouts_json(os, layouts_obj, &layouts_json);
+    if (err != 0) {
+        result = make_error(err, "zap_cursor_retrieve failed for SA "
+            "layouts %llu: %s", (unsigned long long)layouts_obj,
+            strerror(err));
+        goto out;
+    }
+
+    char *json = json_format(
+        "{"
+        "\"objset_id\":%llu,"
+        "\"sa_enabled\":%s,"
+        "\"sa_attrs_obj\":%llu,"
+        "\"registry_obj\":%llu,"
+        "\"layouts_obj\":%llu,"
+        "\"registry\":%s,"
+        "\"layouts\":%s"
+        "}",
+        (unsigned long long)objset_id,
+        sa_attrs != 0 ? "true" : "false",
+        (unsigned long long)sa_attrs,
+        (unsigned long long)registry_obj,
+        (unsigned long long)layouts_obj,
+        registry_json,
+        layouts_json);
+    if (!json) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+
+    result = make_success(json);
+
+out:
+    free(registry_json);
+    free(layouts_json);
+    dsl_dataset_rele(ds, FTAG);
+    dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    return result;
+}
+
+typedef struct zdx_largest_file {
+    uint64_t objid;
+    uint64_t size;
+} zdx_largest_file_t;
+
+/*
+ * Insert into a descending-by-size array holding at most `cap` entries.
+ */
+static void
+zdx_largest_files_insert(zdx_largest_file_t *top, uint64_t *count,
+    uint64_t cap, uint64_t objid, uint64_t size)
+{
+    if (*count == cap && (cap == 0 || top[cap - 1].size >= size))
+        return;
+
+    uint64_t pos = (*count < cap) ? (*count)++ : cap - 1;
+    while (pos > 0 && top[pos - 1].size < size) {
+        top[pos] = top[pos - 1];
+        pos--;
+    }
+    top[pos].objid = objid;
+    top[pos].size = size;
+}
+
+/*
+ * Top-N plain files by ZPL size, scanning at most scan_limit objects from
+ * object `start` onward. Files smaller than min_size are counted but not
+ * ranked, which lets callers scanning in batches skip path resolution for
+ * files that cannot beat their running top-N. `next` is the object to resume
+ * from, or null once the objset is exhausted. Paths are resolved through the
+ * znode parent pointers.
+ */
+zdx_result_t
+zdx_objset_largest_files(zdx_pool_t *pool, uint64_t objset_id, uint64_t start,
+    uint64_t limit, uint64_t scan_limit, uint64_t min_size)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    sa_attr_type_t *sa_table = NULL;
+    zdx_largest_file_t *top = NULL;
+    char *array = NULL;
+    zdx_result_t result;
+    int err;
+
+    err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
+    if (err != 0) {
+        result = make_error(err, "objset_largest_files: failed to hold objset: %s",
+            strerror(err));
+        goto out;
+    }
+
+    if (dmu_objset_type(os) != DMU_OST_ZFS) {
+        result = make_error(EINVAL, "objset is not ZFS (type %d)",
+            dmu_objset_type(os));
+        goto out;
+    }
+
+    err = zdx_sa_setup(os, &sa_table);
+    if (err != 0) {
+        result = make_error(err, "sa_setup failed: %s", strerror(err));
+        goto out;
+    }
+
+    top = calloc(limit > 0 ? limit : 1, sizeof (*top));
+    if (!top) {
+        result = make_error(ENOMEM, "failed to allocate top-N buffer");
+        goto out;
+    }
+
+    uint64_t object = start > 0 ? start - 1 : 0;
+    uint64_t scanned = 0;
+    uint64_t files = 0;
+    uint64_t count = 0;
+    boolean_t truncated = B_FALSE;
+
+    while (dmu_object_next(os, &object, B_FALSE, 0) == 0) {
+        if (scanned >= scan_limit) {
+            truncated = B_TRUE;
+            break;
+        }
+        scanned++;
+
+        dmu_object_info_t doi;
+        if (dmu_object_info(os, object, &doi) != 0 ||
+            doi.doi_type != DMU_OT_PLAIN_FILE_CONTENTS)
+            continue;
+
+        sa_handle_t *hdl = NULL;
+        if (sa_handle_get(os, object, NULL, SA_HDL_PRIVATE, &hdl) != 0)
+            continue;
+
+        uint64_t size = 0;
+        err = sa_lookup(hdl, sa_table[ZPL_SIZE], &size, sizeof (size));
+        sa_handle_destroy(hdl);
+        if (err != 0)
+            continue;
+
+        files++;
+        if (size >= min_size)
+            zdx_largest_files_insert(top, &count, limit, object, size);
+    }
+
+    array = json_array_start();
+    if (!array) {
+        result = make_error(ENOMEM, "failed to allocate JSON array");
+        goto out;
+    }
+
+    for (uint64_t i = 0; i < count; i++) {
+        char pathbuf[MAXPATHLEN];
+        char *path_json;
+        if (zfs_obj_to_path(os, top[i].objid, pathbuf, sizeof (pathbuf)) == 0)
+            path_json = json_string(pathbuf);
+        else
+            path_json = strdup("null");
+        if (!path_json) {
+            result = make_error(ENOMEM, "failed to allocate path JSON");
+            goto out;
+        }
+
+        char *item = json_format(
+            "{\"objid\":%llu,\"size\":%llu,\"path\":%s}",
+            (unsigned long long)top[i].objid,
+            (unsigned long long)top[i].size,
+            path_json);
+        free(path_json);
+        if (!item) {
+            result = make_error(ENOMEM, "failed to allocate JSON item");
+            goto out;
+        }
+
+        char *new_array = json_array_append(array, item);
+        free(item);
+        if (!new_array) {
+            result = make_error(ENOMEM, "failed to append JSON item");
+            goto out;
+        }
+        free(array);
+        array = new_array;
+    }
+
+    char *files_json = json_array_end(array, count > 0);
+    if (!files_json) {
+        result = make_error(ENOMEM, "failed to finalize JSON array");
+        goto out;
+    }
+
+    char next_json[32] = "null";
+    if (truncated)
+        snprintf(next_json, sizeof (next_json), "%llu",
+            (unsigned long long)object);
+
+    char *result_json = json_format(
+        "{"
+        "\"objset_id\":%llu,"
+        "\"start\":%llu,"
+        "\"next\":%s,"
+        "\"limit\":%llu,"
+        "\"scan_limit\":%llu,"
+        "\"scanned\":%llu,"
+        "\"file_objects\":%llu,"
+        "\"truncated\":%s,"
+        "\"count\":%llu,"
+        "\"files\":%s"
+        "}",
+        (unsigned long long)objset_id,
+        (unsigned long long)start,
+        next_json,
+        (unsigned long long)limit,
+        (unsigned long long)scan_limit,
+        (unsigned long long)scanned,
+        (unsigned long long)files,
+        truncated ? "true" : "false",
+        (unsigned long long)count,
+        files_json);
+    free(files_json);
+    if (!result_json) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+
+    result = make_success(result_json);
+
+out:
+    free(array);
+    free(top);
+    if (ds) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    }
+    return result;
+}
+
+/*
+ * Best-effort name for a POSIX uid/gid from the host's passwd/group
+ * databases. On imported images these are the examining host's names,
+ * not necessarily the ones the pool was written under.
+ */
+static char *
+zdx_userspace_name_json(boolean_t group, uint64_t id)
+{
+    char buf[1024];
+    const char *name = NULL;
+
+    if (id <= UINT32_MAX) {
+        if (group) {
+            struct group gr, *grp = NULL;
+            if (getgrgid_r((gid_t)id, &gr, buf, sizeof (buf), &grp) == 0 &&
+                grp != NULL)
+                name = grp->gr_name;
+        } else {
+            struct passwd pw, *pwp = NULL;
+            if (getpwuid_r((uid_t)id, &pw, buf, sizeof (buf), &pwp) == 0 &&
+                pwp != NULL)
+                name = pwp->pw_name;
+        }
+    }
+    return name ? json_string(name) : strdup("null");
+}
+
+/*
+ * Per-user or per-group space accounting (`zfs userspace`): one row per
+ * entry of the objset's userused/groupused ZAP, joined with the matching
+ * quota ZAP referenced from the ZPL master node. Keys are hex ids, or
+ * "<SID domain>-<hex rid>" for SMB identities. Paged by serialized ZAP
+ * cursor.
+ */
+zdx_result_t
+zdx_dataset_userspace(zdx_pool_t *pool, uint64_t objset_id, int group,
+    uint64_t cursor, uint64_t limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    dsl_dataset_t *ds = NULL;
+    objset_t *os = NULL;
+    zap_attribute_t *attrp = NULL;
+    char *array = NULL;
+    boolean_t cursor_open = B_FALSE;
+    zap_cursor_t zc;
+    zdx_result_t result;
+    int err;
+
+    err = zdx_hold_objset_by_dsobj(spa, objset_id, &ds, &os, FTAG);
+    if (err != 0) {
+        result = make_error(err, "dataset_userspace: failed to hold objset: %s",
+            strerror(err));
+        goto out;
+    }
+
+    if (dmu_objset_type(os) != DMU_OST_ZFS) {
+        result = make_error(EINVAL, "objset is not ZFS (type %d)",
+            dmu_objset_type(os));
+        goto out;
+    }
+
+    uint64_t used_obj = group ? DMU_GROUPUSED_OBJECT : DMU_USERUSED_OBJECT;
+    boolean_t accounting = dmu_objset_userused_enabled(os) &&
+        DMU_USERUSED_DNODE(os) != NULL &&
+        DMU_USERUSED_DNODE(os)->dn_type != DMU_OT_NONE;
+
+    /* The ZPL names these master-node entries after the quota properties. */
+    uint64_t quota_obj = 0;
+    (void) zap_lookup(os, MASTER_NODE_OBJ,
+        group ? "groupquota@" : "userquota@", 8, 1, &quota_obj);
+
+    array = json_array_start();
+    attrp = zap_attribute_long_alloc();
+    if (!array || !attrp) {
+        result = make_error(ENOMEM, "failed to allocate userspace buffers");
+        goto out;
+    }
+
+    uint64_t count = 0;
+    int done = 1;
+    if (accounting) {
+        zap_cursor_init_serialized(&zc, os, used_obj, cursor);
+        cursor_open = B_TRUE;
+        done = 0;
+    }
+
+    while (!done && count < limit) {
+        err = zap_cursor_retrieve(&zc, attrp);
+        if (err == ENOENT) {
+            done = 1;
+            break;
+        }
+        if (err != 0) {
+            result = make_error(err, "zap_cursor_retrieve failed: %s",
+                strerror(err));
+            goto out;
+        }
+
+        uint64_t used = attrp->za_first_integer;
+
+        char quota_buf[32] = "null";
+        uint64_t quota = 0;
+        if (quota_obj != 0 &&
+            zap_lookup(os, quota_obj, attrp->za_name, 8, 1, &quota) == 0)
+            snprintf(quota_buf, sizeof (quota_buf), "%llu",
+                (unsigned long long)quota);
+
+        const char *dash = strrchr(attrp->za_name, '-');
+        const char *rid = dash ? dash + 1 : attrp->za_name;
+        const char *end = NULL;
+        uint64_t id = 0;
+        boolean_t parsed = zdx_parse_u64_token_base(rid, 16, &end, &id) == 0 &&
+            *end == '\0';
+
+        char id_buf[32] = "null";
+        if (parsed)
+            snprintf(id_buf, sizeof (id_buf), "%llu", (unsigned long long)id);
+
+        char *domain_json = NULL;
+        if (dash) {
+            char *domain = dup_range(attrp->za_name,
+                (size_t)(dash - attrp->za_name));
+            domain_json = domain ? json_string(domain) : NULL;
+            free(domain);
+        } else {
+            domain_json = strdup("null");
+        }
+        char *key_json = json_string(attrp->za_name);
+        char *name_json = (parsed && !dash) ?
+            zdx_userspace_name_json(group, id) : strdup("null");
+        char *item = NULL;
+        if (domain_json && key_json && name_json) {
+            item = json_format(
+                "{\"key\":%s,\"id\":%s,\"domain\":%s,\"name\":%s,"
+                "\"used_bytes\":%llu,\"quota_bytes\":%s}",
+                key_json, id_buf, domain_json, name_json,
+                (unsigned long long)used, quota_buf);
+        }
+        free(domain_json);
+        free(key_json);
+        free(name_json);
+
+        char *next_array = item ? json_array_append(array, item) : NULL;
+        free(item);
+        if (!next_array) {
+            result = make_error(ENOMEM, "failed to append userspace row");
+            goto out;
+        }
+        free(array);
+        array = next_array;
+        count++;
+        zap_cursor_advance(&zc);
+    }
+
+    char next_buf[32] = "null";
+    if (!done)
+        snprintf(next_buf, sizeof (next_buf), "%llu",
+            (unsigned long long)zap_cursor_serialize(&zc));
+
+    char *rows_json = json_array_end(array, count > 0);
+    if (!rows_json) {
+        result = make_error(ENOMEM, "failed to finalize userspace rows");
+        goto out;
+    }
+
+    char *json = json_format(
+        "{"
+        "\"objset_id\":%llu,"
+        "\"type\":\"%s\","
+        "\"accounting_enabled\":%s,"
+        "\"quota_obj\":%llu,"
+        "\"cursor\":%llu,"
+        "\"limit\":%llu,"
+        "\"count\":%llu,"
+        "\"next\":%s,"
+        "\"entries\":%s"
+        "}",
+        (unsigned long long)objset_id,
+        group ? "group" : "user",
+        accounting ? "true" : "false",
+        (unsigned long long)quota_obj,
+        (unsigned long long)cursor,
+        (unsigned long long)limit,
+        (unsigned long long)count,
+        next_buf,
+        rows_json);
+    free(rows_json);
+    if (!json) {
+        result = make_error(ENOMEM, "failed to allocate JSON result");
+        goto out;
+    }
+    result = make_success(json);
+
+out:
+    if (cursor_open)
+        zap_cursor_fini(&zc);
+    if (attrp)
+        zap_attribute_free(attrp);
+    free(array);
+    if (ds) {
+        dsl_dataset_rele(ds, FTAG);
+        dsl_pool_config_exit(spa->spa_dsl_pool, FTAG);
+    }
+    return result;
+}
@@ -1,9 +1,16 @@
 #include "zdbdecode_internal.h"
+#include <sys/vdev_impl.h>
+#include <sys/zfeature.h>
+#include <sys/arc_impl.h>
+#include <fcntl.h>
+#include <sys/stat.h>
+#include <unistd.h>
+#include <zfeature_common.h>
 
 /*
  * Convert an nvlist to a JSON string using libnvpair JSON printer.
  */
-static char *
+char *
 nvlist_to_json_string(nvlist_t *nvl)
 {
     if (!nvl)
@@ -143,9 +150,219 @@ pool_features_json(nvlist_t *config)
 }
 
 /*
- * Build a compact rootbp summary JSON for the active uberblock.
+ * Append active features from one MOS feature refcount ZAP that the linked
+ * libzpool does not know about.
+ */
+static int
+append_unsupported_features(spa_t *spa, uint64_t obj, boolean_t for_read,
+    char **array, int *count, uint64_t *checked)
+{
+    if (obj == 0)
+        return 0;
+
+    zap_cursor_t zc;
+    zap_attribute_t *za = zap_attribute_alloc();
+    if (!za)
+        return ENOMEM;
+
+    int err = 0;
+    for (zap_cursor_init(&zc, spa->spa_meta_objset, obj);
+        zap_cursor_retrieve(&zc, za) == 0;
+        zap_cursor_advance(&zc)) {
+        if (za->za_integer_length != sizeof (uint64_t) ||
+            za->za_first_integer == 0)
+            continue;
+        (*checked)++;
+        if (zfeature_is_supported(za->za_name))
+            continue;
+
+        char *guid_json = json_string(za->za_name);
+        char *item = guid_json ? json_format(
+            "{\"guid\":%s,\"for_read\":%s,\"refcount\":%llu}",
+            guid_json, for_read ? "true" : "false",
+            (unsigned long long)za->za_first_integer) : NULL;
+        free(guid_json);
+        char *next = item ? json_array_append(*array, item) : NULL;
+        free(item);
+        if (!next) {
+            err = ENOMEM;
+            break;
+        }
+        free(*array);
+        *array = next;
+        (*count)++;
+    }
+
+    zap_cursor_fini(&zc);
+    zap_attribute_free(za);
+    return err;
+}
+
+/*
+ * Compare the pool's on-disk version and active features against what the
+ * linked libzpool supports. A read-only open tolerates unknown write-side
+ * features, so this reports rather than refuses.
+ */
+static char *
+pool_compatibility_json(spa_t *spa)
+{
+    char *array = json_array_start();
+    if (!array)
+        return NULL;
+
+    int count = 0;
+    uint64_t checked = 0;
+    uint64_t version = spa_version(spa);
+    int err = 0;
+    if (SPA_VERSION_IS_SUPPORTED(version) &&
+        version >= SPA_VERSION_FEATURES) {
+        err = append_unsupported_features(spa, spa->spa_feat_for_read_obj,
+            B_TRUE, &array, &count, &checked);
+        if (err == 0) {
+            err = append_unsupported_features(spa,
+                spa->spa_feat_for_write_obj, B_FALSE, &array, &count,
+                &checked);
+        }
+    }
+    if (err != 0) {
+        free(array);
+        return NULL;
+    }
+
+    char *features_json = json_array_end(array, count > 0);
+    free(array);
+    if (!features_json)
+        return NULL;
+
+    boolean_t supported = SPA_VERSION_IS_SUPPORTED(version) && count == 0;
+    const char *warning = "null";
+    if (!SPA_VERSION_IS_SUPPORTED(version)) {
+        warning = "\"Pool version is newer than this build of libzpool "
+            "supports; decoded structures may be unreliable.\"";
+    } else if (count > 0) {
+        warning = "\"Pool has active features this build of libzpool does "
+            "not recognize; decodes of structures they change may be "
+            "unreliable. Rebuild against a newer OpenZFS.\"";
+    }
+
+    char *result = json_format(
+        "{"
+        "\"supported\":%s,"
+        "\"pool_version\":%llu,"
+        "\"library_version\":%llu,"
+        "\"active_features_checked\":%llu,"
+        "\"unsupported_features\":%s,"
+        "\"warning\":%s"
+        "}",
+        supported ? "true" : "false",
+        (unsigned long long)version,
+        (unsigned long long)SPA_VERSION,
+        (unsigned long long)checked,
+        features_json,
+        warning);
+    free(features_json);
+    return result;
+}
+
+#define ZDX_HEALTH_MAX_STATES 16
+
+typedef struct zdx_vdev_health {
+    const char *names[ZDX_HEALTH_MAX_STATES];
+    uint64_t counts[ZDX_HEALTH_MAX_STATES];
+    int nstates;
+    uint64_t read_errors;
+    uint64_t write_errors;
+    uint64_t cksum_errors;
+} zdx_vdev_health_t;
+
+static void
+vdev_health_walk(vdev_t *vd, zdx_vdev_health_t *health, boolean_t is_root)
+{
+    health->read_errors += vd->vdev_stat.vs_read_errors;
+    health->write_errors += vd->vdev_stat.vs_write_errors;
+    health->cksum_errors += vd->vdev_stat.vs_checksum_errors;
+
+    if (!is_root) {
+        const char *name = zpool_state_to_name(vd->vdev_state,
+            vd->vdev_stat.vs_aux);
+        int i;
+        for (i = 0; i < health->nstates; i++) {
+            if (strcmp(health->names[i], name) == 0)
+                break;
+        }
+        if (i == health->nstates && i < ZDX_HEALTH_MAX_STATES) {
+            health->names[i] = name;
+            health->counts[i] = 0;
+            health->nstates++;
+        }
+        if (i < health->nstates)
+            health->counts[i]++;
+    }
+
+    for (uint64_t c = 0; c < vd->vdev_children; c++) {
+        vdev_t *child = vd->vdev_child[c];
+        if (!child->vdev_ishole)
+            vdev_health_walk(child, health, B_FALSE);
+    }
+}
+
+/*
+ * Health rollup of the in-core vdev tree (data, special, dedup, and log
+ * vdevs; spares and cache devices are not included), using the same state
+ * names as `zpool status`. Error counters are those seen since the pool was
+ * opened, so offline opens usually report zeros.
  */
 static char *
+pool_vdev_health_json(spa_t *spa)
+{
+    zdx_vdev_health_t health = { 0 };
+    const char *state = "UNKNOWN";
+
+    spa_config_enter(spa, SCL_STATE, FTAG, RW_READER);
+    vdev_t *rvd = spa->spa_root_vdev;
+    if (rvd != NULL) {
+        state = zpool_state_to_name(rvd->vdev_state, rvd->vdev_stat.vs_aux);
+        vdev_health_walk(rvd, &health, B_TRUE);
+    }
+    spa_config_exit(spa, SCL_STATE, FTAG);
+
+    char *states = strdup("{");
+    for (int i = 0; states != NULL && i < health.nstates; i++) {
+        char *next = json_format("%s%s\"%s\":%llu", states,
+            i > 0 ? "," : "", health.names[i],
+            (unsigned long long)health.counts[i]);
+        free(states);
+        states = next;
+    }
+    if (states == NULL)
+        return NULL;
+
+    char *json = json_format(
+        "{"
+        "\"source\":\"vdev_tree\","
+        "\"state\":\"%s\","
+        "\"vdev_states\":%s},"
+        "\"read_errors\":%llu,"
+        "\"write_errors\":%llu,"
+        "\"cksum_errors\":%llu,"
+        "\"status\":null,"
+        "\"action\":null,"
+        "\"scan\":null,"
+        "\"errors\":null"
+        "}",
+        state,
+        states,
+        (unsigned long long)health.read_errors,
+        (unsigned long long)health.write_errors,
+        (unsigned long long)health.cksum_errors);
+    free(states);
+    return json;
+}
+
+/*
+ * Build a compact rootbp summary JSON (uberblock or objset root).
+ */
+char *
 rootbp_json(const blkptr_t *bp)
 {
     if (!bp)
@@ -197,6 +414,10 @@ rootbp_json(const blkptr_t *bp)
         "\"psize\":%llu,"
         "\"asize\":%llu,"
         "\"birth_txg\":%llu,"
+        "\"ndvas\":%d,"
+        "\"redundancy\":\"%s\","
+        "\"is_gang\":%s,"
+        "\"gang_warning\":%s,"
         "\"dvas\":%s"
         "}",
         BP_IS_HOLE(bp) ? "true" : "false",
@@ -206,6 +427,10 @@ rootbp_json(const blkptr_t *bp)
         (unsigned long long)BP_GET_PSIZE(bp),
         (unsigned long long)BP_GET_ASIZE(bp),
         (unsigned long long)BP_GET_PHYSICAL_BIRTH(bp),
+        BP_GET_NDVAS(bp),
+        zdx_redundancy_label(BP_GET_NDVAS(bp)),
+        BP_IS_GANG(bp) ? "true" : "false",
+        BP_IS_GANG(bp) ? "\"" ZDX_GANG_BLOCK_WARNING "\"" : "null",
         dvas_json);
     free(dvas_json);
     return result;
@@ -218,6 +443,8 @@ typedef struct zdx_errlog_page {
     uint64_t limit;
     uint64_t seen;
     uint64_t added;
+    uint64_t since_txg;
+    uint64_t max_birth;
     boolean_t has_more;
     char *entries_json;
     int entries_count;
@@ -274,6 +501,14 @@ static int
 errlog_page_append(zdx_errlog_page_t *page, const char *source, uint64_t dsobj,
     uint64_t object, int64_t level, uint64_t blkid, uint64_t *birth_opt)
 {
+    /*
+     * since_txg filters before cursor accounting so cursors index the
+     * filtered sequence. Legacy entries carry no birth and never match.
+     */
+    if (page->since_txg != 0 &&
+        (birth_opt == NULL || *birth_opt < page->since_txg))
+        return 0;
+
     if (page->seen < page->cursor) {
         page->seen++;
         return 0;
@@ -307,6 +542,8 @@ errlog_page_append(zdx_errlog_page_t *page, const char *source, uint64_t dsobj,
     char birth_buf[32];
     const char *birth_json = "null";
     if (birth_opt != NULL) {
+        if (*birth_opt > page->max_birth)
+            page->max_birth = *birth_opt;
         (void) snprintf(birth_buf, sizeof (birth_buf), "%llu",
             (unsigned long long)*birth_opt);
         birth_json = birth_buf;
@@ -455,7 +692,7 @@ scan_errlog_head(spa_t *spa, uint64_t obj, const char *source,
  */
 zdx_result_t
 zdx_pool_errors(zdx_pool_t *pool, uint64_t cursor, uint64_t limit,
-    int resolve_paths)
+    int resolve_paths, uint64_t since_txg)
 {
     if (!pool || !pool->spa)
         return make_error(EINVAL, "pool not open");
@@ -492,6 +729,7 @@ zdx_pool_errors(zdx_pool_t *pool, uint64_t cursor, uint64_t limit,
     zdx_errlog_page_t page = { 0 };
     page.cursor = cursor;
     page.limit = limit;
+    page.since_txg = since_txg;
     page.entries_json = json_array_start();
     page.zhp = zhp;
     if (!page.entries_json) {
@@ -535,6 +773,22 @@ zdx_pool_errors(zdx_pool_t *pool, uint64_t cursor, uint64_t limit,
         next_json = next_buf;
     }
 
+    char since_buf[32];
+    const char *since_json = "null";
+    if (since_txg != 0) {
+        (void) snprintf(since_buf, sizeof (since_buf), "%llu",
+            (unsigned long long)since_txg);
+        since_json = since_buf;
+    }
+
+    char max_birth_buf[32];
+    const char *max_birth_json = "null";
+    if (page.max_birth != 0) {
+        (void) snprintf(max_birth_buf, sizeof (max_birth_buf), "%llu",
+            (unsigned long long)page.max_birth);
+        max_birth_json = max_birth_buf;
+    }
+
     char *pool_name_json = json_string(pool->name ? pool->name : "");
     if (!pool_name_json) {
         free(entries_final);
@@ -551,6 +805,8 @@ zdx_pool_errors(zdx_pool_t *pool, uint64_t cursor, uint64_t limit,
         "\"errlog_scrub_obj\":%llu,"
         "\"cursor\":%llu,"
         "\"limit\":%llu,"
+        "\"since_txg\":%s,"
+        "\"max_birth_txg\":%s,"
         "\"count\":%llu,"
         "\"next\":%s,"
         "\"entries\":%s"
@@ -563,6 +819,8 @@ zdx_pool_errors(zdx_pool_t *pool, uint64_t cursor, uint64_t limit,
         (unsigned long long)errlog_scrub,
         (unsigned long long)cursor,
         (unsigned long long)limit,
+        since_json,
+        max_birth_json,
         (unsigned long long)page.added,
         next_json,
         entries_final);
@@ -598,6 +856,8 @@ zdx_pool_summary(zdx_pool_t *pool)
     char *hostname_json = NULL;
     char *pool_json = NULL;
     char *rootbp = NULL;
+    char *compat_json = NULL;
+    char *health_json = NULL;
     char *result = NULL;
 
     spa_config_enter(spa, SCL_CONFIG, FTAG, RW_READER);
@@ -732,6 +992,37 @@ zdx_pool_summary(zdx_pool_t *pool)
         return make_error(ENOMEM, "failed to encode pool object");
     }
 
+    compat_json = pool_compatibility_json(spa);
+    if (!compat_json) {
+        free(pool_json);
+        free(features_json);
+        free(vdev_tree_json);
+        free(rootbp);
+        return make_error(ENOMEM, "failed to encode compatibility report");
+    }
+
+    /*
+     * The loaded uberblock is already byteswapped to host order, so use the
+     * byte order recorded in the MOS root block pointer instead.
+     */
+    boolean_t swapped = !BP_IS_HOLE(&ub.ub_rootbp) &&
+        BP_SHOULD_BYTESWAP(&ub.ub_rootbp);
+
+    health_json = pool_vdev_health_json(spa);
+    if (!health_json) {
+        free(pool_json);
+        free(features_json);
+        free(vdev_tree_json);
+        free(rootbp);
+        free(compat_json);
+        return make_error(ENOMEM, "failed to encode vdev health");
+    }
+
+    char requested_txg[32] = "null";
+    if (pool->requested_txg != 0)
+        (void) snprintf(requested_txg, sizeof (requested_txg), "%llu",
+            (unsigned long long)pool->requested_txg);
+
     result = json_format(
         "{"
         "\"pool\":%s,"
@@ -740,19 +1031,33 @@ zdx_pool_summary(zdx_pool_t *pool)
         "\"uberblock\":{"
             "\"txg\":%llu,"
             "\"timestamp\":%llu,"
-            "\"rootbp\":%s"
-        "}"
+            "\"rootbp\":%s,"
+            "\"requested_txg\":%s"
+        "},"
+        "\"compatibility\":%s,"
+        "\"health\":%s,"
+        "\"byte_order\":\"%s\","
+        "\"byte_order_warning\":%s"
         "}",
         pool_json,
         features_json,
         vdev_tree_json ? vdev_tree_json : "null",
         (unsigned long long)ub.ub_txg,
         (unsigned long long)ub.ub_timestamp,
-        rootbp);
+        rootbp,
+        requested_txg,
+        compat_json,
+        health_json,
+        swapped ? "swapped" : "native",
+        swapped ? "\"Pool was written on an opposite-endian host; "
+        "structures the bundled library does not byteswap may decode "
+        "incorrectly.\"" : "null");
     free(pool_json);
     free(features_json);
     free(vdev_tree_json);
     free(rootbp);
+    free(compat_json);
+    free(health_json);
 
     if (!result)
         return make_error(ENOMEM, "failed to encode pool summary");
@@ -760,6 +1065,342 @@ zdx_pool_summary(zdx_pool_t *pool)
     return make_success(result);
 }
 
+#define ZDX_HISTORY_BUF_SIZE (128 * 1024)
+
+/*
+ * Per-record callback for zdx_history_walk: return 0 to continue,
+ * ZDX_HISTORY_STOP to end the walk early (reported as truncated), or an
+ * errno to abort it.
+ */
+#define ZDX_HISTORY_STOP (-1)
+typedef int (*zdx_history_cb_t)(nvlist_t *nv, void *arg);
+
+/*
+ * Unpack every pool history record oldest-first and hand it to cb. Read
+ * problems that leave the history partial are described in *history_error
+ * (a JSON string, caller frees) rather than failing the walk.
+ */
+static int
+zdx_history_walk(spa_t *spa, zdx_history_cb_t cb, void *arg,
+    uint64_t *records_out, boolean_t *truncated_out, char **history_error)
+{
+    char *buf = malloc(ZDX_HISTORY_BUF_SIZE);
+    if (!buf)
+        return ENOMEM;
+
+    uint64_t off = 0;
+    uint64_t records = 0;
+    boolean_t truncated = B_FALSE;
+    int rc = 0;
+    *history_error = NULL;
+
+    while (!truncated && rc == 0) {
+        uint64_t len = ZDX_HISTORY_BUF_SIZE;
+        int err = spa_history_get(spa, &off, &len, buf);
+        if (err != 0) {
+            /* Pools without a history object simply have no records. */
+            if (err != ENOENT)
+                *history_error = json_format(
+                    "\"spa_history_get failed: %s\"", strerror(err));
+            break;
+        }
+        if (len == 0)
+            break;
+
+        uint64_t pos = 0;
+        while (len - pos > sizeof (uint64_t)) {
+            uint64_t reclen = 0;
+            for (int i = 0; i < (int)sizeof (uint64_t); i++)
+                reclen += (uint64_t)((uint8_t)buf[pos + i]) << (8 * i);
+            if (len - pos - sizeof (uint64_t) < reclen)
+                break;
+
+            nvlist_t *nv = NULL;
+            if (nvlist_unpack(buf + pos + sizeof (uint64_t), reclen, &nv,
+                0) == 0) {
+                records++;
+                int cb_rc = cb(nv, arg);
+                nvlist_free(nv);
+                if (cb_rc == ZDX_HISTORY_STOP)
+                    truncated = B_TRUE;
+                else if (cb_rc != 0)
+                    rc = cb_rc;
+            }
+            pos += sizeof (uint64_t) + reclen;
+            if (truncated || rc != 0)
+                break;
+        }
+
+        if (pos == 0) {
+            /* A single record larger than the buffer; give up rather than spin. */
+            *history_error = json_format(
+                "\"history record exceeds %d bytes\"", ZDX_HISTORY_BUF_SIZE);
+            break;
+        }
+        /* Re-read any partial record at the end of this chunk. */
+        off -= len - pos;
+    }
+    free(buf);
+
+    if (rc != 0) {
+        free(*history_error);
+        *history_error = NULL;
+        return rc;
+    }
+    *records_out = records;
+    *truncated_out = truncated;
+    return 0;
+}
+
+typedef struct zdx_history_ctx {
+    char *array;
+    uint64_t count;
+    uint64_t max;
+} zdx_history_ctx_t;
+
+static int
+zdx_history_ctx_append(zdx_history_ctx_t *ctx, char *item)
+{
+    char *next = item ? json_array_append(ctx->array, item) : NULL;
+    free(item);
+    if (!next)
+        return ENOMEM;
+    free(ctx->array);
+    ctx->array = next;
+    ctx->count++;
+    return 0;
+}
+
+static int
+zdx_anchor_cb(nvlist_t *nv, void *arg)
+{
+    zdx_history_ctx_t *ctx = arg;
+    uint64_t txg = 0;
+    uint64_t when = 0;
+    if (nvlist_lookup_uint64(nv, ZPOOL_HIST_TXG, &txg) != 0 ||
+        nvlist_lookup_uint64(nv, ZPOOL_HIST_TIME, &when) != 0)
+        return 0;
+    if (ctx->count >= ctx->max)
+        return ZDX_HISTORY_STOP;
+    return zdx_history_ctx_append(ctx, json_format("[%llu,%llu]",
+        (unsigned long long)txg, (unsigned long long)when));
+}
+
+/*
+ * Collect (txg, unix time) anchor pairs: the active uberblock plus every
+ * pool history record that carries a txg.  Records are read oldest-first
+ * and collection stops after max_records anchors.
+ */
+zdx_result_t
+zdx_txg_time_anchors(zdx_pool_t *pool, uint64_t max_records)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    uberblock_t ub = { 0 };
+    spa_config_enter(spa, SCL_STATE, FTAG, RW_READER);
+    ub = spa->spa_uberblock;
+    spa_config_exit(spa, SCL_STATE, FTAG);
+
+    zdx_history_ctx_t ctx = { json_array_start(), 0, max_records };
+    if (!ctx.array)
+        return make_error(ENOMEM, "failed to allocate history buffer");
+
+    uint64_t records = 0;
+    boolean_t truncated = B_FALSE;
+    char *history_error = NULL;
+    int err = zdx_history_walk(spa, zdx_anchor_cb, &ctx, &records,
+        &truncated, &history_error);
+    if (err != 0) {
+        free(ctx.array);
+        return make_error(err, "failed to append JSON item");
+    }
+
+    char *anchors_json = json_array_end(ctx.array, ctx.count > 0);
+    free(ctx.array);
+    if (!anchors_json) {
+        free(history_error);
+        return make_error(ENOMEM, "failed to finalize JSON array");
+    }
+
+    char *result = json_format(
+        "{"
+        "\"uberblock\":{\"txg\":%llu,\"timestamp\":%llu},"
+        "\"history_records\":%llu,"
+        "\"max_records\":%llu,"
+        "\"truncated\":%s,"
+        "\"history_error\":%s,"
+        "\"anchors\":%s"
+        "}",
+        (unsigned long long)ub.ub_txg,
+        (unsigned long long)ub.ub_timestamp,
+        (unsigned long long)records,
+        (unsigned long long)max_records,
+        truncated ? "true" : "false",
+        history_error ? history_error : "null",
+        anchors_json);
+    free(history_error);
+    free(anchors_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/* Copy of an optional string history field as JSON, "null" when absent. */
+static char *
+zdx_history_string_json(nvlist_t *nv, const char *key)
+{
+    const char *value = NULL;
+    if (nvlist_lookup_string(nv, key, &value) != 0)
+        return strdup("null");
+    return json_string(value);
+}
+
+/*
+ * Newest-N window of history events: once max records are held, each new
+ * record replaces the oldest, so a long history keeps its most recent end.
+ */
+typedef struct zdx_history_ring {
+    char **items;
+    uint64_t max;
+    uint64_t seen;
+} zdx_history_ring_t;
+
+static int
+zdx_history_event_cb(nvlist_t *nv, void *arg)
+{
+    zdx_history_ring_t *ring = arg;
+    uint64_t when = 0;
+    if (nvlist_lookup_uint64(nv, ZPOOL_HIST_TIME, &when) != 0)
+        return 0;
+
+    char txg[32] = "null";
+    uint64_t value = 0;
+    if (nvlist_lookup_uint64(nv, ZPOOL_HIST_TXG, &value) == 0)
+        (void) snprintf(txg, sizeof (txg), "%llu", (unsigned long long)value);
+
+    char *cmd = zdx_history_string_json(nv, ZPOOL_HIST_CMD);
+    char *internal = zdx_history_string_json(nv, ZPOOL_HIST_INT_NAME);
+    char *dataset = zdx_history_string_json(nv, ZPOOL_HIST_DSNAME);
+    char *message = zdx_history_string_json(nv, ZPOOL_HIST_INT_STR);
+    char *ioctl = zdx_history_string_json(nv, ZPOOL_HIST_IOCTL);
+    char *item = (cmd && internal && dataset && message && ioctl) ?
+        json_format(
+        "{\"time\":%llu,\"txg\":%s,\"command\":%s,\"internal\":%s,"
+        "\"dataset\":%s,\"message\":%s,\"ioctl\":%s}",
+        (unsigned long long)when, txg, cmd, internal, dataset, message,
+        ioctl) : NULL;
+    free(cmd);
+    free(internal);
+    free(dataset);
+    free(message);
+    free(ioctl);
+    if (!item)
+        return ENOMEM;
+
+    uint64_t slot = ring->seen % ring->max;
+    free(ring->items[slot]);
+    ring->items[slot] = item;
+    ring->seen++;
+    return 0;
+}
+
+/* The events a ring holds, oldest first, joined into one JSON array. */
+static char *
+zdx_history_ring_json(const zdx_history_ring_t *ring)
+{
+    uint64_t held = MIN(ring->seen, ring->max);
+    uint64_t first = ring->seen > ring->max ? ring->seen % ring->max : 0;
+    size_t len = 2;
+    for (uint64_t i = 0; i < held; i++)
+        len += strlen(ring->items[(first + i) % ring->max]) + 1;
+
+    char *out = malloc(len + 1);
+    if (!out)
+        return NULL;
+    char *p = out;
+    *p++ = '[';
+    for (uint64_t i = 0; i < held; i++) {
+        const char *item = ring->items[(first + i) % ring->max];
+        size_t item_len = strlen(item);
+        if (i > 0)
+            *p++ = ',';
+        memcpy(p, item, item_len);
+        p += item_len;
+    }
+    *p++ = ']';
+    *p = '\0';
+    return out;
+}
+
+static void
+zdx_history_ring_free(zdx_history_ring_t *ring)
+{
+    for (uint64_t i = 0; i < ring->max; i++)
+        free(ring->items[i]);
+    free(ring->items);
+}
+
+/*
+ * Pool history records (`zpool history -i`), oldest first: user commands,
+ * internal events with their dataset and message, and ioctl records.
+ * Records without a timestamp are skipped; of the rest, the newest
+ * max_records are kept and `truncated` reports that older ones were dropped.
+ */
+zdx_result_t
+zdx_pool_history(zdx_pool_t *pool, uint64_t max_records)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+    if (max_records == 0)
+        return make_error(EINVAL, "max_records must be positive");
+
+    zdx_history_ring_t ring = { NULL, max_records, 0 };
+    ring.items = calloc(max_records, sizeof (char *));
+    if (!ring.items)
+        return make_error(ENOMEM, "failed to allocate history buffer");
+
+    uint64_t records = 0;
+    boolean_t truncated = B_FALSE;
+    char *history_error = NULL;
+    int err = zdx_history_walk(pool->spa, zdx_history_event_cb, &ring,
+        &records, &truncated, &history_error);
+    if (err != 0) {
+        zdx_history_ring_free(&ring);
+        return make_error(err, "failed to append JSON item");
+    }
+    truncated = ring.seen > ring.max;
+
+    char *events_json = zdx_history_ring_json(&ring);
+    zdx_history_ring_free(&ring);
+    if (!events_json) {
+        free(history_error);
+        return make_error(ENOMEM, "failed to finalize JSON array");
+    }
+
+    char *result = json_format(
+        "{"
+        "\"history_records\":%llu,"
+        "\"max_records\":%llu,"
+        "\"truncated\":%s,"
+        "\"history_error\":%s,"
+        "\"events\":%s"
+        "}",
+        (unsigned long long)records,
+        (unsigned long long)max_records,
+        truncated ? "true" : "false",
+        history_error ? history_error : "null",
+        events_json);
+    free(history_error);
+    free(events_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
 /*
  * Pool info (compat shim).
  */
@@ -778,6 +1419,319 @@ zdx_pool_vdevs(zdx_pool_t *pool)
     return zdx_pool_summary(pool);
 }
 
+/*
+ * Allocation classes, in report order. Normal is always listed; the others
+ * only when the pool has a top-level vdev biased toward them.
+ */
+typedef enum zdx_alloc_class {
+    ZDX_CLASS_NORMAL,
+    ZDX_CLASS_SPECIAL,
+    ZDX_CLASS_DEDUP,
+    ZDX_CLASS_LOG,
+    ZDX_CLASS_COUNT,
+} zdx_alloc_class_t;
+
+static const char *zdx_alloc_class_names[ZDX_CLASS_COUNT] = {
+    "normal", "special", "dedup", "log",
+};
+
+static zdx_alloc_class_t
+vdev_alloc_class(const vdev_t *tvd)
+{
+    if (tvd->vdev_islog || tvd->vdev_alloc_bias == VDEV_BIAS_LOG)
+        return ZDX_CLASS_LOG;
+    if (tvd->vdev_alloc_bias == VDEV_BIAS_SPECIAL)
+        return ZDX_CLASS_SPECIAL;
+    if (tvd->vdev_alloc_bias == VDEV_BIAS_DEDUP)
+        return ZDX_CLASS_DEDUP;
+    return ZDX_CLASS_NORMAL;
+}
+
+/*
+ * Per-class space usage summed over top-level vdevs. Space and allocation
+ * come from the in-core vdev stats, which a read-only import populates from
+ * the metaslab space maps, so live and offline opens report the same way.
+ */
+zdx_result_t
+zdx_pool_alloc_classes(zdx_pool_t *pool)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    uint64_t class_size[ZDX_CLASS_COUNT] = { 0 };
+    uint64_t class_alloc[ZDX_CLASS_COUNT] = { 0 };
+    uint64_t class_vdevs[ZDX_CLASS_COUNT] = { 0 };
+    int vdev_count = 0;
+
+    char *vdevs = json_array_start();
+    if (!vdevs)
+        return make_error(ENOMEM, "failed to allocate JSON array");
+
+    spa_config_enter(spa, SCL_VDEV, FTAG, RW_READER);
+    vdev_t *rvd = spa->spa_root_vdev;
+    for (uint64_t c = 0; rvd != NULL && c < rvd->vdev_children; c++) {
+        vdev_t *tvd = rvd->vdev_child[c];
+        if (tvd->vdev_ishole || tvd->vdev_mg == NULL)
+            continue;
+
+        zdx_alloc_class_t cls = vdev_alloc_class(tvd);
+        uint64_t size = tvd->vdev_stat.vs_space;
+        uint64_t alloc = tvd->vdev_stat.vs_alloc;
+        class_size[cls] += size;
+        class_alloc[cls] += alloc;
+        class_vdevs[cls]++;
+
+        char *item = json_format(
+            "{\"id\":%llu,\"guid\":%llu,\"type\":\"%s\","
+            "\"class\":\"%s\",\"size_bytes\":%llu,"
+            "\"alloc_bytes\":%llu,\"free_bytes\":%llu}",
+            (unsigned long long)tvd->vdev_id,
+            (unsigned long long)tvd->vdev_guid,
+            tvd->vdev_ops->vdev_op_type,
+            zdx_alloc_class_names[cls],
+            (unsigned long long)size,
+            (unsigned long long)alloc,
+            (unsigned long long)(size > alloc ? size - alloc : 0));
+        char *next = item ? json_array_append(vdevs, item) : NULL;
+        free(item);
+        if (!next) {
+            spa_config_exit(spa, SCL_VDEV, FTAG);
+            free(vdevs);
+            return make_error(ENOMEM, "failed to append vdev JSON");
+        }
+        free(vdevs);
+        vdevs = next;
+        vdev_count++;
+    }
+    spa_config_exit(spa, SCL_VDEV, FTAG);
+
+    char *vdevs_json = json_array_end(vdevs, vdev_count > 0);
+    free(vdevs);
+    if (!vdevs_json)
+        return make_error(ENOMEM, "failed to finalize vdev JSON");
+
+    char *classes = json_array_start();
+    int class_count = 0;
+    for (int i = 0; classes != NULL && i < ZDX_CLASS_COUNT; i++) {
+        if (i != ZDX_CLASS_NORMAL && class_vdevs[i] == 0)
+            continue;
+        uint64_t size = class_size[i];
+        uint64_t alloc = class_alloc[i];
+        char *item = json_format(
+            "{\"class\":\"%s\",\"vdev_count\":%llu,"
+            "\"size_bytes\":%llu,\"alloc_bytes\":%llu,"
+            "\"free_bytes\":%llu}",
+            zdx_alloc_class_names[i],
+            (unsigned long long)class_vdevs[i],
+            (unsigned long long)size,
+            (unsigned long long)alloc,
+            (unsigned long long)(size > alloc ? size - alloc : 0));
+        char *next = item ? json_array_append(classes, item) : NULL;
+        free(item);
+        free(classes);
+        classes = next;
+        class_count++;
+    }
+    char *classes_json = classes ? json_array_end(classes, class_count > 0) :
+        NULL;
+    free(classes);
+    if (!classes_json) {
+        free(vdevs_json);
+        return make_error(ENOMEM, "failed to encode allocation classes");
+    }
+
+    char *result = json_format("{\"classes\":%s,\"vdevs\":%s}",
+        classes_json, vdevs_json);
+    free(classes_json);
+    free(vdevs_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
+
+/*
+ * Pool checkpoint state. The checkpointed uberblock lives in the MOS object
+ * directory; the space it pins is summed from the per-vdev checkpoint space
+ * maps when the pool is loaded. While a checkpoint is being discarded the
+ * directory entry is already gone but the feature stays active.
+ */
+zdx_result_t
+zdx_pool_checkpoint(zdx_pool_t *pool)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    objset_t *mos = spa_meta_objset(spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    boolean_t active = spa_feature_is_active(spa, SPA_FEATURE_POOL_CHECKPOINT);
+
+    uberblock_t ub;
+    int err = zap_lookup(mos, DMU_POOL_DIRECTORY_OBJECT,
+        DMU_POOL_ZPOOL_CHECKPOINT, sizeof (uint64_t),
+        sizeof (uberblock_t) / sizeof (uint64_t), &ub);
+    if (err == ENOENT) {
+        char *json = json_format("{\"exists\":false,\"discarding\":%s}",
+            active ? "true" : "false");
+        if (!json)
+            return make_error(ENOMEM, "failed to allocate JSON result");
+        return make_success(json);
+    }
+    if (err != 0)
+        return make_error(err, "failed to read checkpointed uberblock: %s",
+            strerror(err));
+
+    char *json = json_format(
+        "{"
+        "\"exists\":true,"
+        "\"discarding\":false,"
+        "\"checkpoint_txg\":%llu,"
+        "\"timestamp\":%llu,"
+        "\"space_bytes\":%llu"
+        "}",
+        (unsigned long long)ub.ub_txg,
+        (unsigned long long)ub.ub_timestamp,
+        (unsigned long long)spa->spa_checkpoint_info.sci_dspace);
+    if (!json)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+    return make_success(json);
+}
+
+/*
+ * Read the persistent L2ARC device header that sits right after the front
+ * labels of a cache device and classify it. The header checksum is not
+ * verified; a matching magic and pool/vdev GUIDs are taken as valid.
+ */
+static char *
+l2arc_device_header_json(spa_t *spa, vdev_t *vd, const char **rebuild_state)
+{
+    l2arc_dev_hdr_phys_t hdr;
+
+    *rebuild_state = "unreadable";
+    if (vd->vdev_path == NULL)
+        return strdup("null");
+
+    int fd = open(vd->vdev_path, O_RDONLY);
+    if (fd < 0)
+        return strdup("null");
+    ssize_t got = pread(fd, &hdr, sizeof (hdr), VDEV_LABEL_START_SIZE);
+    (void) close(fd);
+    if (got != (ssize_t)sizeof (hdr))
+        return strdup("null");
+
+    if (hdr.dh_magic == BSWAP_64(L2ARC_DEV_HDR_MAGIC))
+        byteswap_uint64_array(&hdr, sizeof (hdr));
+    if (hdr.dh_magic != L2ARC_DEV_HDR_MAGIC) {
+        *rebuild_state = "no_header";
+        return strdup("null");
+    }
+
+    boolean_t spa_match = hdr.dh_spa_guid == spa_guid(spa);
+    boolean_t vdev_match = hdr.dh_vdev_guid == vd->vdev_guid;
+    if (!spa_match || !vdev_match)
+        *rebuild_state = "foreign";
+    else if (hdr.dh_lb_count == 0)
+        *rebuild_state = "empty";
+    else
+        *rebuild_state = "rebuildable";
+
+    return json_format(
+        "{"
+        "\"version\":%llu,"
+        "\"spa_guid_match\":%s,"
+        "\"vdev_guid_match\":%s,"
+        "\"log_entries\":%llu,"
+        "\"log_blocks\":%llu,"
+        "\"log_blocks_asize\":%llu,"
+        "\"start\":%llu,"
+        "\"end\":%llu,"
+        "\"evict\":%llu,"
+        "\"flags\":%llu"
+        "}",
+        (unsigned long long)hdr.dh_version,
+        spa_match ? "true" : "false",
+        vdev_match ? "true" : "false",
+        (unsigned long long)hdr.dh_log_entries,
+        (unsigned long long)hdr.dh_lb_count,
+        (unsigned long long)hdr.dh_lb_asize,
+        (unsigned long long)hdr.dh_start,
+        (unsigned long long)hdr.dh_end,
+        (unsigned long long)hdr.dh_evict,
+        (unsigned long long)hdr.dh_flags);
+}
+
+/*
+ * Cache (L2ARC) devices from the pool config, each with its persistent
+ * L2ARC header state.
+ */
+zdx_result_t
+zdx_pool_l2arc(zdx_pool_t *pool)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    char *array = json_array_start();
+    if (!array)
+        return make_error(ENOMEM, "failed to allocate JSON array");
+
+    int count = 0;
+    spa_config_enter(spa, SCL_STATE, FTAG, RW_READER);
+    for (int i = 0; i < spa->spa_l2cache.sav_count; i++) {
+        vdev_t *vd = spa->spa_l2cache.sav_vdevs[i];
+        const char *rebuild_state = NULL;
+        char *header = l2arc_device_header_json(spa, vd, &rebuild_state);
+        char *path = json_string(vd->vdev_path != NULL ? vd->vdev_path : "");
+        char *item = NULL;
+        if (header && path) {
+            item = json_format(
+                "{"
+                "\"guid\":%llu,"
+                "\"path\":%s,"
+                "\"state\":\"%s\","
+                "\"size_bytes\":%llu,"
+                "\"rebuild_state\":\"%s\","
+                "\"header\":%s"
+                "}",
+                (unsigned long long)vd->vdev_guid,
+                path,
+                zpool_state_to_name(vd->vdev_state, vd->vdev_stat.vs_aux),
+                (unsigned long long)(vd->vdev_psize != 0 ? vd->vdev_psize :
+                vd->vdev_asize),
+                rebuild_state,
+                header);
+        }
+        free(header);
+        free(path);
+
+        char *next = item ? json_array_append(array, item) : NULL;
+        free(item);
+        free(array);
+        array = next;
+        if (!array) {
+            spa_config_exit(spa, SCL_STATE, FTAG);
+            return make_error(ENOMEM, "failed to build L2ARC device JSON");
+        }
+        count++;
+    }
+    spa_config_exit(spa, SCL_STATE, FTAG);
+
+    char *devices = json_array_end(array, count > 0);
+    free(array);
+    if (!devices)
+        return make_error(ENOMEM, "failed to allocate JSON array");
+
+    char *json = json_format("{\"count\":%d,\"devices\":%s}", count, devices);
+    free(devices);
+    if (!json)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+    return make_success(json);
+}
+
 /*
  * Dataset list callback context
  */
@@ -946,3 +1900,448 @@ zdx_pool_datasets(zdx_pool_t *pool)
 
     return make_success(final_json);
 }
+
+#define ZDX_LABEL_MAX_LEAVES 64
+
+typedef struct zdx_label_leaf {
+    uint64_t id;
+    uint64_t guid;
+    uint64_t ub_shift;
+    char *path;
+} zdx_label_leaf_t;
+
+/*
+ * Append item to *arr, taking ownership of item. Returns ENOMEM on failure,
+ * leaving *arr untouched.
+ */
+static int
+zdx_label_json_append(char **arr, char *item)
+{
+    if (!item)
+        return ENOMEM;
+    char *next = json_array_append(*arr, item);
+    free(item);
+    if (!next)
+        return ENOMEM;
+    free(*arr);
+    *arr = next;
+    return 0;
+}
+
+static void
+zdx_collect_label_leaves(vdev_t *vd, zdx_label_leaf_t *leaves, int *count,
+    boolean_t *truncated)
+{
+    if (vd->vdev_ops->vdev_op_leaf) {
+        if (*count >= ZDX_LABEL_MAX_LEAVES) {
+            *truncated = B_TRUE;
+            return;
+        }
+        zdx_label_leaf_t *leaf = &leaves[*count];
+        leaf->id = vd->vdev_id;
+        leaf->guid = vd->vdev_guid;
+        leaf->ub_shift = VDEV_UBERBLOCK_SHIFT(vd);
+        leaf->path = vd->vdev_path ? strdup(vd->vdev_path) : NULL;
+        (*count)++;
+        return;
+    }
+    for (uint64_t c = 0; c < vd->vdev_children; c++)
+        zdx_collect_label_leaves(vd->vdev_child[c], leaves, count, truncated);
+}
+
+/*
+ * Valid uberblocks in one label's ring, in slot order. Slots with a bad
+ * magic or txg 0 are unused and skipped; byteswapped slots are converted.
+ */
+static char *
+zdx_label_uberblocks_json(const vdev_label_t *label, uint64_t ub_shift,
+    uint64_t active_txg, uint64_t *count_out, uint64_t *best_txg_out)
+{
+    char *ubs = json_array_start();
+    uint64_t slots = VDEV_UBERBLOCK_RING >> ub_shift;
+    uint64_t count = 0;
+    uint64_t best_txg = 0;
+
+    for (uint64_t n = 0; ubs != NULL && n < slots; n++) {
+        uberblock_t ub;
+        memcpy(&ub, label->vl_uberblock + (n << ub_shift), sizeof (ub));
+        if (ub.ub_magic == BSWAP_64((uint64_t)UBERBLOCK_MAGIC))
+            byteswap_uint64_array(&ub, sizeof (ub));
+        else if (ub.ub_magic != UBERBLOCK_MAGIC)
+            continue;
+        if (ub.ub_txg == 0)
+            continue;
+
+        char *item = json_format(
+            "{\"slot\":%llu,\"txg\":%llu,\"timestamp\":%llu,"
+            "\"guid_sum\":%llu,\"version\":%llu,\"active\":%s}",
+            (unsigned long long)n,
+            (unsigned long long)ub.ub_txg,
+            (unsigned long long)ub.ub_timestamp,
+            (unsigned long long)ub.ub_guid_sum,
+            (unsigned long long)ub.ub_version,
+            ub.ub_txg == active_txg ? "true" : "false");
+        if (zdx_label_json_append(&ubs, item) != 0) {
+            free(ubs);
+            return NULL;
+        }
+        count++;
+        if (ub.ub_txg > best_txg)
+            best_txg = ub.ub_txg;
+    }
+
+    if (!ubs)
+        return NULL;
+    char *out = json_array_end(ubs, count > 0);
+    free(ubs);
+    *count_out = count;
+    *best_txg_out = best_txg;
+    return out;
+}
+
+/*
+ * One label: the unpacked config nvlist plus its uberblock ring. A label is
+ * valid when it reads and its nvlist unpacks; matches_device additionally
+ * requires its pool and vdev guids to agree with the open pool.
+ */
+static char *
+zdx_label_json(int fd, int l, uint64_t psize, vdev_label_t *label,
+    const zdx_label_leaf_t *leaf, uint64_t pool_guid, uint64_t active_txg,
+    boolean_t *valid_out, uint64_t *txg_out)
+{
+    uint64_t offset = vdev_label_offset(psize, l, 0);
+    *valid_out = B_FALSE;
+    *txg_out = 0;
+
+    if (pread(fd, label, sizeof (*label), (off_t)offset) !=
+        (ssize_t)sizeof (*label)) {
+        return json_format(
+            "{\"index\":%d,\"offset\":%llu,\"valid\":false,"
+            "\"matches_device\":false,\"txg\":null,"
+            "\"error\":\"short read\",\"config\":null,"
+            "\"uberblock_count\":0,\"best_txg\":null,\"uberblocks\":[]}",
+            l, (unsigned long long)offset);
+    }
+
+    nvlist_t *config = NULL;
+    uint64_t label_pool_guid = 0, label_guid = 0, label_txg = 0;
+    char *config_json = NULL;
+    if (nvlist_unpack(label->vl_vdev_phys.vp_nvlist,
+        sizeof (label->vl_vdev_phys.vp_nvlist), &config, 0) == 0) {
+        (void) nvlist_lookup_uint64(config, ZPOOL_CONFIG_POOL_GUID,
+            &label_pool_guid);
+        (void) nvlist_lookup_uint64(config, ZPOOL_CONFIG_GUID, &label_guid);
+        (void) nvlist_lookup_uint64(config, ZPOOL_CONFIG_POOL_TXG,
+            &label_txg);
+        config_json = nvlist_to_json_string(config);
+        nvlist_free(config);
+        if (!config_json)
+            return NULL;
+        *valid_out = B_TRUE;
+        *txg_out = label_txg;
+    }
+
+    uint64_t ub_count = 0, best_txg = 0;
+    char *ubs = zdx_label_uberblocks_json(label, leaf->ub_shift, active_txg,
+        &ub_count, &best_txg);
+    if (!ubs) {
+        free(config_json);
+        return NULL;
+    }
+
+    char txg_buf[32] = "null";
+    char best_buf[32] = "null";
+    if (*valid_out)
+        (void) snprintf(txg_buf, sizeof (txg_buf), "%llu",
+            (unsigned long long)label_txg);
+    if (ub_count > 0)
+        (void) snprintf(best_buf, sizeof (best_buf), "%llu",
+            (unsigned long long)best_txg);
+
+    boolean_t matches = *valid_out && label_pool_guid == pool_guid &&
+        label_guid == leaf->guid;
+    char *item = json_format(
+        "{\"index\":%d,\"offset\":%llu,\"valid\":%s,\"matches_device\":%s,"
+        "\"txg\":%s,\"error\":%s,\"config\":%s,\"uberblock_count\":%llu,"
+        "\"best_txg\":%s,\"uberblocks\":%s}",
+        l,
+        (unsigned long long)offset,
+        *valid_out ? "true" : "false",
+        matches ? "true" : "false",
+        txg_buf,
+        *valid_out ? "null" : "\"config nvlist did not unpack\"",
+        config_json ? config_json : "null",
+        (unsigned long long)ub_count,
+        best_buf,
+        ubs);
+    free(config_json);
+    free(ubs);
+    return item;
+}
+
+/*
+ * All four labels of one leaf device, read straight from its path the way
+ * `zdb -l` does. consistent is true when every label is valid and carries
+ * the same config txg.
+ */
+static char *
+zdx_label_leaf_json(const zdx_label_leaf_t *leaf, uint64_t pool_guid,
+    uint64_t active_txg)
+{
+    char *path_json = json_string(leaf->path ? leaf->path : "");
+    if (!path_json)
+        return NULL;
+
+    const char *open_error = NULL;
+    int fd = -1;
+    uint64_t psize = 0;
+    if (!leaf->path) {
+        open_error = "device has no path";
+    } else if ((fd = open(leaf->path, O_RDONLY)) < 0) {
+        open_error = strerror(errno);
+    } else {
+        off_t end = lseek(fd, 0, SEEK_END);
+        psize = end > 0 ? P2ALIGN((uint64_t)end, sizeof (vdev_label_t)) : 0;
+        if (psize < VDEV_LABELS * sizeof (vdev_label_t))
+            open_error = "device is too small to hold four labels";
+    }
+
+    if (open_error) {
+        if (fd >= 0)
+            (void) close(fd);
+        char *err_json = json_string(open_error);
+        char *item = err_json ? json_format(
+            "{\"id\":%llu,\"guid\":%llu,\"path\":%s,\"valid_labels\":0,"
+            "\"consistent\":false,\"error\":%s,\"labels\":[]}",
+            (unsigned long long)leaf->id,
+            (unsigned long long)leaf->guid,
+            path_json,
+            err_json) : NULL;
+        free(err_json);
+        free(path_json);
+        return item;
+    }
+
+    vdev_label_t *label = malloc(sizeof (vdev_label_t));
+    char *labels = label ? json_array_start() : NULL;
+    int valid_count = 0;
+    uint64_t first_txg = 0;
+    boolean_t same_txg = B_TRUE;
+    for (int l = 0; labels != NULL && l < VDEV_LABELS; l++) {
+        boolean_t valid = B_FALSE;
+        uint64_t txg = 0;
+        char *item = zdx_label_json(fd, l, psize, label, leaf, pool_guid,
+            active_txg, &valid, &txg);
+        if (zdx_label_json_append(&labels, item) != 0) {
+            free(labels);
+            labels = NULL;
+            break;
+        }
+        if (valid) {
+            if (valid_count == 0)
+                first_txg = txg;
+            else if (txg != first_txg)
+                same_txg = B_FALSE;
+            valid_count++;
+        }
+    }
+    (void) close(fd);
+    free(label);
+
+    char *labels_json = labels ? json_array_end(labels, 1) : NULL;
+    free(labels);
+    if (!labels_json) {
+        free(path_json);
+        return NULL;
+    }
+
+    char *item = json_format(
+        "{\"id\":%llu,\"guid\":%llu,\"path\":%s,\"valid_labels\":%d,"
+        "\"consistent\":%s,\"error\":null,\"labels\":%s}",
+        (unsigned long long)leaf->id,
+        (unsigned long long)leaf->guid,
+        path_json,
+        valid_count,
+        valid_count == VDEV_LABELS && same_txg ? "true" : "false",
+        labels_json);
+    free(path_json);
+    free(labels_json);
+    return item;
+}
+
+/*
+ * Raw labels (`zdb -l` in structured form) for every leaf device under a
+ * top-level vdev: each label's config nvlist and uberblock ring.
+ */
+zdx_result_t
+zdx_vdev_labels(zdx_pool_t *pool, uint64_t vdev)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    zdx_label_leaf_t *leaves = calloc(ZDX_LABEL_MAX_LEAVES, sizeof (*leaves));
+    if (!leaves)
+        return make_error(ENOMEM, "failed to allocate leaf list");
+
+    int leaf_count = 0;
+    boolean_t truncated = B_FALSE;
+    spa_config_enter(spa, SCL_CONFIG, FTAG, RW_READER);
+    vdev_t *rvd = spa->spa_root_vdev;
+    if (rvd == NULL || vdev >= rvd->vdev_children) {
+        spa_config_exit(spa, SCL_CONFIG, FTAG);
+        free(leaves);
+        return make_error(ENOENT, "top-level vdev %llu not found",
+            (unsigned long long)vdev);
+    }
+    vdev_t *tvd = rvd->vdev_child[vdev];
+    const char *vdev_type = tvd->vdev_ops->vdev_op_type;
+    zdx_collect_label_leaves(tvd, leaves, &leaf_count, &truncated);
+    spa_config_exit(spa, SCL_CONFIG, FTAG);
+
+    uint64_t pool_guid = spa_guid(spa);
+    uint64_t active_txg = spa->spa_uberblock.ub_txg;
+
+    char *devices = json_array_start();
+    for (int i = 0; devices != NULL && i < leaf_count; i++) {
+        char *item = zdx_label_leaf_json(&leaves[i], pool_guid, active_txg);
+        if (zdx_label_json_append(&devices, item) != 0) {
+            free(devices);
+            devices = NULL;
+        }
+    }
+    for (int i = 0; i < leaf_count; i++)
+        free(leaves[i].path);
+    free(leaves);
+
+    char *devices_json = devices ? json_array_end(devices, leaf_count > 0) :
+        NULL;
+    free(devices);
+    if (!devices_json)
+        return make_error(ENOMEM, "failed to encode vdev labels");
+
+    char *result = json_format(
+        "{\"vdev\":%llu,\"vdev_type\":\"%s\",\"pool_guid\":%llu,"
+        "\"active_txg\":%llu,\"device_count\":%d,\"truncated\":%s,"
+        "\"devices\":%s}",
+        (unsigned long long)vdev,
+        vdev_type,
+        (unsigned long long)pool_guid,
+        (unsigned long long)active_txg,
+        leaf_count,
+        truncated ? "true" : "false",
+        devices_json);
+    free(devices_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to encode vdev labels");
+    return make_success(result);
+}
+
+/*
+ * Identify the pool on a device or image file from its labels alone, without
+ * importing it. Of the four labels, the valid one with the highest config
+ * txg wins; labels without a pool name (spares, cache devices) do not count.
+ * Only regular files and block devices are read: the open is non-blocking so
+ * a FIFO or tty cannot stall the caller, and errors do not echo errno, so the
+ * result does not reveal which other paths exist. Touches no pool state, so
+ * callers need not hold the FFI lock.
+ */
+zdx_result_t
+zdx_label_pool_name(const char *path)
+{
+    if (!path || path[0] == '\0')
+        return make_error(EINVAL, "device path is required");
+
+    int fd = open(path, O_RDONLY | O_NONBLOCK | O_NOCTTY | O_CLOEXEC);
+    if (fd < 0)
+        return make_error(EINVAL, "cannot open %s", path);
+
+    struct stat st;
+    if (fstat(fd, &st) != 0 ||
+        !(S_ISREG(st.st_mode) || S_ISBLK(st.st_mode))) {
+        (void) close(fd);
+        return make_error(EINVAL, "cannot open %s: not a regular file or "
+            "block device", path);
+    }
+
+    off_t end = lseek(fd, 0, SEEK_END);
+    uint64_t psize = end > 0 ?
+        P2ALIGN((uint64_t)end, sizeof (vdev_label_t)) : 0;
+    if (psize < VDEV_LABELS * sizeof (vdev_label_t)) {
+        (void) close(fd);
+        return make_error(ENOENT, "no ZFS label found on %s "
+            "(too small to hold four labels)", path);
+    }
+
+    vdev_label_t *label = malloc(sizeof (vdev_label_t));
+    if (!label) {
+        (void) close(fd);
+        return make_error(ENOMEM, "failed to allocate label buffer");
+    }
+
+    char *best_name = NULL;
+    uint64_t best_guid = 0, best_txg = 0, best_state = 0;
+    int best_index = -1;
+    int valid_count = 0;
+    for (int l = 0; l < VDEV_LABELS; l++) {
+        uint64_t offset = vdev_label_offset(psize, l, 0);
+        if (pread(fd, label, sizeof (*label), (off_t)offset) !=
+            (ssize_t)sizeof (*label))
+            continue;
+
+        nvlist_t *config = NULL;
+        if (nvlist_unpack(label->vl_vdev_phys.vp_nvlist,
+            sizeof (label->vl_vdev_phys.vp_nvlist), &config, 0) != 0)
+            continue;
+
+        const char *name = NULL;
+        uint64_t guid = 0, txg = 0, state = 0;
+        if (nvlist_lookup_string(config, ZPOOL_CONFIG_POOL_NAME,
+            &name) == 0 &&
+            nvlist_lookup_uint64(config, ZPOOL_CONFIG_POOL_GUID,
+            &guid) == 0) {
+            (void) nvlist_lookup_uint64(config, ZPOOL_CONFIG_POOL_TXG, &txg);
+            (void) nvlist_lookup_uint64(config, ZPOOL_CONFIG_POOL_STATE,
+                &state);
+            valid_count++;
+            if (best_index < 0 || txg > best_txg) {
+                char *copy = strdup(name);
+                if (copy) {
+                    free(best_name);
+                    best_name = copy;
+                    best_guid = guid;
+                    best_txg = txg;
+                    best_state = state;
+                    best_index = l;
+                }
+            }
+        }
+        nvlist_free(config);
+    }
+    (void) close(fd);
+    free(label);
+
+    if (!best_name)
+        return make_error(ENOENT, "no ZFS label found on %s", path);
+
+    char *path_json = json_string(path);
+    char *name_json = json_string(best_name);
+    free(best_name);
+    char *result = (path_json && name_json) ? json_format(
+        "{\"path\":%s,\"pool_name\":%s,\"pool_guid\":%llu,\"state\":\"%s\","
+        "\"txg\":%llu,\"label_index\":%d,\"valid_labels\":%d}",
+        path_json,
+        name_json,
+        (unsigned long long)best_guid,
+        zpool_pool_state_to_name((pool_state_t)best_state),
+        (unsigned long long)best_txg,
+        best_index,
+        valid_count) : NULL;
+    free(path_json);
+    free(name_json);
+
+    if (!result)
+        return make_error(ENOMEM, "failed to allocate JSON result");
+
+    return make_success(result);
+}
@@ -1,4 +1,6 @@
 #include "zdbdecode_internal.h"
+#include <sys/vdev_impl.h>
+#include <sys/metaslab_impl.h>
 
 #define ZDX_SPACEMAP_PAGE_STOP 1
 #define ZDX_SPACEMAP_OP_ANY 0
@@ -1046,3 +1048,355 @@ zdx_spacemap_bins(zdx_pool_t *pool, uint64_t objid, uint64_t bin_size,
         return make_error(ENOMEM, "failed to encode spacemap bins");
     return make_success(result);
 }
+
+typedef struct zdx_spacemap_list_ctx {
+    zdx_pool_t *pool;
+    uint64_t cursor;
+    uint64_t limit;
+    uint64_t seen;
+    uint64_t added;
+    boolean_t has_more;
+    char *rows_json;
+} zdx_spacemap_list_ctx_t;
+
+/*
+ * Append one spacemap row to the current page. vdev, metaslab_id, and txg
+ * are UINT64_MAX when they do not apply to the role.
+ */
+static int
+zdx_spacemap_list_add(zdx_spacemap_list_ctx_t *ctx, uint64_t objid,
+    const char *role, uint64_t vdev, uint64_t metaslab_id, uint64_t txg)
+{
+    if (objid == 0)
+        return 0;
+
+    if (ctx->seen < ctx->cursor) {
+        ctx->seen++;
+        return 0;
+    }
+    if (ctx->added >= ctx->limit) {
+        ctx->has_more = B_TRUE;
+        return ZDX_SPACEMAP_PAGE_STOP;
+    }
+
+    char length_buf[32], alloc_buf[32];
+    const char *length_json = "null";
+    const char *alloc_json = "null";
+    space_map_t *sm = NULL;
+    if (zdx_open_spacemap(ctx->pool, objid, &sm) == 0) {
+        (void) snprintf(length_buf, sizeof (length_buf), "%llu",
+            (unsigned long long)space_map_length(sm));
+        (void) snprintf(alloc_buf, sizeof (alloc_buf), "%lld",
+            (long long)space_map_allocated(sm));
+        length_json = length_buf;
+        alloc_json = alloc_buf;
+        space_map_close(sm);
+    }
+
+    char vdev_buf[32], ms_buf[32], txg_buf[32];
+    (void) snprintf(vdev_buf, sizeof (vdev_buf), "%llu",
+        (unsigned long long)vdev);
+    (void) snprintf(ms_buf, sizeof (ms_buf), "%llu",
+        (unsigned long long)metaslab_id);
+    (void) snprintf(txg_buf, sizeof (txg_buf), "%llu",
+        (unsigned long long)txg);
+
+    char *item = json_format(
+        "{\"objid\":%llu,\"role\":\"%s\",\"vdev\":%s,\"metaslab_id\":%s,"
+        "\"txg\":%s,\"length_bytes\":%s,\"allocated_bytes\":%s}",
+        (unsigned long long)objid,
+        role,
+        vdev == UINT64_MAX ? "null" : vdev_buf,
+        metaslab_id == UINT64_MAX ? "null" : ms_buf,
+        txg == UINT64_MAX ? "null" : txg_buf,
+        length_json,
+        alloc_json);
+    if (!item)
+        return ENOMEM;
+
+    char *next = json_array_append(ctx->rows_json, item);
+    free(item);
+    if (!next)
+        return ENOMEM;
+    free(ctx->rows_json);
+    ctx->rows_json = next;
+    ctx->added++;
+    ctx->seen++;
+    return 0;
+}
+
+static int
+zdx_spacemap_list_vdev(zdx_spacemap_list_ctx_t *ctx, objset_t *mos,
+    vdev_t *tvd)
+{
+    int err = 0;
+
+    if (tvd->vdev_ms_array != 0) {
+        for (uint64_t m = 0; m < tvd->vdev_ms_count; m++) {
+            uint64_t sm_obj = 0;
+            if (dmu_read(mos, tvd->vdev_ms_array, m * sizeof (uint64_t),
+                sizeof (uint64_t), &sm_obj, DMU_READ_PREFETCH) != 0)
+                continue;
+            err = zdx_spacemap_list_add(ctx, sm_obj, "metaslab",
+                tvd->vdev_id, m, UINT64_MAX);
+            if (err != 0)
+                return err;
+        }
+    }
+
+    if (tvd->vdev_top_zap == 0)
+        return 0;
+
+    static const struct {
+        const char *key;
+        const char *role;
+    } top_zap_maps[] = {
+        { VDEV_TOP_ZAP_POOL_CHECKPOINT_SM, "checkpoint" },
+        { VDEV_TOP_ZAP_INDIRECT_OBSOLETE_SM, "obsolete" },
+    };
+    for (size_t i = 0; i < ARRAY_SIZE(top_zap_maps); i++) {
+        uint64_t sm_obj = 0;
+        if (zap_lookup(mos, tvd->vdev_top_zap, top_zap_maps[i].key,
+            sizeof (uint64_t), 1, &sm_obj) != 0)
+            continue;
+        err = zdx_spacemap_list_add(ctx, sm_obj, top_zap_maps[i].role,
+            tvd->vdev_id, UINT64_MAX, UINT64_MAX);
+        if (err != 0)
+            return err;
+    }
+    return 0;
+}
+
+/*
+ * Pool-wide log spacemaps (log_spacemap feature), keyed by creation txg.
+ */
+static int
+zdx_spacemap_list_logs(zdx_spacemap_list_ctx_t *ctx, objset_t *mos)
+{
+    uint64_t log_zap = 0;
+    if (zap_lookup(mos, DMU_POOL_DIRECTORY_OBJECT, DMU_POOL_LOG_SPACEMAP_ZAP,
+        sizeof (uint64_t), 1, &log_zap) != 0 || log_zap == 0)
+        return 0;
+
+    zap_cursor_t zc;
+    zap_attribute_t *za = zap_attribute_alloc();
+    if (!za)
+        return ENOMEM;
+
+    int err = 0;
+    for (zap_cursor_init(&zc, mos, log_zap);
+        zap_cursor_retrieve(&zc, za) == 0;
+        zap_cursor_advance(&zc)) {
+        uint64_t txg = UINT64_MAX;
+        (void) zdx_parse_u64_token_base(za->za_name, 16, NULL, &txg);
+        err = zdx_spacemap_list_add(ctx, za->za_first_integer, "log",
+            UINT64_MAX, UINT64_MAX, txg);
+        if (err != 0)
+            break;
+    }
+    zap_cursor_fini(&zc);
+    zap_attribute_free(za);
+    return err;
+}
+
+/*
+ * Enumerate every space map object in the pool: per-metaslab maps, the
+ * per-vdev checkpoint and indirect-obsolete maps, and pool log spacemaps.
+ */
+zdx_result_t
+zdx_pool_spacemaps(zdx_pool_t *pool, uint64_t cursor, uint64_t limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    objset_t *mos = spa_meta_objset(spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    zdx_spacemap_list_ctx_t ctx = { 0 };
+    ctx.pool = pool;
+    ctx.cursor = cursor;
+    ctx.limit = limit;
+    ctx.rows_json = json_array_start();
+    if (!ctx.rows_json)
+        return make_error(ENOMEM, "failed to allocate JSON array");
+
+    int err = 0;
+    spa_config_enter(spa, SCL_CONFIG, FTAG, RW_READER);
+    vdev_t *rvd = spa->spa_root_vdev;
+    for (uint64_t c = 0; rvd != NULL && c < rvd->vdev_children; c++) {
+        err = zdx_spacemap_list_vdev(&ctx, mos, rvd->vdev_child[c]);
+        if (err != 0)
+            break;
+    }
+    spa_config_exit(spa, SCL_CONFIG, FTAG);
+    if (err == 0)
+        err = zdx_spacemap_list_logs(&ctx, mos);
+
+    if (err != 0 && err != ZDX_SPACEMAP_PAGE_STOP) {
+        free(ctx.rows_json);
+        return make_error(err, "failed to enumerate spacemaps: %s",
+            strerror(err));
+    }
+
+    char *rows = json_array_end(ctx.rows_json, ctx.added > 0);
+    free(ctx.rows_json);
+    if (!rows)
+        return make_error(ENOMEM, "failed to finalize spacemap list JSON");
+
+    char next_buf[32];
+    const char *next_json = "null";
+    if (ctx.has_more) {
+        (void) snprintf(next_buf, sizeof (next_buf), "%llu",
+            (unsigned long long)(cursor + ctx.added));
+        next_json = next_buf;
+    }
+
+    char *result = json_format(
+        "{\"cursor\":%llu,\"limit\":%llu,\"count\":%llu,\"next\":%s,"
+        "\"spacemaps\":%s}",
+        (unsigned long long)cursor,
+        (unsigned long long)limit,
+        (unsigned long long)ctx.added,
+        next_json,
+        rows);
+    free(rows);
+    if (!result)
+        return make_error(ENOMEM, "failed to encode spacemap list");
+    return make_success(result);
+}
+
+/*
+ * Per-metaslab allocation for one top-level vdev: the structured form of
+ * `zdb -mm`. Metaslabs set up at import supply their range, space map, and
+ * fragmentation; otherwise the range is derived from vdev_ms_shift, the
+ * space map comes from the vdev's metaslab array, and fragmentation is null.
+ * Allocation is the on-disk space map total as of the last synced txg.
+ */
+zdx_result_t
+zdx_vdev_metaslabs(zdx_pool_t *pool, uint64_t vdev, uint64_t cursor,
+    uint64_t limit)
+{
+    if (!pool || !pool->spa)
+        return make_error(EINVAL, "pool not open");
+
+    spa_t *spa = pool->spa;
+    objset_t *mos = spa_meta_objset(spa);
+    if (!mos)
+        return make_error(EINVAL, "failed to access MOS");
+
+    spa_config_enter(spa, SCL_CONFIG, FTAG, RW_READER);
+    vdev_t *rvd = spa->spa_root_vdev;
+    if (rvd == NULL || vdev >= rvd->vdev_children) {
+        spa_config_exit(spa, SCL_CONFIG, FTAG);
+        return make_error(ENOENT, "top-level vdev %llu not found",
+            (unsigned long long)vdev);
+    }
+
+    vdev_t *tvd = rvd->vdev_child[vdev];
+    uint64_t ms_count = tvd->vdev_ms_count;
+    uint64_t ms_shift = tvd->vdev_ms_shift;
+    uint64_t added = 0;
+    uint64_t m;
+
+    char *rows = json_array_start();
+    if (!rows) {
+        spa_config_exit(spa, SCL_CONFIG, FTAG);
+        return make_error(ENOMEM, "failed to allocate JSON array");
+    }
+
+    for (m = cursor; m < ms_count && added < limit; m++) {
+        metaslab_t *ms = (tvd->vdev_ms != NULL) ? tvd->vdev_ms[m] : NULL;
+        uint64_t start = m << ms_shift;
+        uint64_t size = 1ULL << ms_shift;
+        uint64_t sm_obj = 0;
+        uint64_t frag = ZFS_FRAG_INVALID;
+        int64_t alloc = 0;
+
+        if (ms != NULL) {
+            start = ms->ms_start;
+            size = ms->ms_size;
+            frag = ms->ms_fragmentation;
+            if (ms->ms_sm != NULL) {
+                sm_obj = space_map_object(ms->ms_sm);
+                alloc = space_map_allocated(ms->ms_sm);
+            }
+        } else {
+            space_map_t *sm = NULL;
+            if (start + size > tvd->vdev_asize)
+                size = tvd->vdev_asize > start ? tvd->vdev_asize - start : 0;
+            if (tvd->vdev_ms_array != 0)
+                (void) dmu_read(mos, tvd->vdev_ms_array,
+                    m * sizeof (uint64_t), sizeof (uint64_t), &sm_obj,
+                    DMU_READ_PREFETCH);
+            if (sm_obj != 0 && zdx_open_spacemap(pool, sm_obj, &sm) == 0) {
+                alloc = space_map_allocated(sm);
+                space_map_close(sm);
+            }
+        }
+
+        uint64_t allocated = alloc > 0 ? (uint64_t)alloc : 0;
+        char frag_buf[32] = "null";
+        char sm_buf[32] = "null";
+        if (frag != ZFS_FRAG_INVALID)
+            (void) snprintf(frag_buf, sizeof (frag_buf), "%llu",
+                (unsigned long long)frag);
+        if (sm_obj != 0)
+            (void) snprintf(sm_buf, sizeof (sm_buf), "%llu",
+                (unsigned long long)sm_obj);
+
+        char *item = json_format(
+            "{\"metaslab_id\":%llu,\"offset\":%llu,\"size_bytes\":%llu,"
+            "\"allocated_bytes\":%llu,\"free_bytes\":%llu,"
+            "\"frag_percent\":%s,\"spacemap_objid\":%s}",
+            (unsigned long long)m,
+            (unsigned long long)start,
+            (unsigned long long)size,
+            (unsigned long long)allocated,
+            (unsigned long long)(size > allocated ? size - allocated : 0),
+            frag_buf,
+            sm_buf);
+        char *next = item ? json_array_append(rows, item) : NULL;
+        free(item);
+        if (!next) {
+            spa_config_exit(spa, SCL_CONFIG, FTAG);
+            free(rows);
+            return make_error(ENOMEM, "failed to append metaslab JSON");
+        }
+        free(rows);
+        rows = next;
+        added++;
+    }
+
+    const char *vdev_type = tvd->vdev_ops->vdev_op_type;
+    spa_config_exit(spa, SCL_CONFIG, FTAG);
+
+    char *rows_json = json_array_end(rows, added > 0);
+    free(rows);
+    if (!rows_json)
+        return make_error(ENOMEM, "failed to finalize metaslab list JSON");
+
+    char next_buf[32] = "null";
+    if (m < ms_count)
+        (void) snprintf(next_buf, sizeof (next_buf), "%llu",
+            (unsigned long long)m);
+
+    char *result = json_format(
+        "{\"vdev\":%llu,\"vdev_type\":\"%s\",\"ms_shift\":%llu,"
+        "\"metaslab_count\":%llu,\"cursor\":%llu,\"limit\":%llu,"
+        "\"count\":%llu,\"next\":%s,\"metaslabs\":%s}",
+        (unsigned long long)vdev,
+        vdev_type,
+        (unsigned long long)ms_shift,
+        (unsigned long long)ms_count,
+        (unsigned long long)cursor,
+        (unsigned long long)limit,
+        (unsigned long long)added,
+        next_buf,
+        rows_json);
+    free(rows_json);
+    if (!result)
+        return make_error(ENOMEM, "failed to encode metaslab list");
+    return make_success(result);
+}
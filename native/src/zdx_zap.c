@@ -4,6 +4,68 @@
  * Stub implementations for M2 functions
  */
 
+/*
+ * Fat-ZAP layout summary derived from zap_get_stats(). prefix_len is the
+ * pointer table's hash prefix width; free_blocks counts block ids below
+ * zap_freeblk that hold neither the header, a leaf, nor the live pointer
+ * table (e.g. retired pointer tables after growth). leaf_fill_histogram[i]
+ * is the number of leaves that are i tenths full.
+ */
+char *
+zap_structure_json(const zap_stats_t *zs)
+{
+    int is_mega = (zs->zs_ptrtbl_len != 0);
+    uint64_t used = 0;
+    if (is_mega)
+        used = 1 + zs->zs_num_leafs + zs->zs_ptrtbl_zt_numblks;
+    uint64_t free_blocks =
+        (is_mega && zs->zs_num_blocks > used) ? zs->zs_num_blocks - used : 0;
+
+    char *fill = json_array_start();
+    if (!fill)
+        return NULL;
+    for (int i = 0; is_mega && i < ZAP_HISTOGRAM_SIZE; i++) {
+        char *item = json_format("%llu",
+            (unsigned long long)zs->zs_blocks_n_tenths_full[i]);
+        if (!item) {
+            free(fill);
+            return NULL;
+        }
+        char *next = json_array_append(fill, item);
+        free(item);
+        free(fill);
+        if (!next)
+            return NULL;
+        fill = next;
+    }
+    char *fill_json = json_array_end(fill, is_mega);
+    free(fill);
+    if (!fill_json)
+        return NULL;
+
+    char *result = json_format(
+        "{"
+        "\"is_mega_zap\":%s,"
+        "\"num_leaf_blocks\":%llu,"
+        "\"num_entries\":%llu,"
+        "\"prefix_len\":%llu,"
+        "\"salt\":%llu,"
+        "\"block_size\":%llu,"
+        "\"free_blocks\":%llu,"
+        "\"leaf_fill_histogram\":%s"
+        "}",
+        is_mega ? "true" : "false",
+        (unsigned long long)(is_mega ? zs->zs_num_leafs : 0),
+        (unsigned long long)zs->zs_num_entries,
+        (unsigned long long)(is_mega ? zs->zs_ptrtbl_zt_shift : 0),
+        (unsigned long long)zs->zs_salt,
+        (unsigned long long)zs->zs_blocksize,
+        (unsigned long long)free_blocks,
+        fill_json);
+    free(fill_json);
+    return result;
+}
+
 zdx_result_t
 zdx_zap_info(zdx_pool_t *pool, uint64_t objid)
 {
@@ -19,6 +81,10 @@ zdx_zap_info(zdx_pool_t *pool, uint64_t objid)
     if (err != 0)
         return make_error(err, "zap_get_stats failed: %s", strerror(err));
 
+    char *structure = zap_structure_json(&zs);
+    if (!structure)
+        return make_error(ENOMEM, "failed to allocate ZAP structure");
+
     int is_micro = (zs.zs_ptrtbl_len == 0);
     char *result = json_format(
         "{"
@@ -36,7 +102,8 @@ zdx_zap_info(zdx_pool_t *pool, uint64_t objid)
         "\"ptrtbl_nextblk\":%llu,"
         "\"zap_block_type\":%llu,"
         "\"zap_magic\":%llu,"
-        "\"zap_salt\":%llu"
+        "\"zap_salt\":%llu,"
+        "\"structure\":%s"
         "}",
         (unsigned long long)objid,
         is_micro ? "microzap" : "fatzap",
@@ -52,7 +119,9 @@ zdx_zap_info(zdx_pool_t *pool, uint64_t objid)
         (unsigned long long)zs.zs_ptrtbl_nextblk,
         (unsigned long long)zs.zs_block_type,
         (unsigned long long)zs.zs_magic,
-        (unsigned long long)zs.zs_salt);
+        (unsigned long long)zs.zs_salt,
+        structure);
+    free(structure);
 
     if (!result)
         return make_error(ENOMEM, "failed to allocate JSON result");
@@ -138,6 +207,9 @@ zdx_zap_entries(zdx_pool_t *pool, uint64_t objid,
         int maybe_ref = 0;
         uint64_t target_obj = 0;
         char *value_preview = NULL;
+        char *typed_json = NULL;
+        const char *value_type = zap_value_type(NULL,
+            attrp->za_integer_length, attrp->za_num_integers);
         int truncated = 0;
 
         if (attrp->za_num_integers > 0) {
@@ -228,17 +300,26 @@ zdx_zap_entries(zdx_pool_t *pool, uint64_t objid,
                         attrp->za_num_integers, attrp->za_integer_length);
                 }
 
+                value_type = zap_value_type(prop,
+                    attrp->za_integer_length, attrp->za_num_integers);
+                typed_json = zap_value_json(prop,
+                    attrp->za_integer_length, attrp->za_num_integers);
                 free(prop);
             }
         } else {
             value_preview = strdup("");
+            typed_json = strdup("\"\"");
         }
 
         if (!value_preview)
             value_preview = strdup("");
+        if (!typed_json)
+            typed_json = strdup("null");
 
         char *value_json = json_string(value_preview);
-        if (!value_json) {
+        if (!value_json || !typed_json) {
+            free(value_json);
+            free(typed_json);
             free(value_preview);
             free(name_json);
             free(array);
@@ -278,6 +359,10 @@ zdx_zap_entries(zdx_pool_t *pool, uint64_t objid,
             "\"key_u64\":%s,"
             "\"integer_length\":%d,"
             "\"num_integers\":%llu,"
+            "\"value_type\":\"%s\","
+            "\"int_length\":%d,"
+            "\"int_count\":%llu,"
+            "\"value\":%s,"
             "\"value_preview\":%s,"
             "\"value_u64\":%s,"
             "\"ref_objid\":%s,"
@@ -289,6 +374,10 @@ zdx_zap_entries(zdx_pool_t *pool, uint64_t objid,
             key_json,
             attrp->za_integer_length,
             (unsigned long long)attrp->za_num_integers,
+            value_type,
+            attrp->za_integer_length,
+            (unsigned long long)attrp->za_num_integers,
+            typed_json,
             value_json,
             value_u64_json,
             ref_json,
@@ -297,6 +386,7 @@ zdx_zap_entries(zdx_pool_t *pool, uint64_t objid,
             truncated ? "true" : "false");
 
         free(value_preview);
+        free(typed_json);
         free(value_json);
         free(name_json);
 
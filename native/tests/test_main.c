@@ -247,6 +247,44 @@ test_errno_normalization_helper(void)
     }
 }
 
+static void
+test_redundancy_label_helper(void)
+{
+    assert_string_eq("redundancy none", zdx_redundancy_label(0), "no copies");
+    assert_string_eq("redundancy single", zdx_redundancy_label(1), "1 copy");
+    assert_string_eq("redundancy ditto", zdx_redundancy_label(2),
+        "2 copies (ditto)");
+    assert_string_eq("redundancy triple", zdx_redundancy_label(3),
+        "3 copies (ditto)");
+    assert_string_eq("redundancy out of range", zdx_redundancy_label(7),
+        "unknown");
+}
+
+static void
+test_gang_header_byteorder_helper(void)
+{
+    uint8_t block[ZDX_GANG_HEADER_SIZE + 64];
+    uint64_t magic = ZDX_EMBEDDED_CKSUM_MAGIC;
+    size_t tail = ZDX_GANG_HEADER_SIZE - ZDX_EMBEDDED_CKSUM_TAIL_SIZE;
+
+    memset(block, 0, sizeof (block));
+    ASSERT_TRUE(zdx_gang_header_byteorder(block, sizeof (block)) == 0,
+        "gang header absent without magic");
+
+    memcpy(block + tail, &magic, sizeof (magic));
+    ASSERT_TRUE(zdx_gang_header_byteorder(block, sizeof (block)) == 1,
+        "gang header native magic");
+    ASSERT_TRUE(zdx_gang_header_byteorder(block, ZDX_GANG_HEADER_SIZE - 1) == 0,
+        "gang header needs a full 512-byte block");
+
+    magic = __builtin_bswap64(magic);
+    memcpy(block + tail, &magic, sizeof (magic));
+    ASSERT_TRUE(zdx_gang_header_byteorder(block, sizeof (block)) == -1,
+        "gang header byteswapped magic");
+    ASSERT_TRUE(zdx_gang_header_byteorder(NULL, 0) == 0,
+        "gang header null buffer");
+}
+
 int
 main(void)
 {
@@ -256,6 +294,8 @@ main(void)
     test_parse_u64_token_base_cases();
     test_u64_math_and_clamp_helpers();
     test_errno_normalization_helper();
+    test_redundancy_label_helper();
+    test_gang_header_byteorder_helper();
 
     if (g_failures != 0) {
         fprintf(stderr, "native unit tests failed: %d\n", g_failures);
@@ -92,7 +92,10 @@ const parseApiErrorMessage = async (response: Response): Promise<string | null>
   }
 }
 
-async function fetchJson<T>(url: string, init?: RequestInit): Promise<T> {
+async function fetchJsonWithHeaders<T>(
+  url: string,
+  init?: RequestInit,
+): Promise<{ data: T; headers: Headers }> {
   const response = await fetch(url, init)
   if (!response.ok) {
     const message = await parseApiErrorMessage(response)
@@ -102,7 +105,17 @@ async function fetchJson<T>(url: string, init?: RequestInit): Promise<T> {
   if (screenshotRedactionEnabled) {
     redactJsonValue(parsed)
   }
-  return parsed as T
+  return { data: parsed as T, headers: response.headers }
+}
+
+async function fetchJson<T>(url: string, init?: RequestInit): Promise<T> {
+  const { data } = await fetchJsonWithHeaders<T>(url, init)
+  return data
+}
+
+async function fetchPoolList(): Promise<{ pools: string[]; note: string | null }> {
+  const { data, headers } = await fetchJsonWithHeaders<string[]>(`${API_BASE}/api/pools`)
+  return { pools: data, note: headers.get('X-Zfs-Note') }
 }
 
 function readStoredTheme(): UiTheme {
@@ -1134,6 +1147,7 @@ const MOS_OBJECT_SCOPE: ObjectScope = {
 
 function App() {
   const [pools, setPools] = useState<string[]>([])
+  const [poolListNote, setPoolListNote] = useState<string | null>(null)
   const [loading, setLoading] = useState(true)
   const [error, setError] = useState<string | null>(null)
   const [selectedPool, setSelectedPool] = useState<string | null>(null)
@@ -2167,9 +2181,10 @@ function App() {
   }, [screenshotMode])
 
   useEffect(() => {
-    fetchJson<string[]>(`${API_BASE}/api/pools`)
+    fetchPoolList()
       .then(data => {
-        setPools(data)
+        setPools(data.pools)
+        setPoolListNote(data.note)
         setLoading(false)
       })
       .catch(err => {
@@ -4155,8 +4170,10 @@ function App() {
 
       applyModePayload(modeData)
 
-      const nextPools = await fetchJson<string[]>(`${API_BASE}/api/pools`)
+      const poolList = await fetchPoolList()
+      const nextPools = poolList.pools
       setPools(nextPools)
+      setPoolListNote(poolList.note)
       setError(null)
 
       const activePool =
@@ -8371,7 +8388,7 @@ function App() {
                   </div>
                 )}
                 {!loading && !error && pools.length === 0 && (
-                  <p className="muted">No pools found</p>
+                  <p className="muted">{poolListNote ?? 'No pools found'}</p>
                 )}
                 {!loading && !error && pools.length > 0 && (
                   <select
@@ -8482,7 +8499,7 @@ function App() {
                   </div>
                 )}
                 {!loading && !error && pools.length === 0 && (
-                  <p className="muted">No pools found</p>
+                  <p className="muted">{poolListNote ?? 'No pools found'}</p>
                 )}
                 {!loading && !error && pools.length > 0 && (
                   <select